@@ -1,6 +1,9 @@
 use crate::cache::AnalysisCache;
+use crate::metrics::complexity;
+use crate::metrics::halstead::{self, HalsteadMetrics};
 use crate::metrics::language::LanguageDetector;
-use crate::metrics::models::{FileMetrics, LanguageMetrics};
+use crate::metrics::lexer::{self, LineKind};
+use crate::metrics::models::{FileMetrics, FunctionMetrics, LanguageMetrics};
 use std::fs;
 use std::path::Path;
 use std::sync::Arc;
@@ -39,11 +42,8 @@ impl FileAnalyzer {
             return Some(metrics);
         }
         
-        let file_name = path.file_name().and_then(|name| name.to_str()).unwrap_or("");
-        let extension = path.extension().and_then(|ext| ext.to_str()).unwrap_or("");
-        
-        let language = self.detect_file_language(&path_str, file_name, extension);
         let content = self.get_file_content(&path_str, path)?;
+        let language = self.detect_file_language(&path_str, path, &content);
         
         let (lines_of_code, blank_lines, comment_lines) = self.count_lines(&content, &language);
         
@@ -60,6 +60,26 @@ impl FileAnalyzer {
         Some(metrics)
     }
     
+    /// Function-level metrics of a file, reusing the content and language cached by `analyze_file`.
+    pub fn analyze_functions<P: AsRef<Path>>(&self, file_path: P) -> Vec<FunctionMetrics> {
+        let path = file_path.as_ref();
+        let path_str = path.to_string_lossy().to_string();
+
+        match self.get_file_content(&path_str, path) {
+            Some(content) => complexity::analyze_functions(&content, &self.detect_file_language(&path_str, path, &content)),
+            None => Vec::new(),
+        }
+    }
+    
+    /// Halstead metrics of a file, reusing the content and language cached by `analyze_file`.
+    pub fn analyze_halstead<P: AsRef<Path>>(&self, file_path: P) -> Option<HalsteadMetrics> {
+        let path = file_path.as_ref();
+        let path_str = path.to_string_lossy().to_string();
+
+        let content = self.get_file_content(&path_str, path)?;
+        halstead::analyze_halstead(&content, &self.detect_file_language(&path_str, path, &content))
+    }
+    
     fn get_cached_metrics(&self, path_str: &str) -> Option<LanguageMetrics> {
         if let Some(file_metrics) = self.cache.get_metrics(path_str) {
             let mut metrics = LanguageMetrics::new(file_metrics.language);
@@ -72,16 +92,12 @@ impl FileAnalyzer {
         None
     }
     
-    fn detect_file_language(&self, path_str: &str, file_name: &str, extension: &str) -> String {
+    fn detect_file_language(&self, path_str: &str, path: &Path, content: &str) -> String {
         if let Some(cached_lang) = self.cache.get_language(path_str) {
             return cached_lang;
         }
         
-        let detected_lang = if extension.is_empty() && !file_name.is_empty() {
-            self.language_detector.detect_by_filename(file_name)
-        } else {
-            self.language_detector.detect_language(extension)
-        };
+        let detected_lang = self.language_detector.detect(path, Some(content));
         
         self.cache.cache_language(path_str, detected_lang.clone());
         detected_lang
@@ -124,52 +140,23 @@ impl FileAnalyzer {
             blank_lines,
             comment_lines,
             is_test_file,
+            ..FileMetrics::default()
         };
         self.cache.cache_metrics(path_str, file_metrics);
     }
 
+    /// Counts code, blank and comment lines. A line with both code and a comment counts as code.
     fn count_lines(&self, content: &str, language: &str) -> (usize, usize, usize) {
         let mut lines_of_code = 0;
         let mut blank_lines = 0;
         let mut comment_lines = 0;
 
-        let (line_comment, block_comment_start, block_comment_end) =
-            self.language_detector.get_comment_syntax(language);
-
-        let mut in_block_comment = false;
-
-        for line in content.lines() {
-            let trimmed = line.trim();
-
-            if trimmed.is_empty() {
-                blank_lines += 1;
-                continue;
-            }
-
-            if in_block_comment {
-                comment_lines += 1;
-                if !block_comment_end.is_empty() && trimmed.contains(&block_comment_end) {
-                    in_block_comment = false;
-                }
-                continue;
-            }
-
-            if !block_comment_start.is_empty() && trimmed.contains(&block_comment_start) {
-                if !block_comment_end.is_empty() && trimmed.contains(&block_comment_end) {
-                    comment_lines += 1;
-                } else {
-                    in_block_comment = true;
-                    comment_lines += 1;
-                }
-                continue;
+        for kind in lexer::classify_lines(content, language) {
+            match kind {
+                LineKind::Code | LineKind::Mixed => lines_of_code += 1,
+                LineKind::Comment => comment_lines += 1,
+                LineKind::Blank => blank_lines += 1,
             }
-
-            if !line_comment.is_empty() && trimmed.starts_with(&line_comment) {
-                comment_lines += 1;
-                continue;
-            }
-
-            lines_of_code += 1;
         }
 
         (lines_of_code, blank_lines, comment_lines)
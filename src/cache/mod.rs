@@ -1,3 +1,4 @@
+use crate::dependency::dependency_graph::EdgeImport;
 use dashmap::DashMap;
 use std::hash::Hash;
 use std::path::Path;
@@ -7,7 +8,7 @@ pub struct AnalysisCache {
     file_content_cache: DashMap<String, (String, SystemTime)>,
     language_cache: DashMap<String, (String, SystemTime)>,
     metrics_cache: DashMap<String, (crate::metrics::models::FileMetrics, SystemTime)>,
-    dependency_cache: DashMap<String, (Vec<String>, SystemTime)>,
+    dependency_cache: DashMap<String, (Vec<EdgeImport>, SystemTime)>,
 }
 
 impl Default for AnalysisCache {
@@ -65,12 +66,12 @@ impl AnalysisCache {
         }
     }
 
-    pub fn get_dependencies(&self, path: &str) -> Option<Vec<String>> {
+    pub fn get_dependencies(&self, path: &str) -> Option<Vec<EdgeImport>> {
         self.get_if_not_modified(path, &self.dependency_cache)
             .map(|(deps, _)| deps)
     }
 
-    pub fn cache_dependencies(&self, path: &str, dependencies: Vec<String>) {
+    pub fn cache_dependencies(&self, path: &str, dependencies: Vec<EdgeImport>) {
         if let Ok(metadata) = std::fs::metadata(path) {
             if let Ok(modified) = metadata.modified() {
                 self.dependency_cache.insert(path.to_string(), (dependencies, modified));
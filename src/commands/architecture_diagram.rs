@@ -1,9 +1,10 @@
+use crate::dependency::crate_analyzer::CrateDependencyAnalyzer;
+use crate::dependency::dependency_analyzer::DependencyAnalyzer;
 use crate::dependency::dependency_graph::DependencyGraph;
-use crate::util::file_filter::FileFilter;
-use crate::util::parallel;
+use crate::util::parallel::{self, ParallelProcessing};
 use crate::output::path;
 use crate::output::style;
-use std::collections::HashMap;
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
 use std::path::{Path, PathBuf};
 use std::fs;
 use std::io::Write;
@@ -20,6 +21,8 @@ pub async fn execute(
     include_tests: bool,
     group_by_module: bool,
     focus: Option<String>,
+    hide_external: bool,
+    crate_level: bool,
 ) -> i32 {
     let path = Path::new(&path);
     
@@ -53,85 +56,62 @@ pub async fn execute(
         return 1;
     }
     
-    let exclude_tests = !include_tests;
-    
     let use_parallel = !no_parallel;
     
-    let mut dependency_graph = DependencyGraph::new();
-    
-    
     info!("Building dependency graph...");
     
-    let path_str = path.to_string_lossy().to_string();
-    let is_parallel = use_parallel;
+    parallel::log_parallel_status(use_parallel);
     
-    parallel::log_parallel_status(is_parallel);
+    let graph_result = if crate_level {
+        CrateDependencyAnalyzer::new()
+            .include_dev_dependencies(include_tests)
+            .include_external(!hide_external)
+            .analyze_crates(path)
+    } else {
+        DependencyAnalyzer::new()
+            .enable_parallel_processing(use_parallel)
+            .include_test_files(include_tests)
+            .analyze_dependencies(path)
+    };
     
-    let source_files = match crate::util::file_filter::get_all_source_files(&path_str, is_parallel) {
-        Ok(files) => files,
+    let dependency_graph = match graph_result {
+        Ok(graph) => graph,
         Err(err) => {
-            error!("Failed to scan directory: {}", err);
+            error!("Failed to analyze dependencies: {}", err);
             return 1;
         }
     };
     
-    info!("Found {} source files", source_files.len());
-    
-    for file in &source_files {
-        let file_path = file.to_string_lossy().to_string();
-        
-        if exclude_tests && FileFilter::is_test_file(file) {
-            continue;
-        }
-        
-        dependency_graph.add_node(&file_path);
-        
-        let parent_dir = file.parent();
-        if let Some(dir) = parent_dir {
-            for other_file in &source_files {
-                if file == other_file {
-                    continue;
-                }
-                
-                if exclude_tests && FileFilter::is_test_file(other_file) {
-                    continue;
-                }
-                
-                let other_path = other_file.to_string_lossy().to_string();
-                
-                if other_file.parent() == Some(dir) {
-                    dependency_graph.add_node(&other_path);
-                    
-                    if file_path.len() > other_path.len() {
-                        dependency_graph.add_edge(&file_path, &other_path);
-                    }
-                }
-            }
-        }
-    }
+    info!(
+        "Found {} {} and {} external packages",
+        dependency_graph.get_internal_nodes().len(),
+        if crate_level { "crates" } else { "source files" },
+        dependency_graph.get_external_nodes().len()
+    );
     
-    let mut all_dependencies = HashMap::new();
+    let external_nodes: HashSet<String> = if hide_external {
+        HashSet::new()
+    } else {
+        dependency_graph.get_external_nodes().clone()
+    };
     
-    for node in dependency_graph.get_nodes() {
-        let dependencies = dependency_graph.get_dependencies(node);
-        all_dependencies.insert(node.clone(), dependencies);
-    }
+    let all_dependencies = collect_diagram_dependencies(&dependency_graph, hide_external);
     
     let diagram_content = match format.as_str() {
         "dot" => {
-            generate_dot_diagram(&all_dependencies, &detail, group_by_module, focus.as_deref())
+            generate_dot_diagram(&all_dependencies, &external_nodes, &detail, group_by_module, focus.as_deref())
         },
         "plantuml" => {
-            generate_plantuml_diagram(&all_dependencies, &detail, group_by_module, focus.as_deref())
+            generate_plantuml_diagram(&all_dependencies, &external_nodes, &detail, group_by_module, focus.as_deref())
         },
         "mermaid" => {
-            generate_mermaid_diagram(&all_dependencies, &detail, group_by_module, focus.as_deref())
+            generate_mermaid_diagram(&all_dependencies, &external_nodes, &detail, group_by_module, focus.as_deref())
         },
         "c4" => {
-            generate_c4_diagram(&all_dependencies, &detail, group_by_module, focus.as_deref())
+            generate_c4_diagram(&all_dependencies, &external_nodes, &detail, group_by_module, focus.as_deref())
         },
         "svg" => {
-            generate_svg_diagram(&all_dependencies, &detail, group_by_module, focus.as_deref())
+            generate_svg_diagram(&all_dependencies, &external_nodes, &detail, group_by_module, focus.as_deref())
         },
         _ => {
             error!("Unsupported diagram format: {}", format);
@@ -200,6 +180,30 @@ pub async fn execute(
     0
 }
 
+fn collect_diagram_dependencies(
+    graph: &DependencyGraph,
+    hide_external: bool,
+) -> BTreeMap<String, Vec<String>> {
+    let mut dependencies = BTreeMap::new();
+    
+    for node in graph.get_nodes() {
+        if graph.is_external(node) {
+            continue;
+        }
+        
+        let mut targets: Vec<String> = graph
+            .get_dependencies(node)
+            .into_iter()
+            .filter(|target| !(hide_external && graph.is_external(target)))
+            .collect();
+        targets.sort();
+        
+        dependencies.insert(node.clone(), targets);
+    }
+    
+    dependencies
+}
+
 fn get_file_extension(format: &str) -> &str {
     match format {
         "dot" => "dot",
@@ -212,7 +216,8 @@ fn get_file_extension(format: &str) -> &str {
 }
 
 fn generate_dot_diagram(
-    dependencies: &HashMap<String, Vec<String>>,
+    dependencies: &BTreeMap<String, Vec<String>>,
+    external_nodes: &HashSet<String>,
     detail_level: &str,
     group_by_module: bool,
     focus: Option<&str>,
@@ -221,19 +226,35 @@ fn generate_dot_diagram(
     dot.push_str("  rankdir=LR;\n");
     dot.push_str("  node [shape=box, style=filled, fillcolor=lightblue];\n");
     dot.push_str("  edge [arrowhead=vee];\n\n");
+    let ids = NodeIds::for_graph(dependencies);
+    
     
     if group_by_module {
-        let module_dependencies = group_dependencies_by_module(&dependencies, focus);
+        let module_dependencies = group_dependencies_by_module(dependencies, external_nodes, focus);
+        let module_ids = NodeIds::new(module_dependencies.keys());
         
         for (module, deps) in module_dependencies.iter() {
-            dot.push_str(&format!("  subgraph cluster_{} {{\n", sanitize_node_id(module)));
+            dot.push_str(&format!("  subgraph cluster_{} {{\n", module_ids.get(module)));
             dot.push_str(&format!("    label=\"{}\";\n", module));
             dot.push_str("    style=filled;\n");
             dot.push_str("    color=lightgrey;\n");
             
             for (from, _) in deps.iter() {
                 let file_name = extract_file_name(from);
-                dot.push_str(&format!("    \"{}\" [label=\"{}\"];\n", sanitize_node_id(from), file_name));
+                dot.push_str(&format!("    \"{}\" [label=\"{}\"];\n", ids.get(from), file_name));
+            }
+            
+            dot.push_str("  }\n");
+        }
+        
+        let referenced_externals = referenced_external_nodes(dependencies, external_nodes, focus);
+        if !referenced_externals.is_empty() {
+            dot.push_str("  subgraph cluster_external {\n");
+            dot.push_str("    label=\"external\";\n");
+            dot.push_str("    style=dashed;\n");
+            
+            for external in &referenced_externals {
+                dot.push_str(&format_dot_external_node(external, ids.get(external), "    "));
             }
             
             dot.push_str("  }\n");
@@ -248,8 +269,8 @@ fn generate_dot_diagram(
                     
                     dot.push_str(&format!(
                         "  \"{}\" -> \"{}\";\n",
-                        sanitize_node_id(from),
-                        sanitize_node_id(to)
+                        ids.get(from),
+                        ids.get(to)
                     ));
                 }
             }
@@ -266,11 +287,15 @@ fn generate_dot_diagram(
             
             dot.push_str(&format!(
                 "  \"{}\" [label=\"{}\"];\n",
-                sanitize_node_id(from),
+                ids.get(from),
                 file_name
             ));
         }
         
+        for external in referenced_external_nodes(dependencies, external_nodes, focus) {
+            dot.push_str(&format_dot_external_node(&external, ids.get(&external), "  "));
+        }
+        
         for (from, to_list) in dependencies.iter() {
             if let Some(focus_path) = focus {
                 if !from.contains(focus_path) {
@@ -280,7 +305,7 @@ fn generate_dot_diagram(
             
             for to in to_list {
                 if let Some(focus_path) = focus {
-                    if !to.contains(focus_path) {
+                    if !to.contains(focus_path) && !external_nodes.contains(to) {
                         continue;
                     }
                 }
@@ -291,8 +316,8 @@ fn generate_dot_diagram(
                 
                 dot.push_str(&format!(
                     "  \"{}\" -> \"{}\";\n",
-                    sanitize_node_id(from),
-                    sanitize_node_id(to)
+                    ids.get(from),
+                    ids.get(to)
                 ));
             }
         }
@@ -303,7 +328,8 @@ fn generate_dot_diagram(
 }
 
 fn generate_plantuml_diagram(
-    dependencies: &HashMap<String, Vec<String>>,
+    dependencies: &BTreeMap<String, Vec<String>>,
+    external_nodes: &HashSet<String>,
     detail_level: &str,
     group_by_module: bool,
     focus: Option<&str>,
@@ -311,16 +337,29 @@ fn generate_plantuml_diagram(
     let mut puml = String::from("@startuml Architecture\n");
     puml.push_str("!theme sketchy-outline\n");
     puml.push_str("skinparam linetype ortho\n\n");
+    let ids = NodeIds::for_graph(dependencies);
+    
     
     if group_by_module {
-        let module_dependencies = group_dependencies_by_module(&dependencies, focus);
+        let module_dependencies = group_dependencies_by_module(dependencies, external_nodes, focus);
         
         for (module, deps) in module_dependencies.iter() {
             puml.push_str(&format!("package \"{}\" {{\n", module));
             
             for (from, _) in deps.iter() {
                 let file_name = extract_file_name(from);
-                puml.push_str(&format!("  component \"{}\" as {}\n", file_name, sanitize_node_id(from)));
+                puml.push_str(&format!("  component \"{}\" as {}\n", file_name, ids.get(from)));
+            }
+            
+            puml.push_str("}\n\n");
+        }
+        
+        let referenced_externals = referenced_external_nodes(dependencies, external_nodes, focus);
+        if !referenced_externals.is_empty() {
+            puml.push_str("package \"external\" {\n");
+            
+            for external in &referenced_externals {
+                puml.push_str(&format!("  component \"{}\" as {} <<external>>\n", external, ids.get(external)));
             }
             
             puml.push_str("}\n\n");
@@ -335,8 +374,8 @@ fn generate_plantuml_diagram(
                     
                     puml.push_str(&format!(
                         "{} --> {}\n",
-                        sanitize_node_id(from),
-                        sanitize_node_id(to)
+                        ids.get(from),
+                        ids.get(to)
                     ));
                 }
             }
@@ -354,7 +393,15 @@ fn generate_plantuml_diagram(
             puml.push_str(&format!(
                 "component \"{}\" as {}\n",
                 file_name,
-                sanitize_node_id(from)
+                ids.get(from)
+            ));
+        }
+        
+        for external in referenced_external_nodes(dependencies, external_nodes, focus) {
+            puml.push_str(&format!(
+                "component \"{}\" as {} <<external>>\n",
+                external,
+                ids.get(&external)
             ));
         }
         
@@ -369,7 +416,7 @@ fn generate_plantuml_diagram(
             
             for to in to_list {
                 if let Some(focus_path) = focus {
-                    if !to.contains(focus_path) {
+                    if !to.contains(focus_path) && !external_nodes.contains(to) {
                         continue;
                     }
                 }
@@ -380,8 +427,8 @@ fn generate_plantuml_diagram(
                 
                 puml.push_str(&format!(
                     "{} --> {}\n",
-                    sanitize_node_id(from),
-                    sanitize_node_id(to)
+                    ids.get(from),
+                    ids.get(to)
                 ));
             }
         }
@@ -392,22 +439,37 @@ fn generate_plantuml_diagram(
 }
 
 fn generate_mermaid_diagram(
-    dependencies: &HashMap<String, Vec<String>>,
+    dependencies: &BTreeMap<String, Vec<String>>,
+    external_nodes: &HashSet<String>,
     detail_level: &str,
     group_by_module: bool,
     focus: Option<&str>,
 ) -> String {
     let mut mmd = String::from("graph LR\n");
+    let ids = NodeIds::for_graph(dependencies);
+    
     
     if group_by_module {
-        let module_dependencies = group_dependencies_by_module(&dependencies, focus);
+        let module_dependencies = group_dependencies_by_module(dependencies, external_nodes, focus);
+        let module_ids = NodeIds::new(module_dependencies.keys());
         
         for (module, deps) in module_dependencies.iter() {
-            mmd.push_str(&format!("  subgraph {}\n", module));
+            mmd.push_str(&format!("  subgraph {}[\"{}\"]\n", module_ids.get(module), module));
             
             for (from, _) in deps.iter() {
                 let file_name = extract_file_name(from);
-                mmd.push_str(&format!("    {}[\"{}\"]\n", sanitize_node_id(from), file_name));
+                mmd.push_str(&format!("    {}[\"{}\"]\n", ids.get(from), file_name));
+            }
+            
+            mmd.push_str("  end\n");
+        }
+        
+        let referenced_externals = referenced_external_nodes(dependencies, external_nodes, focus);
+        if !referenced_externals.is_empty() {
+            mmd.push_str("  subgraph external\n");
+            
+            for external in &referenced_externals {
+                mmd.push_str(&format!("    {}([\"{}\"]):::external\n", ids.get(external), external));
             }
             
             mmd.push_str("  end\n");
@@ -422,8 +484,8 @@ fn generate_mermaid_diagram(
                     
                     mmd.push_str(&format!(
                         "  {} --> {}\n",
-                        sanitize_node_id(from),
-                        sanitize_node_id(to)
+                        ids.get(from),
+                        ids.get(to)
                     ));
                 }
             }
@@ -440,11 +502,19 @@ fn generate_mermaid_diagram(
             
             mmd.push_str(&format!(
                 "  {}[\"{}\"]\n",
-                sanitize_node_id(from),
+                ids.get(from),
                 file_name
             ));
         }
         
+        for external in referenced_external_nodes(dependencies, external_nodes, focus) {
+            mmd.push_str(&format!(
+                "  {}([\"{}\"]):::external\n",
+                ids.get(&external),
+                external
+            ));
+        }
+        
         for (from, to_list) in dependencies.iter() {
             if let Some(focus_path) = focus {
                 if !from.contains(focus_path) {
@@ -454,7 +524,7 @@ fn generate_mermaid_diagram(
             
             for to in to_list {
                 if let Some(focus_path) = focus {
-                    if !to.contains(focus_path) {
+                    if !to.contains(focus_path) && !external_nodes.contains(to) {
                         continue;
                     }
                 }
@@ -465,18 +535,23 @@ fn generate_mermaid_diagram(
                 
                 mmd.push_str(&format!(
                     "  {} --> {}\n",
-                    sanitize_node_id(from),
-                    sanitize_node_id(to)
+                    ids.get(from),
+                    ids.get(to)
                 ));
             }
         }
     }
     
+    if !external_nodes.is_empty() {
+        mmd.push_str("  classDef external fill:#eeeeee,stroke:#999999,stroke-dasharray:4 2\n");
+    }
+    
     mmd
 }
 
 fn generate_c4_diagram(
-    dependencies: &HashMap<String, Vec<String>>,
+    dependencies: &BTreeMap<String, Vec<String>>,
+    external_nodes: &HashSet<String>,
     detail_level: &str,
     group_by_module: bool,
     focus: Option<&str>,
@@ -486,23 +561,30 @@ fn generate_c4_diagram(
     c4.push_str("LAYOUT_WITH_LEGEND()\n\n");
     
     c4.push_str("System_Boundary(system, \"Codebase Architecture\") {\n");
+    let ids = NodeIds::for_graph(dependencies);
+    
     
     if group_by_module {
-        let module_dependencies = group_dependencies_by_module(&dependencies, focus);
+        let module_dependencies = group_dependencies_by_module(dependencies, external_nodes, focus);
+        let module_ids = NodeIds::new(module_dependencies.keys());
         
         for (module, deps) in module_dependencies.iter() {
-            let module_id = sanitize_node_id(module);
+            let module_id = module_ids.get(module);
             c4.push_str(&format!("  Container({}Cont, \"{}\") {{\n", module_id, module));
             
             for (from, _) in deps.iter() {
                 let file_name = extract_file_name(from);
-                let file_id = sanitize_node_id(from);
+                let file_id = ids.get(from);
                 c4.push_str(&format!("    Component({}, \"{}\", \"File\")\n", file_id, file_name));
             }
             
             c4.push_str("  }\n\n");
         }
         
+        for external in referenced_external_nodes(dependencies, external_nodes, focus) {
+            c4.push_str(&format!("  Component_Ext({}, \"{}\", \"External package\")\n", ids.get(&external), external));
+        }
+        
         for (_, deps) in module_dependencies.iter() {
             for (from, to_list) in deps.iter() {
                 for to in to_list {
@@ -510,8 +592,8 @@ fn generate_c4_diagram(
                         continue;
                     }
                     
-                    let from_id = sanitize_node_id(from);
-                    let to_id = sanitize_node_id(to);
+                    let from_id = ids.get(from);
+                    let to_id = ids.get(to);
                     c4.push_str(&format!("  Rel({}, {}, \"depends on\")\n", from_id, to_id));
                 }
             }
@@ -526,10 +608,14 @@ fn generate_c4_diagram(
                 }
             }
             
-            let file_id = sanitize_node_id(from);
+            let file_id = ids.get(from);
             c4.push_str(&format!("  Component({}, \"{}\", \"File\")\n", file_id, file_name));
         }
         
+        for external in referenced_external_nodes(dependencies, external_nodes, focus) {
+            c4.push_str(&format!("  Component_Ext({}, \"{}\", \"External package\")\n", ids.get(&external), external));
+        }
+        
         c4.push_str("\n");
         
         for (from, to_list) in dependencies.iter() {
@@ -541,7 +627,7 @@ fn generate_c4_diagram(
             
             for to in to_list {
                 if let Some(focus_path) = focus {
-                    if !to.contains(focus_path) {
+                    if !to.contains(focus_path) && !external_nodes.contains(to) {
                         continue;
                     }
                 }
@@ -550,8 +636,8 @@ fn generate_c4_diagram(
                     continue;
                 }
                 
-                let from_id = sanitize_node_id(from);
-                let to_id = sanitize_node_id(to);
+                let from_id = ids.get(from);
+                let to_id = ids.get(to);
                 c4.push_str(&format!("  Rel({}, {}, \"depends on\")\n", from_id, to_id));
             }
         }
@@ -563,12 +649,13 @@ fn generate_c4_diagram(
 }
 
 fn generate_svg_diagram(
-    dependencies: &HashMap<String, Vec<String>>,
+    dependencies: &BTreeMap<String, Vec<String>>,
+    external_nodes: &HashSet<String>,
     detail_level: &str,
     group_by_module: bool,
     focus: Option<&str>,
 ) -> String {
-    let dot_content = generate_dot_diagram(dependencies, detail_level, group_by_module, focus);
+    let dot_content = generate_dot_diagram(dependencies, external_nodes, detail_level, group_by_module, focus);
     
     match Command::new("dot").arg("-V").output() {
         Ok(_) => {
@@ -633,10 +720,11 @@ fn convert_dot_to_svg(dot_content: &str) -> Result<String, String> {
 }
 
 fn group_dependencies_by_module(
-    dependencies: &HashMap<String, Vec<String>>,
+    dependencies: &BTreeMap<String, Vec<String>>,
+    external_nodes: &HashSet<String>,
     focus: Option<&str>,
-) -> HashMap<String, HashMap<String, Vec<String>>> {
-    let mut module_deps: HashMap<String, HashMap<String, Vec<String>>> = HashMap::new();
+) -> BTreeMap<String, BTreeMap<String, Vec<String>>> {
+    let mut module_deps: BTreeMap<String, BTreeMap<String, Vec<String>>> = BTreeMap::new();
     
     for (from, to_list) in dependencies {
         if let Some(focus_path) = focus {
@@ -646,13 +734,13 @@ fn group_dependencies_by_module(
         }
         
         let module_name = extract_module_name(from);
-        let module_entry = module_deps.entry(module_name).or_insert_with(HashMap::new);
+        let module_entry = module_deps.entry(module_name).or_default();
         
         let filtered_to_list: Vec<String> = to_list
             .iter()
             .filter(|to| {
                 if let Some(focus_path) = focus {
-                    to.contains(focus_path)
+                    to.contains(focus_path) || external_nodes.contains(*to)
                 } else {
                     true
                 }
@@ -668,6 +756,32 @@ fn group_dependencies_by_module(
     module_deps
 }
 
+fn referenced_external_nodes(
+    dependencies: &BTreeMap<String, Vec<String>>,
+    external_nodes: &HashSet<String>,
+    focus: Option<&str>,
+) -> Vec<String> {
+    let referenced: HashSet<&String> = dependencies
+        .iter()
+        .filter(|(from, _)| focus.is_none_or(|focus_path| from.contains(focus_path)))
+        .flat_map(|(_, to_list)| to_list.iter())
+        .filter(|to| external_nodes.contains(*to))
+        .collect();
+    
+    let mut referenced: Vec<String> = referenced.into_iter().cloned().collect();
+    referenced.sort();
+    referenced
+}
+
+fn format_dot_external_node(external: &str, id: &str, indent: &str) -> String {
+    format!(
+        "{}\"{}\" [label=\"{}\", shape=ellipse, style=\"filled,dashed\", fillcolor=lightgrey];\n",
+        indent,
+        id,
+        external
+    )
+}
+
 fn extract_module_name(file_path: &str) -> String {
     let path = Path::new(file_path);
     
@@ -694,8 +808,45 @@ fn extract_file_name(file_path: &str) -> String {
     file_path.to_string()
 }
 
+/// Diagram identifiers for the nodes of a graph. Every diagram format accepts letters, digits
+/// and underscores, so names are reduced to those, and a numeric suffix keeps apart names that
+/// reduce to the same identifier, such as `x-y.js` and `x_y.js`.
+struct NodeIds {
+    ids: HashMap<String, String>,
+}
+
+impl NodeIds {
+    fn new<'a>(names: impl IntoIterator<Item = &'a String>) -> Self {
+        let names: BTreeSet<&String> = names.into_iter().collect();
+        let mut used = HashSet::new();
+        let mut ids = HashMap::new();
+        
+        for name in names {
+            let base = sanitize_node_id(name);
+            let mut id = base.clone();
+            let mut suffix = 2;
+            while !used.insert(id.clone()) {
+                id = format!("{}_{}", base, suffix);
+                suffix += 1;
+            }
+            ids.insert(name.clone(), id);
+        }
+        
+        NodeIds { ids }
+    }
+    
+    /// Identifiers for every file and package that appears in `dependencies`.
+    fn for_graph(dependencies: &BTreeMap<String, Vec<String>>) -> Self {
+        NodeIds::new(dependencies.iter().flat_map(|(from, to_list)| std::iter::once(from).chain(to_list)))
+    }
+    
+    fn get<'a>(&'a self, name: &'a str) -> &'a str {
+        self.ids.get(name).map(String::as_str).unwrap_or(name)
+    }
+}
+
 fn sanitize_node_id(id: &str) -> String {
-    id.replace(&['.', '/', '\\', ' ', '-'][..], "_")
+    id.chars().map(|c| if c.is_ascii_alphanumeric() { c } else { '_' }).collect()
 }
 
 fn suggest_visualization(format: &str, output_file: &Path) {
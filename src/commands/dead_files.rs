@@ -0,0 +1,162 @@
+use crate::dependency::dead_files::{DeadFile, DeadFileAnalysis, DeadFileReport};
+use crate::dependency::dependency_analyzer::DependencyAnalyzer;
+use crate::metrics::collector::MetricsCollector;
+use crate::output::style::*;
+use crate::util::error::{AppError, AppResult, handle_command_error};
+use crate::util::parallel::{log_parallel_status, parse_parallel_flag, ParallelProcessing};
+use crate::util::paths::display_path;
+use std::fs;
+
+pub fn execute(
+    path: String,
+    entry_points: Vec<String>,
+    no_output: bool,
+    output_path: Option<String>,
+    no_parallel: bool,
+) -> i32 {
+    match execute_dead_files_command(path, entry_points, no_output, output_path, no_parallel) {
+        Ok(_) => 0,
+        Err(error) => handle_command_error(&error)
+    }
+}
+
+fn execute_dead_files_command(
+    path: String,
+    entry_points: Vec<String>,
+    no_output: bool,
+    custom_output_path: Option<String>,
+    no_parallel: bool,
+) -> AppResult<()> {
+    let parallel_enabled = parse_parallel_flag(no_parallel);
+    log_parallel_status(parallel_enabled);
+
+    // Tests are entry points, so files used only by tests are not reported
+    let graph = DependencyAnalyzer::new()
+        .enable_parallel_processing(parallel_enabled)
+        .include_test_files(true)
+        .analyze_dependencies(&path)
+        .map_err(|error| AppError::Dependency(format!("Error analyzing dependencies: {}", error)))?;
+
+    let metrics = MetricsCollector::new()
+        .enable_parallel_processing(parallel_enabled)
+        .collect_metrics(&path)
+        .map_err(|error| AppError::Metrics(format!("Error analyzing directory: {}", error)))?;
+
+    let report = DeadFileAnalysis::new()
+        .entry_points(entry_points)
+        .run(&graph, &path, &metrics.files);
+
+    print_report(&report);
+
+    if !no_output {
+        let output_path = custom_output_path.unwrap_or_else(|| path.clone());
+        export_report(&report, &output_path)?;
+    }
+
+    Ok(())
+}
+
+fn print_report(report: &DeadFileReport) {
+    println!();
+    print_header("Dead Files:");
+    println!(
+        "{}",
+        StyledText::new("===========").foreground(ThemeColors::SEPARATOR)
+    );
+
+    for (label, value) in summary_rows(report) {
+        println!(
+            "{} {}",
+            StyledText::new(&format!("{:<20}", label)).foreground(ThemeColors::LABEL),
+            StyledText::new(&value).foreground(ThemeColors::NUMBER)
+        );
+    }
+
+    if report.dead_files.is_empty() {
+        println!();
+        print_success("Every file is reachable from an entry point");
+        return;
+    }
+
+    println!();
+    println!(
+        "{}",
+        StyledText::new(&format!("{:<60} {:>8}  {}", "File", "Lines", "Used By")).foreground(ThemeColors::TABLE_HEADER)
+    );
+    for file in &report.dead_files {
+        println!(
+            "{} {}  {}",
+            StyledText::new(&format!("{:<60}", display_path(&file.path))).foreground(ThemeColors::LABEL),
+            StyledText::new(&format!("{:>8}", format_lines(file))).foreground(ThemeColors::NUMBER),
+            StyledText::new(describe_usage(file)).foreground(ThemeColors::LANGUAGE)
+        );
+    }
+}
+
+fn summary_rows(report: &DeadFileReport) -> Vec<(&'static str, String)> {
+    vec![
+        ("Entry points:", report.entry_points.len().to_string()),
+        ("Reachable files:", report.reachable_files.to_string()),
+        ("Dead files:", report.dead_files.len().to_string()),
+        ("Dead lines of code:", report.dead_lines_of_code().to_string()),
+    ]
+}
+
+fn format_markdown(report: &DeadFileReport) -> String {
+    let mut output = String::new();
+
+    output.push_str("# Dead Files\n\n");
+
+    output.push_str("## Summary\n\n");
+    output.push_str("| Metric | Value |\n");
+    output.push_str("|--------|-------|\n");
+    for (label, value) in summary_rows(report) {
+        output.push_str(&format!("| {} | {} |\n", label.trim_end_matches(':'), value));
+    }
+
+    output.push_str("\n## Unreachable Files\n\n");
+    if report.dead_files.is_empty() {
+        output.push_str("Every file is reachable from an entry point.\n");
+    } else {
+        output.push_str("| File | Language | Lines of Code | Used By |\n");
+        output.push_str("|------|----------|---------------|---------|\n");
+        for file in &report.dead_files {
+            output.push_str(&format!(
+                "| `{}` | {} | {} | {} |\n",
+                display_path(&file.path),
+                file.metrics.as_ref().map_or("-", |metrics| metrics.language.as_str()),
+                format_lines(file),
+                describe_usage(file)
+            ));
+        }
+    }
+
+    output.push_str("\n## Entry Points\n\n");
+    for entry_point in &report.entry_points {
+        output.push_str(&format!("- `{}`\n", display_path(entry_point)));
+    }
+
+    output
+}
+
+fn export_report(report: &DeadFileReport, output_path: &str) -> AppResult<()> {
+    let path = crate::output::path::resolve_output_path("dead-files", output_path, "md")?;
+
+    fs::write(&path, format_markdown(report)).map_err(|e| AppError::FileSystem {
+        path: path.clone(),
+        message: format!("Error exporting dead file report: {}", e),
+    })?;
+
+    print_success(&format!("Dead file report exported to {}", path.display()));
+    Ok(())
+}
+
+fn format_lines(file: &DeadFile) -> String {
+    file.metrics
+        .as_ref()
+        .map_or("-".to_string(), |metrics| metrics.lines_of_code.to_string())
+}
+
+fn describe_usage(file: &DeadFile) -> &'static str {
+    if file.orphan { "nothing" } else { "dead files only" }
+}
@@ -1,3 +1,4 @@
+use crate::metrics::lexer::{self, SpanKind};
 use crate::output::style;
 use crate::util::error::{AppError, AppResult, handle_command_error, AppErrorType, to_app_error};
 use crate::util::file_filter::FileFilter;
@@ -5,6 +6,7 @@ use crate::util::parallel::{log_parallel_status, parse_parallel_flag};
 use std::time::Instant;
 use std::path::{Path, PathBuf};
 use std::fs;
+use std::ops::Range;
 use std::io::{self, Write};
 use std::process::Command;
 use walkdir::WalkDir;
@@ -419,31 +421,11 @@ fn delete_comments(directory_path: &str, language: &str, output_dir: Option<&str
         deleted_comments: Vec::new(),
     };
     
-    let (file_extension, comment_pattern, doc_comment_prefix, ignore_pattern) = match language.to_lowercase().as_str() {
-        "rust" => (
-            "rs",
-            r"//.+$",
-            "///",
-            r"//.*aicodeanalyzer:\s*ignore"
-        ),
-        "python" | "py" => (
-            "py",
-            r"#.+$",
-            "###",
-            r"#.*aicodeanalyzer:\s*ignore"
-        ),
-        "csharp" | "cs" | "c#" => (
-            "cs",
-            r"//.+$",
-            "///",
-            r"//.*aicodeanalyzer:\s*ignore"
-        ),
-        "typescript" | "ts" => (
-            "ts",
-            r"//.+$",
-            "///",
-            r"//.*aicodeanalyzer:\s*ignore"
-        ),
+    let (file_extension, language_name, doc_comment_prefixes): (&str, &str, &[&str]) = match language.to_lowercase().as_str() {
+        "rust" => ("rs", "Rust", &["///", "//!", "/**", "/*!"]),
+        "python" | "py" => ("py", "Python", &["###"]),
+        "csharp" | "cs" | "c#" => ("cs", "C#", &["///", "/**"]),
+        "typescript" | "ts" => ("ts", "TypeScript", &["///", "/**"]),
         _ => {
             return Err(to_app_error(
                 format!("Language '{}' is not supported.", language),
@@ -452,16 +434,10 @@ fn delete_comments(directory_path: &str, language: &str, output_dir: Option<&str
         }
     };
     
-    let comment_regex = Regex::new(comment_pattern).map_err(|e| {
+    let ignore_regex = Regex::new(r"aicodeanalyzer:\s*ignore").map_err(|e| {
         to_app_error(format!("Failed to compile regex: {}", e), AppErrorType::Internal)
     })?;
     
-    let ignore_regex = Regex::new(ignore_pattern).map_err(|e| {
-        to_app_error(format!("Failed to compile regex: {}", e), AppErrorType::Internal)
-    })?;
-    
-    let _ignore_pattern_str = r"aicodeanalyzer:\s*ignore";
-    
     let output_base = match output_dir {
         Some(dir) => {
             if dir.starts_with('/') {
@@ -508,7 +484,7 @@ fn delete_comments(directory_path: &str, language: &str, output_dir: Option<&str
                 stats.processed_files += 1;
                 
                 let mut comment_count = 0;
-                let cleaned_content = delete_file_content(&content, &comment_regex, &ignore_regex, doc_comment_prefix, &mut comment_count, path.to_str().unwrap_or(""), &mut stats);
+                let cleaned_content = delete_file_content(&content, language_name, doc_comment_prefixes, &ignore_regex, &mut comment_count, path.to_str().unwrap_or(""), &mut stats);
                 
                 if comment_count > 0 {
                     stats.changed_files += 1;
@@ -562,7 +538,7 @@ fn delete_comments(directory_path: &str, language: &str, output_dir: Option<&str
                 stats.processed_files += 1;
                 
                 let mut comment_count = 0;
-                let cleaned_content = delete_file_content(&content, &comment_regex, &ignore_regex, doc_comment_prefix, &mut comment_count, file_path.to_str().unwrap_or(""), &mut stats);
+                let cleaned_content = delete_file_content(&content, language_name, doc_comment_prefixes, &ignore_regex, &mut comment_count, file_path.to_str().unwrap_or(""), &mut stats);
                 
                 if comment_count > 0 {
                     stats.changed_files += 1;
@@ -627,242 +603,82 @@ fn delete_comments(directory_path: &str, language: &str, output_dir: Option<&str
     Ok(stats)
 }
 
+/// Removes the comments found by the lexer, keeping doc comments and comments marked with
+/// `aicodeanalyzer: ignore`. Lines left empty by a removal are dropped and trailing whitespace
+/// before a removed comment is trimmed; comment markers inside string literals are untouched.
 fn delete_file_content(
     content: &str, 
-    comment_regex: &Regex, 
+    language: &str,
+    doc_comment_prefixes: &[&str],
     ignore_regex: &Regex, 
-    doc_comment_prefix: &str,
     comment_count: &mut usize,
     file_path: &str,
     stats: &mut DeleteStats
 ) -> String {
-    let mut result = String::with_capacity(content.len());
-    
-    let content_to_process = if content.starts_with('\u{feff}') {
-        &content[3..]
-    } else {
-        content
-    };
-    
-    let pattern = comment_regex.as_str();
-    let is_python = pattern.starts_with("#");
-    let is_csharp = !is_python && file_path.ends_with(".cs");
-    let is_typescript = !is_python && file_path.ends_with(".ts");
-    
+    let removed: Vec<Range<usize>> = lexer::lex(content, language)
+        .into_iter()
+        .filter(|span| span.kind == SpanKind::Comment)
+        .map(|span| span.range)
+        .filter(|range| {
+            let comment = &content[range.clone()];
+            !doc_comment_prefixes.iter().any(|prefix| comment.starts_with(prefix)) && !ignore_regex.is_match(comment)
+        })
+        .collect();
+    
+    for range in &removed {
+        *comment_count += 1;
+        stats.deleted_comments.push(DeletedComment {
+            file: file_path.to_string(),
+            line: content[..range.start].matches('\n').count() + 1,
+            comment_removed: content[range.clone()].trim_end().to_string(),
+        });
+    }
     
-    let content = if is_csharp || is_typescript {
-        remove_multiline_comments(content_to_process.to_string(), comment_count, file_path, stats)
-    } else {
-        content_to_process.to_string()
-    };
+    let mut result = String::with_capacity(content.len());
+    let mut next_removal = 0;
+    let mut offset = 0;
     
-    for line in content.lines() {
-        let trimmed = line.trim_start();
+    for line in content.split_inclusive('\n') {
+        let line_range = offset..offset + line.len();
+        offset += line.len();
         
-        if line.trim().is_empty() {
-            result.push_str(line);
-            result.push('\n');
-            continue;
-        }
-
-        if (is_csharp || is_typescript) && trimmed.starts_with("///") {
-            result.push_str(line);
-            result.push('\n');
-            continue;
-        }
-        else if is_typescript && trimmed.starts_with("/**") {
-            result.push_str(line);
-            result.push('\n');
-            continue;
-        }
-        else if trimmed.starts_with(doc_comment_prefix) {
-            result.push_str(line);
-            result.push('\n');
-            continue;
-        }
+        let body_end = line_range.end - (line.len() - line.trim_end_matches(['\r', '\n']).len());
+        let line_ending = &content[body_end..line_range.end];
         
-        if ignore_regex.is_match(line) {
-            result.push_str(line);
-            result.push('\n');
-            continue;
+        while next_removal < removed.len() && removed[next_removal].end <= line_range.start {
+            next_removal += 1;
         }
         
-        if line.contains('\\') {
-            result.push_str(line);
-            result.push('\n');
-            continue;
+        let mut kept = String::new();
+        let mut position = line_range.start;
+        for range in removed[next_removal..].iter().take_while(|range| range.start < body_end) {
+            if range.start > position {
+                kept.push_str(&content[position..range.start]);
+            }
+            position = position.max(range.end);
         }
         
-        if line.contains("r#") {
+        if position == line_range.start {
             result.push_str(line);
-            result.push('\n');
             continue;
         }
         
-        if !is_python && trimmed.starts_with("//") && !trimmed.starts_with("///") {
-            *comment_count += 1;
-            
-            stats.deleted_comments.push(DeletedComment {
-                file: file_path.to_string(),
-                line: result.lines().count() + 1,
-                comment_removed: line.to_string(),
-            });
-            
-            continue;
+        if position < body_end {
+            kept.push_str(&content[position..body_end]);
         }
         
-        if is_python && trimmed.starts_with("#") && !trimmed.starts_with("###") {
-            *comment_count += 1;
-            
-            stats.deleted_comments.push(DeletedComment {
-                file: file_path.to_string(),
-                line: result.lines().count() + 1,
-                comment_removed: line.to_string(),
-            });
-            
-            continue;
-        }
-        
-        if let Some((cleaned_line, removed_comment)) = process_line_preserving_strings(line, comment_regex, comment_count) {
-            if !removed_comment.is_empty() {
-                stats.deleted_comments.push(DeletedComment {
-                    file: file_path.to_string(),
-                    line: result.lines().count() + 1,
-                    comment_removed: removed_comment,
-                });
-            }
-            
-            result.push_str(&cleaned_line);
-            result.push('\n');
+        let kept_code = kept.trim_start_matches('\u{feff}');
+        if kept_code.trim().is_empty() {
+            result.push_str(&kept[..kept.len() - kept_code.len()]);
         } else {
-            result.push_str(line);
-            result.push('\n');
+            result.push_str(kept.trim_end());
+            result.push_str(line_ending);
         }
     }
     
     result
 }
 
-/// Removes multi-line comments (/* ... */) from C# and TypeScript code
-/// Preserves JSDoc style comments (/** ... */) for TypeScript
-fn remove_multiline_comments(content: String, comment_count: &mut usize, file_path: &str, stats: &mut DeleteStats) -> String {
-    let mut result = String::with_capacity(content.len());
-    let mut in_string = false;
-    let mut in_char = false;
-    let mut in_verbatim_string = false;
-    let mut in_template_string = false;
-    let mut escape_next = false;
-    let mut in_multiline_comment = false;
-    let mut in_jsdoc_comment = false;
-    let mut multiline_comment_start = 0;
-    
-    let has_bom = content.starts_with('\u{feff}');
-    
-    let chars: Vec<char> = content.chars().collect();
-    let mut i = if has_bom { 1 } else { 0 };
-    
-    while i < chars.len() {
-        if in_jsdoc_comment {
-            result.push(chars[i]);
-            
-            if i + 1 < chars.len() && chars[i] == '*' && chars[i+1] == '/' {
-                in_jsdoc_comment = false;
-                result.push(chars[i+1]);
-                i += 2;
-                continue;
-            }
-            i += 1;
-            continue;
-        }
-        
-        if in_multiline_comment {
-            if i + 1 < chars.len() && chars[i] == '*' && chars[i+1] == '/' {
-                in_multiline_comment = false;
-                *comment_count += 1;
-                
-                let comment_text = content.chars().skip(multiline_comment_start).take(i + 2 - multiline_comment_start).collect::<String>();
-                stats.deleted_comments.push(DeletedComment {
-                    file: file_path.to_string(),
-                    line: content[..multiline_comment_start].matches('\n').count() + 1,
-                    comment_removed: comment_text,
-                });
-                
-                i += 2;
-                continue;
-            }
-            i += 1;
-            continue;
-        }
-        
-        if escape_next {
-            escape_next = false;
-            result.push(chars[i]);
-            i += 1;
-            continue;
-        }
-        
-        match chars[i] {
-            '\\' if in_string || in_char => {
-                escape_next = true;
-                result.push(chars[i]);
-            },
-            
-            '"' => {
-                if !in_char {
-                    if in_verbatim_string {
-                        if i + 1 < chars.len() && chars[i+1] == '"' {
-                            result.push(chars[i]);
-                            result.push(chars[i+1]);
-                            i += 2;
-                            continue;
-                        }
-                        in_verbatim_string = false;
-                        in_string = false;
-                    } else if in_string {
-                        in_string = false;
-                    } else if i > 0 && chars[i-1] == '@' {
-                        in_verbatim_string = true;
-                        in_string = true;
-                    } else {
-                        in_string = true;
-                    }
-                }
-                result.push(chars[i]);
-            },
-            
-            '\'' if !in_string => {
-                in_char = !in_char;
-                result.push(chars[i]);
-            },
-            
-            '`' => {
-                if !in_string && !in_char {
-                    in_template_string = !in_template_string;
-                }
-                result.push(chars[i]);
-            },
-            
-            '/' if !in_string && !in_char && !in_template_string && i + 1 < chars.len() && chars[i+1] == '*' => {
-                if i + 2 < chars.len() && chars[i+2] == '*' {
-                    in_jsdoc_comment = true;
-                    result.push(chars[i]);
-                } else {
-                    in_multiline_comment = true;
-                    multiline_comment_start = i;
-                    i += 2;
-                    continue;
-                }
-            },
-            
-            _ => result.push(chars[i]),
-        }
-        
-        i += 1;
-    }
-    
-    result
-}
-
 fn display_delete_results(stats: &DeleteStats, start_time: Instant) {
     let elapsed = start_time.elapsed();
     
@@ -879,109 +695,6 @@ fn display_delete_results(stats: &DeleteStats, start_time: Instant) {
     style::print_success(&format!("⏱️ Deletion completed in {:.2?}", elapsed));
 }
 
-/// Process a line of code, preserving string literals while removing end-of-line comments
-/// Returns Some((cleaned_line, removed_comment)) if a comment was found and removed, None if no comments were found
-fn process_line_preserving_strings(line: &str, comment_regex: &Regex, comment_count: &mut usize) -> Option<(String, String)> {
-    let mut in_string = false;
-    let mut in_char = false;
-    let mut in_template_string = false;
-    let mut escape_next = false;
-    let mut in_triple_double_quotes = false;
-    let mut in_triple_single_quotes = false;
-    
-    let line_to_process = if line.starts_with('\u{feff}') {
-        &line[3..]
-    } else {
-        line
-    };
-    
-    let chars = line_to_process.chars().collect::<Vec<_>>();
-    let length = chars.len();
-    
-    let mut comment_pos = None;
-    
-    let pattern = comment_regex.as_str();
-    let is_python = pattern.starts_with("#");
-    let _is_typescript = !is_python && line_to_process.ends_with(".ts");
-    
-    for i in 0..length {
-        let c = chars[i];
-        
-        if escape_next {
-            escape_next = false;
-            continue;
-        }
-        
-        match c {
-            '\\' if in_string || in_char => {
-                escape_next = true;
-            },
-            
-            '"' => {
-                if !in_char && !in_template_string && !in_triple_single_quotes {
-                    if is_python && i + 2 < length && chars[i+1] == '"' && chars[i+2] == '"' {
-                        in_triple_double_quotes = !in_triple_double_quotes;
-                        continue;
-                    }
-                    
-                    if i > 0 && chars[i-1] == '@' {
-                        in_string = !in_string;
-                    } else {
-                        in_string = !in_string;
-                    }
-                }
-            },
-            
-            '`' => {
-                if !in_char && !in_string && !in_triple_double_quotes && !in_triple_single_quotes {
-                    in_template_string = !in_template_string;
-                }
-            },
-            
-            '\'' => {
-                if is_python && i + 2 < length && chars[i+1] == '\'' && chars[i+2] == '\'' && !in_string && !in_triple_double_quotes {
-                    in_triple_single_quotes = !in_triple_single_quotes;
-                    continue;
-                } else if !in_string && !in_triple_double_quotes && !in_triple_single_quotes {
-                    in_char = !in_char;
-                }
-            },
-            
-            '/' if !is_python && i + 1 < length && chars[i+1] == '/' && !in_string && !in_char && !in_template_string => {
-                let prefix = &line[0..i];
-                if !prefix.trim().is_empty() {
-                    comment_pos = Some(i);
-                    break;
-                }
-            },
-            
-            '#' if is_python && !in_string && !in_char && !in_triple_double_quotes && !in_triple_single_quotes => {
-                let prefix = &line[0..i];
-                if !prefix.trim().is_empty() {
-                    comment_pos = Some(i);
-                    break;
-                }
-            },
-            
-            _ => {}
-        }
-    }
-    
-    if let Some(pos) = comment_pos {
-        *comment_count += 1;
-        let adjusted_pos = if line.starts_with('\u{feff}') && line_to_process != line {
-            pos + 3
-        } else {
-            pos
-        };
-        let cleaned = line[0..adjusted_pos].trim_end().to_string();
-        let comment = line[adjusted_pos..].trim_end().to_string();
-        return Some((cleaned, comment));
-    }
-    
-    None
-}
-
 fn print_comment_preview(original: &str, cleaned: &str, file_path: &str) {
     let original_lines: Vec<&str> = original.lines().collect();
     let cleaned_lines: Vec<&str> = cleaned.lines().collect();
@@ -1,13 +1,38 @@
+use crate::dependency::coupling::CouplingSort;
+use crate::dependency::crate_analyzer::CrateDependencyAnalyzer;
 use crate::dependency::dependency_analyzer::DependencyAnalyzer;
 use crate::dependency::dependency_graph::DependencyGraph;
 use crate::dependency::dependency_reporter::DependencyReporter;
+use crate::dependency::graph_export::GraphFormat;
+use crate::dependency::layer_rules::{DEFAULT_RULES_FILE, LayerRules};
+use crate::metrics::collector::MetricsCollector;
+use crate::metrics::models::FileMetrics;
 use crate::output::style;
 use crate::util::error::{AppError, AppResult, handle_command_error};
 use crate::util::parallel::{log_parallel_status, parse_parallel_flag, ParallelProcessing};
+use std::path::{Path, PathBuf};
 use std::time::Instant;
 
-pub fn execute(path: String, no_output: bool, output_path: Option<String>, no_parallel: bool) -> i32 {
-    match execute_dependencies_command(path, no_output, output_path, no_parallel) {
+/// How the dependency graph is built, beyond the options shared by every command.
+#[derive(Debug, Clone, Default)]
+pub struct DependencyOptions {
+    pub crate_level: bool,
+    pub include_dev_deps: bool,
+    pub all_features: bool,
+    pub include_dirs: Vec<String>,
+    pub sort_by: Option<String>,
+    pub rules: Option<String>,
+    pub format: Option<String>,
+}
+
+pub fn execute(
+    path: String,
+    no_output: bool,
+    output_path: Option<String>,
+    no_parallel: bool,
+    options: DependencyOptions
+) -> i32 {
+    match execute_dependencies_command(path, no_output, output_path, no_parallel, options) {
         Ok(_) => 0,
         Err(error) => handle_command_error(&error)
     }
@@ -17,30 +42,81 @@ fn execute_dependencies_command(
     path: String, 
     no_output: bool, 
     custom_output_path: Option<String>,
-    no_parallel: bool
+    no_parallel: bool,
+    options: DependencyOptions
 ) -> AppResult<()> {
-    let parallel_enabled = parse_parallel_flag(no_parallel);
+    let start_time = Instant::now();
     
-    let analyzer = initialize_analyzer(parallel_enabled);
-    let reporter = DependencyReporter::new();
+    let coupling_sort: CouplingSort = match &options.sort_by {
+        Some(sort_by) => sort_by.parse().map_err(AppError::Dependency)?,
+        None => CouplingSort::default(),
+    };
     
-    log_parallel_status(parallel_enabled);
+    let format: GraphFormat = match &options.format {
+        Some(format) => format.parse().map_err(AppError::Dependency)?,
+        None => GraphFormat::default(),
+    };
     
-    let start_time = Instant::now();
-    let graph = perform_dependency_analysis(&analyzer, &path)?;
+    let (graph, reporter) = if options.crate_level {
+        let analyzer = CrateDependencyAnalyzer::new()
+            .include_dev_dependencies(options.include_dev_deps)
+            .all_features(options.all_features);
+        (perform_crate_analysis(&analyzer, &path)?, DependencyReporter::for_crates())
+    } else {
+        let parallel_enabled = parse_parallel_flag(no_parallel);
+        let analyzer = initialize_analyzer(parallel_enabled)
+            .include_directories(options.include_dirs.into_iter().map(PathBuf::from).collect());
+        log_parallel_status(parallel_enabled);
+        (perform_dependency_analysis(&analyzer, &path)?, DependencyReporter::new())
+    };
+    
+    let violations = load_layer_rules(&path, options.rules.as_deref())?
+        .map(|rules| rules.check(&graph, &path));
     
+    let mut reporter = reporter.sort_coupling_by(coupling_sort);
+    if let Some(violations) = &violations {
+        reporter = reporter.layer_violations(violations.clone());
+    }
     display_analysis_results(&reporter, &graph, start_time);
     
     if !no_output {
+        // Only file graphs carry per-file metrics; DOT has nowhere to put them
+        let files = if options.crate_level || format == GraphFormat::Dot {
+            Vec::new()
+        } else {
+            collect_file_metrics(&path, parse_parallel_flag(no_parallel))?
+        };
+        
         if let Some(output_path) = custom_output_path {
-            export_dependency_graph(&reporter, &graph, output_path)?;
+            export_dependency_graph(&reporter, &graph, format, &files, output_path)?;
         } else {
             let default_output = path.clone();
-            export_dependency_graph(&reporter, &graph, default_output)?;
+            export_dependency_graph(&reporter, &graph, format, &files, default_output)?;
         }
     }
     
-    Ok(())
+    match violations {
+        Some(violations) if !violations.is_empty() => Err(AppError::Dependency(format!(
+            "{} dependencies break the layering rules",
+            violations.len()
+        ))),
+        _ => Ok(()),
+    }
+}
+
+/// Loads the rules file given on the command line, or the default one in the analyzed
+/// directory when it exists.
+fn load_layer_rules(path: &str, rules_path: Option<&str>) -> AppResult<Option<LayerRules>> {
+    if let Some(rules_path) = rules_path {
+        return LayerRules::load(rules_path).map(Some);
+    }
+    
+    let default_path = Path::new(path).join(DEFAULT_RULES_FILE);
+    if default_path.is_file() {
+        return LayerRules::load(default_path).map(Some);
+    }
+    
+    Ok(None)
 }
 
 fn initialize_analyzer(parallel_enabled: bool) -> DependencyAnalyzer {
@@ -56,6 +132,14 @@ fn perform_dependency_analysis(
         .map_err(|error| AppError::Dependency(format!("Error analyzing dependencies: {}", error)))
 }
 
+fn perform_crate_analysis(
+    analyzer: &CrateDependencyAnalyzer,
+    directory_path: &str
+) -> AppResult<DependencyGraph> {
+    analyzer.analyze_crates(directory_path)
+        .map_err(|error| AppError::Dependency(format!("Error analyzing crate dependencies: {}", error)))
+}
+
 fn display_analysis_results(
     reporter: &DependencyReporter,
     graph: &DependencyGraph,
@@ -66,13 +150,25 @@ fn display_analysis_results(
     style::print_success(&format!("Analysis completed in {:.2?}", elapsed));
 }
 
+fn collect_file_metrics(path: &str, parallel_enabled: bool) -> AppResult<Vec<FileMetrics>> {
+    MetricsCollector::new()
+        .enable_parallel_processing(parallel_enabled)
+        .collect_metrics(path)
+        .map(|metrics| metrics.files)
+        .map_err(|error| AppError::Metrics(format!("Error analyzing directory: {}", error)))
+}
+
 fn export_dependency_graph(
     reporter: &DependencyReporter,
     graph: &DependencyGraph,
+    format: GraphFormat,
+    files: &[FileMetrics],
     output_path: String
 ) -> AppResult<()> {
-    reporter.export_dot(graph, output_path)
+    reporter.export_graph(graph, format, files, &output_path)
         .map_err(|error| AppError::Dependency(format!("Error exporting dependency graph: {}", error)))?;
+    reporter.export_markdown(graph, &output_path)
+        .map_err(|error| AppError::Dependency(format!("Error exporting dependency report: {}", error)))?;
     
     style::print_success("Dependency graph exported successfully");
     Ok(())
@@ -0,0 +1,383 @@
+use crate::dependency::coupling::CouplingMetrics;
+use crate::dependency::cycle_analysis::{DependencyCycle, Edge};
+use crate::dependency::dependency_analyzer::DependencyAnalyzer;
+use crate::dependency::dependency_graph::DependencyGraph;
+use crate::dependency::graph_diff::{CouplingChange, GraphDiff, diff_graphs, relative_to};
+use crate::output::style::*;
+use crate::util::error::{AppError, AppResult, handle_command_error};
+use crate::util::git;
+use crate::util::parallel::{log_parallel_status, parse_parallel_flag, ParallelProcessing};
+use std::fs;
+use std::path::{Path, PathBuf};
+use tempfile::TempDir;
+
+const VALID_FORMATS: [&str; 2] = ["plain", "markdown"];
+
+/// Tables longer than this are cut short so the report still fits in a pull request comment
+const MAX_TABLE_ROWS: usize = 50;
+
+/// The two trees to compare. The head defaults to the working tree at the analyzed path.
+#[derive(Debug, Clone, Default)]
+pub struct DependencyDiffOptions {
+    pub base: Option<String>,
+    pub base_dir: Option<String>,
+    pub head: Option<String>,
+    pub head_dir: Option<String>,
+    pub format: String,
+}
+
+/// A tree to analyze, kept alive for as long as it is read from when it was extracted from git.
+struct Snapshot {
+    label: String,
+    root: PathBuf,
+    _checkout: Option<TempDir>,
+}
+
+impl Snapshot {
+    fn directory(path: &str) -> AppResult<Self> {
+        if !Path::new(path).is_dir() {
+            return Err(AppError::FileSystem {
+                path: PathBuf::from(path),
+                message: "Not a directory".to_string(),
+            });
+        }
+
+        Ok(Snapshot {
+            label: format!("`{}`", path),
+            root: PathBuf::from(path),
+            _checkout: None,
+        })
+    }
+
+    fn revision(path: &str, revision: &str) -> AppResult<Self> {
+        let checkout = git::export_revision(path, revision)?;
+
+        Ok(Snapshot {
+            label: format!("`{}`", revision),
+            root: checkout.path().to_path_buf(),
+            _checkout: Some(checkout),
+        })
+    }
+
+    fn load_content(&self, node: &str) -> Option<String> {
+        fs::read_to_string(self.root.join(node)).ok()
+    }
+}
+
+pub fn execute(
+    path: String,
+    no_output: bool,
+    output_path: Option<String>,
+    no_parallel: bool,
+    options: DependencyDiffOptions,
+) -> i32 {
+    match execute_dependency_diff_command(path, no_output, output_path, no_parallel, options) {
+        Ok(_) => 0,
+        Err(error) => handle_command_error(&error)
+    }
+}
+
+fn execute_dependency_diff_command(
+    path: String,
+    no_output: bool,
+    custom_output_path: Option<String>,
+    no_parallel: bool,
+    options: DependencyDiffOptions,
+) -> AppResult<()> {
+    if !VALID_FORMATS.contains(&options.format.as_str()) {
+        return Err(AppError::Analysis(format!(
+            "Invalid output format: {}. Valid formats are: {}",
+            options.format,
+            VALID_FORMATS.join(", ")
+        )));
+    }
+
+    let base = match (&options.base, &options.base_dir) {
+        (_, Some(directory)) => Snapshot::directory(directory)?,
+        (Some(revision), None) => Snapshot::revision(&path, revision)?,
+        (None, None) => return Err(AppError::Analysis("Specify a --base revision or a --base-dir".to_string())),
+    };
+    let head = match (&options.head, &options.head_dir) {
+        (_, Some(directory)) => Snapshot::directory(directory)?,
+        (Some(revision), None) => Snapshot::revision(&path, revision)?,
+        (None, None) => Snapshot {
+            label: "working tree".to_string(),
+            ..Snapshot::directory(&path)?
+        },
+    };
+
+    let parallel_enabled = parse_parallel_flag(no_parallel);
+    if options.format == "plain" {
+        log_parallel_status(parallel_enabled);
+    }
+
+    let before = analyze_snapshot(&base, parallel_enabled)?;
+    let after = analyze_snapshot(&head, parallel_enabled)?;
+    let diff = diff_graphs(
+        &before,
+        &after,
+        &|node| base.load_content(node),
+        &|node| head.load_content(node),
+    );
+
+    let markdown = format_markdown(&diff, &base.label, &head.label);
+    if options.format == "markdown" {
+        print!("{}", markdown);
+    } else {
+        print_report(&diff, &base.label, &head.label);
+    }
+
+    if !no_output {
+        let output_path = custom_output_path.unwrap_or_else(|| path.clone());
+        export_report(&markdown, &output_path, options.format == "plain")?;
+    }
+
+    Ok(())
+}
+
+fn analyze_snapshot(snapshot: &Snapshot, parallel_enabled: bool) -> AppResult<DependencyGraph> {
+    let graph = DependencyAnalyzer::new()
+        .enable_parallel_processing(parallel_enabled)
+        .analyze_dependencies(&snapshot.root)
+        .map_err(|error| AppError::Dependency(format!("Error analyzing dependencies of {}: {}", snapshot.label, error)))?;
+
+    Ok(relative_to(&graph, &snapshot.root))
+}
+
+fn print_report(diff: &GraphDiff, base_label: &str, head_label: &str) {
+    println!();
+    print_header(&format!("Dependency Changes ({} → {}):", base_label, head_label));
+    println!(
+        "{}",
+        StyledText::new("===================").foreground(ThemeColors::SEPARATOR)
+    );
+
+    for (label, before, after) in summary_rows(diff) {
+        println!(
+            "{} {}",
+            StyledText::new(&format!("{:<20}", label)).foreground(ThemeColors::LABEL),
+            StyledText::new(&format_change(before, after)).foreground(ThemeColors::NUMBER)
+        );
+    }
+
+    if diff.is_empty() {
+        println!();
+        print_success("The dependency structure is unchanged");
+        return;
+    }
+
+    print_edges("Added dependencies:", "+", &diff.added_edges);
+    print_edges("Removed dependencies:", "-", &diff.removed_edges);
+
+    for (title, cycles) in [("New tangles:", &diff.new_cycles), ("Broken tangles:", &diff.broken_cycles)] {
+        if cycles.is_empty() {
+            continue;
+        }
+        println!();
+        println!("{}", highlight(title));
+        for cycle in cycles {
+            println!("  {}", StyledText::new(&describe_cycle(cycle)).foreground(Color::Yellow));
+        }
+    }
+
+    if !diff.directory_changes.is_empty() {
+        println!();
+        println!("{}", highlight("Coupling changes by directory:"));
+        for change in diff.directory_changes.iter().take(MAX_TABLE_ROWS) {
+            println!(
+                "  {} {}",
+                StyledText::new(&format!("{:<50}", change.name)).foreground(ThemeColors::LABEL),
+                StyledText::new(&describe_coupling_change(change)).foreground(ThemeColors::NUMBER)
+            );
+        }
+    }
+}
+
+fn print_edges(title: &str, marker: &str, edges: &[Edge]) {
+    if edges.is_empty() {
+        return;
+    }
+
+    println!();
+    println!("{}", highlight(title));
+    for (from, to) in edges.iter().take(MAX_TABLE_ROWS) {
+        println!(
+            "  {} {} → {}",
+            marker,
+            StyledText::new(from).foreground(ThemeColors::LABEL),
+            StyledText::new(to).foreground(ThemeColors::LANGUAGE)
+        );
+    }
+    if edges.len() > MAX_TABLE_ROWS {
+        println!("  … and {} more", edges.len() - MAX_TABLE_ROWS);
+    }
+}
+
+fn summary_rows(diff: &GraphDiff) -> Vec<(&'static str, usize, usize)> {
+    vec![
+        ("Files:", diff.before.files, diff.after.files),
+        ("External packages:", diff.before.external_packages, diff.after.external_packages),
+        ("Dependencies:", diff.before.edges, diff.after.edges),
+        ("Tangles:", diff.before.tangles, diff.after.tangles),
+    ]
+}
+
+pub fn format_markdown(diff: &GraphDiff, base_label: &str, head_label: &str) -> String {
+    let mut output = String::new();
+
+    output.push_str("## Dependency Changes\n\n");
+    output.push_str(&format!("Comparing {} with {}.\n\n", base_label, head_label));
+
+    output.push_str("| Metric | Before | After | Change |\n");
+    output.push_str("|--------|--------|-------|--------|\n");
+    for (label, before, after) in summary_rows(diff) {
+        output.push_str(&format!(
+            "| {} | {} | {} | {} |\n",
+            label.trim_end_matches(':'),
+            before,
+            after,
+            format_delta(before, after)
+        ));
+    }
+
+    if diff.is_empty() {
+        output.push_str("\nThe dependency structure is unchanged.\n");
+        return output;
+    }
+
+    push_edge_table(&mut output, "Added Dependencies", &diff.added_edges);
+    push_edge_table(&mut output, "Removed Dependencies", &diff.removed_edges);
+
+    if !diff.new_cycles.is_empty() {
+        output.push_str("\n### :warning: New Tangles\n\n");
+        for cycle in &diff.new_cycles {
+            output.push_str(&format!("- {}\n", describe_cycle_markdown(cycle)));
+            for (from, to) in &cycle.suggested_removals {
+                output.push_str(&format!("  - remove `{}` → `{}` to break it\n", from, to));
+            }
+        }
+    }
+
+    if !diff.broken_cycles.is_empty() {
+        output.push_str("\n### Broken Tangles\n\n");
+        for cycle in &diff.broken_cycles {
+            output.push_str(&format!("- {}\n", describe_cycle_markdown(cycle)));
+        }
+    }
+
+    push_coupling_table(&mut output, "Coupling Changes by Directory", "Directory", &diff.directory_changes);
+    push_coupling_table(&mut output, "Coupling Changes by File", "File", &diff.file_changes);
+
+    output
+}
+
+fn push_edge_table(output: &mut String, title: &str, edges: &[Edge]) {
+    if edges.is_empty() {
+        return;
+    }
+
+    output.push_str(&format!("\n### {} ({})\n\n", title, edges.len()));
+    output.push_str("| File | Depends On |\n");
+    output.push_str("|------|------------|\n");
+    for (from, to) in edges.iter().take(MAX_TABLE_ROWS) {
+        output.push_str(&format!("| `{}` | `{}` |\n", from, to));
+    }
+    push_truncation_note(output, edges.len());
+}
+
+fn push_coupling_table(output: &mut String, title: &str, kind: &str, changes: &[CouplingChange]) {
+    if changes.is_empty() {
+        return;
+    }
+
+    output.push_str(&format!("\n### {} ({})\n\n", title, changes.len()));
+    output.push_str(&format!("| {} | Ca | Ce | I | A |\n", kind));
+    output.push_str(&format!("|{}|----|----|---|---|\n", "-".repeat(kind.len() + 2)));
+    for change in changes.iter().take(MAX_TABLE_ROWS) {
+        output.push_str(&format!(
+            "| `{}` | {} | {} | {} | {} |\n",
+            change.name,
+            format_value_change(change, |m| m.afferent.to_string()),
+            format_value_change(change, |m| m.efferent.to_string()),
+            format_value_change(change, format_instability),
+            format_value_change(change, format_abstractness),
+        ));
+    }
+    push_truncation_note(output, changes.len());
+}
+
+fn push_truncation_note(output: &mut String, total: usize) {
+    if total > MAX_TABLE_ROWS {
+        output.push_str(&format!("\n_… and {} more._\n", total - MAX_TABLE_ROWS));
+    }
+}
+
+fn export_report(markdown: &str, output_path: &str, announce: bool) -> AppResult<()> {
+    let path = crate::output::path::resolve_output_path("dependency-diff", output_path, "md")?;
+
+    fs::write(&path, markdown).map_err(|e| AppError::FileSystem {
+        path: path.clone(),
+        message: format!("Error exporting dependency diff: {}", e),
+    })?;
+
+    if announce {
+        print_success(&format!("Dependency diff exported to {}", path.display()));
+    }
+    Ok(())
+}
+
+fn describe_cycle(cycle: &DependencyCycle) -> String {
+    cycle.members.join(" ↔ ")
+}
+
+fn describe_cycle_markdown(cycle: &DependencyCycle) -> String {
+    let members: Vec<String> = cycle.members.iter().map(|member| format!("`{}`", member)).collect();
+    members.join(" ↔ ")
+}
+
+fn describe_coupling_change(change: &CouplingChange) -> String {
+    format!(
+        "Ca {}  Ce {}  I {}",
+        format_value_change(change, |m| m.afferent.to_string()),
+        format_value_change(change, |m| m.efferent.to_string()),
+        format_value_change(change, format_instability),
+    )
+}
+
+fn format_instability(metrics: &CouplingMetrics) -> String {
+    format!("{:.2}", metrics.instability())
+}
+
+fn format_abstractness(metrics: &CouplingMetrics) -> String {
+    metrics
+        .abstractness()
+        .map_or_else(|| "n/a".to_string(), |abstractness| format!("{:.2}", abstractness))
+}
+
+/// One value of a coupling change; files and directories that exist on one side only are marked
+/// as added or removed.
+fn format_value_change(change: &CouplingChange, value: fn(&CouplingMetrics) -> String) -> String {
+    match (change.before.as_ref().map(value), change.after.as_ref().map(value)) {
+        (Some(before), Some(after)) if before == after => after,
+        (Some(before), Some(after)) => format!("{} → {}", before, after),
+        (None, Some(after)) => format!("{} (added)", after),
+        (Some(before), None) => format!("{} (removed)", before),
+        (None, None) => String::new(),
+    }
+}
+
+fn format_change(before: usize, after: usize) -> String {
+    if before == after {
+        after.to_string()
+    } else {
+        format!("{} → {} ({})", before, after, format_delta(before, after))
+    }
+}
+
+fn format_delta(before: usize, after: usize) -> String {
+    match after.cmp(&before) {
+        std::cmp::Ordering::Greater => format!("+{}", after - before),
+        std::cmp::Ordering::Less => format!("-{}", before - after),
+        std::cmp::Ordering::Equal => "0".to_string(),
+    }
+}
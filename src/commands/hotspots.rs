@@ -0,0 +1,134 @@
+use crate::metrics::collector::MetricsCollector;
+use crate::metrics::hotspots::HotspotReport;
+use crate::output::report;
+use crate::output::style::*;
+use crate::util::error::{AppError, AppResult, handle_command_error};
+use crate::util::git;
+use crate::util::parallel::{ParallelProcessing, log_parallel_status, parse_parallel_flag};
+
+const VALID_FORMATS: [&str; 3] = ["markdown", "json", "svg"];
+
+/// Rows of each table printed to the terminal; the saved report lists `top` rows.
+const PRINTED_ROWS: usize = 10;
+
+pub struct HotspotOptions {
+    /// Start of the time window, in any form `git log --since` takes
+    pub since: String,
+    pub until: Option<String>,
+    pub top: usize,
+    pub min_shared_commits: usize,
+    pub format: String,
+}
+
+pub fn execute(
+    path: String,
+    no_output: bool,
+    output_path: Option<String>,
+    no_parallel: bool,
+    options: HotspotOptions,
+) -> i32 {
+    match execute_hotspots_command(path, no_output, output_path, no_parallel, options) {
+        Ok(_) => 0,
+        Err(error) => handle_command_error(&error),
+    }
+}
+
+fn execute_hotspots_command(
+    path: String,
+    no_output: bool,
+    custom_output_path: Option<String>,
+    no_parallel: bool,
+    options: HotspotOptions,
+) -> AppResult<()> {
+    report::validate_format(&options.format, &VALID_FORMATS)?;
+
+    let history = git::change_history(&path, &options.since, options.until.as_deref())?;
+
+    let parallel_enabled = parse_parallel_flag(no_parallel);
+    log_parallel_status(parallel_enabled);
+
+    let metrics = MetricsCollector::new()
+        .enable_parallel_processing(parallel_enabled)
+        .collect_metrics(&path)
+        .map_err(|error| AppError::Metrics(format!("Error analyzing directory: {}", error)))?;
+
+    let report = HotspotReport::analyze(&history, &metrics, options.min_shared_commits);
+    print_report(&report);
+    print_success("Hotspot analysis completed successfully");
+
+    if !no_output {
+        let output_path = custom_output_path.unwrap_or_else(|| path.clone());
+        export_report(&report, &output_path, &options)?;
+    }
+
+    Ok(())
+}
+
+fn print_report(report: &HotspotReport) {
+    println!();
+    print_header("Hotspots:");
+    println!("{}", StyledText::new("=========").foreground(ThemeColors::SEPARATOR));
+    println!(
+        "{}",
+        StyledText::new(&format!("{} commits, {} changed files", report.commits, report.hotspots.len()))
+            .foreground(ThemeColors::LABEL)
+    );
+
+    if !report.hotspots.is_empty() {
+        let rows: Vec<_> = report.hotspots.iter().take(PRINTED_ROWS).collect();
+        let path_width = rows.iter().map(|hotspot| hotspot.path.chars().count()).max().unwrap_or(0).max("File".len()) + 2;
+        let header = format!(
+            "{:<path_width$}{:>9}{:>9}{:>9}{:>9}{:>12}{:>9}",
+            "File", "Commits", "Added", "Removed", "Authors", "Complexity", "Score"
+        );
+        println!("{}", StyledText::new(&header).foreground(ThemeColors::TABLE_HEADER));
+
+        for hotspot in rows {
+            print!("{}", StyledText::new(&format!("{:<path_width$}", hotspot.path)).foreground(ThemeColors::LANGUAGE));
+            let values = format!(
+                "{:>9}{:>9}{:>9}{:>9}{:>12}{:>9}",
+                hotspot.churn.commits,
+                hotspot.churn.lines_added,
+                hotspot.churn.lines_removed,
+                hotspot.churn.authors.len(),
+                hotspot.complexity_label(),
+                hotspot.score
+            );
+            println!("{}", StyledText::new(&values).foreground(ThemeColors::NUMBER));
+        }
+    }
+
+    println!();
+    print_header("Temporal Coupling:");
+    println!("{}", StyledText::new("==================").foreground(ThemeColors::SEPARATOR));
+    if report.couplings.is_empty() {
+        println!("No files changed together in at least {} commits", report.min_shared_commits);
+    }
+    for coupling in report.couplings.iter().take(PRINTED_ROWS) {
+        println!(
+            "{} {} {} {}",
+            highlight(&coupling.first),
+            StyledText::new("<->").foreground(ThemeColors::SEPARATOR),
+            highlight(&coupling.second),
+            StyledText::new(&format!("({} shared commits, {:.0}%)", coupling.shared_commits, coupling.degree * 100.0))
+                .foreground(ThemeColors::NUMBER)
+        );
+    }
+    println!();
+}
+
+/// Saves the report in the chosen format. The markdown report gets the treemap saved next to
+/// it and linked from it.
+fn export_report(hotspots: &HotspotReport, output_path: &str, options: &HotspotOptions) -> AppResult<()> {
+    let path = report::report_path("hotspots", output_path, &options.format)?;
+
+    let content = match options.format.as_str() {
+        "json" => hotspots.to_json(options.top).map_err(AppError::Metrics)?,
+        "svg" => hotspots.to_svg(),
+        _ => {
+            let chart_name = report::save_chart(&path, &hotspots.to_svg())?;
+            hotspots.to_markdown(options.top, chart_name.as_deref())
+        }
+    };
+    report::save_report(&path, &content, "Hotspots")
+}
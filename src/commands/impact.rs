@@ -0,0 +1,158 @@
+use crate::dependency::dependency_analyzer::DependencyAnalyzer;
+use crate::dependency::impact::{ImpactQuery, ImpactReport, ImpactedFile};
+use crate::output::style::*;
+use crate::util::error::{AppError, AppResult, handle_command_error};
+use crate::util::git;
+use crate::util::parallel::{log_parallel_status, parse_parallel_flag, ParallelProcessing};
+use std::path::{Path, PathBuf};
+
+const VALID_FORMATS: [&str; 3] = ["plain", "json", "lines"];
+
+/// Which files to start from and how to print what they affect.
+#[derive(Debug, Clone, Default)]
+pub struct ImpactOptions {
+    pub files: Vec<String>,
+    pub git_range: Option<String>,
+    pub include_dependencies: bool,
+    pub max_depth: Option<usize>,
+    pub format: String,
+    pub tests_only: bool,
+}
+
+pub fn execute(path: String, no_parallel: bool, options: ImpactOptions) -> i32 {
+    match execute_impact_command(path, no_parallel, options) {
+        Ok(_) => 0,
+        Err(error) => handle_command_error(&error)
+    }
+}
+
+fn execute_impact_command(path: String, no_parallel: bool, options: ImpactOptions) -> AppResult<()> {
+    if !VALID_FORMATS.contains(&options.format.as_str()) {
+        return Err(AppError::Analysis(format!(
+            "Invalid output format: {}. Valid formats are: {}",
+            options.format,
+            VALID_FORMATS.join(", ")
+        )));
+    }
+
+    if options.files.is_empty() && options.git_range.is_none() {
+        return Err(AppError::Analysis("Specify at least one --file or a --git-range".to_string()));
+    }
+
+    let requested = collect_requested_files(&path, &options)?;
+
+    let parallel_enabled = parse_parallel_flag(no_parallel);
+    if options.format == "plain" {
+        log_parallel_status(parallel_enabled);
+    }
+
+    // Tests are part of the graph so that the tests affected by a change can be listed
+    let graph = DependencyAnalyzer::new()
+        .enable_parallel_processing(parallel_enabled)
+        .include_test_files(true)
+        .analyze_dependencies(&path)
+        .map_err(|error| AppError::Dependency(format!("Error analyzing dependencies: {}", error)))?;
+
+    let report = ImpactQuery::new()
+        .max_depth(options.max_depth)
+        .include_dependencies(options.include_dependencies)
+        .run(&graph, &requested);
+
+    match options.format.as_str() {
+        "json" => print_json(&report, options.tests_only)?,
+        "lines" => print_lines(&report, options.tests_only),
+        _ => print_plain(&report, options.tests_only),
+    }
+
+    Ok(())
+}
+
+/// Files named on the command line (relative to the working directory or the analyzed path)
+/// plus the files changed in the git range.
+fn collect_requested_files(path: &str, options: &ImpactOptions) -> AppResult<Vec<PathBuf>> {
+    let mut requested: Vec<PathBuf> = options
+        .files
+        .iter()
+        .map(|file| {
+            let file = PathBuf::from(file);
+            if file.exists() { file } else { Path::new(path).join(file) }
+        })
+        .collect();
+
+    if let Some(range) = &options.git_range {
+        requested.extend(git::changed_files(path, range)?);
+    }
+
+    Ok(requested)
+}
+
+fn print_json(report: &ImpactReport, tests_only: bool) -> AppResult<()> {
+    let json = if tests_only {
+        serde_json::to_string_pretty(&serde_json::json!({ "affected_tests": report.affected_tests }))
+    } else {
+        serde_json::to_string_pretty(report)
+    }
+    .map_err(|e| AppError::Analysis(format!("Failed to serialize impact report: {}", e)))?;
+
+    println!("{}", json);
+    Ok(())
+}
+
+fn print_lines(report: &ImpactReport, tests_only: bool) {
+    let files = if tests_only { report.affected_tests.clone() } else { report.affected_files() };
+    for file in files {
+        println!("{}", file);
+    }
+}
+
+fn print_plain(report: &ImpactReport, tests_only: bool) {
+    println!();
+    print_header("Change Impact:");
+    println!(
+        "{}",
+        StyledText::new("==============").foreground(ThemeColors::SEPARATOR)
+    );
+
+    if !tests_only {
+        print_file_list("Changed Files", &report.changed);
+        print_impacted_files("Dependents", &report.dependents);
+        if !report.dependencies.is_empty() {
+            print_impacted_files("Dependencies", &report.dependencies);
+        }
+    }
+
+    print_file_list("Affected Tests", &report.affected_tests);
+
+    if !report.not_in_graph.is_empty() {
+        println!();
+        print_warning(&format!("Not in the dependency graph: {}", report.not_in_graph.join(", ")));
+    }
+}
+
+fn print_file_list(title: &str, files: &[String]) {
+    println!();
+    println!("{}", highlight(&format!("{} ({})", title, files.len())));
+
+    if files.is_empty() {
+        println!("  {}", StyledText::new("None").foreground(ThemeColors::LANGUAGE));
+    }
+    for file in files {
+        println!("  {}", StyledText::new(file).foreground(ThemeColors::LABEL));
+    }
+}
+
+fn print_impacted_files(title: &str, files: &[ImpactedFile]) {
+    println!();
+    println!("{}", highlight(&format!("{} ({})", title, files.len())));
+
+    if files.is_empty() {
+        println!("  {}", StyledText::new("None").foreground(ThemeColors::LANGUAGE));
+    }
+    for file in files {
+        println!(
+            "  {} {}",
+            StyledText::new(&format!("[{}]", file.depth)).foreground(ThemeColors::NUMBER),
+            StyledText::new(&file.path).foreground(ThemeColors::LABEL)
+        );
+    }
+}
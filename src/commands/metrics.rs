@@ -1,3 +1,4 @@
+use crate::metrics::baseline::{MetricsDiff, MetricsSnapshot};
 use crate::metrics::collector::MetricsCollector;
 use crate::metrics::models::CodeMetrics;
 use crate::metrics::reporter::MetricsReporter;
@@ -5,13 +6,32 @@ use crate::output::style;
 use crate::util::error::{AppError, AppResult, handle_command_error};
 use crate::util::parallel::{ParallelProcessing, log_parallel_status, parse_parallel_flag};
 
+const VALID_FORMATS: [&str; 3] = ["markdown", "json", "csv"];
+
+pub struct MetricsOptions {
+    pub top_functions: usize,
+    pub format: String,
+    /// Also save a JSON snapshot to this exact path, for use as a later baseline
+    pub snapshot: Option<String>,
+    /// JSON snapshot of an earlier run to compare with
+    pub baseline: Option<String>,
+    pub fail_on_test_ratio_drop: bool,
+    /// Shortest duplicated token sequence reported as a clone
+    pub clone_min_tokens: usize,
+    /// Fewest lines each copy of a clone must span
+    pub clone_min_lines: usize,
+    /// Directory levels shown in the directory breakdown
+    pub directory_depth: usize,
+}
+
 pub fn execute(
     path: String,
     no_output: bool,
     output_path: Option<String>,
     no_parallel: bool,
+    options: MetricsOptions,
 ) -> i32 {
-    match execute_metrics_command(path, no_output, output_path, no_parallel) {
+    match execute_metrics_command(path, no_output, output_path, no_parallel, options) {
         Ok(_) => 0,
         Err(error) => handle_command_error(&error),
     }
@@ -22,31 +42,72 @@ fn execute_metrics_command(
     no_output: bool,
     custom_output_path: Option<String>,
     no_parallel: bool,
+    options: MetricsOptions,
 ) -> AppResult<()> {
+    if !VALID_FORMATS.contains(&options.format.as_str()) {
+        return Err(AppError::Metrics(format!(
+            "Invalid output format: {}. Valid formats are: {}",
+            options.format,
+            VALID_FORMATS.join(", ")
+        )));
+    }
+
     let parallel_enabled = parse_parallel_flag(no_parallel);
 
-    let collector = initialize_metrics_collector(parallel_enabled);
-    let reporter = MetricsReporter::new();
+    let baseline = options.baseline.as_deref().map(MetricsSnapshot::load).transpose().map_err(AppError::Metrics)?;
+
+    let collector = initialize_metrics_collector(parallel_enabled, &options);
+    let mut reporter = MetricsReporter::new().top_functions(options.top_functions).directory_depth(options.directory_depth);
 
     log_parallel_status(parallel_enabled);
 
     let metrics = collect_code_metrics(&collector, &path)?;
+    let diff = baseline.map(|baseline| MetricsDiff::compare(&baseline, &MetricsSnapshot::from_metrics(&metrics)));
+    if let Some(diff) = &diff {
+        reporter = reporter.baseline(diff.clone());
+    }
     display_metrics_results(&reporter, &metrics);
 
     if !no_output {
         if let Some(output_path) = custom_output_path {
-            export_metrics(&reporter, &metrics, output_path)?;
+            export_metrics(&reporter, &metrics, output_path, &options.format)?;
         } else {
             let default_output = path.clone();
-            export_metrics(&reporter, &metrics, default_output)?;
+            export_metrics(&reporter, &metrics, default_output, &options.format)?;
         }
     }
 
+    if let Some(snapshot_path) = &options.snapshot {
+        save_snapshot(&reporter, &metrics, snapshot_path)?;
+    }
+
+    if options.fail_on_test_ratio_drop && let Some(diff) = diff.filter(MetricsDiff::test_ratio_dropped) {
+        return Err(AppError::Metrics(format!(
+            "Test to production ratio dropped from {:.1}% to {:.1}% since the baseline",
+            diff.total.baseline.test_ratio().unwrap_or_default() * 100.0,
+            diff.total.current.test_ratio().unwrap_or_default() * 100.0
+        )));
+    }
+
     Ok(())
 }
 
-fn initialize_metrics_collector(parallel_enabled: bool) -> MetricsCollector {
-    MetricsCollector::new().enable_parallel_processing(parallel_enabled)
+fn save_snapshot(reporter: &MetricsReporter, metrics: &CodeMetrics, snapshot_path: &str) -> AppResult<()> {
+    reporter
+        .export_metrics_json(metrics, snapshot_path)
+        .map_err(|error| AppError::FileSystem {
+            path: snapshot_path.into(),
+            message: format!("Error saving metrics snapshot: {}", error),
+        })?;
+
+    style::print_success(&format!("Metrics snapshot saved to {}", snapshot_path));
+    Ok(())
+}
+
+fn initialize_metrics_collector(parallel_enabled: bool, options: &MetricsOptions) -> MetricsCollector {
+    MetricsCollector::new()
+        .enable_parallel_processing(parallel_enabled)
+        .clone_thresholds(options.clone_min_tokens, options.clone_min_lines)
 }
 
 fn collect_code_metrics(
@@ -67,11 +128,21 @@ fn export_metrics(
     reporter: &MetricsReporter,
     metrics: &CodeMetrics,
     output_path: String,
+    format: &str,
 ) -> AppResult<()> {
-    let path = crate::output::path::resolve_output_path("metrics", &output_path, "md")?;
+    let extension = match format {
+        "json" => "json",
+        "csv" => "csv",
+        _ => "md",
+    };
+    let path = crate::output::path::resolve_output_path("metrics", &output_path, extension)?;
 
-    reporter
-        .export_metrics(metrics, &path)
+    let result = match format {
+        "json" => reporter.export_metrics_json(metrics, &path),
+        "csv" => reporter.export_metrics_csv(metrics, &path),
+        _ => reporter.export_metrics(metrics, &path),
+    };
+    result
         .map_err(|error| AppError::FileSystem {
             path: path.clone(),
             message: format!("Error exporting metrics: {}", error),
@@ -1,6 +1,13 @@
 mod run;
 mod metrics;
+mod trend;
+mod hotspots;
+mod ownership;
 mod dependencies;
+mod dead_files;
+pub mod dependency_diff;
+mod impact;
+mod packages;
 mod style;
 mod describe;
 pub mod delete_comments;
@@ -46,6 +53,144 @@ pub enum Commands {
         /// Disable parallel processing for large codebases
         #[arg(long)]
         no_parallel: bool,
+        
+        /// Number of functions listed in the most complex functions table
+        #[arg(long, default_value = "10")]
+        top_functions: usize,
+        
+        /// Output format for the saved report (markdown, json, csv)
+        #[arg(long, default_value = "markdown")]
+        format: String,
+        
+        /// Also save a JSON snapshot of the metrics to this file, to compare later runs with
+        #[arg(long, value_name = "FILE")]
+        snapshot: Option<String>,
+        
+        /// JSON snapshot of an earlier run to report changes against
+        #[arg(long, value_name = "FILE")]
+        baseline: Option<String>,
+        
+        /// Fail when the test to production ratio is lower than in the baseline
+        #[arg(long, requires = "baseline")]
+        fail_on_test_ratio_drop: bool,
+        
+        /// Shortest run of tokens, with identifiers and literals abstracted, reported as duplicated code
+        #[arg(long, default_value = "50")]
+        clone_min_tokens: usize,
+        
+        /// Fewest lines each copy of duplicated code must span
+        #[arg(long, default_value = "5")]
+        clone_min_lines: usize,
+        
+        /// Directory levels below the analyzed directory shown in the directory breakdown
+        #[arg(long, default_value = "2")]
+        directory_depth: usize,
+    },
+    /// Track code metrics over the git history, reading each sampled commit without touching the working copy
+    Trend {
+        /// Path to analyze (defaults to current directory)
+        #[arg(default_value = ".")]
+        path: String,
+        
+        /// Disable auto-saving of the output file
+        #[arg(long)]
+        no_output: bool,
+        
+        /// Custom output path (optional, uses default structured output if not specified)
+        #[arg(short, long)]
+        output_path: Option<String>,
+        
+        /// Disable parallel processing for large codebases
+        #[arg(long)]
+        no_parallel: bool,
+        
+        /// Number of commits sampled evenly over the history
+        #[arg(long, default_value = "10", conflicts_with = "since")]
+        samples: usize,
+        
+        /// Sample weekly from this date instead (YYYY-MM-DD)
+        #[arg(long, value_name = "DATE")]
+        since: Option<String>,
+        
+        /// End of the weekly samples (YYYY-MM-DD, defaults to now)
+        #[arg(long, value_name = "DATE", requires = "since")]
+        until: Option<String>,
+        
+        /// Revision whose first-parent history is sampled
+        #[arg(long, default_value = "HEAD")]
+        revision: String,
+        
+        /// Output format for the saved report (markdown, json, csv, svg); markdown also saves the SVG chart
+        #[arg(long, default_value = "markdown")]
+        format: String,
+    },
+    /// Rank files by change frequency times complexity and find files that change together
+    Hotspots {
+        /// Path to analyze (defaults to current directory)
+        #[arg(default_value = ".")]
+        path: String,
+        
+        /// Disable auto-saving of the output file
+        #[arg(long)]
+        no_output: bool,
+        
+        /// Custom output path (optional, uses default structured output if not specified)
+        #[arg(short, long)]
+        output_path: Option<String>,
+        
+        /// Disable parallel processing for large codebases
+        #[arg(long)]
+        no_parallel: bool,
+        
+        /// Start of the time window, as a date or in any form git log takes (e.g. "6 months ago")
+        #[arg(long, default_value = "1 year ago")]
+        since: String,
+        
+        /// End of the time window (defaults to now)
+        #[arg(long)]
+        until: Option<String>,
+        
+        /// Number of hotspots and coupled file pairs in the saved report
+        #[arg(long, default_value = "20")]
+        top: usize,
+        
+        /// Commits two files must share to be reported as coupled
+        #[arg(long, default_value = "3")]
+        min_shared_commits: usize,
+        
+        /// Output format for the saved report (markdown, json, svg); markdown also saves the treemap
+        #[arg(long, default_value = "markdown")]
+        format: String,
+    },
+    /// Report who wrote the surviving code of each module, its bus factor and how it compares with CODEOWNERS
+    Ownership {
+        /// Path to analyze (defaults to current directory)
+        #[arg(default_value = ".")]
+        path: String,
+        
+        /// Disable auto-saving of the output file
+        #[arg(long)]
+        no_output: bool,
+        
+        /// Custom output path (optional, uses default structured output if not specified)
+        #[arg(short, long)]
+        output_path: Option<String>,
+        
+        /// Disable parallel processing for large codebases
+        #[arg(long)]
+        no_parallel: bool,
+        
+        /// Directory levels from the repository root that make up a module (2 groups by e.g. src/metrics)
+        #[arg(long, default_value = "2")]
+        depth: usize,
+        
+        /// Flag modules whose CODEOWNERS owners wrote less than this percentage of the lines
+        #[arg(long, default_value = "20")]
+        min_owner_share: f64,
+        
+        /// Output format for the saved report (markdown, json)
+        #[arg(long, default_value = "markdown")]
+        format: String,
     },
     /// Analyze dependencies and generate a dependency graph
     Dependencies {
@@ -64,6 +209,147 @@ pub enum Commands {
         /// Disable parallel processing for large codebases
         #[arg(long)]
         no_parallel: bool,
+        
+        /// Analyze dependencies between Cargo crates instead of source files
+        #[arg(long)]
+        crate_level: bool,
+        
+        /// Include dev-dependencies in the crate-level graph
+        #[arg(long)]
+        include_dev_deps: bool,
+        
+        /// Treat every optional dependency as enabled in the crate-level graph
+        #[arg(long)]
+        all_features: bool,
+        
+        /// Additional directory to search for C/C++ #include targets (repeatable)
+        #[arg(long = "include-dir")]
+        include_dirs: Vec<String>,
+        
+        /// Sort the coupling tables by name, ca, ce, instability, abstractness or distance (default: instability)
+        #[arg(long)]
+        sort_by: Option<String>,
+        
+        /// Layering rules file to check the graph against (defaults to dependency-rules.toml in the analyzed directory); violations exit non-zero
+        #[arg(long)]
+        rules: Option<String>,
+        
+        /// Graph export format (dot, json, graphml, gexf, csv)
+        #[arg(long)]
+        format: Option<String>,
+    },
+    /// Find files that no entry point reaches through the dependency graph
+    #[command(name = "dead-files")]
+    DeadFiles {
+        /// Path to analyze (defaults to current directory)
+        #[arg(default_value = ".")]
+        path: String,
+        
+        /// Entry point glob or path prefix, relative to the analyzed path (repeatable); replaces the detected entry points
+        #[arg(long = "entry")]
+        entry_points: Vec<String>,
+        
+        /// Disable auto-saving of the output file
+        #[arg(long)]
+        no_output: bool,
+        
+        /// Custom output path (optional, uses default structured output if not specified)
+        #[arg(short, long)]
+        output_path: Option<String>,
+        
+        /// Disable parallel processing for large codebases
+        #[arg(long)]
+        no_parallel: bool,
+    },
+    /// Compare the dependency graphs of two git revisions or two directories
+    DependencyDiff {
+        /// Path to analyze (defaults to current directory)
+        #[arg(default_value = ".")]
+        path: String,
+        
+        /// Base revision, read with `git archive` without touching the working copy (e.g. main)
+        #[arg(long, required_unless_present = "base_dir", conflicts_with = "base_dir")]
+        base: Option<String>,
+        
+        /// Base directory, instead of a revision
+        #[arg(long)]
+        base_dir: Option<String>,
+        
+        /// Head revision (defaults to the working tree at the analyzed path)
+        #[arg(long, conflicts_with = "head_dir")]
+        head: Option<String>,
+        
+        /// Head directory, instead of a revision
+        #[arg(long)]
+        head_dir: Option<String>,
+        
+        /// Output format (plain, markdown); markdown prints the report ready for a pull request comment
+        #[arg(long, default_value = "plain")]
+        format: String,
+        
+        /// Disable auto-saving of the output file
+        #[arg(long)]
+        no_output: bool,
+        
+        /// Custom output path (optional, uses default structured output if not specified)
+        #[arg(short, long)]
+        output_path: Option<String>,
+        
+        /// Disable parallel processing for large codebases
+        #[arg(long)]
+        no_parallel: bool,
+    },
+    /// List third-party packages and their licenses from lockfiles
+    Packages {
+        /// Path to analyze (defaults to current directory)
+        #[arg(default_value = ".")]
+        path: String,
+        
+        /// Output format for the saved report (markdown, json)
+        #[arg(long, default_value = "markdown")]
+        format: String,
+        
+        /// Disable auto-saving of the output file
+        #[arg(long)]
+        no_output: bool,
+        
+        /// Custom output path (optional, uses default structured output if not specified)
+        #[arg(short, long)]
+        output_path: Option<String>,
+    },
+    /// List the files and tests affected by changes to the given files or a git diff range
+    Impact {
+        /// Path to analyze (defaults to current directory)
+        #[arg(default_value = ".")]
+        path: String,
+        
+        /// Changed file, relative to the working directory or the analyzed path (repeatable)
+        #[arg(short, long = "file")]
+        files: Vec<String>,
+        
+        /// Take the changed files from `git diff` over this range (e.g. main...HEAD)
+        #[arg(long)]
+        git_range: Option<String>,
+        
+        /// Also list the transitive dependencies of the changed files
+        #[arg(long)]
+        dependencies: bool,
+        
+        /// Only follow this many dependency edges from the changed files
+        #[arg(long)]
+        max_depth: Option<usize>,
+        
+        /// Output format (plain, json, lines)
+        #[arg(long, default_value = "plain")]
+        format: String,
+        
+        /// Only report the affected test files
+        #[arg(long)]
+        tests_only: bool,
+        
+        /// Disable parallel processing for large codebases
+        #[arg(long)]
+        no_parallel: bool,
     },
     /// Analyze code style patterns and generate a style guide
     Style {
@@ -201,16 +487,52 @@ pub enum Commands {
         /// Focus on specific module or directory (relative to analysis path)
         #[arg(long)]
         focus: Option<String>,
+        
+        /// Hide external (third-party) packages from the architecture diagram
+        #[arg(long)]
+        hide_external: bool,
+        
+        /// Draw crates from Cargo.toml/Cargo.lock instead of source files (--include-tests adds dev-dependencies)
+        #[arg(long)]
+        crate_level: bool,
     },
 }
 
 pub async fn execute(cli: Cli) -> i32 {
     match cli.command {
         Commands::Run { path, no_parallel } => run::execute(path, no_parallel),
-        Commands::Metrics { path, no_output, output_path, no_parallel } => 
-            metrics::execute(path, no_output, output_path, no_parallel),
-        Commands::Dependencies { path, no_output, output_path, no_parallel } => 
-            dependencies::execute(path, no_output, output_path, no_parallel),
+        Commands::Metrics { path, no_output, output_path, no_parallel, top_functions, format, snapshot, baseline, fail_on_test_ratio_drop, clone_min_tokens, clone_min_lines, directory_depth } => {
+            let options = metrics::MetricsOptions { top_functions, format, snapshot, baseline, fail_on_test_ratio_drop, clone_min_tokens, clone_min_lines, directory_depth };
+            metrics::execute(path, no_output, output_path, no_parallel, options)
+        },
+        Commands::Trend { path, no_output, output_path, no_parallel, samples, since, until, revision, format } => {
+            let options = trend::TrendOptions { samples, since, until, revision, format };
+            trend::execute(path, no_output, output_path, no_parallel, options)
+        },
+        Commands::Hotspots { path, no_output, output_path, no_parallel, since, until, top, min_shared_commits, format } => {
+            let options = hotspots::HotspotOptions { since, until, top, min_shared_commits, format };
+            hotspots::execute(path, no_output, output_path, no_parallel, options)
+        },
+        Commands::Ownership { path, no_output, output_path, no_parallel, depth, min_owner_share, format } => {
+            let options = ownership::OwnershipOptions { depth, min_owner_share, format };
+            ownership::execute(path, no_output, output_path, no_parallel, options)
+        },
+        Commands::Dependencies { path, no_output, output_path, no_parallel, crate_level, include_dev_deps, all_features, include_dirs, sort_by, rules, format } => {
+            let options = dependencies::DependencyOptions { crate_level, include_dev_deps, all_features, include_dirs, sort_by, rules, format };
+            dependencies::execute(path, no_output, output_path, no_parallel, options)
+        },
+        Commands::DeadFiles { path, entry_points, no_output, output_path, no_parallel } => 
+            dead_files::execute(path, entry_points, no_output, output_path, no_parallel),
+        Commands::DependencyDiff { path, base, base_dir, head, head_dir, format, no_output, output_path, no_parallel } => {
+            let options = dependency_diff::DependencyDiffOptions { base, base_dir, head, head_dir, format };
+            dependency_diff::execute(path, no_output, output_path, no_parallel, options)
+        },
+        Commands::Packages { path, format, no_output, output_path } => 
+            packages::execute(path, format, no_output, output_path),
+        Commands::Impact { path, files, git_range, dependencies, max_depth, format, tests_only, no_parallel } => {
+            let options = impact::ImpactOptions { files, git_range, include_dependencies: dependencies, max_depth, format, tests_only };
+            impact::execute(path, no_parallel, options)
+        },
         Commands::Style { path, no_output, output_path, no_parallel } => 
             style::execute(path, no_output, output_path, no_parallel),
         Commands::Describe { path, no_output, output_path, no_parallel } => 
@@ -219,7 +541,7 @@ pub async fn execute(cli: Cli) -> i32 {
             delete_comments::execute(path, language, no_output, output_path, no_parallel, no_git, force, dry_run),
         Commands::CleanCodeAnalyze { path, output_path, no_parallel, ai_level, actionable_only, analyze_level } => 
             clean_code_analyze::execute(path, output_path, no_parallel, ai_level, actionable_only, analyze_level).await,
-        Commands::ArchitectureDiagram { path, no_output, output_path, no_parallel, format, detail, include_tests, group_by_module, focus } => 
-            architecture_diagram::execute(path, no_output, output_path, no_parallel, format, detail, include_tests, group_by_module, focus).await,
+        Commands::ArchitectureDiagram { path, no_output, output_path, no_parallel, format, detail, include_tests, group_by_module, focus, hide_external, crate_level } => 
+            architecture_diagram::execute(path, no_output, output_path, no_parallel, format, detail, include_tests, group_by_module, focus, hide_external, crate_level).await,
     }
 }
\ No newline at end of file
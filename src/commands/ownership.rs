@@ -0,0 +1,130 @@
+use crate::metrics::ownership::{CodeOwners, OwnershipReport, blame_files, top_authors};
+use crate::output::report;
+use crate::output::style::*;
+use crate::util::error::{AppError, AppResult, handle_command_error};
+use crate::util::file_filter::get_all_source_files;
+use crate::util::git;
+use crate::util::parallel::{log_parallel_status, parse_parallel_flag};
+use std::fs;
+use std::path::PathBuf;
+
+const VALID_FORMATS: [&str; 2] = ["markdown", "json"];
+
+pub struct OwnershipOptions {
+    /// Directory levels that make up a module, e.g. 2 for `src/metrics`
+    pub depth: usize,
+    /// Percentage of a module's lines its declared owners should have written
+    pub min_owner_share: f64,
+    pub format: String,
+}
+
+pub fn execute(
+    path: String,
+    no_output: bool,
+    output_path: Option<String>,
+    no_parallel: bool,
+    options: OwnershipOptions,
+) -> i32 {
+    match execute_ownership_command(path, no_output, output_path, no_parallel, options) {
+        Ok(_) => 0,
+        Err(error) => handle_command_error(&error),
+    }
+}
+
+fn execute_ownership_command(
+    path: String,
+    no_output: bool,
+    custom_output_path: Option<String>,
+    no_parallel: bool,
+    options: OwnershipOptions,
+) -> AppResult<()> {
+    report::validate_format(&options.format, &VALID_FORMATS)?;
+
+    let repository_root = git::repository_root(&path)?;
+    let repository_root = fs::canonicalize(&repository_root).unwrap_or(repository_root);
+    let parallel_enabled = parse_parallel_flag(no_parallel);
+    log_parallel_status(parallel_enabled);
+
+    let files = get_all_source_files(&path, parallel_enabled).map_err(|error| AppError::FileSystem {
+        path: PathBuf::from(&path),
+        message: format!("Error scanning directory: {}", error),
+    })?;
+    print_info(&format!("Running git blame on {} files", files.len()));
+
+    let blames = blame_files(&repository_root, &files, parallel_enabled);
+    if blames.len() < files.len() {
+        print_warning(&format!("Skipped {} files that are not committed", files.len() - blames.len()));
+    }
+
+    let codeowners = CodeOwners::load(&repository_root).map(|(codeowners_path, codeowners)| {
+        let relative = codeowners_path.strip_prefix(&repository_root).unwrap_or(&codeowners_path);
+        (relative.to_string_lossy().replace('\\', "/"), codeowners)
+    });
+    let report = OwnershipReport::analyze(
+        &blames,
+        codeowners.as_ref().map(|(codeowners_path, codeowners)| (codeowners_path.as_str(), codeowners)),
+        options.depth,
+        options.min_owner_share / 100.0,
+    );
+
+    print_report(&report);
+    print_success("Ownership analysis completed successfully");
+
+    if !no_output {
+        let output_path = custom_output_path.unwrap_or_else(|| path.clone());
+        export_report(&report, &output_path, &options.format)?;
+    }
+
+    Ok(())
+}
+
+fn print_report(report: &OwnershipReport) {
+    println!();
+    print_header("Code Ownership:");
+    println!("{}", StyledText::new("===============").foreground(ThemeColors::SEPARATOR));
+    match &report.codeowners {
+        Some(codeowners) => println!("Declared owners from {}", highlight(codeowners)),
+        None => println!("No CODEOWNERS file found"),
+    }
+
+    let module_width = report.modules.iter().map(|module| module.module.chars().count()).max().unwrap_or(0).max("Module".len()) + 2;
+    let header = format!("{:<module_width$}{:>7}{:>9}{:>12}  {}", "Module", "Files", "Lines", "Bus Factor", "Top Authors");
+    println!("{}", StyledText::new(&header).foreground(ThemeColors::TABLE_HEADER));
+
+    for module in &report.modules {
+        print!("{}", StyledText::new(&format!("{:<module_width$}", module.module)).foreground(ThemeColors::LANGUAGE));
+        let values = format!("{:>7}{:>9}", module.files, module.lines);
+        print!("{}", StyledText::new(&values).foreground(ThemeColors::NUMBER));
+        let bus_factor = StyledText::new(&format!("{:>12}", module.bus_factor));
+        let bus_factor = if module.bus_factor == 1 { bus_factor.foreground(Color::Yellow) } else { bus_factor.foreground(ThemeColors::NUMBER) };
+        println!("{}  {}", bus_factor, top_authors(module));
+    }
+
+    let flagged = report.flagged();
+    if !flagged.is_empty() {
+        println!();
+        print_warning(&format!(
+            "Modules whose declared owners wrote less than {:.0}% of the code:",
+            report.min_owner_share * 100.0
+        ));
+        for module in flagged {
+            println!(
+                "  {} {} wrote {:.0}%",
+                highlight(&module.module),
+                module.declared_owners.join(" "),
+                module.owner_share.unwrap_or_default() * 100.0
+            );
+        }
+    }
+    println!();
+}
+
+fn export_report(ownership: &OwnershipReport, output_path: &str, format: &str) -> AppResult<()> {
+    let path = report::report_path("ownership", output_path, format)?;
+
+    let content = match format {
+        "json" => ownership.to_json().map_err(AppError::Metrics)?,
+        _ => ownership.to_markdown(),
+    };
+    report::save_report(&path, &content, "Ownership")
+}
@@ -0,0 +1,196 @@
+use crate::dependency::package_inventory::{InventoryScanner, Package, PackageInventory};
+use crate::output::style::*;
+use crate::util::error::{AppError, AppResult, handle_command_error};
+use std::fs;
+
+const VALID_FORMATS: [&str; 2] = ["markdown", "json"];
+
+pub fn execute(path: String, format: String, no_output: bool, output_path: Option<String>) -> i32 {
+    match execute_packages_command(path, format, no_output, output_path) {
+        Ok(_) => 0,
+        Err(error) => handle_command_error(&error)
+    }
+}
+
+fn execute_packages_command(
+    path: String,
+    format: String,
+    no_output: bool,
+    custom_output_path: Option<String>,
+) -> AppResult<()> {
+    if !VALID_FORMATS.contains(&format.as_str()) {
+        return Err(AppError::Analysis(format!(
+            "Invalid output format: {}. Valid formats are: {}",
+            format,
+            VALID_FORMATS.join(", ")
+        )));
+    }
+
+    let inventory = InventoryScanner::new().scan(&path)?;
+
+    print_inventory(&inventory);
+
+    if !no_output {
+        let output_path = custom_output_path.unwrap_or_else(|| path.clone());
+        export_inventory(&inventory, &format, &output_path)?;
+    }
+
+    Ok(())
+}
+
+fn print_inventory(inventory: &PackageInventory) {
+    println!();
+    print_header("Third-Party Packages:");
+    println!(
+        "{}",
+        StyledText::new("=====================").foreground(ThemeColors::SEPARATOR)
+    );
+
+    if inventory.lockfiles.is_empty() {
+        println!("No lockfiles found.");
+        return;
+    }
+
+    for (label, value) in summary_rows(inventory) {
+        println!(
+            "{} {}",
+            StyledText::new(&format!("{:<22}", label)).foreground(ThemeColors::LABEL),
+            StyledText::new(&value).foreground(ThemeColors::NUMBER)
+        );
+    }
+
+    println!();
+    println!("{}", highlight("Lockfiles:"));
+    for lockfile in &inventory.lockfiles {
+        println!("  {}", StyledText::new(lockfile).foreground(ThemeColors::LABEL));
+    }
+
+    println!();
+    println!("{}", highlight("Licenses:"));
+    for (license, count) in inventory.license_counts() {
+        println!(
+            "  {} {}",
+            StyledText::new(&format!("{:<40}", license)).foreground(ThemeColors::LANGUAGE),
+            StyledText::new(&count.to_string()).foreground(ThemeColors::NUMBER)
+        );
+    }
+
+    if !inventory.duplicates.is_empty() {
+        println!();
+        println!("{}", highlight("Duplicated packages:"));
+        for duplicate in &inventory.duplicates {
+            println!(
+                "  {} {}",
+                StyledText::new(&format!("{:<40}", format!("{} ({})", duplicate.name, duplicate.ecosystem.as_str())))
+                    .foreground(ThemeColors::LABEL),
+                StyledText::new(&duplicate.versions.join(", ")).foreground(Color::Yellow)
+            );
+        }
+    }
+}
+
+fn summary_rows(inventory: &PackageInventory) -> Vec<(&'static str, String)> {
+    vec![
+        ("Lockfiles:", inventory.lockfiles.len().to_string()),
+        ("Packages:", inventory.packages.len().to_string()),
+        ("Direct:", inventory.direct_count().to_string()),
+        ("Transitive:", inventory.transitive_count().to_string()),
+        ("Duplicated packages:", inventory.duplicates.len().to_string()),
+        (
+            "Unknown licenses:",
+            inventory.packages.iter().filter(|package| package.license.is_none()).count().to_string(),
+        ),
+    ]
+}
+
+fn format_markdown(inventory: &PackageInventory) -> String {
+    let mut output = String::new();
+
+    output.push_str("# Third-Party Packages\n\n");
+
+    output.push_str("## Summary\n\n");
+    output.push_str("| Metric | Value |\n");
+    output.push_str("|--------|-------|\n");
+    for (label, value) in summary_rows(inventory) {
+        output.push_str(&format!("| {} | {} |\n", label.trim_end_matches(':'), value));
+    }
+
+    output.push_str("\n## Lockfiles\n\n");
+    if inventory.lockfiles.is_empty() {
+        output.push_str("No lockfiles found.\n");
+        return output;
+    }
+    for lockfile in &inventory.lockfiles {
+        output.push_str(&format!("- `{}`\n", lockfile));
+    }
+
+    output.push_str("\n## Licenses\n\n");
+    output.push_str("| License | Packages |\n");
+    output.push_str("|---------|----------|\n");
+    for (license, count) in inventory.license_counts() {
+        output.push_str(&format!("| {} | {} |\n", license, count));
+    }
+
+    output.push_str("\n## Duplicated Packages\n\n");
+    if inventory.duplicates.is_empty() {
+        output.push_str("Every package is locked at a single version.\n");
+    } else {
+        output.push_str("| Package | Ecosystem | Versions |\n");
+        output.push_str("|---------|-----------|----------|\n");
+        for duplicate in &inventory.duplicates {
+            output.push_str(&format!(
+                "| `{}` | {} | {} |\n",
+                duplicate.name,
+                duplicate.ecosystem.as_str(),
+                duplicate.versions.join(", ")
+            ));
+        }
+    }
+
+    let (direct, transitive): (Vec<&Package>, Vec<&Package>) = inventory.packages.iter().partition(|package| package.direct);
+    push_package_table(&mut output, "Direct Packages", &direct);
+    push_package_table(&mut output, "Transitive Packages", &transitive);
+
+    output
+}
+
+fn push_package_table(output: &mut String, title: &str, packages: &[&Package]) {
+    output.push_str(&format!("\n## {} ({})\n\n", title, packages.len()));
+    if packages.is_empty() {
+        output.push_str("None.\n");
+        return;
+    }
+
+    output.push_str("| Package | Version | Ecosystem | License | Lockfiles |\n");
+    output.push_str("|---------|---------|-----------|---------|-----------|\n");
+    for package in packages {
+        output.push_str(&format!(
+            "| `{}` | {} | {} | {} | {} |\n",
+            package.name,
+            package.version,
+            package.ecosystem.as_str(),
+            package.license.as_deref().unwrap_or("Unknown"),
+            package.lockfiles.join(", ")
+        ));
+    }
+}
+
+fn export_inventory(inventory: &PackageInventory, format: &str, output_path: &str) -> AppResult<()> {
+    let (content, extension) = if format == "json" {
+        let json = serde_json::to_string_pretty(inventory)
+            .map_err(|e| AppError::Dependency(format!("Failed to serialize package inventory: {}", e)))?;
+        (json, "json")
+    } else {
+        (format_markdown(inventory), "md")
+    };
+
+    let path = crate::output::path::resolve_output_path("packages", output_path, extension)?;
+
+    fs::write(&path, content).map_err(|e| AppError::FileSystem {
+        path: path.clone(),
+        message: format!("Error exporting package inventory: {}", e),
+    })?;
+
+    print_success(&format!("Package inventory exported to {}", path.display()));
+    Ok(())
+}
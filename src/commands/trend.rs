@@ -0,0 +1,145 @@
+use crate::metrics::collector::MetricsCollector;
+use crate::metrics::trend::{MetricsTrend, TrendPoint, format_percent, sample_evenly, sample_weekly};
+use crate::output::report;
+use crate::output::style::*;
+use crate::util::error::{AppError, AppResult, handle_command_error};
+use crate::util::git::{self, Commit};
+use crate::util::parallel::{ParallelProcessing, log_parallel_status, parse_parallel_flag};
+use chrono::{NaiveDate, Utc};
+
+const VALID_FORMATS: [&str; 4] = ["markdown", "json", "csv", "svg"];
+
+/// Which commits to sample: `samples` spread evenly over the history of `revision`, or one
+/// per week between `since` and `until` when `since` is given.
+pub struct TrendOptions {
+    pub samples: usize,
+    pub since: Option<String>,
+    pub until: Option<String>,
+    pub revision: String,
+    pub format: String,
+}
+
+pub fn execute(
+    path: String,
+    no_output: bool,
+    output_path: Option<String>,
+    no_parallel: bool,
+    options: TrendOptions,
+) -> i32 {
+    match execute_trend_command(path, no_output, output_path, no_parallel, options) {
+        Ok(_) => 0,
+        Err(error) => handle_command_error(&error),
+    }
+}
+
+fn execute_trend_command(
+    path: String,
+    no_output: bool,
+    custom_output_path: Option<String>,
+    no_parallel: bool,
+    options: TrendOptions,
+) -> AppResult<()> {
+    report::validate_format(&options.format, &VALID_FORMATS)?;
+
+    let commits = select_commits(&path, &options)?;
+    if commits.is_empty() {
+        return Err(AppError::Metrics(format!("No commits to sample in {}", options.revision)));
+    }
+
+    let parallel_enabled = parse_parallel_flag(no_parallel);
+    log_parallel_status(parallel_enabled);
+
+    let mut points = Vec::with_capacity(commits.len());
+    for (index, commit) in commits.iter().enumerate() {
+        print_info(&format!("[{}/{}] Analyzing {} ({})", index + 1, commits.len(), commit.short_hash(), commit.date));
+        points.push(analyze_commit(&path, commit, parallel_enabled)?);
+    }
+
+    let trend = MetricsTrend::new(points);
+    print_trend(&trend);
+    print_success("Metrics trend completed successfully");
+
+    if !no_output {
+        let output_path = custom_output_path.unwrap_or_else(|| path.clone());
+        export_trend(&trend, &output_path, &options.format)?;
+    }
+
+    Ok(())
+}
+
+fn select_commits(path: &str, options: &TrendOptions) -> AppResult<Vec<Commit>> {
+    let history = git::first_parent_history(path, &options.revision)?;
+
+    let Some(since) = &options.since else {
+        return Ok(sample_evenly(&history, options.samples));
+    };
+
+    let since = parse_date(since)?.and_hms_opt(0, 0, 0).unwrap_or_default().and_utc().timestamp();
+    let until = match &options.until {
+        Some(until) => parse_date(until)?.and_hms_opt(23, 59, 59).unwrap_or_default().and_utc().timestamp(),
+        None => Utc::now().timestamp(),
+    };
+    if since > until {
+        return Err(AppError::Metrics("--since must not be later than --until".to_string()));
+    }
+
+    Ok(sample_weekly(&history, since, until))
+}
+
+fn parse_date(date: &str) -> AppResult<NaiveDate> {
+    NaiveDate::parse_from_str(date, "%Y-%m-%d")
+        .map_err(|_| AppError::Metrics(format!("Invalid date: {}. Expected YYYY-MM-DD", date)))
+}
+
+fn analyze_commit(path: &str, commit: &Commit, parallel_enabled: bool) -> AppResult<TrendPoint> {
+    let checkout = git::export_revision(path, &commit.hash)?;
+    let collector = MetricsCollector::new().enable_parallel_processing(parallel_enabled);
+    let metrics = collector
+        .collect_metrics(checkout.path())
+        .map_err(|error| AppError::Metrics(format!("Error analyzing {}: {}", commit.short_hash(), error)))?;
+
+    Ok(TrendPoint::from_metrics(commit, &metrics))
+}
+
+fn print_trend(trend: &MetricsTrend) {
+    println!();
+    print_header("Metrics Trend:");
+    println!("{}", StyledText::new("=============").foreground(ThemeColors::SEPARATOR));
+
+    let header = format!(
+        "{:<12}{:<10}{:>8}{:>16}{:>18}{:>12}",
+        "Date", "Commit", "Files", "Lines of Code", "Comment Density", "Test Ratio"
+    );
+    println!("{}", StyledText::new(&header).foreground(ThemeColors::TABLE_HEADER));
+
+    for point in &trend.points {
+        print!("{}", StyledText::new(&format!("{:<12}", point.date)).foreground(ThemeColors::LABEL));
+        print!("{}", StyledText::new(&format!("{:<10}", point.commit)).foreground(ThemeColors::LANGUAGE));
+        let values = format!(
+            "{:>8}{:>16}{:>18}{:>12}",
+            point.stats.files,
+            point.stats.lines_of_code,
+            format_percent(point.stats.comment_ratio()),
+            format_percent(point.stats.test_ratio())
+        );
+        println!("{}", StyledText::new(&values).foreground(ThemeColors::NUMBER));
+    }
+    println!();
+}
+
+/// Saves the trend in the chosen format. The markdown report gets the SVG chart saved next to
+/// it and linked from it.
+fn export_trend(trend: &MetricsTrend, output_path: &str, format: &str) -> AppResult<()> {
+    let path = report::report_path("trend", output_path, format)?;
+
+    let content = match format {
+        "json" => trend.to_json().map_err(AppError::Metrics)?,
+        "csv" => trend.to_csv(),
+        "svg" => trend.to_svg(),
+        _ => {
+            let chart_name = report::save_chart(&path, &trend.to_svg())?;
+            trend.to_markdown(chart_name.as_deref())
+        }
+    };
+    report::save_report(&path, &content, "Metrics trend")
+}
@@ -0,0 +1,148 @@
+use crate::dependency::dependency_graph::{EdgeImport, ImportKind};
+use crate::dependency::resolver::{ResolvedDependency, SourceIndex, normalize_path};
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+
+/// Top-level directories of system and C library headers, which are never reported as packages.
+const SYSTEM_HEADER_DIRS: [&str; 8] = ["sys", "bits", "linux", "asm", "arpa", "netinet", "net", "mach"];
+
+/// Returns the headers named by `#include` directives. Quoted includes are returned as written,
+/// angle-bracket includes keep their brackets (`<vector>`) so the resolver can tell them apart.
+pub fn extract_c_includes(content: &str) -> Vec<EdgeImport> {
+    let mut includes = Vec::new();
+    let mut in_block_comment = false;
+
+    for (index, line) in content.lines().enumerate() {
+        let mut line = line.trim();
+
+        if in_block_comment {
+            match line.find("*/") {
+                Some(end) => {
+                    in_block_comment = false;
+                    line = line[end + 2..].trim();
+                }
+                None => continue,
+            }
+        }
+
+        if let Some(start) = line.find("/*")
+            && !line[start..].contains("*/")
+        {
+            in_block_comment = true;
+        }
+
+        let Some(directive) = line.strip_prefix('#') else {
+            continue;
+        };
+        let Some(rest) = directive.trim_start().strip_prefix("include") else {
+            continue;
+        };
+        let rest = rest.trim_start();
+
+        if let Some(quoted) = rest.strip_prefix('"')
+            && let Some(end) = quoted.find('"')
+        {
+            includes.push(EdgeImport::new(&quoted[..end], index + 1, ImportKind::Include));
+        } else if let Some(angled) = rest.strip_prefix('<')
+            && let Some(end) = angled.find('>')
+        {
+            includes.push(EdgeImport::new(format!("<{}>", &angled[..end]), index + 1, ImportKind::Include));
+        }
+    }
+
+    includes
+}
+
+/// Resolves `#include` directives against the including file's directory, the configured
+/// include directories and every `include/` directory found in the tree.
+pub struct CIncludeResolver {
+    include_dirs: Vec<PathBuf>,
+    /// Files keyed by file name, used when no include directory matches a quoted include
+    files_by_name: HashMap<String, Vec<String>>,
+}
+
+impl CIncludeResolver {
+    pub fn build(index: &SourceIndex, configured_dirs: &[PathBuf]) -> Self {
+        let root = normalize_path(index.root());
+        let mut include_dirs: Vec<PathBuf> = configured_dirs
+            .iter()
+            .map(|dir| normalize_path(&if dir.is_absolute() { dir.clone() } else { root.join(dir) }))
+            .collect();
+
+        let mut discovered = HashSet::new();
+        let mut files_by_name: HashMap<String, Vec<String>> = HashMap::new();
+
+        for file in index.files() {
+            let path = normalize_path(Path::new(file));
+
+            if let Some(file_name) = path.file_name().and_then(|name| name.to_str()) {
+                files_by_name.entry(file_name.to_string()).or_default().push(file.clone());
+            }
+
+            for ancestor in path.ancestors().skip(1) {
+                if !ancestor.starts_with(&root) {
+                    break;
+                }
+                if ancestor.file_name().is_some_and(|name| name == "include") {
+                    discovered.insert(ancestor.to_path_buf());
+                }
+            }
+        }
+
+        let mut discovered: Vec<PathBuf> = discovered.into_iter().collect();
+        discovered.sort();
+        include_dirs.extend(discovered);
+        include_dirs.push(root);
+
+        CIncludeResolver {
+            include_dirs,
+            files_by_name,
+        }
+    }
+
+    pub fn resolve(&self, index: &SourceIndex, source_file: &str, dependency: &str) -> Option<ResolvedDependency> {
+        let (header, angled) = match dependency.strip_prefix('<').and_then(|rest| rest.strip_suffix('>')) {
+            Some(header) => (header, true),
+            None => (dependency, false),
+        };
+
+        if !angled {
+            let source_dir = Path::new(source_file).parent().unwrap_or(Path::new(""));
+            if let Some(found) = index.lookup(source_dir.join(header)) {
+                return Some(ResolvedDependency::Internal(found.to_string()));
+            }
+        }
+
+        for dir in &self.include_dirs {
+            if let Some(found) = index.lookup(dir.join(header)) {
+                return Some(ResolvedDependency::Internal(found.to_string()));
+            }
+        }
+
+        if angled {
+            let first = header.split('/').next().unwrap_or(header);
+            if header.contains('/') && !SYSTEM_HEADER_DIRS.contains(&first) {
+                return Some(ResolvedDependency::External(first.to_string()));
+            }
+            return None;
+        }
+
+        self.unique_suffix_match(header).map(ResolvedDependency::Internal)
+    }
+
+    /// A quoted include that no search directory satisfies still resolves when exactly one file
+    /// in the tree ends with the included path.
+    fn unique_suffix_match(&self, header: &str) -> Option<String> {
+        let file_name = Path::new(header).file_name()?.to_str()?;
+        let suffix = normalize_path(Path::new(header));
+
+        let mut matches = self
+            .files_by_name
+            .get(file_name)?
+            .iter()
+            .filter(|file| normalize_path(Path::new(file)).ends_with(&suffix));
+
+        let first = matches.next()?;
+        matches.next().is_none().then(|| first.clone())
+    }
+}
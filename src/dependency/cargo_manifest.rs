@@ -0,0 +1,457 @@
+use crate::util::error::{AppError, AppResult};
+use crate::util::file_filter::wildcard_match;
+use std::collections::{BTreeMap, HashMap, HashSet, VecDeque};
+use std::fs;
+use std::path::{Path, PathBuf};
+use toml::Value;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum CargoDependencyKind {
+    Normal,
+    Dev,
+    Build,
+}
+
+#[derive(Debug, Clone)]
+pub struct CargoDependency {
+    /// Name of the package on the registry or in its own manifest
+    pub name: String,
+    /// Key used in the manifest, which differs from `name` for renamed dependencies
+    pub alias: String,
+    pub kind: CargoDependencyKind,
+    pub optional: bool,
+    pub path: Option<PathBuf>,
+    pub version: Option<String>,
+}
+
+#[derive(Debug, Clone)]
+pub struct CargoManifest {
+    pub name: String,
+    /// Name from the `[lib]` section, when it overrides the package name
+    pub lib_name: Option<String>,
+    #[allow(dead_code)]
+    pub version: Option<String>,
+    #[allow(dead_code)]
+    pub manifest_path: PathBuf,
+    pub dependencies: Vec<CargoDependency>,
+    pub features: BTreeMap<String, Vec<String>>,
+}
+
+#[derive(Debug, Clone)]
+pub struct LockedPackage {
+    pub name: String,
+    pub version: String,
+    /// Registry or git source; `None` for workspace and path crates
+    #[allow(dead_code)]
+    pub source: Option<String>,
+    pub dependencies: Vec<String>,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct CargoLock {
+    pub packages: Vec<LockedPackage>,
+}
+
+/// Entries of `[workspace.dependencies]` that members can inherit with `workspace = true`.
+#[derive(Debug, Clone, Default)]
+pub struct WorkspaceDependencies {
+    root: PathBuf,
+    specs: HashMap<String, Value>,
+}
+
+/// All local crates of a Cargo project: workspace members plus any path dependencies they pull in.
+#[derive(Debug, Clone)]
+pub struct CargoWorkspace {
+    #[allow(dead_code)]
+    pub root: PathBuf,
+    pub members: Vec<CargoManifest>,
+    pub lock: Option<CargoLock>,
+}
+
+impl CargoManifest {
+    pub fn parse(manifest_path: &Path, content: &str, workspace_dependencies: &WorkspaceDependencies) -> AppResult<Option<Self>> {
+        let value: Value = content.parse().map_err(|e| {
+            AppError::Dependency(format!("Failed to parse {}: {}", manifest_path.display(), e))
+        })?;
+
+        let Some(package) = value.get("package") else {
+            return Ok(None);
+        };
+
+        let name = package
+            .get("name")
+            .and_then(Value::as_str)
+            .ok_or_else(|| AppError::Dependency(format!("{} has no package name", manifest_path.display())))?
+            .to_string();
+        let version = package.get("version").and_then(Value::as_str).map(String::from);
+        let lib_name = value
+            .get("lib")
+            .and_then(|lib| lib.get("name"))
+            .and_then(Value::as_str)
+            .map(String::from);
+
+        let manifest_dir = manifest_path.parent().unwrap_or(Path::new("")).to_path_buf();
+        let mut dependencies = Vec::new();
+
+        collect_dependency_tables(&value, &manifest_dir, workspace_dependencies, &mut dependencies);
+
+        if let Some(targets) = value.get("target").and_then(Value::as_table) {
+            for target in targets.values() {
+                collect_dependency_tables(target, &manifest_dir, workspace_dependencies, &mut dependencies);
+            }
+        }
+
+        let features = value
+            .get("features")
+            .and_then(Value::as_table)
+            .map(|table| {
+                table
+                    .iter()
+                    .map(|(feature, entries)| {
+                        let entries = entries
+                            .as_array()
+                            .map(|items| items.iter().filter_map(Value::as_str).map(String::from).collect())
+                            .unwrap_or_default();
+                        (feature.clone(), entries)
+                    })
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        Ok(Some(CargoManifest {
+            name,
+            lib_name,
+            version,
+            manifest_path: manifest_path.to_path_buf(),
+            dependencies,
+            features,
+        }))
+    }
+
+    /// Name the library is referred to by in Rust paths: the `[lib]` name, or the package name
+    /// with dashes replaced by underscores.
+    pub fn crate_name(&self) -> String {
+        self.lib_name.as_deref().unwrap_or(&self.name).replace('-', "_")
+    }
+
+    /// Aliases of the optional dependencies switched on by the default features, or by every
+    /// feature when `all_features` is set.
+    pub fn enabled_optional_dependencies(&self, all_features: bool) -> HashSet<String> {
+        let optional: HashSet<&str> = self
+            .dependencies
+            .iter()
+            .filter(|dependency| dependency.optional)
+            .map(|dependency| dependency.alias.as_str())
+            .collect();
+
+        if all_features {
+            return optional.into_iter().map(String::from).collect();
+        }
+
+        let mut enabled = HashSet::new();
+        let mut visited = HashSet::new();
+        let mut pending: VecDeque<String> = VecDeque::from(vec!["default".to_string()]);
+
+        while let Some(feature) = pending.pop_front() {
+            if !visited.insert(feature.clone()) {
+                continue;
+            }
+
+            let Some(entries) = self.features.get(&feature) else {
+                if optional.contains(feature.as_str()) {
+                    enabled.insert(feature);
+                }
+                continue;
+            };
+
+            for entry in entries {
+                if let Some(dependency) = entry.strip_prefix("dep:") {
+                    enabled.insert(dependency.to_string());
+                } else if let Some((dependency, _)) = entry.split_once('/') {
+                    if !dependency.ends_with('?') && optional.contains(dependency) {
+                        enabled.insert(dependency.to_string());
+                    }
+                } else {
+                    pending.push_back(entry.clone());
+                }
+            }
+        }
+
+        enabled
+    }
+}
+
+fn collect_dependency_tables(
+    value: &Value,
+    manifest_dir: &Path,
+    workspace_dependencies: &WorkspaceDependencies,
+    dependencies: &mut Vec<CargoDependency>,
+) {
+    let tables = [
+        ("dependencies", CargoDependencyKind::Normal),
+        ("dev-dependencies", CargoDependencyKind::Dev),
+        ("dev_dependencies", CargoDependencyKind::Dev),
+        ("build-dependencies", CargoDependencyKind::Build),
+        ("build_dependencies", CargoDependencyKind::Build),
+    ];
+
+    for (table_name, kind) in tables {
+        let Some(table) = value.get(table_name).and_then(Value::as_table) else {
+            continue;
+        };
+
+        for (alias, spec) in table {
+            dependencies.push(parse_dependency(alias, spec, kind, manifest_dir, workspace_dependencies));
+        }
+    }
+}
+
+fn parse_dependency(
+    alias: &str,
+    spec: &Value,
+    kind: CargoDependencyKind,
+    manifest_dir: &Path,
+    workspace_dependencies: &WorkspaceDependencies,
+) -> CargoDependency {
+    let mut dependency = CargoDependency {
+        name: alias.to_string(),
+        alias: alias.to_string(),
+        kind,
+        optional: false,
+        path: None,
+        version: None,
+    };
+
+    match spec {
+        Value::String(version) => dependency.version = Some(version.clone()),
+        Value::Table(table) => {
+            let inherited = table.get("workspace").and_then(Value::as_bool).unwrap_or(false);
+
+            if inherited {
+                match workspace_dependencies.specs.get(alias) {
+                    Some(Value::String(version)) => dependency.version = Some(version.clone()),
+                    Some(spec) => apply_dependency_spec(&mut dependency, spec, &workspace_dependencies.root),
+                    None => {}
+                }
+            }
+
+            apply_dependency_spec(&mut dependency, spec, manifest_dir);
+        }
+        _ => {}
+    }
+
+    dependency
+}
+
+fn apply_dependency_spec(dependency: &mut CargoDependency, spec: &Value, base_dir: &Path) {
+    if let Some(package) = spec.get("package").and_then(Value::as_str) {
+        dependency.name = package.to_string();
+    }
+    if let Some(version) = spec.get("version").and_then(Value::as_str) {
+        dependency.version = Some(version.to_string());
+    }
+    if let Some(path) = spec.get("path").and_then(Value::as_str) {
+        dependency.path = Some(base_dir.join(path));
+    }
+    if let Some(optional) = spec.get("optional").and_then(Value::as_bool) {
+        dependency.optional = optional;
+    }
+}
+
+impl CargoLock {
+    pub fn parse(content: &str) -> AppResult<Self> {
+        let value: Value = content
+            .parse()
+            .map_err(|e| AppError::Dependency(format!("Failed to parse Cargo.lock: {}", e)))?;
+
+        let packages = value
+            .get("package")
+            .and_then(Value::as_array)
+            .map(|packages| {
+                packages
+                    .iter()
+                    .filter_map(|package| {
+                        Some(LockedPackage {
+                            name: package.get("name")?.as_str()?.to_string(),
+                            version: package.get("version")?.as_str()?.to_string(),
+                            source: package.get("source").and_then(Value::as_str).map(String::from),
+                            dependencies: package
+                                .get("dependencies")
+                                .and_then(Value::as_array)
+                                .map(|items| items.iter().filter_map(Value::as_str).map(String::from).collect())
+                                .unwrap_or_default(),
+                        })
+                    })
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        Ok(CargoLock { packages })
+    }
+
+    pub fn packages_named(&self, name: &str) -> Vec<&LockedPackage> {
+        self.packages.iter().filter(|package| package.name == name).collect()
+    }
+
+    /// Version of `dependency` that the locked package `dependent` was resolved against.
+    pub fn resolved_version(&self, dependent: &str, dependency: &str) -> Option<String> {
+        let candidates = self.packages_named(dependency);
+        if candidates.len() == 1 {
+            return Some(candidates[0].version.clone());
+        }
+
+        let dependent_package = self.packages.iter().find(|package| package.name == dependent)?;
+        dependent_package.dependencies.iter().find_map(|entry| {
+            let mut parts = entry.split_whitespace();
+            match (parts.next(), parts.next()) {
+                (Some(name), Some(version)) if name == dependency => Some(version.to_string()),
+                _ => None,
+            }
+        })
+    }
+}
+
+impl CargoWorkspace {
+    pub fn load<P: AsRef<Path>>(path: P) -> AppResult<Self> {
+        let root = path.as_ref().to_path_buf();
+        let root_manifest_path = root.join("Cargo.toml");
+
+        let root_content = fs::read_to_string(&root_manifest_path).map_err(|e| AppError::FileSystem {
+            path: root_manifest_path.clone(),
+            message: format!("Failed to read Cargo manifest: {}", e),
+        })?;
+        let root_value: Value = root_content.parse().map_err(|e| {
+            AppError::Dependency(format!("Failed to parse {}: {}", root_manifest_path.display(), e))
+        })?;
+
+        let workspace_dependencies = read_workspace_dependencies(&root_value, &root);
+
+        let mut manifest_paths = Vec::new();
+        if root_value.get("package").is_some() {
+            manifest_paths.push(root_manifest_path.clone());
+        }
+
+        if let Some(workspace) = root_value.get("workspace") {
+            let excluded: Vec<PathBuf> = string_array(workspace.get("exclude"))
+                .iter()
+                .map(|pattern| root.join(pattern))
+                .collect();
+
+            for pattern in string_array(workspace.get("members")) {
+                for member_dir in expand_member_pattern(&root, &pattern) {
+                    let manifest = member_dir.join("Cargo.toml");
+                    if manifest.is_file() && !excluded.iter().any(|excluded| member_dir.starts_with(excluded)) {
+                        manifest_paths.push(manifest);
+                    }
+                }
+            }
+        }
+
+        let mut members: Vec<CargoManifest> = Vec::new();
+        let mut seen: HashSet<PathBuf> = HashSet::new();
+        let mut pending: VecDeque<PathBuf> = manifest_paths.into();
+
+        while let Some(manifest_path) = pending.pop_front() {
+            let canonical = manifest_path.canonicalize().unwrap_or_else(|_| manifest_path.clone());
+            if !seen.insert(canonical) {
+                continue;
+            }
+
+            let Ok(content) = fs::read_to_string(&manifest_path) else {
+                continue;
+            };
+
+            if let Some(manifest) = CargoManifest::parse(&manifest_path, &content, &workspace_dependencies)? {
+                for dependency in &manifest.dependencies {
+                    if let Some(path) = &dependency.path {
+                        let dependency_manifest = path.join("Cargo.toml");
+                        if dependency_manifest.is_file() {
+                            pending.push_back(dependency_manifest);
+                        }
+                    }
+                }
+                members.push(manifest);
+            }
+        }
+
+        if members.is_empty() {
+            return Err(AppError::Dependency(format!(
+                "No Cargo packages found under '{}'",
+                root.display()
+            )));
+        }
+
+        members.sort_by(|a, b| a.name.cmp(&b.name));
+
+        let lock = match fs::read_to_string(root.join("Cargo.lock")) {
+            Ok(content) => Some(CargoLock::parse(&content)?),
+            Err(_) => None,
+        };
+
+        Ok(CargoWorkspace { root, members, lock })
+    }
+
+    pub fn member(&self, name: &str) -> Option<&CargoManifest> {
+        self.members.iter().find(|member| member.name == name)
+    }
+
+    pub fn is_member(&self, name: &str) -> bool {
+        self.member(name).is_some()
+    }
+}
+
+fn read_workspace_dependencies(root_value: &Value, root: &Path) -> WorkspaceDependencies {
+    let specs = root_value
+        .get("workspace")
+        .and_then(|workspace| workspace.get("dependencies"))
+        .and_then(Value::as_table)
+        .map(|table| table.iter().map(|(name, spec)| (name.clone(), spec.clone())).collect())
+        .unwrap_or_default();
+
+    WorkspaceDependencies {
+        root: root.to_path_buf(),
+        specs,
+    }
+}
+
+fn string_array(value: Option<&Value>) -> Vec<String> {
+    value
+        .and_then(Value::as_array)
+        .map(|items| items.iter().filter_map(Value::as_str).map(String::from).collect())
+        .unwrap_or_default()
+}
+
+/// Expands a workspace `members` entry such as `crates/*` into the matching directories.
+fn expand_member_pattern(root: &Path, pattern: &str) -> Vec<PathBuf> {
+    let mut matches = vec![root.to_path_buf()];
+
+    for component in pattern.split('/').filter(|component| !component.is_empty() && *component != ".") {
+        let mut next = Vec::new();
+
+        for base in &matches {
+            if component.contains('*') || component.contains('?') {
+                let Ok(entries) = fs::read_dir(base) else {
+                    continue;
+                };
+                let mut dirs: Vec<PathBuf> = entries
+                    .filter_map(Result::ok)
+                    .map(|entry| entry.path())
+                    .filter(|path| path.is_dir())
+                    .filter(|path| {
+                        path.file_name()
+                            .and_then(|name| name.to_str())
+                            .is_some_and(|name| wildcard_match(component, name))
+                    })
+                    .collect();
+                dirs.sort();
+                next.extend(dirs);
+            } else {
+                next.push(base.join(component));
+            }
+        }
+
+        matches = next;
+    }
+
+    matches
+}
@@ -0,0 +1,122 @@
+/// Token of a C-family source file (Go, Java, C#), with comments and literal contents removed.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Token {
+    Ident(String),
+    Str(String),
+    Punct(char),
+}
+
+pub fn tokenize(content: &str) -> Vec<Token> {
+    tokenize_with_lines(content).0
+}
+
+/// Tokenizes `content` and returns the 1-based line each token starts on alongside the tokens.
+pub fn tokenize_with_lines(content: &str) -> (Vec<Token>, Vec<usize>) {
+    let chars: Vec<char> = content.chars().collect();
+    let mut tokens = Vec::new();
+    let mut lines = Vec::new();
+    let mut line = 1;
+    let mut i = 0;
+
+    while i < chars.len() {
+        let token_start = i;
+        let c = chars[i];
+
+        if c.is_whitespace() {
+            i += 1;
+        } else if c == '/' && chars.get(i + 1) == Some(&'/') {
+            while i < chars.len() && chars[i] != '\n' {
+                i += 1;
+            }
+        } else if c == '/' && chars.get(i + 1) == Some(&'*') {
+            i += 2;
+            while i < chars.len() && !(chars[i] == '*' && chars.get(i + 1) == Some(&'/')) {
+                i += 1;
+            }
+            i += 2;
+        } else if c == '"' && chars.get(i + 1) == Some(&'"') && chars.get(i + 2) == Some(&'"') {
+            // Java text blocks and C# raw string literals
+            let start = i + 3;
+            i = start;
+            while i < chars.len() && !(chars[i] == '"' && chars.get(i + 1) == Some(&'"') && chars.get(i + 2) == Some(&'"')) {
+                i += 1;
+            }
+            tokens.push(Token::Str(chars[start..i.min(chars.len())].iter().collect()));
+            i += 3;
+        } else if c == '@' && chars.get(i + 1) == Some(&'"') {
+            // C# verbatim strings escape quotes by doubling them
+            let mut value = String::new();
+            i += 2;
+            while i < chars.len() {
+                if chars[i] == '"' {
+                    if chars.get(i + 1) == Some(&'"') {
+                        value.push('"');
+                        i += 2;
+                        continue;
+                    }
+                    break;
+                }
+                value.push(chars[i]);
+                i += 1;
+            }
+            tokens.push(Token::Str(value));
+            i += 1;
+        } else if c == '"' || c == '\'' || c == '`' {
+            let mut value = String::new();
+            i += 1;
+            while i < chars.len() && chars[i] != c {
+                if chars[i] == '\\' && c != '`' && i + 1 < chars.len() {
+                    i += 1;
+                }
+                value.push(chars[i]);
+                i += 1;
+            }
+            if c != '\'' {
+                tokens.push(Token::Str(value));
+            }
+            i += 1;
+        } else if c.is_alphabetic() || c == '_' {
+            let start = i;
+            while i < chars.len() && (chars[i].is_alphanumeric() || chars[i] == '_') {
+                i += 1;
+            }
+            tokens.push(Token::Ident(chars[start..i].iter().collect()));
+        } else {
+            tokens.push(Token::Punct(c));
+            i += 1;
+        }
+
+        lines.resize(tokens.len(), line);
+        line += count_newlines(&chars[token_start..i.min(chars.len())]);
+    }
+
+    (tokens, lines)
+}
+
+pub fn count_newlines(chars: &[char]) -> usize {
+    chars.iter().filter(|&&c| c == '\n').count()
+}
+
+/// Reads a dotted name such as `com.example.Type` starting at `pos`, accepting a trailing `.*`.
+pub fn read_dotted_name(tokens: &[Token], pos: &mut usize) -> Option<String> {
+    let mut name = match tokens.get(*pos) {
+        Some(Token::Ident(first)) => first.clone(),
+        _ => return None,
+    };
+    *pos += 1;
+
+    while tokens.get(*pos) == Some(&Token::Punct('.')) {
+        match tokens.get(*pos + 1) {
+            Some(Token::Ident(segment)) => name.push_str(&format!(".{}", segment)),
+            Some(Token::Punct('*')) => name.push_str(".*"),
+            _ => break,
+        }
+        *pos += 2;
+    }
+
+    Some(name)
+}
+
+pub fn is_ident(token: Option<&Token>, word: &str) -> bool {
+    matches!(token, Some(Token::Ident(ident)) if ident == word)
+}
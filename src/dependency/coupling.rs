@@ -0,0 +1,368 @@
+use crate::dependency::clike::{Token, tokenize};
+use crate::dependency::dependency_graph::DependencyGraph;
+use crate::metrics::language::LanguageDetector;
+use std::cmp::Ordering;
+use std::collections::{BTreeMap, HashSet};
+use std::path::Path;
+
+/// Package-design metrics for a file or directory: afferent coupling (Ca, how many outside
+/// files depend on it), efferent coupling (Ce, how many files and packages it depends on) and
+/// the abstract/concrete type declarations that abstractness is derived from.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CouplingMetrics {
+    pub name: String,
+    pub files: usize,
+    pub afferent: usize,
+    pub efferent: usize,
+    pub abstract_types: usize,
+    pub concrete_types: usize,
+}
+
+impl CouplingMetrics {
+    /// I = Ce / (Ca + Ce); an unconnected module counts as stable.
+    pub fn instability(&self) -> f64 {
+        let total = self.afferent + self.efferent;
+        if total == 0 {
+            0.0
+        } else {
+            self.efferent as f64 / total as f64
+        }
+    }
+
+    /// Share of abstract types, or `None` when no types were found in a language that has them.
+    pub fn abstractness(&self) -> Option<f64> {
+        let total = self.abstract_types + self.concrete_types;
+        (total > 0).then(|| self.abstract_types as f64 / total as f64)
+    }
+
+    /// Distance from the main sequence, D = |A + I - 1|.
+    pub fn distance(&self) -> Option<f64> {
+        self.abstractness()
+            .map(|abstractness| (abstractness + self.instability() - 1.0).abs())
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum CouplingSort {
+    Name,
+    Afferent,
+    Efferent,
+    #[default]
+    Instability,
+    Abstractness,
+    Distance,
+}
+
+impl std::str::FromStr for CouplingSort {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "name" => Ok(CouplingSort::Name),
+            "ca" | "afferent" => Ok(CouplingSort::Afferent),
+            "ce" | "efferent" => Ok(CouplingSort::Efferent),
+            "i" | "instability" => Ok(CouplingSort::Instability),
+            "a" | "abstractness" => Ok(CouplingSort::Abstractness),
+            "d" | "distance" => Ok(CouplingSort::Distance),
+            _ => Err(format!(
+                "Invalid coupling sort: {}. Valid values are: name, ca, ce, instability, abstractness, distance",
+                s
+            )),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct CouplingReport {
+    pub files: Vec<CouplingMetrics>,
+    pub directories: Vec<CouplingMetrics>,
+}
+
+impl CouplingReport {
+    /// Sorts both tables; numeric keys put the highest values first and unknown values last.
+    pub fn sort_by(&mut self, sort: CouplingSort) {
+        self.files.sort_by(|a, b| compare_metrics(a, b, sort));
+        self.directories.sort_by(|a, b| compare_metrics(a, b, sort));
+    }
+}
+
+fn compare_metrics(a: &CouplingMetrics, b: &CouplingMetrics, sort: CouplingSort) -> Ordering {
+    let descending = |x: Option<f64>, y: Option<f64>| match (x, y) {
+        (Some(x), Some(y)) => y.total_cmp(&x),
+        (Some(_), None) => Ordering::Less,
+        (None, Some(_)) => Ordering::Greater,
+        (None, None) => Ordering::Equal,
+    };
+
+    let ordering = match sort {
+        CouplingSort::Name => Ordering::Equal,
+        CouplingSort::Afferent => b.afferent.cmp(&a.afferent),
+        CouplingSort::Efferent => b.efferent.cmp(&a.efferent),
+        CouplingSort::Instability => descending(Some(a.instability()), Some(b.instability())),
+        CouplingSort::Abstractness => descending(a.abstractness(), b.abstractness()),
+        CouplingSort::Distance => descending(a.distance(), b.distance()),
+    };
+
+    ordering.then_with(|| a.name.cmp(&b.name))
+}
+
+/// Computes coupling for every internal node and for every directory that contains one.
+/// `load_content` supplies file contents for abstractness; nodes it cannot load (such as
+/// crates) simply have no abstractness.
+pub fn analyze_coupling(graph: &DependencyGraph, load_content: &dyn Fn(&str) -> Option<String>) -> CouplingReport {
+    let detector = LanguageDetector::new();
+    let mut internal_nodes = graph.get_internal_nodes();
+    internal_nodes.sort();
+
+    let mut report = CouplingReport::default();
+    let mut directory_files: BTreeMap<String, Vec<usize>> = BTreeMap::new();
+
+    for node in &internal_nodes {
+        let dependents: HashSet<String> = graph.get_dependents(node).into_iter().filter(|other| other != node).collect();
+        let dependencies: HashSet<String> = graph.get_dependencies(node).into_iter().filter(|other| other != node).collect();
+
+        let extension = Path::new(node).extension().and_then(|extension| extension.to_str()).unwrap_or("");
+        let language = detector.detect_language(extension);
+        let types = load_content(node)
+            .and_then(|content| count_type_declarations(&language, &content))
+            .unwrap_or_default();
+
+        if let Some(directory) = Path::new(node).parent().and_then(|parent| parent.to_str())
+            && !directory.is_empty()
+        {
+            directory_files.entry(directory.to_string()).or_default().push(report.files.len());
+        }
+
+        report.files.push(CouplingMetrics {
+            name: node.clone(),
+            files: 1,
+            afferent: dependents.len(),
+            efferent: dependencies.len(),
+            abstract_types: types.abstract_types,
+            concrete_types: types.concrete_types,
+        });
+    }
+
+    for (directory, indices) in directory_files {
+        let members: HashSet<&str> = indices.iter().map(|&i| report.files[i].name.as_str()).collect();
+        let mut dependents = HashSet::new();
+        let mut dependencies = HashSet::new();
+
+        for member in &members {
+            dependents.extend(graph.get_dependents(member).into_iter().filter(|other| !members.contains(other.as_str())));
+            dependencies.extend(graph.get_dependencies(member).into_iter().filter(|other| !members.contains(other.as_str())));
+        }
+
+        report.directories.push(CouplingMetrics {
+            files: members.len(),
+            afferent: dependents.len(),
+            efferent: dependencies.len(),
+            abstract_types: indices.iter().map(|&i| report.files[i].abstract_types).sum(),
+            concrete_types: indices.iter().map(|&i| report.files[i].concrete_types).sum(),
+            name: directory,
+        });
+    }
+
+    report.sort_by(CouplingSort::default());
+    report
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct TypeCounts {
+    pub abstract_types: usize,
+    pub concrete_types: usize,
+}
+
+/// Counts abstract declarations (traits, interfaces, abstract classes, protocols) against
+/// concrete ones. Returns `None` for languages without a notion of abstract types.
+pub fn count_type_declarations(language: &str, content: &str) -> Option<TypeCounts> {
+    match language {
+        "Rust" => Some(count_rust_types(content)),
+        "Go" => Some(count_go_types(content)),
+        "Python" => Some(count_python_types(content)),
+        "Java" | "C#" | "TypeScript" => Some(count_class_based_types(content)),
+        "C++" => Some(count_cpp_types(content)),
+        _ => None,
+    }
+}
+
+fn count_rust_types(content: &str) -> TypeCounts {
+    let mut counts = TypeCounts::default();
+
+    for line in content.lines() {
+        let mut line = line.trim_start();
+        if line.starts_with("pub(") {
+            line = line.split_once(')').map_or("", |(_, rest)| rest.trim_start());
+        }
+        for prefix in ["pub ", "unsafe ", "auto "] {
+            line = line.strip_prefix(prefix).unwrap_or(line).trim_start();
+        }
+
+        if line.starts_with("trait ") {
+            counts.abstract_types += 1;
+        } else if ["struct ", "enum ", "union "].iter().any(|keyword| line.starts_with(keyword)) {
+            counts.concrete_types += 1;
+        }
+    }
+
+    counts
+}
+
+fn count_go_types(content: &str) -> TypeCounts {
+    let mut counts = TypeCounts::default();
+    let mut in_type_block = false;
+
+    for line in content.lines() {
+        let line = line.trim();
+
+        let declaration = if in_type_block {
+            if line.starts_with(')') {
+                in_type_block = false;
+                continue;
+            }
+            line
+        } else if line == "type (" {
+            in_type_block = true;
+            continue;
+        } else if let Some(rest) = line.strip_prefix("type ") {
+            rest
+        } else {
+            continue;
+        };
+
+        let kind = declaration.split_whitespace().nth(1).unwrap_or("");
+        if kind.starts_with("interface") {
+            counts.abstract_types += 1;
+        } else if kind.starts_with("struct") {
+            counts.concrete_types += 1;
+        }
+    }
+
+    counts
+}
+
+fn count_python_types(content: &str) -> TypeCounts {
+    const ABSTRACT_BASES: [&str; 4] = ["ABC", "ABCMeta", "Protocol", "abc.ABC"];
+    let mut counts = TypeCounts::default();
+
+    for line in content.lines() {
+        let Some(rest) = line.trim_start().strip_prefix("class ") else {
+            continue;
+        };
+
+        let bases = rest
+            .split_once('(')
+            .map(|(_, bases)| bases.split(')').next().unwrap_or(""))
+            .unwrap_or("");
+        let is_abstract = bases.split(',').any(|base| {
+            let base = base.trim();
+            let base = base.strip_prefix("metaclass=").unwrap_or(base);
+            let base = base.split('[').next().unwrap_or(base);
+            ABSTRACT_BASES.contains(&base) || base.ends_with(".Protocol") || base.ends_with(".ABCMeta")
+        });
+
+        if is_abstract {
+            counts.abstract_types += 1;
+        } else {
+            counts.concrete_types += 1;
+        }
+    }
+
+    counts
+}
+
+/// Java, C# and TypeScript: interfaces and `abstract class` are abstract; classes, enums,
+/// structs and records are concrete.
+fn count_class_based_types(content: &str) -> TypeCounts {
+    const CONCRETE_KEYWORDS: [&str; 4] = ["class", "enum", "struct", "record"];
+    let tokens = tokenize(content);
+    let mut counts = TypeCounts::default();
+    let mut pending_abstract = false;
+
+    for (i, token) in tokens.iter().enumerate() {
+        match token {
+            Token::Ident(word) if word == "abstract" => pending_abstract = true,
+            Token::Ident(word) if word == "interface" || CONCRETE_KEYWORDS.contains(&word.as_str()) => {
+                let Some(Token::Ident(name)) = tokens.get(i + 1) else {
+                    continue;
+                };
+                // `record struct`, `enum class` and similar pairs declare a single type
+                if CONCRETE_KEYWORDS.contains(&name.as_str()) {
+                    continue;
+                }
+
+                if word == "interface" || (word == "class" && pending_abstract) {
+                    counts.abstract_types += 1;
+                } else {
+                    counts.concrete_types += 1;
+                }
+                pending_abstract = false;
+            }
+            Token::Punct(';' | '{' | '}' | '(' | ')' | '=') => pending_abstract = false,
+            _ => {}
+        }
+    }
+
+    counts
+}
+
+/// C++: a class or struct whose body declares a pure virtual function (`= 0;`) is abstract.
+fn count_cpp_types(content: &str) -> TypeCounts {
+    let tokens = tokenize(content);
+    let mut counts = TypeCounts::default();
+    // Open class bodies: brace depth inside the body and whether a pure virtual was seen
+    let mut classes: Vec<(usize, bool)> = Vec::new();
+    let mut pending_class = false;
+    let mut depth = 0usize;
+
+    for (i, token) in tokens.iter().enumerate() {
+        match token {
+            Token::Ident(word) if word == "class" || word == "struct" => {
+                let after_enum = i > 0 && tokens[i - 1] == Token::Ident("enum".to_string());
+                let is_definition = matches!(tokens.get(i + 1), Some(Token::Ident(_)))
+                    && !matches!(tokens.get(i + 2), Some(Token::Punct(',' | '>' | '=' | ';' | ')')));
+                pending_class = is_definition && !after_enum;
+            }
+            Token::Punct(';') => pending_class = false,
+            Token::Punct('{') => {
+                depth += 1;
+                if pending_class {
+                    classes.push((depth, false));
+                    pending_class = false;
+                }
+            }
+            Token::Punct('}') => {
+                if classes.last().is_some_and(|(class_depth, _)| *class_depth == depth)
+                    && let Some((_, is_abstract)) = classes.pop()
+                {
+                    if is_abstract {
+                        counts.abstract_types += 1;
+                    } else {
+                        counts.concrete_types += 1;
+                    }
+                }
+                depth = depth.saturating_sub(1);
+            }
+            Token::Punct('=') => {
+                // `virtual void f() const = 0;`, not a member initializer such as `int n = 0;`
+                let after_signature = match i.checked_sub(1).map(|previous| &tokens[previous]) {
+                    Some(Token::Punct(')')) => true,
+                    Some(Token::Ident(word)) => ["const", "override", "noexcept", "final"].contains(&word.as_str()),
+                    _ => false,
+                };
+                let pure_virtual = after_signature
+                    && tokens.get(i + 1) == Some(&Token::Punct('0'))
+                    && tokens.get(i + 2) == Some(&Token::Punct(';'));
+                if pure_virtual
+                    && let Some((class_depth, is_abstract)) = classes.last_mut()
+                    && *class_depth == depth
+                {
+                    *is_abstract = true;
+                }
+            }
+            _ => {}
+        }
+    }
+
+    counts
+}
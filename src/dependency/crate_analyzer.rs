@@ -0,0 +1,98 @@
+use crate::dependency::cargo_manifest::{CargoDependencyKind, CargoWorkspace};
+use crate::dependency::dependency_graph::{DependencyGraph, EdgeImport, ImportKind};
+use crate::util::error::AppResult;
+use std::path::Path;
+
+/// Builds a crate-level dependency graph from `Cargo.toml` manifests and `Cargo.lock`.
+pub struct CrateDependencyAnalyzer {
+    include_dev_dependencies: bool,
+    include_external: bool,
+    all_features: bool,
+}
+
+impl Default for CrateDependencyAnalyzer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl CrateDependencyAnalyzer {
+    pub fn new() -> Self {
+        CrateDependencyAnalyzer {
+            include_dev_dependencies: false,
+            include_external: true,
+            all_features: false,
+        }
+    }
+
+    pub fn include_dev_dependencies(mut self, include: bool) -> Self {
+        self.include_dev_dependencies = include;
+        self
+    }
+
+    pub fn include_external(mut self, include: bool) -> Self {
+        self.include_external = include;
+        self
+    }
+
+    /// Treat every optional dependency as enabled instead of only those reachable from `default`.
+    pub fn all_features(mut self, all_features: bool) -> Self {
+        self.all_features = all_features;
+        self
+    }
+
+    pub fn analyze_crates<P: AsRef<Path>>(&self, path: P) -> AppResult<DependencyGraph> {
+        let workspace = CargoWorkspace::load(path)?;
+        Ok(self.build_graph(&workspace))
+    }
+
+    pub fn build_graph(&self, workspace: &CargoWorkspace) -> DependencyGraph {
+        let mut graph = DependencyGraph::new();
+
+        for member in &workspace.members {
+            graph.add_node(&member.name);
+        }
+
+        for member in &workspace.members {
+            let enabled_optional = member.enabled_optional_dependencies(self.all_features);
+
+            for dependency in &member.dependencies {
+                let skipped_dev = dependency.kind == CargoDependencyKind::Dev && !self.include_dev_dependencies;
+                if skipped_dev || (dependency.optional && !enabled_optional.contains(&dependency.alias)) {
+                    continue;
+                }
+
+                if dependency.name == member.name {
+                    continue;
+                }
+
+                let import = EdgeImport {
+                    specifier: dependency.alias.clone(),
+                    line: None,
+                    kind: Some(match dependency.kind {
+                        CargoDependencyKind::Normal => ImportKind::Dependency,
+                        CargoDependencyKind::Dev => ImportKind::DevDependency,
+                        CargoDependencyKind::Build => ImportKind::BuildDependency,
+                    }),
+                };
+
+                if workspace.is_member(&dependency.name) {
+                    graph.add_edge_with_import(&member.name, &dependency.name, import);
+                } else if self.include_external {
+                    let node = match workspace
+                        .lock
+                        .as_ref()
+                        .and_then(|lock| lock.resolved_version(&member.name, &dependency.name))
+                    {
+                        Some(version) => format!("{}@{}", dependency.name, version),
+                        None => dependency.name.clone(),
+                    };
+                    graph.add_external_node(&node);
+                    graph.add_edge_with_import(&member.name, &node, import);
+                }
+            }
+        }
+
+        graph
+    }
+}
@@ -0,0 +1,276 @@
+use crate::dependency::clike::{Token, is_ident, read_dotted_name, tokenize_with_lines};
+use crate::dependency::dependency_graph::{EdgeImport, ImportKind};
+use crate::dependency::resolver::{ResolvedDependency, SourceIndex, normalize_path};
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::path::{Path, PathBuf};
+
+const DOTNET_FRAMEWORK_ROOTS: [&str; 3] = ["System", "Microsoft", "Windows"];
+const PROJECT_EXTENSIONS: [&str; 4] = [".csproj", ".vbproj", ".fsproj", ".proj"];
+const TYPE_KEYWORDS: [&str; 5] = ["class", "struct", "interface", "enum", "record"];
+
+/// Namespaces and types declared by a C# file, plus the targets of its `using` directives.
+#[derive(Debug, Default, PartialEq)]
+pub struct CSharpSourceItems {
+    pub usings: Vec<EdgeImport>,
+    pub namespaces: Vec<String>,
+    pub types: Vec<String>,
+}
+
+pub fn parse_csharp_source(content: &str) -> CSharpSourceItems {
+    let (tokens, lines) = tokenize_with_lines(content);
+    let mut items = CSharpSourceItems::default();
+    // Enclosing namespaces with the brace depth their body opened at
+    let mut namespace_stack: Vec<(String, usize)> = Vec::new();
+    let mut file_namespace: Option<String> = None;
+    let mut depth = 0usize;
+    let mut i = 0;
+
+    while i < tokens.len() {
+        match &tokens[i] {
+            Token::Punct('{') => depth += 1,
+            Token::Punct('}') => {
+                depth = depth.saturating_sub(1);
+                if namespace_stack.last().is_some_and(|(_, open_depth)| *open_depth == depth) {
+                    namespace_stack.pop();
+                }
+            }
+            Token::Ident(word) if word == "using" && depth == namespace_stack.len() => {
+                let mut pos = i + 1;
+                if is_ident(tokens.get(pos), "static") {
+                    pos += 1;
+                }
+                // `using Alias = Some.Namespace.Type;`
+                if matches!(tokens.get(pos), Some(Token::Ident(_))) && tokens.get(pos + 1) == Some(&Token::Punct('=')) {
+                    pos += 2;
+                }
+                if let Some(name) = read_dotted_name(&tokens, &mut pos)
+                    && tokens.get(pos) == Some(&Token::Punct(';'))
+                {
+                    items.usings.push(EdgeImport::new(name, lines[i], ImportKind::Using));
+                    i = pos;
+                }
+            }
+            Token::Ident(word) if word == "namespace" => {
+                let mut pos = i + 1;
+                if let Some(name) = read_dotted_name(&tokens, &mut pos) {
+                    let full_name = match namespace_stack.last() {
+                        Some((outer, _)) => format!("{}.{}", outer, name),
+                        None => name,
+                    };
+                    items.namespaces.push(full_name.clone());
+
+                    if tokens.get(pos) == Some(&Token::Punct(';')) {
+                        file_namespace = Some(full_name);
+                    } else {
+                        namespace_stack.push((full_name, depth));
+                    }
+                    i = pos;
+                    continue;
+                }
+            }
+            Token::Ident(word) if TYPE_KEYWORDS.contains(&word.as_str()) => {
+                if let Some(Token::Ident(type_name)) = tokens.get(i + 1) {
+                    let namespace = namespace_stack
+                        .last()
+                        .map(|(name, _)| name.clone())
+                        .or_else(|| file_namespace.clone());
+                    items.types.push(match namespace {
+                        Some(namespace) => format!("{}.{}", namespace, type_name),
+                        None => type_name.clone(),
+                    });
+                }
+            }
+            _ => {}
+        }
+        i += 1;
+    }
+
+    items
+}
+
+pub fn extract_csharp_dependencies(content: &str) -> Vec<EdgeImport> {
+    parse_csharp_source(content).usings
+}
+
+/// Returns the `ProjectReference` paths and `PackageReference` names of an MSBuild project file.
+pub fn extract_msbuild_dependencies(content: &str) -> Vec<EdgeImport> {
+    let mut dependencies = Vec::new();
+
+    for (element, kind) in [
+        ("ProjectReference", ImportKind::ProjectReference),
+        ("PackageReference", ImportKind::PackageReference),
+    ] {
+        let opening = format!("<{}", element);
+        let mut offset = 0;
+
+        while let Some(start) = content[offset..].find(&opening) {
+            let tag_start = offset + start;
+            offset = tag_start + opening.len();
+            let rest = &content[offset..];
+            let tag_end = rest.find('>').unwrap_or(rest.len());
+            if let Some(include) = xml_attribute(&rest[..tag_end], "Include") {
+                let line = content[..tag_start].matches('\n').count() + 1;
+                dependencies.push(EdgeImport::new(include.replace('\\', "/"), line, kind));
+            }
+        }
+    }
+
+    dependencies
+}
+
+fn xml_attribute(tag: &str, attribute: &str) -> Option<String> {
+    let start = tag.find(&format!("{}=", attribute))? + attribute.len() + 1;
+    let quote = tag[start..].chars().next()?;
+    let value_start = start + quote.len_utf8();
+    let value_end = value_start + tag[value_start..].find(quote)?;
+    Some(tag[value_start..value_end].to_string())
+}
+
+#[derive(Debug, Clone)]
+struct MsBuildProject {
+    dir: PathBuf,
+    references: Vec<PathBuf>,
+    packages: Vec<String>,
+}
+
+/// Maps C# `using` directives to the files that declare the namespace or type, limited to the
+/// project of the importing file and the projects it references.
+#[derive(Default)]
+pub struct CSharpNamespaceResolver {
+    namespaces: HashMap<String, Vec<String>>,
+    types: HashMap<String, String>,
+    /// Projects keyed by the normalized path of their project file
+    projects: HashMap<PathBuf, MsBuildProject>,
+}
+
+impl CSharpNamespaceResolver {
+    pub fn build(index: &SourceIndex, load_content: &dyn Fn(&str) -> Option<String>) -> Self {
+        let mut resolver = CSharpNamespaceResolver::default();
+
+        for file in index.files() {
+            let path = Path::new(file);
+
+            if file.ends_with(".cs") {
+                let Some(content) = load_content(file) else {
+                    continue;
+                };
+                let items = parse_csharp_source(&content);
+                for namespace in items.namespaces {
+                    resolver.namespaces.entry(namespace).or_default().push(file.clone());
+                }
+                for type_name in items.types {
+                    resolver.types.entry(type_name).or_insert_with(|| file.clone());
+                }
+            } else if is_project_file(file) {
+                let Some(content) = load_content(file) else {
+                    continue;
+                };
+                let dir = normalize_path(path.parent().unwrap_or(Path::new("")));
+                let (references, packages) = extract_msbuild_dependencies(&content)
+                    .into_iter()
+                    .map(|dependency| dependency.specifier)
+                    .partition::<Vec<String>, _>(|dependency| is_project_file(dependency));
+
+                resolver.projects.insert(normalize_path(path), MsBuildProject {
+                    references: references.iter().map(|reference| normalize_path(&dir.join(reference))).collect(),
+                    dir,
+                    packages,
+                });
+            }
+        }
+
+        for files in resolver.namespaces.values_mut() {
+            files.sort();
+            files.dedup();
+        }
+
+        resolver
+    }
+
+    pub fn resolve(&self, source_file: &str, dependency: &str) -> Vec<ResolvedDependency> {
+        let visible_projects = self.visible_projects(source_file);
+        let is_visible = |file: &String| {
+            visible_projects.as_ref().is_none_or(|projects| {
+                self.project_of(file).is_none_or(|project| projects.contains(project))
+            })
+        };
+
+        if let Some(file) = self.types.get(dependency).filter(|file| is_visible(file)) {
+            return vec![ResolvedDependency::Internal(file.clone())];
+        }
+
+        let files: Vec<ResolvedDependency> = self
+            .namespaces
+            .get(dependency)
+            .map(|files| files.iter().filter(|file| is_visible(file)).cloned().map(ResolvedDependency::Internal).collect())
+            .unwrap_or_default();
+        if !files.is_empty() {
+            return files;
+        }
+
+        let package = visible_projects
+            .iter()
+            .flatten()
+            .filter_map(|project| self.projects.get(project))
+            .flat_map(|project| project.packages.iter())
+            .filter(|package| dependency == package.as_str() || dependency.starts_with(&format!("{}.", package)))
+            .max_by_key(|package| package.len());
+        if let Some(package) = package {
+            return vec![ResolvedDependency::External(package.clone())];
+        }
+
+        let root = dependency.split('.').next().unwrap_or(dependency);
+        let declared_root = self
+            .namespaces
+            .keys()
+            .any(|namespace| namespace == root || namespace.starts_with(&format!("{}.", root)));
+        if DOTNET_FRAMEWORK_ROOTS.contains(&root) || declared_root {
+            return Vec::new();
+        }
+
+        vec![ResolvedDependency::External(root.to_string())]
+    }
+
+    /// Resolves a project file's `ProjectReference` paths and `PackageReference` names.
+    pub fn resolve_project_reference(&self, index: &SourceIndex, source_file: &str, dependency: &str) -> Option<ResolvedDependency> {
+        if is_project_file(dependency) {
+            let dir = Path::new(source_file).parent().unwrap_or(Path::new(""));
+            return index.lookup(dir.join(dependency)).map(|found| ResolvedDependency::Internal(found.to_string()));
+        }
+
+        Some(ResolvedDependency::External(dependency.to_string()))
+    }
+
+    /// Deepest project directory that contains the file.
+    fn project_of(&self, file: &str) -> Option<&PathBuf> {
+        let file = normalize_path(Path::new(file));
+        self.projects
+            .iter()
+            .filter(|(_, project)| file.starts_with(&project.dir))
+            .max_by_key(|(_, project)| project.dir.components().count())
+            .map(|(path, _)| path)
+    }
+
+    /// The importing file's project and everything it references transitively, or `None`
+    /// when the file does not belong to any project.
+    fn visible_projects(&self, source_file: &str) -> Option<HashSet<PathBuf>> {
+        let start = self.project_of(source_file)?.clone();
+        let mut visible = HashSet::new();
+        let mut pending = VecDeque::from(vec![start]);
+
+        while let Some(project) = pending.pop_front() {
+            if !visible.insert(project.clone()) {
+                continue;
+            }
+            if let Some(info) = self.projects.get(&project) {
+                pending.extend(info.references.iter().cloned());
+            }
+        }
+
+        Some(visible)
+    }
+}
+
+pub fn is_project_file(path: &str) -> bool {
+    PROJECT_EXTENSIONS.iter().any(|extension| path.ends_with(extension))
+}
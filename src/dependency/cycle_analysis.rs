@@ -0,0 +1,153 @@
+use crate::dependency::dependency_graph::DependencyGraph;
+use std::collections::{HashMap, HashSet, VecDeque};
+
+/// A dependency edge as `(from, to)`.
+pub type Edge = (String, String);
+
+/// A strongly connected group of files, with the edges inside it and a small set of those
+/// edges whose removal leaves the group acyclic.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DependencyCycle {
+    pub members: Vec<String>,
+    pub internal_edges: usize,
+    pub suggested_removals: Vec<Edge>,
+}
+
+/// Reports every tangle in the graph once, largest first.
+pub fn find_dependency_cycles(graph: &DependencyGraph) -> Vec<DependencyCycle> {
+    let mut cycles: Vec<DependencyCycle> = graph
+        .find_circular_dependencies()
+        .into_iter()
+        .map(|members| analyze_component(graph, members))
+        .collect();
+
+    cycles.sort_by(|a, b| {
+        b.members.len()
+            .cmp(&a.members.len())
+            .then_with(|| a.members.cmp(&b.members))
+    });
+    cycles
+}
+
+fn analyze_component(graph: &DependencyGraph, members: Vec<String>) -> DependencyCycle {
+    let member_set: HashSet<&str> = members.iter().map(String::as_str).collect();
+    let mut edges: Vec<Edge> = Vec::new();
+
+    for member in &members {
+        let mut dependencies: Vec<String> = graph
+            .get_dependencies(member)
+            .into_iter()
+            .filter(|dependency| member_set.contains(dependency.as_str()))
+            .collect();
+        dependencies.sort();
+        edges.extend(dependencies.into_iter().map(|dependency| (member.clone(), dependency)));
+    }
+
+    let suggested_removals = minimal_feedback_edges(&members, &edges);
+
+    DependencyCycle {
+        internal_edges: edges.len(),
+        members,
+        suggested_removals,
+    }
+}
+
+/// Picks edges to remove with the Eades–Lin–Smyth ordering heuristic: files are ordered so that
+/// as many edges as possible point forward, and the edges pointing backward are removed. Any
+/// removed edge that can be restored without closing a cycle again is then put back, so the
+/// suggestion is minimal even where the heuristic overshoots.
+fn minimal_feedback_edges(members: &[String], edges: &[Edge]) -> Vec<Edge> {
+    let order = eades_ordering(members, edges);
+    let position: HashMap<&str, usize> = order
+        .iter()
+        .enumerate()
+        .map(|(i, node)| (node.as_str(), i))
+        .collect();
+
+    let (backward, forward): (Vec<&Edge>, Vec<&Edge>) = edges
+        .iter()
+        .partition(|(from, to)| position[to.as_str()] <= position[from.as_str()]);
+
+    let mut kept: HashMap<&str, Vec<&str>> = HashMap::new();
+    for (from, to) in &forward {
+        kept.entry(from.as_str()).or_default().push(to.as_str());
+    }
+
+    let mut removals = Vec::new();
+    for (from, to) in backward {
+        if from != to && !reaches(&kept, to, from) {
+            kept.entry(from.as_str()).or_default().push(to.as_str());
+        } else {
+            removals.push((from.clone(), to.clone()));
+        }
+    }
+
+    removals
+}
+
+fn eades_ordering(members: &[String], edges: &[Edge]) -> Vec<String> {
+    let mut outgoing: HashMap<&str, HashSet<&str>> = HashMap::new();
+    let mut incoming: HashMap<&str, HashSet<&str>> = HashMap::new();
+    for (from, to) in edges.iter().filter(|(from, to)| from != to) {
+        outgoing.entry(from.as_str()).or_default().insert(to.as_str());
+        incoming.entry(to.as_str()).or_default().insert(from.as_str());
+    }
+
+    let mut remaining: Vec<&str> = members.iter().map(String::as_str).collect();
+    let mut head: Vec<&str> = Vec::new();
+    let mut tail: VecDeque<&str> = VecDeque::new();
+
+    let degree = |map: &HashMap<&str, HashSet<&str>>, node: &str| map.get(node).map_or(0, HashSet::len);
+
+    while !remaining.is_empty() {
+        let next = if let Some(sink) = remaining.iter().find(|node| degree(&outgoing, node) == 0) {
+            let sink = *sink;
+            tail.push_front(sink);
+            sink
+        } else if let Some(source) = remaining.iter().find(|node| degree(&incoming, node) == 0) {
+            let source = *source;
+            head.push(source);
+            source
+        } else {
+            // Members are sorted, so the first maximum makes ties deterministic
+            let best = remaining
+                .iter()
+                .copied()
+                .rev()
+                .max_by_key(|node| degree(&outgoing, node) as isize - degree(&incoming, node) as isize)
+                .unwrap_or(remaining[0]);
+            head.push(best);
+            best
+        };
+
+        remaining.retain(|node| *node != next);
+        for target in outgoing.remove(next).unwrap_or_default() {
+            if let Some(sources) = incoming.get_mut(target) {
+                sources.remove(next);
+            }
+        }
+        for source in incoming.remove(next).unwrap_or_default() {
+            if let Some(targets) = outgoing.get_mut(source) {
+                targets.remove(next);
+            }
+        }
+    }
+
+    head.into_iter().chain(tail).map(String::from).collect()
+}
+
+fn reaches(adjacency: &HashMap<&str, Vec<&str>>, from: &str, to: &str) -> bool {
+    let mut visited = HashSet::new();
+    let mut pending = vec![from];
+
+    while let Some(node) = pending.pop() {
+        if node == to {
+            return true;
+        }
+        if visited.insert(node) {
+            pending.extend(adjacency.get(node).into_iter().flatten().copied());
+        }
+    }
+
+    false
+}
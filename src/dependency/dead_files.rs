@@ -0,0 +1,308 @@
+use crate::dependency::dependency_graph::DependencyGraph;
+use crate::dependency::resolver::normalize_path;
+use crate::metrics::language::LanguageDetector;
+use crate::metrics::models::FileMetrics;
+use crate::util::file_filter::{FileFilter, glob_match_path, matches_path_pattern};
+use std::collections::{BTreeSet, HashMap, HashSet, VecDeque};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Files that tools and runtimes load by name rather than through an import.
+const ENTRY_POINT_GLOBS: [&str; 21] = [
+    "**/main.rs",
+    "**/lib.rs",
+    "**/build.rs",
+    "**/src/bin/**",
+    "**/examples/**",
+    "**/benches/**",
+    "**/__main__.py",
+    "**/setup.py",
+    "**/conftest.py",
+    "**/manage.py",
+    "**/index.js",
+    "**/index.jsx",
+    "**/index.mjs",
+    "**/index.ts",
+    "**/index.tsx",
+    "**/*.config.js",
+    "**/*.config.mjs",
+    "**/*.config.cjs",
+    "**/*.config.ts",
+    "**/*.d.ts",
+    "**/Program.cs",
+];
+
+/// C and C++ sources are compiled on their own, so nothing includes them.
+const TRANSLATION_UNIT_EXTENSIONS: [&str; 4] = ["c", "cc", "cpp", "cxx"];
+
+/// Languages whose files see the rest of their package or namespace without importing it.
+const PACKAGE_SCOPED_LANGUAGES: [&str; 3] = ["Go", "Java", "C#"];
+
+/// A project file that no entry point reaches.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DeadFile {
+    pub path: String,
+    /// No file depends on it at all; otherwise it is only used by other dead files
+    pub orphan: bool,
+    pub metrics: Option<FileMetrics>,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct DeadFileReport {
+    pub entry_points: Vec<String>,
+    pub reachable_files: usize,
+    /// Unreachable files, largest first
+    pub dead_files: Vec<DeadFile>,
+}
+
+impl DeadFileReport {
+    pub fn dead_lines_of_code(&self) -> usize {
+        self.dead_files
+            .iter()
+            .filter_map(|file| file.metrics.as_ref())
+            .map(|metrics| metrics.lines_of_code)
+            .sum()
+    }
+}
+
+/// Finds the files of a dependency graph that cannot be reached from its entry points.
+///
+/// Entry points are detected per ecosystem: binaries, libraries and build scripts declared by
+/// `Cargo.toml` or found in the conventional places, `package.json` `main`/`module`/`bin`/`exports`
+/// targets, `index` modules and tool configs, Python scripts with a `__main__` guard, Go
+/// `package main` files, Java and C# `main` methods, and C/C++ translation units. Test files are
+/// always entry points.
+#[derive(Debug, Clone, Default)]
+pub struct DeadFileAnalysis {
+    entry_patterns: Vec<String>,
+}
+
+impl DeadFileAnalysis {
+    pub fn new() -> Self {
+        DeadFileAnalysis {
+            entry_patterns: Vec::new(),
+        }
+    }
+
+    /// Replaces the detected entry points with the files matching these globs or path prefixes,
+    /// relative to the analyzed directory.
+    pub fn entry_points(mut self, patterns: Vec<String>) -> Self {
+        self.entry_patterns = patterns;
+        self
+    }
+
+    /// `file_metrics` supplies the size of each dead file; files it does not cover are reported
+    /// without metrics.
+    pub fn run<P: AsRef<Path>>(&self, graph: &DependencyGraph, root: P, file_metrics: &[FileMetrics]) -> DeadFileReport {
+        let root = root.as_ref();
+        let detector = LanguageDetector::new();
+
+        let mut nodes = graph.get_internal_nodes();
+        nodes.sort();
+
+        let declared = if self.entry_patterns.is_empty() {
+            declared_entry_points(&nodes, root)
+        } else {
+            HashSet::new()
+        };
+
+        let entry_points: Vec<String> = nodes
+            .iter()
+            .filter(|node| {
+                let relative = relative_path(node, root);
+                if FileFilter::is_test_file(format!("/{}", relative)) {
+                    return true;
+                }
+                if !self.entry_patterns.is_empty() {
+                    return self.entry_patterns.iter().any(|pattern| matches_path_pattern(pattern, &relative));
+                }
+                declared.contains(&normalize_path(Path::new(node.as_str())))
+                    || is_conventional_entry_point(node, &relative, &detector)
+            })
+            .cloned()
+            .collect();
+
+        let reachable = reachable_files(graph, &nodes, &entry_points, &detector);
+
+        let metrics_by_path: HashMap<PathBuf, &FileMetrics> = file_metrics
+            .iter()
+            .map(|metrics| (normalize_path(Path::new(&metrics.path)), metrics))
+            .collect();
+
+        let mut dead_files: Vec<DeadFile> = nodes
+            .iter()
+            .filter(|node| !reachable.contains(node.as_str()))
+            .map(|node| DeadFile {
+                path: node.clone(),
+                orphan: graph.get_dependents(node).iter().all(|dependent| dependent == node),
+                metrics: metrics_by_path.get(&normalize_path(Path::new(node.as_str()))).map(|metrics| (*metrics).clone()),
+            })
+            .collect();
+        dead_files.sort_by_key(|file| std::cmp::Reverse(file.metrics.as_ref().map_or(0, |metrics| metrics.lines_of_code)));
+
+        DeadFileReport {
+            entry_points,
+            reachable_files: reachable.len(),
+            dead_files,
+        }
+    }
+}
+
+/// Walks dependencies from the entry points. Reaching a file of a package-scoped language also
+/// reaches the other files of that language in its directory.
+fn reachable_files<'a>(
+    graph: &DependencyGraph,
+    nodes: &'a [String],
+    entry_points: &[String],
+    detector: &LanguageDetector,
+) -> HashSet<&'a str> {
+    let node_set: HashMap<&str, &'a String> = nodes.iter().map(|node| (node.as_str(), node)).collect();
+
+    let mut packages: HashMap<(PathBuf, String), Vec<&'a String>> = HashMap::new();
+    for node in nodes {
+        let language = language_of(node, detector);
+        if PACKAGE_SCOPED_LANGUAGES.contains(&language.as_str()) {
+            let dir = Path::new(node).parent().map(Path::to_path_buf).unwrap_or_default();
+            packages.entry((dir, language)).or_default().push(node);
+        }
+    }
+
+    let mut reachable: HashSet<&'a str> = HashSet::new();
+    let mut pending: VecDeque<&'a String> = entry_points
+        .iter()
+        .filter_map(|entry| node_set.get(entry.as_str()).copied())
+        .collect();
+
+    while let Some(node) = pending.pop_front() {
+        if !reachable.insert(node.as_str()) {
+            continue;
+        }
+
+        for dependency in graph.get_dependencies(node) {
+            if let Some(target) = node_set.get(dependency.as_str()) {
+                pending.push_back(target);
+            }
+        }
+
+        let dir = Path::new(node).parent().map(Path::to_path_buf).unwrap_or_default();
+        if let Some(siblings) = packages.get(&(dir, language_of(node, detector))) {
+            pending.extend(siblings.iter().copied());
+        }
+    }
+
+    reachable
+}
+
+fn is_conventional_entry_point(node: &str, relative: &str, detector: &LanguageDetector) -> bool {
+    if ENTRY_POINT_GLOBS.iter().any(|glob| glob_match_path(glob, relative)) {
+        return true;
+    }
+
+    let extension = Path::new(node).extension().and_then(|extension| extension.to_str()).unwrap_or("");
+    if TRANSLATION_UNIT_EXTENSIONS.contains(&extension) {
+        return true;
+    }
+
+    let language = detector.detect_language(extension);
+    if !matches!(language.as_str(), "Python" | "Go" | "Java" | "C#") {
+        return false;
+    }
+
+    fs::read_to_string(node).is_ok_and(|content| has_main(&language, &content))
+}
+
+/// Whether the file starts a program: a `__main__` guard, `package main` or a `main` method.
+fn has_main(language: &str, content: &str) -> bool {
+    content.lines().map(str::trim).any(|line| match language {
+        "Python" => line.starts_with("if __name__ ==") && line.contains("__main__"),
+        "Go" => line == "package main",
+        "Java" => line.contains("static void main("),
+        "C#" => line.contains("static ") && line.contains(" Main("),
+        _ => false,
+    })
+}
+
+/// Files named as entry points by the `Cargo.toml` and `package.json` manifests next to the
+/// graph's files or in their parent directories up to the analyzed directory.
+fn declared_entry_points(nodes: &[String], root: &Path) -> HashSet<PathBuf> {
+    let mut dirs = BTreeSet::new();
+    for node in nodes {
+        let mut dir = Path::new(node).parent();
+        while let Some(current) = dir {
+            if !current.starts_with(root) || !dirs.insert(current.to_path_buf()) {
+                break;
+            }
+            dir = current.parent();
+        }
+    }
+
+    let mut declared = HashSet::new();
+    for dir in dirs {
+        if let Ok(content) = fs::read_to_string(dir.join("Cargo.toml"))
+            && let Ok(manifest) = content.parse::<toml::Table>()
+        {
+            for target in cargo_target_paths(&manifest) {
+                declared.insert(normalize_path(&dir.join(target)));
+            }
+        }
+
+        if let Ok(content) = fs::read_to_string(dir.join("package.json"))
+            && let Ok(package) = serde_json::from_str::<serde_json::Value>(&content)
+        {
+            let mut targets = Vec::new();
+            for field in ["main", "module", "browser", "bin", "exports"] {
+                if let Some(value) = package.get(field) {
+                    collect_strings(value, &mut targets);
+                }
+            }
+            for target in targets {
+                declared.insert(normalize_path(&dir.join(target)));
+            }
+        }
+    }
+
+    declared
+}
+
+/// `path` values of the `[lib]`, `[[bin]]`, `[[example]]`, `[[bench]]` and `[[test]]` targets.
+fn cargo_target_paths(manifest: &toml::Table) -> Vec<String> {
+    let mut paths = Vec::new();
+
+    if let Some(path) = manifest.get("lib").and_then(|lib| lib.get("path")).and_then(|path| path.as_str()) {
+        paths.push(path.to_string());
+    }
+
+    for section in ["bin", "example", "bench", "test"] {
+        let targets = manifest.get(section).and_then(|targets| targets.as_array());
+        for target in targets.into_iter().flatten() {
+            if let Some(path) = target.get("path").and_then(|path| path.as_str()) {
+                paths.push(path.to_string());
+            }
+        }
+    }
+
+    paths
+}
+
+/// Every string in a `package.json` field, including nested `bin` and `exports` maps.
+fn collect_strings(value: &serde_json::Value, strings: &mut Vec<String>) {
+    match value {
+        serde_json::Value::String(string) => strings.push(string.clone()),
+        serde_json::Value::Array(values) => values.iter().for_each(|value| collect_strings(value, strings)),
+        serde_json::Value::Object(map) => map.values().for_each(|value| collect_strings(value, strings)),
+        _ => {}
+    }
+}
+
+fn language_of(node: &str, detector: &LanguageDetector) -> String {
+    let extension = Path::new(node).extension().and_then(|extension| extension.to_str()).unwrap_or("");
+    detector.detect_language(extension)
+}
+
+fn relative_path(node: &str, root: &Path) -> String {
+    let path = Path::new(node);
+    path.strip_prefix(root)
+        .unwrap_or(path)
+        .to_string_lossy()
+        .replace('\\', "/")
+}
@@ -1,5 +1,13 @@
 use crate::cache::AnalysisCache;
-use crate::dependency::dependency_graph::DependencyGraph;
+use crate::dependency::c_resolver::extract_c_includes;
+use crate::dependency::csharp_resolver::{extract_csharp_dependencies, extract_msbuild_dependencies, is_project_file};
+use crate::dependency::dependency_graph::{DependencyGraph, EdgeImport};
+use crate::dependency::go_resolver::extract_go_dependencies;
+use crate::dependency::java_resolver::extract_java_dependencies;
+use crate::dependency::js_resolver::extract_js_dependencies;
+use crate::dependency::python_resolver::extract_python_dependencies;
+use crate::dependency::resolver::{DependencyResolver, ResolvedDependency, SourceIndex};
+use crate::dependency::rust_resolver::extract_rust_dependencies;
 use crate::metrics::language::LanguageDetector;
 use crate::util::error::{AppError, AppResult};
 use crate::util::file_filter::FileFilter;
@@ -8,7 +16,7 @@ use ignore::{DirEntry, WalkBuilder};
 use rayon::prelude::*;
 use std::collections::HashMap;
 use std::fs;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 use std::sync::{Arc, Mutex};
 
 pub struct DependencyAnalyzer {
@@ -16,6 +24,8 @@ pub struct DependencyAnalyzer {
     supported_languages: HashMap<String, Vec<String>>,
     cache: Arc<AnalysisCache>,
     parallel: bool,
+    include_tests: bool,
+    include_dirs: Vec<PathBuf>,
 }
 
 impl Default for DependencyAnalyzer {
@@ -63,11 +73,37 @@ impl DependencyAnalyzer {
             vec!["import".to_string(), "from".to_string()]
         );
         
+        supported_languages.insert(
+            "Go".to_string(), 
+            vec!["import".to_string()]
+        );
+        
+        supported_languages.insert(
+            "Java".to_string(), 
+            vec!["import".to_string(), "package".to_string()]
+        );
+        
+        supported_languages.insert(
+            "C#".to_string(), 
+            vec!["using".to_string(), "namespace".to_string()]
+        );
+        
+        supported_languages.insert(
+            "DotNetProject".to_string(), 
+            vec!["ProjectReference".to_string(), "PackageReference".to_string()]
+        );
+        
+        for language in ["C", "C++"] {
+            supported_languages.insert(language.to_string(), vec!["#include".to_string()]);
+        }
+        
         DependencyAnalyzer {
             language_detector: LanguageDetector::new(),
             supported_languages,
             cache: Arc::new(AnalysisCache::new()),
             parallel: true,
+            include_tests: false,
+            include_dirs: Vec::new(),
         }
     }
     
@@ -77,6 +113,17 @@ impl DependencyAnalyzer {
         analyzer
     }
     
+    pub fn include_test_files(mut self, include_tests: bool) -> Self {
+        self.include_tests = include_tests;
+        self
+    }
+    
+    /// Extra directories searched for C/C++ `#include` targets, relative to the analyzed path.
+    pub fn include_directories(mut self, include_dirs: Vec<PathBuf>) -> Self {
+        self.include_dirs = include_dirs;
+        self
+    }
+    
     pub fn analyze_dependencies<P: AsRef<Path>>(&self, dir_path: P) -> AppResult<DependencyGraph> {
         let path = dir_path.as_ref();
         
@@ -90,76 +137,51 @@ impl DependencyAnalyzer {
         
         let graph = Arc::new(Mutex::new(DependencyGraph::new()));
         
-        let walker = WalkBuilder::new(path)
-            .hidden(false)
-            .git_ignore(true)
-            .git_global(true)
-            .git_exclude(true)
-            .filter_entry(|e| {
-                !FileFilter::should_exclude(e.path()) && !FileFilter::is_test_file(e.path())
-            })
-            .build();
-            
-        let entries: Vec<DirEntry> = walker
-            .filter_map(|result| {
-                match result {
-                    Ok(entry) => {
-                        if !entry.path().is_dir() {
-                            Some(entry) 
-                        } else {
-                            None
-                        }
-                    },
-                    Err(_) => None,
-                }
-            })
-            .collect();
+        let entries = self.collect_entries(path);
+        let load_content = |file: &str| self.read_file_content(Path::new(file));
+        let resolver = DependencyResolver::new(self.build_source_index(path, &entries), &load_content, &self.include_dirs);
             
         let process_entry = |entry: &DirEntry| {
             let path = entry.path();
             let path_str = path.to_string_lossy().to_string();
             
-            if let Some(cached_deps) = self.cache.get_dependencies(&path_str) {
-                let normalized_path = self.normalize_path(path);
-                let mut graph_guard = graph.lock().unwrap();
-                graph_guard.add_node(&normalized_path);
-                
-                for dependency in cached_deps {
-                    let normalized_dependency = self.resolve_dependency(&normalized_path, &dependency);
-                    graph_guard.add_node(&normalized_dependency);
-                    graph_guard.add_edge(&normalized_path, &normalized_dependency);
-                }
+            let language = self.detect_language(path, &path_str);
+            if !self.supported_languages.contains_key(&language) {
                 return;
             }
             
-            let file_name = path.file_name().and_then(|name| name.to_str()).unwrap_or("");
-            let extension = path.extension().and_then(|ext| ext.to_str()).unwrap_or("");
-            
-            let language = if let Some(cached_lang) = self.cache.get_language(&path_str) {
-                cached_lang
+            let dependencies = if let Some(cached_deps) = self.cache.get_dependencies(&path_str) {
+                cached_deps
+            } else if let Some(dependencies) = self.extract_dependencies(path, &language) {
+                self.cache.cache_dependencies(&path_str, dependencies.clone());
+                dependencies
             } else {
-                let detected_lang = if extension.is_empty() && !file_name.is_empty() {
-                    self.language_detector.detect_by_filename(file_name)
-                } else {
-                    self.language_detector.detect_language(extension)
-                };
-                self.cache.cache_language(&path_str, detected_lang.clone());
-                detected_lang
+                return;
             };
             
-            if self.supported_languages.contains_key(&language) {
-                if let Some(dependencies) = self.extract_dependencies(path, &language) {
-                    let normalized_path = self.normalize_path(path);
-                    
-                    self.cache.cache_dependencies(&path_str, dependencies.clone());
-                    
-                    let mut graph_guard = graph.lock().unwrap();
-                    graph_guard.add_node(&normalized_path);
-                    
-                    for dependency in dependencies {
-                        let normalized_dependency = self.resolve_dependency(&normalized_path, &dependency);
-                        graph_guard.add_node(&normalized_dependency);
-                        graph_guard.add_edge(&normalized_path, &normalized_dependency);
+            let normalized_path = self.normalize_path(path);
+            let resolved: Vec<(ResolvedDependency, EdgeImport)> = dependencies
+                .into_iter()
+                .flat_map(|import| {
+                    let targets = resolver.resolve(&normalized_path, &language, &import.specifier);
+                    targets.into_iter().map(move |target| (target, import.clone()))
+                })
+                .collect();
+            
+            let mut graph_guard = graph.lock().unwrap();
+            graph_guard.add_node(&normalized_path);
+            
+            for (dependency, import) in resolved {
+                match dependency {
+                    ResolvedDependency::Internal(target) => {
+                        if target != normalized_path {
+                            graph_guard.add_node(&target);
+                            graph_guard.add_edge_with_import(&normalized_path, &target, import);
+                        }
+                    }
+                    ResolvedDependency::External(package) => {
+                        graph_guard.add_external_node(&package);
+                        graph_guard.add_edge_with_import(&normalized_path, &package, import);
                     }
                 }
             }
@@ -185,161 +207,88 @@ impl DependencyAnalyzer {
         Ok(final_graph)
     }
     
-    fn extract_dependencies(&self, file_path: &Path, language: &str) -> Option<Vec<String>> {
-        let path_str = file_path.to_string_lossy().to_string();
-        
-        let content = if let Some(cached_content) = self.cache.get_file_content(&path_str) {
-            cached_content
-        } else if let Ok(file_content) = fs::read_to_string(file_path) {
-            self.cache.cache_file_content(&path_str, file_content.clone());
-            file_content
-        } else {
-            return None;
-        };
-        
-        let import_patterns = self.supported_languages.get(language)?;
+    fn collect_entries(&self, path: &Path) -> Vec<DirEntry> {
+        let include_tests = self.include_tests;
         
-        match language {
-            "Rust" => self.extract_rust_dependencies(&content, import_patterns),
-            "JavaScript" | "TypeScript" => self.extract_js_dependencies(&content, import_patterns),
-            "Python" => self.extract_python_dependencies(&content, import_patterns),
-            _ => Some(Vec::new())
-        }
-    }
-    
-    fn extract_rust_dependencies(&self, content: &str, import_patterns: &[String]) -> Option<Vec<String>> {
-        let mut dependencies = Vec::new();
-        
-        for line in content.lines() {
-            let trimmed = line.trim();
+        let walker = WalkBuilder::new(path)
+            .hidden(false)
+            .git_ignore(true)
+            .git_global(true)
+            .git_exclude(true)
+            .filter_entry(move |e| {
+                let excluded = FileFilter::is_system_file(e.path()) || FileFilter::is_binary_or_media_file(e.path());
+                !excluded && (include_tests || !FileFilter::is_test_file(e.path()))
+            })
+            .build();
             
-            for pattern in import_patterns {
-                if pattern == "use" && trimmed.starts_with("use ") {
-                    self.extract_rust_use_dependency(trimmed, &mut dependencies);
-                } else if pattern == "mod" && trimmed.starts_with("mod ") && !trimmed.contains('{') {
-                    self.extract_rust_mod_dependency(trimmed, &mut dependencies);
+        walker
+            .filter_map(|result| {
+                match result {
+                    Ok(entry) => {
+                        if !entry.path().is_dir() {
+                            Some(entry) 
+                        } else {
+                            None
+                        }
+                    },
+                    Err(_) => None,
                 }
-            }
-        }
-        
-        Some(dependencies)
+            })
+            .collect()
     }
     
-    fn extract_rust_use_dependency(&self, line: &str, dependencies: &mut Vec<String>) {
-        let parts: Vec<&str> = line.split(&[';', ' ', '{', '}', ':'][..]).collect();
-        if parts.len() > 1 {
-            let module = parts[1].trim();
-            if !module.is_empty() && !module.starts_with("crate::") && 
-               !module.starts_with("self::") && !module.starts_with("std::") {
-                dependencies.push(module.to_string());
-            }
+    fn build_source_index(&self, root: &Path, entries: &[DirEntry]) -> SourceIndex {
+        let mut index = SourceIndex::new(root);
+        for entry in entries {
+            index.add_file(entry.path());
         }
+        index
     }
     
-    fn extract_rust_mod_dependency(&self, line: &str, dependencies: &mut Vec<String>) {
-        let parts: Vec<&str> = line.split(&[';', ' '][..]).collect();
-        if parts.len() > 1 {
-            let module = parts[1].trim();
-            if !module.is_empty() {
-                dependencies.push(module.to_string());
-            }
+    fn detect_language(&self, path: &Path, path_str: &str) -> String {
+        if let Some(cached_lang) = self.cache.get_language(path_str) {
+            return cached_lang;
         }
-    }
-    
-    fn extract_js_dependencies(&self, content: &str, import_patterns: &[String]) -> Option<Vec<String>> {
-        let mut dependencies = Vec::new();
         
-        for line in content.lines() {
-            let trimmed = line.trim();
-            
-            for pattern in import_patterns {
-                if pattern == "import" && trimmed.starts_with("import ") {
-                    self.extract_js_import_dependency(trimmed, &mut dependencies);
-                } else if pattern == "require" && trimmed.contains("require(") {
-                    self.extract_js_require_dependency(trimmed, &mut dependencies);
-                }
-            }
-        }
-        
-        Some(dependencies)
+        let detected_lang = self.language_detector.detect(path, None);
+        self.cache.cache_language(path_str, detected_lang.clone());
+        detected_lang
     }
     
-    fn extract_js_import_dependency(&self, line: &str, dependencies: &mut Vec<String>) {
-        if let Some(from_index) = line.find(" from ") {
-            if let Some(quote_start) = line[from_index + 6..].find(&['\"', '\''][..]) {
-                if let Some(quote_end) = line[(from_index + 6 + quote_start + 1)..].find(&['\"', '\''][..]) {
-                    let module = &line[(from_index + 6 + quote_start + 1)..(from_index + 6 + quote_start + 1 + quote_end)];
-                    if !module.is_empty() && !module.starts_with('@') {
-                        dependencies.push(module.to_string());
-                    }
-                }
-            }
-        }
-    }
-    
-    fn extract_js_require_dependency(&self, line: &str, dependencies: &mut Vec<String>) {
-        let parts: Vec<&str> = line.split("require(").collect();
-        if parts.len() > 1 {
-            let quote_parts: Vec<&str> = parts[1].split(&['\"', '\''][..]).collect();
-            if quote_parts.len() > 1 {
-                let module = quote_parts[1].trim();
-                if !module.is_empty() && !module.starts_with('@') {
-                    dependencies.push(module.to_string());
-                }
-            }
-        }
-    }
-    
-    fn extract_python_dependencies(&self, content: &str, import_patterns: &[String]) -> Option<Vec<String>> {
-        let mut dependencies = Vec::new();
+    fn extract_dependencies(&self, file_path: &Path, language: &str) -> Option<Vec<EdgeImport>> {
+        let content = self.read_file_content(file_path)?;
         
-        for line in content.lines() {
-            let trimmed = line.trim();
-            
-            for pattern in import_patterns {
-                if pattern == "import" && trimmed.starts_with("import ") {
-                    self.extract_python_import_dependency(trimmed, &mut dependencies);
-                } else if pattern == "from" && trimmed.starts_with("from ") {
-                    self.extract_python_from_dependency(trimmed, &mut dependencies);
-                }
-            }
+        if !self.supported_languages.contains_key(language) {
+            return None;
         }
         
-        Some(dependencies)
-    }
-    
-    fn extract_python_import_dependency(&self, line: &str, dependencies: &mut Vec<String>) {
-        let parts: Vec<&str> = line.split(&[' ', ','][..]).collect();
-        if parts.len() > 1 {
-            let module = parts[1].trim();
-            if !module.is_empty() && module != "as" {
-                dependencies.push(module.to_string());
-            }
+        match language {
+            "Rust" => Some(extract_rust_dependencies(&content)),
+            "JavaScript" | "TypeScript" => Some(extract_js_dependencies(&content)),
+            "Python" => Some(extract_python_dependencies(&content)),
+            "Go" => Some(extract_go_dependencies(&content)),
+            "Java" => Some(extract_java_dependencies(&content)),
+            "C#" => Some(extract_csharp_dependencies(&content)),
+            "DotNetProject" if is_project_file(&file_path.to_string_lossy()) => Some(extract_msbuild_dependencies(&content)),
+            "C" | "C++" => Some(extract_c_includes(&content)),
+            _ => Some(Vec::new())
         }
     }
     
-    fn extract_python_from_dependency(&self, line: &str, dependencies: &mut Vec<String>) {
-        if let Some(import_index) = line.find(" import ") {
-            let module = line[5..import_index].trim();
-            if !module.is_empty() && module != "." && module != ".." {
-                dependencies.push(module.to_string());
-            }
+    fn read_file_content(&self, file_path: &Path) -> Option<String> {
+        let path_str = file_path.to_string_lossy().to_string();
+        
+        if let Some(cached_content) = self.cache.get_file_content(&path_str) {
+            Some(cached_content)
+        } else if let Ok(file_content) = fs::read_to_string(file_path) {
+            self.cache.cache_file_content(&path_str, file_content.clone());
+            Some(file_content)
+        } else {
+            None
         }
     }
     
     fn normalize_path(&self, path: &Path) -> String {
         path.to_string_lossy().to_string()
     }
-    
-    fn resolve_dependency(&self, source_path: &str, dependency: &str) -> String {
-        if dependency.starts_with("std") || dependency.contains("std::") || 
-           dependency.starts_with("crate") || dependency.contains("crate::") {
-            return dependency.to_string();
-        }
-        
-        let source_dir = Path::new(source_path).parent().unwrap_or(Path::new(""));
-        let dependency_path = source_dir.join(dependency);
-        
-        dependency_path.to_string_lossy().to_string()
-    }
-}
+}
\ No newline at end of file
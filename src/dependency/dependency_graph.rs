@@ -1,10 +1,167 @@
+use serde::{Deserialize, Serialize};
 use std::collections::{HashMap, HashSet};
 
-#[derive(Clone)]
+/// The statement or manifest entry behind an edge.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ImportKind {
+    /// Rust `use`
+    Use,
+    /// Rust `mod` declaration
+    Module,
+    ExternCrate,
+    /// `import` in JavaScript, TypeScript, Python, Java and Go, and Python `from ... import`
+    Import,
+    /// JavaScript `import()` expression
+    DynamicImport,
+    Require,
+    /// JavaScript `export ... from`
+    ReExport,
+    Include,
+    /// C# `using` directive
+    Using,
+    ProjectReference,
+    PackageReference,
+    /// Cargo `[dependencies]` entry, in crate-level graphs
+    Dependency,
+    DevDependency,
+    BuildDependency,
+}
+
+impl ImportKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ImportKind::Use => "use",
+            ImportKind::Module => "module",
+            ImportKind::ExternCrate => "extern_crate",
+            ImportKind::Import => "import",
+            ImportKind::DynamicImport => "dynamic_import",
+            ImportKind::Require => "require",
+            ImportKind::ReExport => "re_export",
+            ImportKind::Include => "include",
+            ImportKind::Using => "using",
+            ImportKind::ProjectReference => "project_reference",
+            ImportKind::PackageReference => "package_reference",
+            ImportKind::Dependency => "dependency",
+            ImportKind::DevDependency => "dev_dependency",
+            ImportKind::BuildDependency => "build_dependency",
+        }
+    }
+}
+
+/// The import that introduced an edge: the specifier as written by the language extractor, the
+/// line it was found on and the kind of statement.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct EdgeImport {
+    pub specifier: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub line: Option<usize>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub kind: Option<ImportKind>,
+}
+
+impl EdgeImport {
+    /// An import statement found by a language extractor, which always knows its line and kind.
+    pub fn new(specifier: impl Into<String>, line: usize, kind: ImportKind) -> Self {
+        EdgeImport {
+            specifier: specifier.into(),
+            line: Some(line),
+            kind: Some(kind),
+        }
+    }
+}
+
+/// A node of the serialized graph.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct GraphNode {
+    pub id: String,
+    #[serde(default)]
+    pub external: bool,
+}
+
+/// An edge of the serialized graph, with the import behind it when known.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct GraphEdge {
+    pub source: String,
+    pub target: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub import: Option<EdgeImport>,
+}
+
+/// Serialized form of a graph: its nodes and edges as lists sorted by id, so that output is
+/// stable between runs.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct GraphDocument {
+    pub nodes: Vec<GraphNode>,
+    pub edges: Vec<GraphEdge>,
+}
+
+impl From<&DependencyGraph> for GraphDocument {
+    fn from(graph: &DependencyGraph) -> Self {
+        let mut nodes: Vec<GraphNode> = graph
+            .nodes
+            .iter()
+            .map(|node| GraphNode {
+                id: node.clone(),
+                external: graph.is_external(node),
+            })
+            .collect();
+        nodes.sort_by(|a, b| a.id.cmp(&b.id));
+
+        let mut edges: Vec<GraphEdge> = graph
+            .edges
+            .iter()
+            .flat_map(|(source, targets)| {
+                targets.iter().map(move |target| GraphEdge {
+                    source: source.clone(),
+                    target: target.clone(),
+                    import: graph.get_edge_import(source, target).cloned(),
+                })
+            })
+            .collect();
+        edges.sort_by(|a, b| a.source.cmp(&b.source).then_with(|| a.target.cmp(&b.target)));
+
+        GraphDocument { nodes, edges }
+    }
+}
+
+impl From<DependencyGraph> for GraphDocument {
+    fn from(graph: DependencyGraph) -> Self {
+        GraphDocument::from(&graph)
+    }
+}
+
+impl From<GraphDocument> for DependencyGraph {
+    fn from(document: GraphDocument) -> Self {
+        let mut graph = DependencyGraph::new();
+
+        for node in &document.nodes {
+            if node.external {
+                graph.add_external_node(&node.id);
+            } else {
+                graph.add_node(&node.id);
+            }
+        }
+
+        for edge in document.edges {
+            match edge.import {
+                Some(import) => graph.add_edge_with_import(&edge.source, &edge.target, import),
+                None => graph.add_edge(&edge.source, &edge.target),
+            }
+        }
+
+        graph
+    }
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(into = "GraphDocument", from = "GraphDocument")]
 pub struct DependencyGraph {
     nodes: HashSet<String>,
+    external_nodes: HashSet<String>,
     edges: HashMap<String, HashSet<String>>,
     reverse_edges: HashMap<String, HashSet<String>>,
+    edge_imports: HashMap<(String, String), EdgeImport>,
 }
 
 impl Default for DependencyGraph {
@@ -17,8 +174,10 @@ impl DependencyGraph {
     pub fn new() -> Self {
         DependencyGraph {
             nodes: HashSet::new(),
+            external_nodes: HashSet::new(),
             edges: HashMap::new(),
             reverse_edges: HashMap::new(),
+            edge_imports: HashMap::new(),
         }
     }
     
@@ -34,6 +193,27 @@ impl DependencyGraph {
         }
     }
     
+    pub fn add_external_node(&mut self, node: &str) {
+        self.add_node(node);
+        self.external_nodes.insert(node.to_string());
+    }
+    
+    pub fn is_external(&self, node: &str) -> bool {
+        self.external_nodes.contains(node)
+    }
+    
+    pub fn get_external_nodes(&self) -> &HashSet<String> {
+        &self.external_nodes
+    }
+    
+    pub fn get_internal_nodes(&self) -> Vec<String> {
+        self.nodes
+            .iter()
+            .filter(|node| !self.external_nodes.contains(*node))
+            .cloned()
+            .collect()
+    }
+    
     pub fn add_edge(&mut self, from: &str, to: &str) {
         if !self.nodes.contains(from) || !self.nodes.contains(to) {
             return;
@@ -48,6 +228,21 @@ impl DependencyGraph {
         }
     }
     
+    /// Adds an edge and remembers the import behind it; the first import of a target is kept.
+    pub fn add_edge_with_import(&mut self, from: &str, to: &str, import: EdgeImport) {
+        self.add_edge(from, to);
+        
+        if self.has_edge(from, to) {
+            self.edge_imports
+                .entry((from.to_string(), to.to_string()))
+                .or_insert(import);
+        }
+    }
+    
+    pub fn get_edge_import(&self, from: &str, to: &str) -> Option<&EdgeImport> {
+        self.edge_imports.get(&(from.to_string(), to.to_string()))
+    }
+    
     pub fn get_nodes(&self) -> &HashSet<String> {
         &self.nodes
     }
@@ -66,74 +261,104 @@ impl DependencyGraph {
         Vec::new()
     }
     
+    pub fn has_edge(&self, from: &str, to: &str) -> bool {
+        self.edges.get(from).is_some_and(|edges| edges.contains(to))
+    }
+    
+    /// Groups of files that all depend on each other, directly or transitively. Every tangle is
+    /// reported once, as its sorted members; a file that imports itself counts as a tangle too.
     pub fn find_circular_dependencies(&self) -> Vec<Vec<String>> {
-        let mut result = Vec::new();
-        let mut visited = HashSet::new();
-        let mut stack = Vec::new();
-        
-        for node in &self.nodes {
-            if !visited.contains(node) {
-                self.find_cycles(node, &mut visited, &mut stack, &mut result);
-            }
-        }
-        
-        result
+        self.strongly_connected_components()
+            .into_iter()
+            .filter(|component| component.len() > 1 || self.has_edge(&component[0], &component[0]))
+            .collect()
     }
     
-    fn find_cycles(
-        &self,
-        node: &str,
-        visited: &mut HashSet<String>,
-        stack: &mut Vec<String>,
-        result: &mut Vec<Vec<String>>,
-    ) {
-        if stack.contains(&node.to_string()) {
-            let cycle_start = stack.iter().position(|x| x == node).unwrap();
-            let cycle = stack[cycle_start..].to_vec();
-            if !result.contains(&cycle) {
-                result.push(cycle);
-            }
-            return;
-        }
+    /// Tarjan's algorithm with an explicit stack, so deep import chains cannot overflow the
+    /// call stack. Components are returned in reverse topological order with sorted members.
+    pub fn strongly_connected_components(&self) -> Vec<Vec<String>> {
+        let mut nodes: Vec<&String> = self.nodes.iter().collect();
+        nodes.sort();
         
-        if visited.contains(node) {
-            return;
-        }
+        let position: HashMap<&str, usize> = nodes
+            .iter()
+            .enumerate()
+            .map(|(i, node)| (node.as_str(), i))
+            .collect();
+        let successors: Vec<Vec<usize>> = nodes
+            .iter()
+            .map(|node| {
+                let mut next: Vec<usize> = self
+                    .edges
+                    .get(*node)
+                    .into_iter()
+                    .flatten()
+                    .filter_map(|dependency| position.get(dependency.as_str()).copied())
+                    .collect();
+                next.sort_unstable();
+                next
+            })
+            .collect();
         
-        visited.insert(node.to_string());
-        stack.push(node.to_string());
+        const UNVISITED: usize = usize::MAX;
+        let mut index = vec![UNVISITED; nodes.len()];
+        let mut lowlink = vec![0; nodes.len()];
+        let mut on_stack = vec![false; nodes.len()];
+        let mut stack = Vec::new();
+        let mut next_index = 0;
+        let mut components = Vec::new();
         
-        if let Some(dependencies) = self.edges.get(node) {
-            for dependency in dependencies {
-                self.find_cycles(dependency, visited, stack, result);
+        for start in 0..nodes.len() {
+            if index[start] != UNVISITED {
+                continue;
             }
-        }
-        
-        stack.pop();
-    }
-    
-    pub fn to_dot_format(&self) -> String {
-        let mut result = String::from("digraph DependencyGraph {\n");
-        result.push_str("  node [shape=box, style=filled, fillcolor=lightcyan];\n");
-        result.push_str("  edge [color=darkslategray];\n");
-        result.push_str("  graph [bgcolor=white];\n");
-        
-        for node in &self.nodes {
-            let node_id = node.replace(&['/', '.', '-', ' '][..], "_");
-            let node_label = node.split('/').last().unwrap_or(node);
-            result.push_str(&format!("  {} [label=\"{}\"];\n", node_id, node_label));
-        }
-        
-        for (from, to_set) in &self.edges {
-            let from_id = from.replace(&['/', '.', '-', ' '][..], "_");
             
-            for to in to_set {
-                let to_id = to.replace(&['/', '.', '-', ' '][..], "_");
-                result.push_str(&format!("  {} -> {};\n", from_id, to_id));
+            // Each frame holds a node and the position of the next successor to visit
+            let mut call_stack = vec![(start, 0usize)];
+            index[start] = next_index;
+            lowlink[start] = next_index;
+            next_index += 1;
+            stack.push(start);
+            on_stack[start] = true;
+            
+            while let Some((node, child)) = call_stack.last_mut() {
+                let node = *node;
+                
+                if let Some(&next) = successors[node].get(*child) {
+                    *child += 1;
+                    if index[next] == UNVISITED {
+                        index[next] = next_index;
+                        lowlink[next] = next_index;
+                        next_index += 1;
+                        stack.push(next);
+                        on_stack[next] = true;
+                        call_stack.push((next, 0));
+                    } else if on_stack[next] {
+                        lowlink[node] = lowlink[node].min(index[next]);
+                    }
+                    continue;
+                }
+                
+                call_stack.pop();
+                if let Some(&(parent, _)) = call_stack.last() {
+                    lowlink[parent] = lowlink[parent].min(lowlink[node]);
+                }
+                
+                if lowlink[node] == index[node] {
+                    let mut component = Vec::new();
+                    while let Some(member) = stack.pop() {
+                        on_stack[member] = false;
+                        component.push(nodes[member].clone());
+                        if member == node {
+                            break;
+                        }
+                    }
+                    component.sort();
+                    components.push(component);
+                }
             }
         }
         
-        result.push_str("}\n");
-        result
+        components
     }
-}
\ No newline at end of file
+}
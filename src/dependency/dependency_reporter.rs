@@ -1,9 +1,18 @@
+use crate::dependency::coupling::{CouplingMetrics, CouplingSort, analyze_coupling};
+use crate::dependency::cycle_analysis::{DependencyCycle, find_dependency_cycles};
 use crate::dependency::dependency_graph::DependencyGraph;
+use crate::dependency::graph_export::{GraphExporter, GraphFormat};
+use crate::dependency::layer_rules::LayerViolation;
+use crate::metrics::models::FileMetrics;
 use crate::output::style::*;
 use std::fs;
 use std::path::Path;
 
-pub struct DependencyReporter;
+pub struct DependencyReporter {
+    node_kind: &'static str,
+    coupling_sort: CouplingSort,
+    layer_violations: Option<Vec<LayerViolation>>,
+}
 
 impl Default for DependencyReporter {
     fn default() -> Self {
@@ -13,7 +22,32 @@ impl Default for DependencyReporter {
 
 impl DependencyReporter {
     pub fn new() -> Self {
-        DependencyReporter
+        DependencyReporter {
+            node_kind: "File",
+            coupling_sort: CouplingSort::default(),
+            layer_violations: None,
+        }
+    }
+    
+    /// Reporter for graphs whose nodes are Cargo crates rather than source files.
+    pub fn for_crates() -> Self {
+        DependencyReporter {
+            node_kind: "Crate",
+            coupling_sort: CouplingSort::default(),
+            layer_violations: None,
+        }
+    }
+    
+    /// Order of the coupling tables in the markdown report.
+    pub fn sort_coupling_by(mut self, sort: CouplingSort) -> Self {
+        self.coupling_sort = sort;
+        self
+    }
+    
+    /// Result of checking the graph against layering rules, reported after the cycles.
+    pub fn layer_violations(mut self, violations: Vec<LayerViolation>) -> Self {
+        self.layer_violations = Some(violations);
+        self
     }
     
     pub fn report(&self, graph: &DependencyGraph) {
@@ -31,6 +65,10 @@ impl DependencyReporter {
         }
         
         self.print_circular_dependencies(graph);
+        
+        if let Some(violations) = &self.layer_violations {
+            self.print_layer_violations(violations);
+        }
     }
     
     fn print_summary(&self, graph: &DependencyGraph) {
@@ -41,10 +79,29 @@ impl DependencyReporter {
             StyledText::new("====================").foreground(ThemeColors::SEPARATOR)
         );
         
-        let node_count = graph.get_nodes().len();
+        let summary_data = self.summary_rows(graph);
+        
+        let max_label_len = summary_data.iter().map(|(label, _)| label.len()).max().unwrap_or(0);
+        
+        for (label, value) in summary_data {
+            println!(
+                "{}{}    {}",
+                highlight(&label),
+                " ".repeat(max_label_len - label.len()),
+                StyledText::new(&value)
+                    .foreground(ThemeColors::NUMBER)
+                    .style(Style::Bold)
+            );
+        }
+    }
+    
+    fn summary_rows(&self, graph: &DependencyGraph) -> Vec<(String, String)> {
+        let internal_nodes = graph.get_internal_nodes();
+        let node_count = internal_nodes.len();
         
         let mut summary_data = Vec::new();
-        summary_data.push(("Total Files", node_count.to_string()));
+        summary_data.push((format!("Total {}s", self.node_kind), node_count.to_string()));
+        summary_data.push(("External Packages".to_string(), graph.get_external_nodes().len().to_string()));
         
         if node_count > 0 {
             let mut total_dependencies = 0;
@@ -53,7 +110,7 @@ impl DependencyReporter {
             let mut most_depended_node = String::new();
             let mut most_dependent_node = String::new();
             
-            for node in graph.get_nodes() {
+            for node in &internal_nodes {
                 let dependencies = graph.get_dependencies(node);
                 let dependents = graph.get_dependents(node);
                 
@@ -70,35 +127,27 @@ impl DependencyReporter {
                 }
             }
             
-            summary_data.push(("Average Dependencies", format!("{:.2}", total_dependencies as f64 / node_count as f64)));
+            summary_data.push((
+                "Average Dependencies".to_string(),
+                format!("{:.2}", total_dependencies as f64 / node_count as f64),
+            ));
             
             if !most_dependent_node.is_empty() {
-                summary_data.push(("Most Dependent File", most_dependent_node));
-                summary_data.push(("Dependencies Count", max_dependencies.to_string()));
+                summary_data.push((format!("Most Dependent {}", self.node_kind), most_dependent_node));
+                summary_data.push(("Dependencies Count".to_string(), max_dependencies.to_string()));
             }
             
             if !most_depended_node.is_empty() {
-                summary_data.push(("Most Depended-on File", most_depended_node));
-                summary_data.push(("Dependents Count", max_dependents.to_string()));
+                summary_data.push((format!("Most Depended-on {}", self.node_kind), most_depended_node));
+                summary_data.push(("Dependents Count".to_string(), max_dependents.to_string()));
             }
         }
         
-        let max_label_len = summary_data.iter().map(|(label, _)| label.len()).max().unwrap_or(0);
-        
-        for (label, value) in summary_data {
-            println!(
-                "{}{}    {}",
-                highlight(label),
-                " ".repeat(max_label_len - label.len()),
-                StyledText::new(&value)
-                    .foreground(ThemeColors::NUMBER)
-                    .style(Style::Bold)
-            );
-        }
+        summary_data
     }
     
     fn print_circular_dependencies(&self, graph: &DependencyGraph) {
-        let cycles = graph.find_circular_dependencies();
+        let cycles = find_dependency_cycles(graph);
         
         println!();
         print_header("Circular Dependencies:");
@@ -109,39 +158,87 @@ impl DependencyReporter {
         
         if cycles.is_empty() {
             println!("{}", StyledText::new("No circular dependencies found.").foreground(ThemeColors::LANGUAGE));
-        } else {
+            return;
+        }
+        
+        println!(
+            "{}",
+            StyledText::new(&format!("Found {} dependency tangles:", cycles.len()))
+                .foreground(Color::Yellow)
+                .style(Style::Bold)
+        );
+        println!();
+        
+        for (i, cycle) in cycles.iter().enumerate() {
             println!(
                 "{}",
-                StyledText::new(&format!("Found {} circular dependencies:", cycles.len()))
+                StyledText::new(&format!("Tangle {}: {}", i + 1, self.describe_cycle(cycle)))
                     .foreground(Color::Yellow)
                     .style(Style::Bold)
             );
-            println!();
             
-            for (i, cycle) in cycles.iter().enumerate() {
+            for member in &cycle.members {
+                println!("  {}", StyledText::new(member).foreground(ThemeColors::LABEL));
+            }
+            
+            println!("  {}", highlight("Remove to break the tangle:"));
+            for (from, to) in &cycle.suggested_removals {
                 println!(
-                    "{}",
-                    StyledText::new(&format!("Cycle {}:", i + 1))
-                        .foreground(Color::Yellow)
-                        .style(Style::Bold)
+                    "    {} → {}",
+                    StyledText::new(from).foreground(ThemeColors::LABEL),
+                    StyledText::new(to).foreground(ThemeColors::LABEL)
                 );
-                
-                for (j, node) in cycle.iter().enumerate() {
-                    if j < cycle.len() - 1 {
-                        print!("  ");
-                        print!("{}", StyledText::new(node).foreground(ThemeColors::LABEL));
-                        println!(" → ");
-                    } else {
-                        print!("  ");
-                        print!("{}", StyledText::new(node).foreground(ThemeColors::LABEL));
-                        println!(" → (back to start)");
-                    }
-                }
-                println!();
             }
+            println!();
         }
     }
     
+    fn print_layer_violations(&self, violations: &[LayerViolation]) {
+        println!();
+        print_header("Layering Violations:");
+        println!(
+            "{}",
+            StyledText::new("====================").foreground(ThemeColors::SEPARATOR)
+        );
+        
+        if violations.is_empty() {
+            println!("{}", StyledText::new("No layering violations found.").foreground(ThemeColors::LANGUAGE));
+            return;
+        }
+        
+        println!(
+            "{}",
+            StyledText::new(&format!("Found {} layering violations:", violations.len()))
+                .foreground(Color::Red)
+                .style(Style::Bold)
+        );
+        println!();
+        
+        for violation in violations {
+            println!(
+                "{} → {}",
+                StyledText::new(&format_violation_source(violation)).foreground(ThemeColors::LABEL).style(Style::Bold),
+                StyledText::new(&violation.target).foreground(ThemeColors::LABEL)
+            );
+            if let Some(import) = &violation.import {
+                println!("  {} {}", highlight("import:"), import);
+            }
+            println!("  {} {}", highlight("rule:"), violation.rule);
+            if let Some(reason) = &violation.reason {
+                println!("  {} {}", highlight("reason:"), reason);
+            }
+        }
+    }
+    
+    fn describe_cycle(&self, cycle: &DependencyCycle) -> String {
+        format!(
+            "{} {}s, {} internal edges",
+            cycle.members.len(),
+            self.node_kind.to_lowercase(),
+            cycle.internal_edges
+        )
+    }
+    
     fn print_top_dependencies(&self, graph: &DependencyGraph) {
         println!();
         print_header("Top Dependencies:");
@@ -151,7 +248,7 @@ impl DependencyReporter {
         );
         
         let mut nodes_with_counts: Vec<(String, usize, usize)> = graph
-            .get_nodes()
+            .get_internal_nodes()
             .iter()
             .map(|node| {
                 let dependencies = graph.get_dependencies(node);
@@ -174,7 +271,7 @@ impl DependencyReporter {
         }
         
         const COL_SPACING: usize = 4;
-        let file_header = "File";
+        let file_header = self.node_kind;
         let deps_header = "Dependencies";
         let dependents_header = "Dependents";
         
@@ -257,19 +354,162 @@ impl DependencyReporter {
         }
     }
     
-    pub fn export_dot<P: AsRef<Path>>(&self, graph: &DependencyGraph, output_path: P) -> Result<(), String> {
-        let dot_content = graph.to_dot_format();
+    /// Exports the graph in any supported format; `files` supplies the language and lines of
+    /// code of each file node.
+    pub fn export_graph<P: AsRef<Path>>(
+        &self,
+        graph: &DependencyGraph,
+        format: GraphFormat,
+        files: &[FileMetrics],
+        output_path: P,
+    ) -> Result<(), String> {
+        let content = GraphExporter::new(graph).file_metrics(files).render(format)?;
         let path_str = output_path.as_ref().to_str().unwrap_or("");
         
-        let final_path = match crate::output::path::resolve_output_path("dependencies", path_str, "dot") {
+        let final_path = match crate::output::path::resolve_output_path("dependencies", path_str, format.extension()) {
             Ok(p) => p,
             Err(e) => return Err(format!("Error creating output path: {}", e)),
         };
         
-        fs::write(&final_path, dot_content)
-            .map_err(|e| format!("Failed to write DOT file: {}", e))?;
+        fs::write(&final_path, content)
+            .map_err(|e| format!("Failed to write dependency graph: {}", e))?;
             
         println!("Dependency graph exported to {}", final_path.display());
         Ok(())
     }
+    
+    pub fn format_markdown(&self, graph: &DependencyGraph) -> String {
+        let mut output = String::new();
+        
+        output.push_str("# Dependency Analysis\n\n");
+        
+        output.push_str("## Summary\n\n");
+        output.push_str("| Metric | Value |\n");
+        output.push_str("|--------|-------|\n");
+        for (label, value) in self.summary_rows(graph) {
+            output.push_str(&format!("| {} | {} |\n", label, value));
+        }
+        
+        self.push_coupling_markdown(&mut output, graph);
+        
+        if let Some(violations) = &self.layer_violations {
+            push_layer_violations_markdown(&mut output, violations);
+        }
+        
+        output.push_str("\n## Circular Dependencies\n\n");
+        
+        let cycles = find_dependency_cycles(graph);
+        if cycles.is_empty() {
+            output.push_str("No circular dependencies found.\n");
+            return output;
+        }
+        
+        output.push_str(&format!("Found {} dependency tangles.\n", cycles.len()));
+        
+        for (i, cycle) in cycles.iter().enumerate() {
+            output.push_str(&format!("\n### Tangle {}: {}\n\n", i + 1, self.describe_cycle(cycle)));
+            
+            for member in &cycle.members {
+                output.push_str(&format!("- `{}`\n", member));
+            }
+            
+            output.push_str("\nRemove to break the tangle:\n\n");
+            output.push_str(&format!("| {} | Depends On |\n", self.node_kind));
+            output.push_str("|------|------------|\n");
+            for (from, to) in &cycle.suggested_removals {
+                output.push_str(&format!("| `{}` | `{}` |\n", from, to));
+            }
+        }
+        
+        output
+    }
+    
+    fn push_coupling_markdown(&self, output: &mut String, graph: &DependencyGraph) {
+        let mut coupling = analyze_coupling(graph, &|path| fs::read_to_string(path).ok());
+        if coupling.files.is_empty() {
+            return;
+        }
+        coupling.sort_by(self.coupling_sort);
+        
+        output.push_str("\n## Coupling\n\n");
+        output.push_str("Ca counts dependents outside the module, Ce the files and packages it depends on. ");
+        output.push_str("Instability is I = Ce / (Ca + Ce), abstractness A is the share of abstract types ");
+        output.push_str("(traits, interfaces, abstract classes) and D = |A + I - 1| is the distance from the main sequence.\n");
+        
+        if !coupling.directories.is_empty() {
+            output.push_str("\n### By Directory\n\n");
+            output.push_str("| Directory | Files | Ca | Ce | I | A | D |\n");
+            output.push_str("|-----------|-------|----|----|---|---|---|\n");
+            for metrics in &coupling.directories {
+                output.push_str(&format!("| {} | {} | {} |\n", metrics.name, metrics.files, format_coupling_columns(metrics)));
+            }
+        }
+        
+        output.push_str(&format!("\n### By {}\n\n", self.node_kind));
+        output.push_str(&format!("| {} | Ca | Ce | I | A | D |\n", self.node_kind));
+        output.push_str("|------|----|----|---|---|---|\n");
+        for metrics in &coupling.files {
+            output.push_str(&format!("| {} | {} |\n", metrics.name, format_coupling_columns(metrics)));
+        }
+    }
+    
+    pub fn export_markdown<P: AsRef<Path>>(&self, graph: &DependencyGraph, output_path: P) -> Result<(), String> {
+        let path_str = output_path.as_ref().to_str().unwrap_or("");
+        
+        let final_path = match crate::output::path::resolve_output_path("dependencies", path_str, "md") {
+            Ok(p) => p,
+            Err(e) => return Err(format!("Error creating output path: {}", e)),
+        };
+        
+        fs::write(&final_path, self.format_markdown(graph))
+            .map_err(|e| format!("Failed to write dependency report: {}", e))?;
+            
+        println!("Dependency report exported to {}", final_path.display());
+        Ok(())
+    }
+}
+
+fn format_coupling_columns(metrics: &CouplingMetrics) -> String {
+    let format_ratio = |value: Option<f64>| value.map_or("-".to_string(), |value| format!("{:.2}", value));
+    
+    format!(
+        "{} | {} | {:.2} | {} | {}",
+        metrics.afferent,
+        metrics.efferent,
+        metrics.instability(),
+        format_ratio(metrics.abstractness()),
+        format_ratio(metrics.distance())
+    )
+}
+
+fn format_violation_source(violation: &LayerViolation) -> String {
+    match violation.line {
+        Some(line) => format!("{}:{}", violation.source, line),
+        None => violation.source.clone(),
+    }
+}
+
+fn push_layer_violations_markdown(output: &mut String, violations: &[LayerViolation]) {
+    output.push_str("\n## Layering Violations\n\n");
+    
+    if violations.is_empty() {
+        output.push_str("No layering violations found.\n");
+        return;
+    }
+    
+    output.push_str("| Source | Target | Import | Rule |\n");
+    output.push_str("|--------|--------|--------|------|\n");
+    for violation in violations {
+        let rule = match &violation.reason {
+            Some(reason) => format!("{} ({})", violation.rule, reason),
+            None => violation.rule.clone(),
+        };
+        output.push_str(&format!(
+            "| `{}` | `{}` | {} | {} |\n",
+            format_violation_source(violation),
+            violation.target,
+            violation.import.as_deref().map_or("-".to_string(), |import| format!("`{}`", import)),
+            rule
+        ));
+    }
 }
@@ -0,0 +1,158 @@
+use crate::dependency::clike::{Token, is_ident, tokenize_with_lines};
+use crate::dependency::dependency_graph::{EdgeImport, ImportKind};
+use crate::dependency::resolver::{ResolvedDependency, SourceIndex, normalize_path};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+const GO_CODE_HOSTS: [&str; 4] = ["github.com", "gitlab.com", "bitbucket.org", "codeberg.org"];
+
+/// Returns the import paths of a Go file, from single imports and `import ( ... )` blocks.
+pub fn extract_go_dependencies(content: &str) -> Vec<EdgeImport> {
+    let (tokens, lines) = tokenize_with_lines(content);
+    let mut dependencies = Vec::new();
+    let mut i = 0;
+
+    while i < tokens.len() {
+        if !is_ident(tokens.get(i), "import") {
+            i += 1;
+            continue;
+        }
+        i += 1;
+
+        if tokens.get(i) == Some(&Token::Punct('(')) {
+            i += 1;
+            while i < tokens.len() && tokens[i] != Token::Punct(')') {
+                if let Token::Str(path) = &tokens[i] {
+                    dependencies.push(EdgeImport::new(path.clone(), lines[i], ImportKind::Import));
+                }
+                i += 1;
+            }
+        } else {
+            // Skip an optional alias, `_` or `.` before the path
+            if !matches!(tokens.get(i), Some(Token::Str(_))) {
+                i += 1;
+            }
+            if let Some(Token::Str(path)) = tokens.get(i) {
+                dependencies.push(EdgeImport::new(path.clone(), lines[i], ImportKind::Import));
+            }
+        }
+    }
+
+    dependencies
+}
+
+/// Resolves Go import paths against the `go.mod` modules in the tree; a package resolves to
+/// every non-test `.go` file in its directory.
+#[derive(Default)]
+pub struct GoModuleResolver {
+    /// Module paths and their directories, longest path first
+    modules: Vec<(String, PathBuf)>,
+    requirements: Vec<String>,
+    package_files: HashMap<PathBuf, Vec<String>>,
+}
+
+impl GoModuleResolver {
+    pub fn build(index: &SourceIndex, load_content: &dyn Fn(&str) -> Option<String>) -> Self {
+        let mut resolver = GoModuleResolver::default();
+
+        for file in index.files() {
+            let path = Path::new(file);
+            let dir = normalize_path(path.parent().unwrap_or(Path::new("")));
+            let file_name = path.file_name().and_then(|name| name.to_str()).unwrap_or("");
+
+            if file_name == "go.mod" {
+                if let Some(content) = load_content(file) {
+                    let (module, requirements) = parse_go_mod(&content);
+                    if let Some(module) = module {
+                        resolver.modules.push((module, dir));
+                    }
+                    resolver.requirements.extend(requirements);
+                }
+            } else if file_name.ends_with(".go") && !file_name.ends_with("_test.go") {
+                resolver.package_files.entry(dir).or_default().push(file.clone());
+            }
+        }
+
+        resolver.modules.sort_by_key(|(module, _)| std::cmp::Reverse(module.len()));
+        resolver.requirements.sort_by_key(|requirement| std::cmp::Reverse(requirement.len()));
+        for files in resolver.package_files.values_mut() {
+            files.sort();
+        }
+
+        resolver
+    }
+
+    pub fn resolve(&self, dependency: &str) -> Vec<ResolvedDependency> {
+        for (module, dir) in &self.modules {
+            let relative = if dependency == module {
+                ""
+            } else if let Some(rest) = dependency.strip_prefix(&format!("{}/", module)) {
+                rest
+            } else {
+                continue;
+            };
+
+            let package_dir = normalize_path(&dir.join(relative));
+            return self
+                .package_files
+                .get(&package_dir)
+                .map(|files| files.iter().cloned().map(ResolvedDependency::Internal).collect())
+                .unwrap_or_default();
+        }
+
+        let first = dependency.split('/').next().unwrap_or(dependency);
+        if !first.contains('.') {
+            // Standard library packages have no domain in their first path element
+            return Vec::new();
+        }
+
+        vec![ResolvedDependency::External(self.module_name(dependency))]
+    }
+
+    fn module_name(&self, dependency: &str) -> String {
+        if let Some(requirement) = self
+            .requirements
+            .iter()
+            .find(|requirement| dependency == *requirement || dependency.starts_with(&format!("{}/", requirement)))
+        {
+            return requirement.clone();
+        }
+
+        let segments: Vec<&str> = dependency.split('/').collect();
+        if GO_CODE_HOSTS.contains(&segments[0]) && segments.len() > 3 {
+            return segments[..3].join("/");
+        }
+
+        dependency.to_string()
+    }
+}
+
+/// Reads the module path and required modules from a `go.mod` file.
+fn parse_go_mod(content: &str) -> (Option<String>, Vec<String>) {
+    let mut module = None;
+    let mut requirements = Vec::new();
+    let mut in_require_block = false;
+
+    for line in content.lines() {
+        let line = line.split("//").next().unwrap_or("").trim();
+
+        if in_require_block {
+            if line == ")" {
+                in_require_block = false;
+            } else if let Some(path) = line.split_whitespace().next() {
+                requirements.push(path.to_string());
+            }
+        } else if let Some(rest) = line.strip_prefix("module ") {
+            module = Some(rest.trim().trim_matches('"').to_string());
+        } else if let Some(rest) = line.strip_prefix("require") {
+            let rest = rest.trim();
+            if rest == "(" {
+                in_require_block = true;
+            } else if let Some(path) = rest.split_whitespace().next() {
+                requirements.push(path.to_string());
+            }
+        }
+    }
+
+    (module, requirements)
+}
@@ -0,0 +1,184 @@
+use crate::dependency::coupling::{CouplingMetrics, analyze_coupling};
+use crate::dependency::cycle_analysis::{DependencyCycle, Edge, find_dependency_cycles};
+use crate::dependency::dependency_graph::{DependencyGraph, GraphDocument};
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::Path;
+
+/// Size of one side of a comparison.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct GraphSummary {
+    pub files: usize,
+    pub external_packages: usize,
+    pub edges: usize,
+    pub tangles: usize,
+}
+
+/// Coupling of a file or directory before and after; `None` where it does not exist.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CouplingChange {
+    pub name: String,
+    pub before: Option<CouplingMetrics>,
+    pub after: Option<CouplingMetrics>,
+}
+
+impl CouplingChange {
+    pub fn instability_delta(&self) -> f64 {
+        let instability = |metrics: &Option<CouplingMetrics>| metrics.as_ref().map_or(0.0, CouplingMetrics::instability);
+        instability(&self.after) - instability(&self.before)
+    }
+}
+
+/// How the dependency structure changed between two versions of a tree.
+#[derive(Debug, Clone, Default)]
+pub struct GraphDiff {
+    pub before: GraphSummary,
+    pub after: GraphSummary,
+    pub added_files: Vec<String>,
+    pub removed_files: Vec<String>,
+    pub added_edges: Vec<Edge>,
+    pub removed_edges: Vec<Edge>,
+    /// Tangles whose exact set of members did not exist before
+    pub new_cycles: Vec<DependencyCycle>,
+    /// Tangles whose exact set of members no longer exists
+    pub broken_cycles: Vec<DependencyCycle>,
+    /// Directories whose coupling changed, largest instability change first
+    pub directory_changes: Vec<CouplingChange>,
+    /// Files whose coupling changed, largest instability change first
+    pub file_changes: Vec<CouplingChange>,
+}
+
+impl GraphDiff {
+    pub fn is_empty(&self) -> bool {
+        self.added_edges.is_empty()
+            && self.removed_edges.is_empty()
+            && self.added_files.is_empty()
+            && self.removed_files.is_empty()
+            && self.directory_changes.is_empty()
+            && self.file_changes.is_empty()
+    }
+}
+
+/// Rewrites a graph's file nodes relative to `root`, so that graphs built from different
+/// checkouts of the same tree can be compared. External packages keep their names.
+pub fn relative_to<P: AsRef<Path>>(graph: &DependencyGraph, root: P) -> DependencyGraph {
+    let root = root.as_ref();
+    let relative = |id: &str| -> String {
+        Path::new(id)
+            .strip_prefix(root)
+            .map(|path| path.to_string_lossy().replace('\\', "/"))
+            .unwrap_or_else(|_| id.to_string())
+    };
+
+    let mut document = GraphDocument::from(graph);
+    for node in document.nodes.iter_mut().filter(|node| !node.external) {
+        node.id = relative(&node.id);
+    }
+    for edge in &mut document.edges {
+        edge.source = relative(&edge.source);
+        if !graph.is_external(&edge.target) {
+            edge.target = relative(&edge.target);
+        }
+    }
+
+    DependencyGraph::from(document)
+}
+
+/// Compares two graphs with comparable node ids (see [`relative_to`]). The loaders supply file
+/// contents on each side for the abstractness part of the coupling metrics.
+pub fn diff_graphs(
+    before: &DependencyGraph,
+    after: &DependencyGraph,
+    load_before: &dyn Fn(&str) -> Option<String>,
+    load_after: &dyn Fn(&str) -> Option<String>,
+) -> GraphDiff {
+    let before_document = GraphDocument::from(before);
+    let after_document = GraphDocument::from(after);
+
+    let before_edges: BTreeSet<Edge> = edge_set(&before_document);
+    let after_edges: BTreeSet<Edge> = edge_set(&after_document);
+    let before_files: BTreeSet<String> = before.get_internal_nodes().into_iter().collect();
+    let after_files: BTreeSet<String> = after.get_internal_nodes().into_iter().collect();
+
+    let before_cycles = find_dependency_cycles(before);
+    let after_cycles = find_dependency_cycles(after);
+    let before_members: BTreeSet<&Vec<String>> = before_cycles.iter().map(|cycle| &cycle.members).collect();
+    let after_members: BTreeSet<&Vec<String>> = after_cycles.iter().map(|cycle| &cycle.members).collect();
+
+    let before_coupling = analyze_coupling(before, load_before);
+    let after_coupling = analyze_coupling(after, load_after);
+
+    GraphDiff {
+        before: summarize(before, &before_edges, before_cycles.len()),
+        after: summarize(after, &after_edges, after_cycles.len()),
+        added_files: after_files.difference(&before_files).cloned().collect(),
+        removed_files: before_files.difference(&after_files).cloned().collect(),
+        added_edges: after_edges.difference(&before_edges).cloned().collect(),
+        removed_edges: before_edges.difference(&after_edges).cloned().collect(),
+        new_cycles: after_cycles
+            .iter()
+            .filter(|cycle| !before_members.contains(&cycle.members))
+            .cloned()
+            .collect(),
+        broken_cycles: before_cycles
+            .iter()
+            .filter(|cycle| !after_members.contains(&cycle.members))
+            .cloned()
+            .collect(),
+        directory_changes: coupling_changes(&before_coupling.directories, &after_coupling.directories),
+        file_changes: coupling_changes(&before_coupling.files, &after_coupling.files),
+    }
+}
+
+fn edge_set(document: &GraphDocument) -> BTreeSet<Edge> {
+    document
+        .edges
+        .iter()
+        .map(|edge| (edge.source.clone(), edge.target.clone()))
+        .collect()
+}
+
+fn summarize(graph: &DependencyGraph, edges: &BTreeSet<Edge>, tangles: usize) -> GraphSummary {
+    GraphSummary {
+        files: graph.get_internal_nodes().len(),
+        external_packages: graph.get_external_nodes().len(),
+        edges: edges.len(),
+        tangles,
+    }
+}
+
+fn coupling_changes(before: &[CouplingMetrics], after: &[CouplingMetrics]) -> Vec<CouplingChange> {
+    let mut modules: BTreeMap<&str, (Option<&CouplingMetrics>, Option<&CouplingMetrics>)> = BTreeMap::new();
+    for metrics in before {
+        modules.entry(&metrics.name).or_default().0 = Some(metrics);
+    }
+    for metrics in after {
+        modules.entry(&metrics.name).or_default().1 = Some(metrics);
+    }
+
+    let mut changes: Vec<CouplingChange> = modules
+        .into_iter()
+        .filter(|(_, (before, after))| match (before, after) {
+            (Some(before), Some(after)) => coupling_differs(before, after),
+            _ => true,
+        })
+        .map(|(name, (before, after))| CouplingChange {
+            name: name.to_string(),
+            before: before.cloned(),
+            after: after.cloned(),
+        })
+        .collect();
+
+    changes.sort_by(|a, b| {
+        b.instability_delta()
+            .abs()
+            .total_cmp(&a.instability_delta().abs())
+            .then_with(|| a.name.cmp(&b.name))
+    });
+    changes
+}
+
+/// Whether the coupling the diff reports, Ca, Ce and abstractness, changed. A module that only
+/// gained or lost files, or types in the same proportion, looks the same in the report.
+fn coupling_differs(before: &CouplingMetrics, after: &CouplingMetrics) -> bool {
+    before.afferent != after.afferent || before.efferent != after.efferent || before.abstractness() != after.abstractness()
+}
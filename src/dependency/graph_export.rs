@@ -0,0 +1,365 @@
+use crate::dependency::dependency_graph::{DependencyGraph, GraphDocument, GraphEdge};
+use crate::dependency::resolver::normalize_path;
+use crate::metrics::language::LanguageDetector;
+use crate::metrics::models::FileMetrics;
+use crate::output::csv;
+use crate::util::file_filter::FileFilter;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum GraphFormat {
+    #[default]
+    Dot,
+    Json,
+    GraphMl,
+    Gexf,
+    Csv,
+}
+
+impl std::str::FromStr for GraphFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "dot" => Ok(GraphFormat::Dot),
+            "json" => Ok(GraphFormat::Json),
+            "graphml" => Ok(GraphFormat::GraphMl),
+            "gexf" => Ok(GraphFormat::Gexf),
+            "csv" => Ok(GraphFormat::Csv),
+            _ => Err(format!(
+                "Invalid graph format: {}. Valid formats are: dot, json, graphml, gexf, csv",
+                s
+            )),
+        }
+    }
+}
+
+impl GraphFormat {
+    pub fn extension(&self) -> &'static str {
+        match self {
+            GraphFormat::Dot => "dot",
+            GraphFormat::Json => "json",
+            GraphFormat::GraphMl => "graphml",
+            GraphFormat::Gexf => "gexf",
+            GraphFormat::Csv => "csv",
+        }
+    }
+}
+
+/// A node with the attributes exported alongside it.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ExportedNode {
+    pub id: String,
+    pub external: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub language: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub lines_of_code: Option<usize>,
+    pub test: bool,
+}
+
+#[derive(Debug, Clone, Serialize)]
+struct ExportedGraph<'a> {
+    directed: bool,
+    nodes: &'a [ExportedNode],
+    edges: &'a [GraphEdge],
+}
+
+/// Writes a dependency graph in formats other tools can load: DOT (GraphViz), JSON, GraphML
+/// (yEd), GEXF (Gephi) and a CSV edge list. Node ids are the graph's paths and names, unchanged.
+pub struct GraphExporter {
+    nodes: Vec<ExportedNode>,
+    edges: Vec<GraphEdge>,
+}
+
+impl GraphExporter {
+    pub fn new(graph: &DependencyGraph) -> Self {
+        let document = GraphDocument::from(graph);
+        let detector = LanguageDetector::new();
+
+        let nodes = document
+            .nodes
+            .into_iter()
+            .map(|node| {
+                let extension = Path::new(&node.id).extension().and_then(|extension| extension.to_str());
+                let language = match extension {
+                    Some(extension) if !node.external => Some(detector.detect_language(extension)),
+                    _ => None,
+                };
+
+                ExportedNode {
+                    test: !node.external && FileFilter::is_test_file(&node.id),
+                    language: language.filter(|language| language != "Other"),
+                    lines_of_code: None,
+                    external: node.external,
+                    id: node.id,
+                }
+            })
+            .collect();
+
+        GraphExporter {
+            nodes,
+            edges: document.edges,
+        }
+    }
+
+    /// Takes each file's language and lines of code from the metrics collector.
+    pub fn file_metrics(mut self, files: &[FileMetrics]) -> Self {
+        let metrics_by_path: HashMap<PathBuf, &FileMetrics> = files
+            .iter()
+            .map(|metrics| (normalize_path(Path::new(&metrics.path)), metrics))
+            .collect();
+
+        for node in self.nodes.iter_mut().filter(|node| !node.external) {
+            if let Some(metrics) = metrics_by_path.get(&normalize_path(Path::new(&node.id))) {
+                node.language = Some(metrics.language.clone());
+                node.lines_of_code = Some(metrics.lines_of_code);
+            }
+        }
+
+        self
+    }
+
+    pub fn render(&self, format: GraphFormat) -> Result<String, String> {
+        match format {
+            GraphFormat::Dot => Ok(self.to_dot()),
+            GraphFormat::Json => self.to_json(),
+            GraphFormat::GraphMl => Ok(self.to_graphml()),
+            GraphFormat::Gexf => Ok(self.to_gexf()),
+            GraphFormat::Csv => Ok(self.to_csv()),
+        }
+    }
+
+    /// Quoted node ids with the short name as label; language, lines of code and the test flag
+    /// are node attributes and the import kind, specifier and line are edge attributes.
+    pub fn to_dot(&self) -> String {
+        let mut output = String::from("digraph DependencyGraph {\n");
+        output.push_str("  node [shape=box, style=filled, fillcolor=lightcyan];\n");
+        output.push_str("  edge [color=darkslategray];\n");
+        output.push_str("  graph [bgcolor=white];\n");
+
+        for node in &self.nodes {
+            let mut attributes = Vec::new();
+            if node.external {
+                attributes.push(format!("label={}", dot_string(&node.id)));
+                attributes.push("shape=ellipse".to_string());
+                attributes.push("fillcolor=lightgrey".to_string());
+                attributes.push("external=true".to_string());
+            } else {
+                attributes.push(format!("label={}", dot_string(label(&node.id))));
+            }
+            if let Some(language) = &node.language {
+                attributes.push(format!("language={}", dot_string(language)));
+            }
+            if let Some(lines_of_code) = node.lines_of_code {
+                attributes.push(format!("lines_of_code={}", lines_of_code));
+            }
+            attributes.push(format!("test={}", node.test));
+            output.push_str(&format!("  {} [{}];\n", dot_string(&node.id), attributes.join(", ")));
+        }
+
+        for edge in &self.edges {
+            let mut attributes = Vec::new();
+            if let Some(import) = &edge.import {
+                if let Some(kind) = import.kind {
+                    attributes.push(format!("kind={}", dot_string(kind.as_str())));
+                }
+                attributes.push(format!("specifier={}", dot_string(&import.specifier)));
+                if let Some(line) = import.line {
+                    attributes.push(format!("line={}", line));
+                }
+            }
+            let attributes = if attributes.is_empty() { String::new() } else { format!(" [{}]", attributes.join(", ")) };
+            output.push_str(&format!("  {} -> {}{};\n", dot_string(&edge.source), dot_string(&edge.target), attributes));
+        }
+
+        output.push_str("}\n");
+        output
+    }
+
+    pub fn to_json(&self) -> Result<String, String> {
+        let graph = ExportedGraph {
+            directed: true,
+            nodes: &self.nodes,
+            edges: &self.edges,
+        };
+
+        serde_json::to_string_pretty(&graph).map_err(|e| format!("Failed to serialize dependency graph: {}", e))
+    }
+
+    pub fn to_graphml(&self) -> String {
+        let mut output = String::new();
+
+        output.push_str("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+        output.push_str("<graphml xmlns=\"http://graphml.graphdrawing.org/xmlns\" ");
+        output.push_str("xmlns:xsi=\"http://www.w3.org/2001/XMLSchema-instance\" ");
+        output.push_str("xsi:schemaLocation=\"http://graphml.graphdrawing.org/xmlns http://graphml.graphdrawing.org/xmlns/1.0/graphml.xsd\">\n");
+
+        for (id, domain, name, kind) in [
+            ("label", "node", "label", "string"),
+            ("external", "node", "external", "boolean"),
+            ("language", "node", "language", "string"),
+            ("loc", "node", "lines_of_code", "int"),
+            ("test", "node", "test", "boolean"),
+            ("kind", "edge", "kind", "string"),
+            ("specifier", "edge", "specifier", "string"),
+            ("line", "edge", "line", "int"),
+        ] {
+            output.push_str(&format!(
+                "  <key id=\"{}\" for=\"{}\" attr.name=\"{}\" attr.type=\"{}\"/>\n",
+                id, domain, name, kind
+            ));
+        }
+
+        output.push_str("  <graph id=\"dependencies\" edgedefault=\"directed\">\n");
+
+        for node in &self.nodes {
+            output.push_str(&format!("    <node id=\"{}\">\n", escape_xml(&node.id)));
+            output.push_str(&format!("      <data key=\"label\">{}</data>\n", escape_xml(label(&node.id))));
+            output.push_str(&format!("      <data key=\"external\">{}</data>\n", node.external));
+            if let Some(language) = &node.language {
+                output.push_str(&format!("      <data key=\"language\">{}</data>\n", escape_xml(language)));
+            }
+            if let Some(lines_of_code) = node.lines_of_code {
+                output.push_str(&format!("      <data key=\"loc\">{}</data>\n", lines_of_code));
+            }
+            output.push_str(&format!("      <data key=\"test\">{}</data>\n", node.test));
+            output.push_str("    </node>\n");
+        }
+
+        for (index, edge) in self.edges.iter().enumerate() {
+            output.push_str(&format!(
+                "    <edge id=\"e{}\" source=\"{}\" target=\"{}\">\n",
+                index,
+                escape_xml(&edge.source),
+                escape_xml(&edge.target)
+            ));
+            if let Some(import) = &edge.import {
+                if let Some(kind) = import.kind {
+                    output.push_str(&format!("      <data key=\"kind\">{}</data>\n", kind.as_str()));
+                }
+                output.push_str(&format!("      <data key=\"specifier\">{}</data>\n", escape_xml(&import.specifier)));
+                if let Some(line) = import.line {
+                    output.push_str(&format!("      <data key=\"line\">{}</data>\n", line));
+                }
+            }
+            output.push_str("    </edge>\n");
+        }
+
+        output.push_str("  </graph>\n");
+        output.push_str("</graphml>\n");
+        output
+    }
+
+    pub fn to_gexf(&self) -> String {
+        let mut output = String::new();
+
+        output.push_str("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+        output.push_str("<gexf xmlns=\"http://gexf.net/1.3\" version=\"1.3\">\n");
+        output.push_str("  <graph mode=\"static\" defaultedgetype=\"directed\">\n");
+
+        output.push_str("    <attributes class=\"node\">\n");
+        output.push_str("      <attribute id=\"external\" title=\"external\" type=\"boolean\"/>\n");
+        output.push_str("      <attribute id=\"language\" title=\"language\" type=\"string\"/>\n");
+        output.push_str("      <attribute id=\"loc\" title=\"lines_of_code\" type=\"integer\"/>\n");
+        output.push_str("      <attribute id=\"test\" title=\"test\" type=\"boolean\"/>\n");
+        output.push_str("    </attributes>\n");
+        output.push_str("    <attributes class=\"edge\">\n");
+        output.push_str("      <attribute id=\"kind\" title=\"kind\" type=\"string\"/>\n");
+        output.push_str("      <attribute id=\"specifier\" title=\"specifier\" type=\"string\"/>\n");
+        output.push_str("      <attribute id=\"line\" title=\"line\" type=\"integer\"/>\n");
+        output.push_str("    </attributes>\n");
+
+        output.push_str("    <nodes>\n");
+        for node in &self.nodes {
+            output.push_str(&format!(
+                "      <node id=\"{}\" label=\"{}\">\n",
+                escape_xml(&node.id),
+                escape_xml(label(&node.id))
+            ));
+            output.push_str("        <attvalues>\n");
+            output.push_str(&format!("          <attvalue for=\"external\" value=\"{}\"/>\n", node.external));
+            if let Some(language) = &node.language {
+                output.push_str(&format!("          <attvalue for=\"language\" value=\"{}\"/>\n", escape_xml(language)));
+            }
+            if let Some(lines_of_code) = node.lines_of_code {
+                output.push_str(&format!("          <attvalue for=\"loc\" value=\"{}\"/>\n", lines_of_code));
+            }
+            output.push_str(&format!("          <attvalue for=\"test\" value=\"{}\"/>\n", node.test));
+            output.push_str("        </attvalues>\n");
+            output.push_str("      </node>\n");
+        }
+        output.push_str("    </nodes>\n");
+
+        output.push_str("    <edges>\n");
+        for (index, edge) in self.edges.iter().enumerate() {
+            let edge_open = format!(
+                "      <edge id=\"{}\" source=\"{}\" target=\"{}\"",
+                index,
+                escape_xml(&edge.source),
+                escape_xml(&edge.target)
+            );
+            let Some(import) = &edge.import else {
+                output.push_str(&format!("{}/>\n", edge_open));
+                continue;
+            };
+
+            output.push_str(&format!("{}>\n", edge_open));
+            output.push_str("        <attvalues>\n");
+            if let Some(kind) = import.kind {
+                output.push_str(&format!("          <attvalue for=\"kind\" value=\"{}\"/>\n", kind.as_str()));
+            }
+            output.push_str(&format!("          <attvalue for=\"specifier\" value=\"{}\"/>\n", escape_xml(&import.specifier)));
+            if let Some(line) = import.line {
+                output.push_str(&format!("          <attvalue for=\"line\" value=\"{}\"/>\n", line));
+            }
+            output.push_str("        </attvalues>\n");
+            output.push_str("      </edge>\n");
+        }
+        output.push_str("    </edges>\n");
+
+        output.push_str("  </graph>\n");
+        output.push_str("</gexf>\n");
+        output
+    }
+
+    /// One row per edge: `source,target,kind,specifier,line`.
+    pub fn to_csv(&self) -> String {
+        let mut output = String::from("source,target,kind,specifier,line\n");
+
+        for edge in &self.edges {
+            let import = edge.import.as_ref();
+            let row = [
+                edge.source.clone(),
+                edge.target.clone(),
+                import.and_then(|import| import.kind).map(|kind| kind.as_str().to_string()).unwrap_or_default(),
+                import.map(|import| import.specifier.clone()).unwrap_or_default(),
+                import.and_then(|import| import.line).map(|line| line.to_string()).unwrap_or_default(),
+            ];
+            output.push_str(&csv::row(&row));
+        }
+
+        output
+    }
+}
+
+fn label(id: &str) -> &str {
+    id.rsplit('/').next().unwrap_or(id)
+}
+
+/// A quoted DOT string, so that ids and values are written unchanged whatever characters they
+/// contain.
+fn dot_string(value: &str) -> String {
+    format!("\"{}\"", value.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+fn escape_xml(value: &str) -> String {
+    value
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&apos;")
+}
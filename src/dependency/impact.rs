@@ -0,0 +1,155 @@
+use crate::dependency::dependency_graph::DependencyGraph;
+use crate::util::file_filter::FileFilter;
+use crate::util::paths::display_path;
+use serde::Serialize;
+use std::collections::{BTreeSet, HashMap, HashSet, VecDeque};
+use std::fs;
+use std::path::PathBuf;
+
+/// A file reached from the changed files, with the number of edges followed to reach it.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ImpactedFile {
+    pub path: String,
+    pub depth: usize,
+    pub test: bool,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct ImpactReport {
+    /// Requested files that are part of the dependency graph
+    pub changed: Vec<String>,
+    /// Requested files the graph does not know, such as deleted files or documentation
+    pub not_in_graph: Vec<String>,
+    /// Files that depend on a changed file, directly or transitively
+    pub dependents: Vec<ImpactedFile>,
+    /// Files and packages a changed file depends on; empty unless requested
+    pub dependencies: Vec<ImpactedFile>,
+    /// Test files among the changed files and their dependents
+    pub affected_tests: Vec<String>,
+}
+
+impl ImpactReport {
+    /// Changed files followed by everything reached from them, without duplicates.
+    pub fn affected_files(&self) -> Vec<String> {
+        let mut seen = HashSet::new();
+        self.changed
+            .iter()
+            .chain(self.dependents.iter().map(|file| &file.path))
+            .chain(self.dependencies.iter().map(|file| &file.path))
+            .filter(|path| seen.insert(path.as_str()))
+            .cloned()
+            .collect()
+    }
+}
+
+/// Computes the blast radius of a change set over a dependency graph.
+pub struct ImpactQuery {
+    max_depth: Option<usize>,
+    include_dependencies: bool,
+}
+
+impl Default for ImpactQuery {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ImpactQuery {
+    pub fn new() -> Self {
+        ImpactQuery {
+            max_depth: None,
+            include_dependencies: false,
+        }
+    }
+
+    /// Stop following edges after this many steps; unlimited when `None`.
+    pub fn max_depth(mut self, max_depth: Option<usize>) -> Self {
+        self.max_depth = max_depth;
+        self
+    }
+
+    pub fn include_dependencies(mut self, include: bool) -> Self {
+        self.include_dependencies = include;
+        self
+    }
+
+    /// `paths` may be relative to the working directory or absolute; they are matched against
+    /// the graph's files by their canonical location.
+    pub fn run(&self, graph: &DependencyGraph, paths: &[PathBuf]) -> ImpactReport {
+        let nodes_by_location: HashMap<PathBuf, String> = graph
+            .get_internal_nodes()
+            .into_iter()
+            .filter_map(|node| fs::canonicalize(&node).ok().map(|location| (location, node)))
+            .collect();
+
+        let mut changed = BTreeSet::new();
+        let mut not_in_graph = BTreeSet::new();
+        for path in paths {
+            match fs::canonicalize(path).ok().and_then(|location| nodes_by_location.get(&location)) {
+                Some(node) => {
+                    changed.insert(node.clone());
+                }
+                None => {
+                    not_in_graph.insert(display_path(&path.to_string_lossy()));
+                }
+            }
+        }
+        let changed: Vec<String> = changed.into_iter().collect();
+
+        let dependents = self.walk(graph, &changed, |node| graph.get_dependents(node));
+        let dependencies = if self.include_dependencies {
+            self.walk(graph, &changed, |node| graph.get_dependencies(node))
+        } else {
+            Vec::new()
+        };
+
+        let mut affected_tests: Vec<String> = changed
+            .iter()
+            .filter(|node| FileFilter::is_test_file(node))
+            .map(|node| display_path(node))
+            .chain(dependents.iter().filter(|file| file.test).map(|file| file.path.clone()))
+            .collect();
+        affected_tests.sort();
+        affected_tests.dedup();
+
+        ImpactReport {
+            changed: changed.iter().map(|node| display_path(node)).collect(),
+            not_in_graph: not_in_graph.into_iter().collect(),
+            dependents,
+            dependencies,
+            affected_tests,
+        }
+    }
+
+    /// Breadth-first walk from the changed files, recording the shortest depth of each file.
+    fn walk(&self, graph: &DependencyGraph, starts: &[String], next: impl Fn(&str) -> Vec<String>) -> Vec<ImpactedFile> {
+        let mut depths: HashMap<String, usize> = starts.iter().map(|node| (node.clone(), 0)).collect();
+        let mut pending: VecDeque<String> = starts.iter().cloned().collect();
+
+        while let Some(node) = pending.pop_front() {
+            let depth = depths[&node];
+            if self.max_depth.is_some_and(|max_depth| depth >= max_depth) {
+                continue;
+            }
+
+            for neighbour in next(&node) {
+                if !depths.contains_key(&neighbour) {
+                    depths.insert(neighbour.clone(), depth + 1);
+                    pending.push_back(neighbour);
+                }
+            }
+        }
+
+        let mut files: Vec<ImpactedFile> = depths
+            .into_iter()
+            .filter(|(_, depth)| *depth > 0)
+            .map(|(node, depth)| ImpactedFile {
+                test: !graph.is_external(&node) && FileFilter::is_test_file(&node),
+                path: display_path(&node),
+                depth,
+            })
+            .collect();
+        files.sort_by(|a, b| a.depth.cmp(&b.depth).then_with(|| a.path.cmp(&b.path)));
+        files
+    }
+}
@@ -0,0 +1,118 @@
+use crate::dependency::clike::{Token, is_ident, read_dotted_name, tokenize_with_lines};
+use crate::dependency::dependency_graph::{EdgeImport, ImportKind};
+use crate::dependency::resolver::{ResolvedDependency, SourceIndex};
+use std::collections::HashMap;
+use std::path::Path;
+
+const JAVA_PLATFORM_PREFIXES: [&str; 5] = ["java.", "javax.", "jdk.", "sun.", "com.sun."];
+const REVERSED_DOMAIN_ROOTS: [&str; 6] = ["com", "org", "io", "net", "dev", "edu"];
+
+/// The `package` declaration and `import` targets of a Java file. Static imports keep their
+/// member name and wildcard imports end in `.*`.
+#[derive(Debug, Default, PartialEq)]
+pub struct JavaSourceItems {
+    pub package: Option<String>,
+    pub imports: Vec<EdgeImport>,
+}
+
+pub fn parse_java_source(content: &str) -> JavaSourceItems {
+    let (tokens, lines) = tokenize_with_lines(content);
+    let mut items = JavaSourceItems::default();
+    let mut i = 0;
+
+    while i < tokens.len() {
+        let statement_start = i == 0 || matches!(tokens[i - 1], Token::Punct(';') | Token::Punct('}'));
+
+        if statement_start && is_ident(tokens.get(i), "package") {
+            i += 1;
+            items.package = read_dotted_name(&tokens, &mut i);
+        } else if statement_start && is_ident(tokens.get(i), "import") {
+            let line = lines[i];
+            i += 1;
+            if is_ident(tokens.get(i), "static") {
+                i += 1;
+            }
+            if let Some(name) = read_dotted_name(&tokens, &mut i) {
+                items.imports.push(EdgeImport::new(name, line, ImportKind::Import));
+            }
+        } else {
+            i += 1;
+        }
+    }
+
+    items
+}
+
+pub fn extract_java_dependencies(content: &str) -> Vec<EdgeImport> {
+    parse_java_source(content).imports
+}
+
+/// Maps fully-qualified Java class names to the files that declare them, using each file's
+/// `package` declaration so that any source root layout (`src/main/java`, `src`, ...) works.
+#[derive(Default)]
+pub struct JavaPackageResolver {
+    classes: HashMap<String, String>,
+    packages: HashMap<String, Vec<String>>,
+}
+
+impl JavaPackageResolver {
+    pub fn build(index: &SourceIndex, load_content: &dyn Fn(&str) -> Option<String>) -> Self {
+        let mut resolver = JavaPackageResolver::default();
+
+        for file in index.files().filter(|file| file.ends_with(".java")) {
+            let Some(content) = load_content(file) else {
+                continue;
+            };
+            let package = parse_java_source(&content).package.unwrap_or_default();
+            let class_name = Path::new(file).file_stem().and_then(|stem| stem.to_str()).unwrap_or("");
+
+            let qualified_name = if package.is_empty() {
+                class_name.to_string()
+            } else {
+                format!("{}.{}", package, class_name)
+            };
+
+            resolver.classes.insert(qualified_name, file.clone());
+            resolver.packages.entry(package).or_default().push(file.clone());
+        }
+
+        for files in resolver.packages.values_mut() {
+            files.sort();
+        }
+
+        resolver
+    }
+
+    pub fn resolve(&self, dependency: &str) -> Vec<ResolvedDependency> {
+        if let Some(package) = dependency.strip_suffix(".*")
+            && let Some(files) = self.packages.get(package)
+        {
+            return files.iter().cloned().map(ResolvedDependency::Internal).collect();
+        }
+
+        // Static imports and nested classes name members of the class declared by the file
+        let mut name = dependency.trim_end_matches(".*");
+        loop {
+            if let Some(file) = self.classes.get(name) {
+                return vec![ResolvedDependency::Internal(file.clone())];
+            }
+            match name.rsplit_once('.') {
+                Some((parent, _)) => name = parent,
+                None => break,
+            }
+        }
+
+        if JAVA_PLATFORM_PREFIXES.iter().any(|prefix| dependency.starts_with(prefix)) {
+            return Vec::new();
+        }
+
+        vec![ResolvedDependency::External(library_name(dependency))]
+    }
+}
+
+/// Groups an external package by its owner, e.g. `org.junit.jupiter.api.Test` → `org.junit.jupiter`.
+fn library_name(dependency: &str) -> String {
+    let segments: Vec<&str> = dependency.split('.').filter(|segment| *segment != "*").collect();
+    let take = if REVERSED_DOMAIN_ROOTS.contains(&segments[0]) { 3 } else { 2 };
+    segments[..take.min(segments.len().saturating_sub(1)).max(1)].join(".")
+}
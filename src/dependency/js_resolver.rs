@@ -0,0 +1,585 @@
+use crate::dependency::clike::count_newlines;
+use crate::dependency::dependency_graph::{EdgeImport, ImportKind};
+use crate::dependency::resolver::{ResolvedDependency, SourceIndex, js_package_name, normalize_path};
+use crate::util::file_filter::glob_match_path;
+use serde_json::Value;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+const JS_EXTENSIONS: [&str; 10] = ["", ".ts", ".tsx", ".d.ts", ".js", ".jsx", ".mjs", ".cjs", ".mts", ".cts"];
+const JS_INDEX_FILES: [&str; 7] = ["/index.ts", "/index.tsx", "/index.d.ts", "/index.js", "/index.jsx", "/index.mjs", "/index.cjs"];
+const EXPORT_CONDITIONS: [&str; 7] = ["types", "import", "module", "default", "require", "node", "browser"];
+const BUILD_OUTPUT_DIRS: [&str; 4] = ["dist/", "lib/", "build/", "out/"];
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Ident(String),
+    Str(String),
+    Punct(char),
+}
+
+/// Returns every module specifier imported by a JavaScript or TypeScript file: static and
+/// side-effect imports, `export ... from`, dynamic `import()` and `require()` calls.
+pub fn extract_js_dependencies(content: &str) -> Vec<EdgeImport> {
+    let (tokens, lines) = tokenize(content);
+    let mut dependencies = Vec::new();
+
+    for (i, token) in tokens.iter().enumerate() {
+        let Token::Ident(word) = token else {
+            continue;
+        };
+        if i > 0 && tokens[i - 1] == Token::Punct('.') {
+            continue;
+        }
+
+        let found = match word.as_str() {
+            "import" if tokens.get(i + 1) == Some(&Token::Punct('(')) => {
+                parse_call_argument(&tokens, i + 1).map(|index| (index, ImportKind::DynamicImport))
+            }
+            "import" => parse_import(&tokens, i + 1).map(|index| (index, ImportKind::Import)),
+            "export" => parse_export(&tokens, i + 1).map(|index| (index, ImportKind::ReExport)),
+            "require" => parse_call_argument(&tokens, i + 1).map(|index| (index, ImportKind::Require)),
+            _ => None,
+        };
+
+        if let Some((index, kind)) = found
+            && let Token::Str(specifier) = &tokens[index]
+            && !specifier.is_empty()
+        {
+            dependencies.push(EdgeImport::new(specifier.clone(), lines[index], kind));
+        }
+    }
+
+    dependencies
+}
+
+/// The parsers below return the index of the specifier's string token.
+fn parse_import(tokens: &[Token], start: usize) -> Option<usize> {
+    match tokens.get(start)? {
+        Token::Str(_) => Some(start),
+        _ => find_from_clause(tokens, start),
+    }
+}
+
+fn parse_export(tokens: &[Token], start: usize) -> Option<usize> {
+    let mut i = start;
+    if tokens.get(i) == Some(&Token::Ident("type".to_string())) {
+        i += 1;
+    }
+
+    match tokens.get(i)? {
+        Token::Punct('{') | Token::Punct('*') => find_from_clause(tokens, i),
+        _ => None,
+    }
+}
+
+/// Walks an import/export clause such as `{ a, b as c }` or `* as ns` up to its `from '...'`.
+fn find_from_clause(tokens: &[Token], start: usize) -> Option<usize> {
+    for i in start..tokens.len() {
+        match &tokens[i] {
+            Token::Ident(word) if word == "from" => {
+                if let Some(Token::Str(_)) = tokens.get(i + 1) {
+                    return Some(i + 1);
+                }
+            }
+            Token::Ident(_) | Token::Punct('{') | Token::Punct('}') | Token::Punct(',') | Token::Punct('*') => {}
+            _ => return None,
+        }
+    }
+
+    None
+}
+
+fn parse_call_argument(tokens: &[Token], start: usize) -> Option<usize> {
+    match (tokens.get(start), tokens.get(start + 1)) {
+        (Some(Token::Punct('(')), Some(Token::Str(_))) => Some(start + 1),
+        _ => None,
+    }
+}
+
+fn tokenize(content: &str) -> (Vec<Token>, Vec<usize>) {
+    let chars: Vec<char> = content.chars().collect();
+    let mut tokens: Vec<Token> = Vec::new();
+    let mut lines = Vec::new();
+    let mut line = 1;
+    let mut i = 0;
+
+    while i < chars.len() {
+        let token_start = i;
+        let c = chars[i];
+
+        if c.is_whitespace() {
+            i += 1;
+        } else if c == '/' && chars.get(i + 1) == Some(&'/') {
+            while i < chars.len() && chars[i] != '\n' {
+                i += 1;
+            }
+        } else if c == '/' && chars.get(i + 1) == Some(&'*') {
+            i += 2;
+            while i < chars.len() && !(chars[i] == '*' && chars.get(i + 1) == Some(&'/')) {
+                i += 1;
+            }
+            i += 2;
+        } else if c == '/' && regex_allowed(tokens.last()) {
+            i = skip_regex(&chars, i);
+        } else if c == '"' || c == '\'' {
+            let (value, next) = read_string(&chars, i, c);
+            tokens.push(Token::Str(value));
+            i = next;
+        } else if c == '`' {
+            let (value, next) = read_string(&chars, i, '`');
+            // Template literals only count as specifiers when they contain no substitutions
+            if value.contains("${") {
+                tokens.push(Token::Punct('`'));
+            } else {
+                tokens.push(Token::Str(value));
+            }
+            i = next;
+        } else if c.is_alphabetic() || c == '_' || c == '$' {
+            let start = i;
+            while i < chars.len() && (chars[i].is_alphanumeric() || chars[i] == '_' || chars[i] == '$') {
+                i += 1;
+            }
+            tokens.push(Token::Ident(chars[start..i].iter().collect()));
+        } else {
+            tokens.push(Token::Punct(c));
+            i += 1;
+        }
+
+        lines.resize(tokens.len(), line);
+        line += count_newlines(&chars[token_start..i.min(chars.len())]);
+    }
+
+    (tokens, lines)
+}
+
+/// A `/` starts a regular expression literal unless it follows a value, where it is division.
+fn regex_allowed(previous: Option<&Token>) -> bool {
+    match previous {
+        None => true,
+        Some(Token::Punct(c)) => !matches!(c, ')' | ']' | '}'),
+        Some(Token::Ident(word)) => matches!(word.as_str(), "return" | "typeof" | "case" | "in" | "of" | "delete" | "void" | "throw"),
+        Some(Token::Str(_)) => false,
+    }
+}
+
+fn skip_regex(chars: &[char], start: usize) -> usize {
+    let mut i = start + 1;
+    let mut in_class = false;
+
+    while i < chars.len() && chars[i] != '\n' {
+        match chars[i] {
+            '\\' => i += 1,
+            '[' => in_class = true,
+            ']' => in_class = false,
+            '/' if !in_class => return i + 1,
+            _ => {}
+        }
+        i += 1;
+    }
+
+    i
+}
+
+fn read_string(chars: &[char], start: usize, quote: char) -> (String, usize) {
+    let mut value = String::new();
+    let mut i = start + 1;
+
+    while i < chars.len() && chars[i] != quote {
+        if chars[i] == '\\' && i + 1 < chars.len() {
+            i += 1;
+        } else if chars[i] == '\n' && quote != '`' {
+            break;
+        }
+        value.push(chars[i]);
+        i += 1;
+    }
+
+    (value, i + 1)
+}
+
+#[derive(Debug, Clone)]
+struct TsConfig {
+    dir: PathBuf,
+    base_url: Option<PathBuf>,
+    /// `paths` entries as (pattern, targets), with targets already joined to their base directory
+    paths: Vec<(String, Vec<PathBuf>)>,
+}
+
+/// Module resolution for JavaScript and TypeScript following `tsconfig.json`/`jsconfig.json`
+/// path aliases and the `package.json` of every workspace package in the tree.
+pub struct JsModuleResolver {
+    ts_configs: Vec<TsConfig>,
+    /// Every `package.json` in the tree, keyed by its directory
+    manifests: HashMap<PathBuf, Value>,
+    /// Directories of the packages that can be imported by name
+    packages: HashMap<String, PathBuf>,
+}
+
+impl JsModuleResolver {
+    pub fn build(index: &SourceIndex, load_content: &dyn Fn(&str) -> Option<String>) -> Self {
+        let mut config_files = Vec::new();
+        let mut manifests = Vec::new();
+
+        for file in index.files() {
+            let path = Path::new(file);
+            if path.components().any(|component| component.as_os_str() == "node_modules") {
+                continue;
+            }
+
+            match path.file_name().and_then(|name| name.to_str()) {
+                Some("tsconfig.json") | Some("jsconfig.json") => config_files.push(file.clone()),
+                Some("package.json") => {
+                    if let Some(manifest) = load_content(file).and_then(|content| parse_jsonc(&content)) {
+                        manifests.push((normalize_path(path.parent().unwrap_or(Path::new(""))), manifest));
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        let mut ts_configs: Vec<TsConfig> = config_files
+            .iter()
+            .filter_map(|file| load_ts_config(Path::new(file), load_content, 0))
+            .collect();
+        ts_configs.sort_by_key(|config| std::cmp::Reverse(config.dir.components().count()));
+
+        JsModuleResolver {
+            ts_configs,
+            packages: collect_workspace_packages(&manifests),
+            manifests: manifests.into_iter().collect(),
+        }
+    }
+
+    pub fn resolve(&self, index: &SourceIndex, source_file: &str, dependency: &str) -> Option<ResolvedDependency> {
+        if dependency.starts_with('.') || dependency.starts_with('/') {
+            let base = if dependency.starts_with('/') {
+                index.root().join(dependency.trim_start_matches('/'))
+            } else {
+                Path::new(source_file).parent().unwrap_or(Path::new("")).join(dependency)
+            };
+
+            return self.resolve_path(index, &base).map(ResolvedDependency::Internal);
+        }
+
+        if let Some(config) = self.find_ts_config(source_file) {
+            if let Some(found) = self.resolve_ts_paths(index, config, dependency) {
+                return Some(ResolvedDependency::Internal(found));
+            }
+            if let Some(base_url) = &config.base_url
+                && let Some(found) = self.resolve_path(index, &base_url.join(dependency))
+            {
+                return Some(ResolvedDependency::Internal(found));
+            }
+        }
+
+        let package_name = js_package_name(dependency);
+        if let Some(package_dir) = self.packages.get(&package_name) {
+            let subpath = dependency[package_name.len()..].trim_start_matches('/');
+            if let Some(found) = self.resolve_package(index, package_dir, subpath) {
+                return Some(ResolvedDependency::Internal(found));
+            }
+        }
+
+        Some(ResolvedDependency::External(package_name))
+    }
+
+    fn find_ts_config(&self, source_file: &str) -> Option<&TsConfig> {
+        let source = normalize_path(Path::new(source_file));
+        self.ts_configs.iter().find(|config| source.starts_with(&config.dir))
+    }
+
+    /// Probes a path as a file (with and without extensions) and then as a directory.
+    fn resolve_path(&self, index: &SourceIndex, base: &Path) -> Option<String> {
+        if let Some(found) = resolve_file(index, base) {
+            return Some(found);
+        }
+
+        if let Some(manifest) = self.manifests.get(&normalize_path(base)) {
+            for field in ["source", "types", "typings", "module", "main"] {
+                if let Some(target) = manifest.get(field).and_then(Value::as_str)
+                    && let Some(found) = resolve_file(index, &base.join(target))
+                {
+                    return Some(found);
+                }
+            }
+        }
+
+        index.probe(base, &JS_INDEX_FILES).map(String::from)
+    }
+
+    fn resolve_ts_paths(&self, index: &SourceIndex, config: &TsConfig, dependency: &str) -> Option<String> {
+        let mut best: Option<(usize, &Vec<PathBuf>, String)> = None;
+
+        for (pattern, targets) in &config.paths {
+            let (matched_len, captured) = match pattern.split_once('*') {
+                Some((prefix, suffix)) => {
+                    if dependency.len() < prefix.len() + suffix.len()
+                        || !dependency.starts_with(prefix)
+                        || !dependency.ends_with(suffix)
+                    {
+                        continue;
+                    }
+                    (prefix.len(), dependency[prefix.len()..dependency.len() - suffix.len()].to_string())
+                }
+                None if pattern == dependency => (usize::MAX, String::new()),
+                None => continue,
+            };
+
+            if best.as_ref().is_none_or(|(best_len, _, _)| matched_len > *best_len) {
+                best = Some((matched_len, targets, captured));
+            }
+        }
+
+        let (_, targets, captured) = best?;
+        targets.iter().find_map(|target| {
+            let target = PathBuf::from(target.to_string_lossy().replace('*', &captured));
+            self.resolve_path(index, &target)
+        })
+    }
+
+    fn resolve_package(&self, index: &SourceIndex, package_dir: &Path, subpath: &str) -> Option<String> {
+        let manifest = self.manifests.get(package_dir)?;
+        let export_key = if subpath.is_empty() { ".".to_string() } else { format!("./{}", subpath) };
+
+        if let Some(exports) = manifest.get("exports") {
+            let targets = export_targets(exports, &export_key);
+            if let Some(found) = targets.iter().find_map(|target| self.resolve_package_target(index, package_dir, target)) {
+                return Some(found);
+            }
+        }
+
+        if !subpath.is_empty() {
+            return self.resolve_package_target(index, package_dir, subpath);
+        }
+
+        for field in ["source", "types", "typings", "module", "main"] {
+            if let Some(target) = manifest.get(field).and_then(Value::as_str)
+                && let Some(found) = self.resolve_package_target(index, package_dir, target)
+            {
+                return Some(found);
+            }
+        }
+
+        index.probe(package_dir, &JS_INDEX_FILES).map(String::from)
+    }
+
+    /// Resolves a file named by a package manifest, falling back from build output directories
+    /// such as `dist/` to the `src/` directory they are compiled from.
+    fn resolve_package_target(&self, index: &SourceIndex, package_dir: &Path, target: &str) -> Option<String> {
+        let target = target.trim_start_matches("./");
+        if let Some(found) = self.resolve_path(index, &package_dir.join(target)) {
+            return Some(found);
+        }
+
+        let source_target = BUILD_OUTPUT_DIRS
+            .iter()
+            .find_map(|output_dir| target.strip_prefix(output_dir))?;
+        let source_target = source_target
+            .strip_suffix(".d.ts")
+            .or_else(|| source_target.strip_suffix(".js"))
+            .unwrap_or(source_target);
+
+        self.resolve_path(index, &package_dir.join("src").join(source_target))
+    }
+}
+
+fn resolve_file(index: &SourceIndex, base: &Path) -> Option<String> {
+    if let Some(found) = index.probe(base, &JS_EXTENSIONS) {
+        return Some(found.to_string());
+    }
+
+    // ESM-style TypeScript imports name the emitted `.js` file rather than the `.ts` source
+    let base_str = base.to_string_lossy();
+    let rewrites: [(&str, &[&str]); 4] = [
+        (".js", &[".ts", ".tsx", ".d.ts"]),
+        (".jsx", &[".tsx"]),
+        (".mjs", &[".mts", ".d.mts"]),
+        (".cjs", &[".cts", ".d.cts"]),
+    ];
+
+    rewrites.iter().find_map(|(emitted, sources)| {
+        let stem = base_str.strip_suffix(emitted)?;
+        index.probe(stem, sources).map(String::from)
+    })
+}
+
+/// Candidate files for a subpath of a `package.json` `exports` field, most preferred first.
+fn export_targets(exports: &Value, key: &str) -> Vec<String> {
+    let mut targets = Vec::new();
+
+    match exports {
+        Value::String(target) if key == "." => targets.push(target.clone()),
+        Value::Object(map) if map.keys().any(|k| k.starts_with('.')) => {
+            if let Some(entry) = map.get(key) {
+                collect_condition_targets(entry, &mut targets);
+            } else {
+                for (pattern, entry) in map {
+                    let Some((prefix, suffix)) = pattern.split_once('*') else {
+                        continue;
+                    };
+                    if key.len() >= prefix.len() + suffix.len() && key.starts_with(prefix) && key.ends_with(suffix) {
+                        let captured = &key[prefix.len()..key.len() - suffix.len()];
+                        let mut pattern_targets = Vec::new();
+                        collect_condition_targets(entry, &mut pattern_targets);
+                        targets.extend(pattern_targets.iter().map(|target| target.replace('*', captured)));
+                    }
+                }
+            }
+        }
+        _ if key == "." => collect_condition_targets(exports, &mut targets),
+        _ => {}
+    }
+
+    targets
+}
+
+fn collect_condition_targets(entry: &Value, targets: &mut Vec<String>) {
+    match entry {
+        Value::String(target) => targets.push(target.clone()),
+        Value::Array(items) => items.iter().for_each(|item| collect_condition_targets(item, targets)),
+        Value::Object(conditions) => {
+            for condition in EXPORT_CONDITIONS {
+                if let Some(nested) = conditions.get(condition) {
+                    collect_condition_targets(nested, targets);
+                }
+            }
+        }
+        _ => {}
+    }
+}
+
+fn load_ts_config(path: &Path, load_content: &dyn Fn(&str) -> Option<String>, depth: usize) -> Option<TsConfig> {
+    let content = load_content(&path.to_string_lossy())?;
+    let value = parse_jsonc(&content)?;
+    let dir = normalize_path(path.parent().unwrap_or(Path::new("")));
+
+    let mut config = value
+        .get("extends")
+        .and_then(Value::as_str)
+        .filter(|extends| extends.starts_with('.') && depth < 8)
+        .and_then(|extends| {
+            let mut base = dir.join(extends);
+            if base.extension().is_none() {
+                base.set_extension("json");
+            }
+            load_ts_config(&normalize_path(&base), load_content, depth + 1)
+        })
+        .unwrap_or(TsConfig {
+            dir: dir.clone(),
+            base_url: None,
+            paths: Vec::new(),
+        });
+    config.dir = dir.clone();
+
+    let options = value.get("compilerOptions");
+    if let Some(base_url) = options.and_then(|options| options.get("baseUrl")).and_then(Value::as_str) {
+        config.base_url = Some(normalize_path(&dir.join(base_url)));
+    }
+
+    if let Some(paths) = options.and_then(|options| options.get("paths")).and_then(Value::as_object) {
+        let paths_base = config.base_url.clone().unwrap_or_else(|| dir.clone());
+        config.paths = paths
+            .iter()
+            .map(|(pattern, targets)| {
+                let targets = targets
+                    .as_array()
+                    .map(|items| {
+                        items
+                            .iter()
+                            .filter_map(Value::as_str)
+                            .map(|target| normalize_path(&paths_base.join(target)))
+                            .collect()
+                    })
+                    .unwrap_or_default();
+                (pattern.clone(), targets)
+            })
+            .collect();
+    }
+
+    Some(config)
+}
+
+/// Packages that imports can refer to by name: those matched by a `workspaces` glob, or
+/// every named package when the tree declares no workspaces.
+fn collect_workspace_packages(manifests: &[(PathBuf, Value)]) -> HashMap<String, PathBuf> {
+    let workspace_globs: Vec<(PathBuf, String)> = manifests
+        .iter()
+        .flat_map(|(dir, manifest)| {
+            let globs = match manifest.get("workspaces") {
+                Some(Value::Array(items)) => items.clone(),
+                Some(Value::Object(config)) => config.get("packages").and_then(Value::as_array).cloned().unwrap_or_default(),
+                _ => Vec::new(),
+            };
+            globs
+                .into_iter()
+                .filter_map(|glob| glob.as_str().map(String::from))
+                .map(|glob| (dir.clone(), glob))
+                .collect::<Vec<_>>()
+        })
+        .collect();
+
+    manifests
+        .iter()
+        .filter(|(dir, _)| {
+            workspace_globs.is_empty()
+                || workspace_globs.iter().any(|(root, glob)| {
+                    dir.strip_prefix(root)
+                        .is_ok_and(|relative| glob_match_path(glob, &relative.to_string_lossy()))
+                })
+        })
+        .filter_map(|(dir, manifest)| {
+            let name = manifest.get("name")?.as_str()?.to_string();
+            Some((name, dir.clone()))
+        })
+        .collect()
+}
+
+/// Parses JSON that may contain comments and trailing commas, as `tsconfig.json` allows.
+fn parse_jsonc(content: &str) -> Option<Value> {
+    let chars: Vec<char> = content.chars().collect();
+    let mut cleaned = String::with_capacity(content.len());
+    let mut i = 0;
+
+    while i < chars.len() {
+        let c = chars[i];
+
+        if c == '"' {
+            cleaned.push(c);
+            i += 1;
+            while i < chars.len() && chars[i] != '"' {
+                if chars[i] == '\\' && i + 1 < chars.len() {
+                    cleaned.push(chars[i]);
+                    i += 1;
+                }
+                cleaned.push(chars[i]);
+                i += 1;
+            }
+            if i < chars.len() {
+                cleaned.push('"');
+            }
+            i += 1;
+        } else if c == '/' && chars.get(i + 1) == Some(&'/') {
+            while i < chars.len() && chars[i] != '\n' {
+                i += 1;
+            }
+        } else if c == '/' && chars.get(i + 1) == Some(&'*') {
+            i += 2;
+            while i < chars.len() && !(chars[i] == '*' && chars.get(i + 1) == Some(&'/')) {
+                i += 1;
+            }
+            i += 2;
+        } else if c == ',' {
+            let next = chars[i + 1..].iter().find(|c| !c.is_whitespace());
+            if !matches!(next, Some('}') | Some(']')) {
+                cleaned.push(c);
+            }
+            i += 1;
+        } else {
+            cleaned.push(c);
+            i += 1;
+        }
+    }
+
+    serde_json::from_str(&cleaned).ok()
+}
@@ -0,0 +1,203 @@
+use crate::dependency::dependency_graph::DependencyGraph;
+use crate::util::error::{AppError, AppResult};
+use crate::util::file_filter::matches_path_pattern;
+use serde::Deserialize;
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::Path;
+
+/// Rules file picked up from the analyzed directory when no `--rules` path is given.
+pub const DEFAULT_RULES_FILE: &str = "dependency-rules.toml";
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct RulesFile {
+    #[serde(default)]
+    layers: HashMap<String, Vec<String>>,
+    #[serde(default)]
+    rules: Vec<LayerRule>,
+}
+
+/// One dependency direction rule. `from`, `allow` and `forbid` entries are layer names, globs
+/// (`src/domain/**`) or plain path prefixes (`src/commands`), relative to the analyzed directory.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct LayerRule {
+    pub from: String,
+    /// When set, files matched by `from` may only depend on files matched here (or by `from`)
+    pub allow: Option<Vec<String>>,
+    #[serde(default)]
+    pub forbid: Vec<String>,
+    pub reason: Option<String>,
+}
+
+impl LayerRule {
+    pub fn describe(&self) -> String {
+        match &self.allow {
+            Some(allowed) if self.forbid.is_empty() => {
+                format!("{} may only depend on {}", self.from, allowed.join(", "))
+            }
+            Some(allowed) => format!(
+                "{} may only depend on {} and not on {}",
+                self.from,
+                allowed.join(", "),
+                self.forbid.join(", ")
+            ),
+            None => format!("{} must not depend on {}", self.from, self.forbid.join(", ")),
+        }
+    }
+}
+
+/// A dependency edge that breaks a layering rule.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LayerViolation {
+    pub source: String,
+    pub target: String,
+    pub line: Option<usize>,
+    pub import: Option<String>,
+    pub rule: String,
+    pub reason: Option<String>,
+}
+
+/// Allowed and forbidden dependency directions between parts of the tree, loaded from TOML:
+///
+/// ```toml
+/// [layers]
+/// domain = ["src/domain/**"]
+/// infrastructure = ["src/infrastructure/**", "src/db/**"]
+///
+/// [[rules]]
+/// from = "domain"
+/// forbid = ["infrastructure"]
+/// reason = "The domain model stays persistence-agnostic"
+///
+/// [[rules]]
+/// from = "src/analyzer"
+/// forbid = ["src/commands"]
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct LayerRules {
+    layers: HashMap<String, Vec<String>>,
+    rules: Vec<LayerRule>,
+}
+
+impl LayerRules {
+    pub fn load<P: AsRef<Path>>(path: P) -> AppResult<Self> {
+        let path = path.as_ref();
+        let content = fs::read_to_string(path).map_err(|e| AppError::FileSystem {
+            path: path.to_path_buf(),
+            message: format!("Failed to read dependency rules: {}", e),
+        })?;
+
+        Self::parse(&content)
+            .map_err(|error| AppError::Dependency(format!("Invalid rules file {}: {}", path.display(), error)))
+    }
+
+    pub fn parse(content: &str) -> AppResult<Self> {
+        let file: RulesFile = toml::from_str(content)
+            .map_err(|e| AppError::Dependency(format!("Failed to parse dependency rules: {}", e)))?;
+
+        for rule in &file.rules {
+            if rule.allow.is_none() && rule.forbid.is_empty() {
+                return Err(AppError::Dependency(format!(
+                    "Rule for '{}' needs an `allow` or `forbid` list",
+                    rule.from
+                )));
+            }
+        }
+
+        Ok(LayerRules {
+            layers: file.layers,
+            rules: file.rules,
+        })
+    }
+
+    /// Checks every edge of the graph and returns each violating edge once, with the first rule
+    /// it breaks. `root` is the analyzed directory that rule paths are relative to.
+    pub fn check<P: AsRef<Path>>(&self, graph: &DependencyGraph, root: P) -> Vec<LayerViolation> {
+        let root = root.as_ref();
+        let mut violations = Vec::new();
+        let mut reported = HashSet::new();
+
+        let mut sources = graph.get_internal_nodes();
+        sources.sort();
+
+        for source in &sources {
+            let source_path = relative_path(source, root);
+            let applicable: Vec<&LayerRule> = self
+                .rules
+                .iter()
+                .filter(|rule| self.matches(&rule.from, &source_path))
+                .collect();
+            if applicable.is_empty() {
+                continue;
+            }
+
+            let mut targets = graph.get_dependencies(source);
+            targets.sort();
+
+            for target in targets {
+                let target_path = relative_path(&target, root);
+                let external = graph.is_external(&target);
+
+                let broken = applicable
+                    .iter()
+                    .find(|rule| self.violates(rule, &source_path, &target_path, external));
+
+                if let Some(rule) = broken
+                    && reported.insert((source.clone(), target.clone()))
+                {
+                    let import = graph.get_edge_import(source, &target);
+                    violations.push(LayerViolation {
+                        source: source.clone(),
+                        target: target.clone(),
+                        line: import.and_then(|import| import.line),
+                        import: import.map(|import| import.specifier.clone()),
+                        rule: rule.describe(),
+                        reason: rule.reason.clone(),
+                    });
+                }
+            }
+        }
+
+        violations
+    }
+
+    fn violates(&self, rule: &LayerRule, source: &str, target: &str, external: bool) -> bool {
+        // Edges inside the constrained part of the tree are never violations
+        if self.matches(&rule.from, target) {
+            return false;
+        }
+
+        let forbidden = rule
+            .forbid
+            .iter()
+            .any(|pattern| self.matches(pattern, target) && !self.matches(pattern, source));
+        if forbidden {
+            return true;
+        }
+
+        // Allow-lists restrict project files only; external packages are governed by `forbid`
+        match &rule.allow {
+            Some(allowed) if !external => !allowed.iter().any(|pattern| self.matches(pattern, target)),
+            _ => false,
+        }
+    }
+
+    fn matches(&self, pattern: &str, path: &str) -> bool {
+        if let Some(globs) = self.layers.get(pattern) {
+            return globs.iter().any(|glob| matches_path_pattern(glob, path));
+        }
+        matches_path_pattern(pattern, path)
+    }
+}
+
+/// Node path relative to the analyzed directory with `/` separators; nodes outside it (such as
+/// external packages and crates) are returned unchanged.
+fn relative_path(node: &str, root: &Path) -> String {
+    let path = Path::new(node);
+    path.strip_prefix(root)
+        .unwrap_or(path)
+        .to_string_lossy()
+        .replace('\\', "/")
+}
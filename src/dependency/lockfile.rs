@@ -0,0 +1,498 @@
+use crate::dependency::cargo_manifest::CargoLock;
+use crate::dependency::python_resolver::{normalize_distribution, requirement_name};
+use crate::util::error::{AppError, AppResult};
+use serde::Serialize;
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::Path;
+
+/// Package registry a lockfile resolves against.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Ecosystem {
+    Cargo,
+    Npm,
+    PyPI,
+    NuGet,
+}
+
+impl Ecosystem {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Ecosystem::Cargo => "Cargo",
+            Ecosystem::Npm => "npm",
+            Ecosystem::PyPI => "PyPI",
+            Ecosystem::NuGet => "NuGet",
+        }
+    }
+}
+
+/// A third-party package pinned by a lockfile. `direct` packages are declared by the project's
+/// own manifest; the rest are pulled in by other packages.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LockedDependency {
+    pub name: String,
+    pub version: String,
+    pub direct: bool,
+}
+
+#[derive(Debug, Clone)]
+pub struct Lockfile {
+    pub ecosystem: Ecosystem,
+    pub packages: Vec<LockedDependency>,
+}
+
+/// File names recognised as lockfiles, besides `requirements*.txt`.
+pub const LOCKFILE_NAMES: [&str; 6] = [
+    "Cargo.lock",
+    "package-lock.json",
+    "yarn.lock",
+    "pnpm-lock.yaml",
+    "poetry.lock",
+    "packages.lock.json",
+];
+
+pub fn is_lockfile(file_name: &str) -> bool {
+    LOCKFILE_NAMES.contains(&file_name) || (file_name.starts_with("requirements") && file_name.ends_with(".txt"))
+}
+
+/// Parses the lockfile at `path`, reading the manifest next to it where the lockfile alone does
+/// not say which packages are direct. Returns `None` for files that are not lockfiles.
+pub fn parse_lockfile(path: &Path) -> AppResult<Option<Lockfile>> {
+    let Some(file_name) = path.file_name().and_then(|name| name.to_str()) else {
+        return Ok(None);
+    };
+    if !is_lockfile(file_name) {
+        return Ok(None);
+    }
+
+    let content = fs::read_to_string(path).map_err(|e| AppError::FileSystem {
+        path: path.to_path_buf(),
+        message: format!("Failed to read lockfile: {}", e),
+    })?;
+    let dir = path.parent().unwrap_or(Path::new("."));
+    let invalid = |message: String| AppError::Dependency(format!("Failed to parse {}: {}", path.display(), message));
+
+    let (ecosystem, packages) = match file_name {
+        "Cargo.lock" => (Ecosystem::Cargo, parse_cargo_lock(&content)?),
+        "package-lock.json" => (Ecosystem::Npm, parse_package_lock(&content, dir).map_err(invalid)?),
+        "yarn.lock" => (Ecosystem::Npm, parse_yarn_lock(&content, &package_json_dependencies(dir))),
+        "pnpm-lock.yaml" => (Ecosystem::Npm, parse_pnpm_lock(&content, dir)),
+        "poetry.lock" => (Ecosystem::PyPI, parse_poetry_lock(&content, dir).map_err(invalid)?),
+        "packages.lock.json" => (Ecosystem::NuGet, parse_nuget_lock(&content).map_err(invalid)?),
+        _ => (Ecosystem::PyPI, parse_requirements(&content)),
+    };
+
+    Ok(Some(Lockfile {
+        ecosystem,
+        packages: deduplicate(packages),
+    }))
+}
+
+/// Keeps one entry per name and version, direct if any occurrence is.
+fn deduplicate(packages: Vec<LockedDependency>) -> Vec<LockedDependency> {
+    let mut unique: Vec<LockedDependency> = Vec::new();
+    let mut index: HashMap<(String, String), usize> = HashMap::new();
+
+    for package in packages {
+        match index.get(&(package.name.clone(), package.version.clone())) {
+            Some(&i) => unique[i].direct |= package.direct,
+            None => {
+                index.insert((package.name.clone(), package.version.clone()), unique.len());
+                unique.push(package);
+            }
+        }
+    }
+
+    unique
+}
+
+/// Workspace and path crates have no `source`; what they depend on directly is direct.
+fn parse_cargo_lock(content: &str) -> AppResult<Vec<LockedDependency>> {
+    let lock = CargoLock::parse(content)?;
+    let mut direct: HashSet<(String, Option<String>)> = HashSet::new();
+
+    for package in lock.packages.iter().filter(|package| package.source.is_none()) {
+        for entry in &package.dependencies {
+            // "name", "name version" or "name version (source)"
+            let mut parts = entry.split_whitespace();
+            if let Some(name) = parts.next() {
+                direct.insert((name.to_string(), parts.next().map(String::from)));
+            }
+        }
+    }
+
+    Ok(lock
+        .packages
+        .iter()
+        .filter(|package| package.source.is_some())
+        .map(|package| LockedDependency {
+            direct: direct.contains(&(package.name.clone(), Some(package.version.clone())))
+                || direct.contains(&(package.name.clone(), None)),
+            name: package.name.clone(),
+            version: package.version.clone(),
+        })
+        .collect())
+}
+
+/// Names listed in the dependency sections of a `package.json` object.
+fn declared_npm_dependencies(manifest: &serde_json::Value) -> HashSet<String> {
+    ["dependencies", "devDependencies", "optionalDependencies", "peerDependencies"]
+        .iter()
+        .filter_map(|section| manifest.get(section).and_then(serde_json::Value::as_object))
+        .flat_map(|dependencies| dependencies.keys().cloned())
+        .collect()
+}
+
+fn package_json_dependencies(dir: &Path) -> HashSet<String> {
+    fs::read_to_string(dir.join("package.json"))
+        .ok()
+        .and_then(|content| serde_json::from_str::<serde_json::Value>(&content).ok())
+        .map(|manifest| declared_npm_dependencies(&manifest))
+        .unwrap_or_default()
+}
+
+/// Lockfile version 2 and 3 list every installed package under `packages`, keyed by its
+/// `node_modules` path, with the root and workspace manifests under their own directories.
+/// Version 1 nests `dependencies` instead and relies on `package.json` for the direct ones.
+fn parse_package_lock(content: &str, dir: &Path) -> Result<Vec<LockedDependency>, String> {
+    let lock: serde_json::Value = serde_json::from_str(content).map_err(|e| e.to_string())?;
+    let mut packages = Vec::new();
+
+    if let Some(entries) = lock.get("packages").and_then(serde_json::Value::as_object) {
+        let mut direct = HashSet::new();
+        for (key, entry) in entries.iter().filter(|(key, _)| !key.contains("node_modules/")) {
+            for name in declared_npm_dependencies(entry) {
+                if !key.is_empty() {
+                    direct.insert(format!("{}/node_modules/{}", key, name));
+                }
+                // Workspaces share the root node_modules for whatever could be hoisted there
+                direct.insert(format!("node_modules/{}", name));
+            }
+        }
+
+        for (key, entry) in entries {
+            let Some(position) = key.rfind("node_modules/") else {
+                continue;
+            };
+            if entry.get("link").and_then(serde_json::Value::as_bool) == Some(true) {
+                continue;
+            }
+            let Some(version) = entry.get("version").and_then(serde_json::Value::as_str) else {
+                continue;
+            };
+
+            packages.push(LockedDependency {
+                name: entry
+                    .get("name")
+                    .and_then(serde_json::Value::as_str)
+                    .unwrap_or(&key[position + "node_modules/".len()..])
+                    .to_string(),
+                version: version.to_string(),
+                direct: direct.contains(key),
+            });
+        }
+    } else if let Some(dependencies) = lock.get("dependencies").and_then(serde_json::Value::as_object) {
+        let direct = package_json_dependencies(dir);
+        collect_v1_dependencies(dependencies, &direct, true, &mut packages);
+    }
+
+    Ok(packages)
+}
+
+fn collect_v1_dependencies(
+    dependencies: &serde_json::Map<String, serde_json::Value>,
+    direct: &HashSet<String>,
+    top_level: bool,
+    packages: &mut Vec<LockedDependency>,
+) {
+    for (name, entry) in dependencies {
+        if let Some(version) = entry.get("version").and_then(serde_json::Value::as_str)
+            && !version.starts_with("file:")
+        {
+            packages.push(LockedDependency {
+                name: name.clone(),
+                version: version.to_string(),
+                direct: top_level && direct.contains(name),
+            });
+        }
+        if let Some(nested) = entry.get("dependencies").and_then(serde_json::Value::as_object) {
+            collect_v1_dependencies(nested, direct, false, packages);
+        }
+    }
+}
+
+/// Name of an npm specifier such as `lodash@^4.17.0`, `@babel/core@npm:^7.0.0` or `@types/node`.
+fn npm_specifier_name(specifier: &str) -> &str {
+    match specifier.char_indices().skip(1).find(|&(_, c)| c == '@') {
+        Some((index, _)) => &specifier[..index],
+        None => specifier,
+    }
+}
+
+/// Handles both the classic format (`version "1.2.3"`) and Yarn Berry (`version: 1.2.3`).
+fn parse_yarn_lock(content: &str, direct: &HashSet<String>) -> Vec<LockedDependency> {
+    let mut packages = Vec::new();
+    let mut current: Option<(String, bool)> = None;
+
+    for line in content.lines() {
+        if line.trim_start().starts_with('#') || line.trim().is_empty() {
+            continue;
+        }
+
+        if !line.starts_with(' ') {
+            let specifiers = line.trim_end_matches(':');
+            let first = specifiers.split(',').next().unwrap_or("").trim().trim_matches('"');
+            let local = specifiers.contains("@workspace:") || specifiers.contains("@link:") || specifiers.contains("@file:");
+            current = (first != "__metadata" && !local).then(|| (npm_specifier_name(first).to_string(), false));
+            continue;
+        }
+
+        let trimmed = line.trim();
+        let version = trimmed
+            .strip_prefix("version ")
+            .or_else(|| trimmed.strip_prefix("version:"))
+            .map(|version| version.trim().trim_matches('"'));
+        if let (Some(version), Some((name, seen))) = (version, current.as_mut())
+            && !*seen
+            && line.starts_with("  ")
+            && !line.starts_with("   ")
+        {
+            *seen = true;
+            packages.push(LockedDependency {
+                direct: direct.contains(name.as_str()),
+                name: name.clone(),
+                version: version.to_string(),
+            });
+        }
+    }
+
+    packages
+}
+
+/// Reads the `packages` section of `pnpm-lock.yaml` line by line; keys look like `/name/1.2.3`
+/// (lockfile v5), `/name@1.2.3(peer@1.0.0)` (v6) or `name@1.2.3` (v9). Direct dependencies
+/// come from `importers`, or from the top-level dependency sections of older single-project locks.
+fn parse_pnpm_lock(content: &str, dir: &Path) -> Vec<LockedDependency> {
+    let slash_keys = content
+        .lines()
+        .find_map(|line| line.strip_prefix("lockfileVersion:"))
+        .map(|version| version.trim().trim_matches(['\'', '"']).starts_with('5'))
+        .unwrap_or(false);
+
+    let mut direct = HashSet::new();
+    let mut versions: Vec<(String, String)> = Vec::new();
+    let mut section = "";
+    let mut dependency_section = false;
+
+    for line in content.lines() {
+        if line.trim().is_empty() || line.trim_start().starts_with('#') {
+            continue;
+        }
+        let indent = line.len() - line.trim_start().len();
+        let key = line.trim().trim_end_matches(':').trim_matches(['\'', '"']);
+
+        if indent == 0 {
+            section = match key {
+                "importers" => "importers",
+                "packages" => "packages",
+                "dependencies" | "devDependencies" | "optionalDependencies" => "root",
+                _ => "",
+            };
+            continue;
+        }
+
+        match section {
+            "importers" if indent == 4 => {
+                dependency_section = matches!(key, "dependencies" | "devDependencies" | "optionalDependencies");
+            }
+            "importers" if indent == 6 && dependency_section => {
+                direct.insert(key.split(':').next().unwrap_or(key).trim().trim_matches(['\'', '"']).to_string());
+            }
+            "root" if indent == 2 => {
+                direct.insert(key.split(':').next().unwrap_or(key).trim().trim_matches(['\'', '"']).to_string());
+            }
+            "packages" if indent == 2 => {
+                if let Some(package) = pnpm_package_key(key, slash_keys) {
+                    versions.push(package);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    if direct.is_empty() {
+        direct = package_json_dependencies(dir);
+    }
+
+    versions
+        .into_iter()
+        .map(|(name, version)| LockedDependency {
+            direct: direct.contains(&name),
+            name,
+            version,
+        })
+        .collect()
+}
+
+fn pnpm_package_key(key: &str, slash_keys: bool) -> Option<(String, String)> {
+    let key = key.trim_start_matches('/');
+    let key = key.split('(').next().unwrap_or(key);
+
+    if slash_keys {
+        let (name, version) = key.rsplit_once('/')?;
+        let version = version.split('_').next().unwrap_or(version);
+        return Some((name.to_string(), version.to_string()));
+    }
+
+    let name = npm_specifier_name(key);
+    let version = key.get(name.len() + 1..)?;
+    (!version.is_empty() && !version.starts_with("link:") && !version.starts_with("file:"))
+        .then(|| (name.to_string(), version.to_string()))
+}
+
+/// Distribution names declared in `pyproject.toml`, normalized: PEP 621 dependencies and
+/// optional dependencies plus Poetry's main, dev and group dependencies.
+fn pyproject_dependencies(dir: &Path) -> HashSet<String> {
+    let Some(value) = fs::read_to_string(dir.join("pyproject.toml"))
+        .ok()
+        .and_then(|content| content.parse::<toml::Value>().ok())
+    else {
+        return HashSet::new();
+    };
+
+    let mut names = HashSet::new();
+    let project = value.get("project");
+    let requirements = project
+        .and_then(|project| project.get("dependencies"))
+        .and_then(toml::Value::as_array)
+        .into_iter()
+        .chain(
+            project
+                .and_then(|project| project.get("optional-dependencies"))
+                .and_then(toml::Value::as_table)
+                .into_iter()
+                .flat_map(|groups| groups.values().filter_map(toml::Value::as_array)),
+        );
+    for requirement in requirements.flatten().filter_map(toml::Value::as_str) {
+        names.extend(requirement_name(requirement));
+    }
+
+    if let Some(poetry) = value.get("tool").and_then(|tool| tool.get("poetry")) {
+        let tables = [poetry.get("dependencies"), poetry.get("dev-dependencies")]
+            .into_iter()
+            .flatten()
+            .chain(
+                poetry
+                    .get("group")
+                    .and_then(toml::Value::as_table)
+                    .into_iter()
+                    .flat_map(|groups| groups.values().filter_map(|group| group.get("dependencies"))),
+            );
+        for table in tables.filter_map(toml::Value::as_table) {
+            names.extend(table.keys().filter(|name| *name != "python").cloned());
+        }
+    }
+
+    names.iter().map(|name| normalize_distribution(name)).collect()
+}
+
+fn parse_poetry_lock(content: &str, dir: &Path) -> Result<Vec<LockedDependency>, String> {
+    let lock: toml::Value = content.parse().map_err(|e: toml::de::Error| e.to_string())?;
+    let direct = pyproject_dependencies(dir);
+
+    Ok(lock
+        .get("package")
+        .and_then(toml::Value::as_array)
+        .map(|packages| {
+            packages
+                .iter()
+                .filter_map(|package| {
+                    let name = package.get("name")?.as_str()?;
+                    Some(LockedDependency {
+                        direct: direct.contains(&normalize_distribution(name)),
+                        name: name.to_string(),
+                        version: package.get("version")?.as_str()?.to_string(),
+                    })
+                })
+                .collect()
+        })
+        .unwrap_or_default())
+}
+
+/// Every requirement is direct, unless the file was generated by pip-compile: then only the
+/// ones whose `# via` annotation names a requirements input (`-r`) are.
+fn parse_requirements(content: &str) -> Vec<LockedDependency> {
+    let compiled = content.contains("# via");
+    let mut packages: Vec<LockedDependency> = Vec::new();
+
+    for line in content.lines() {
+        let trimmed = line.trim();
+        if let Some(comment) = trimmed.strip_prefix('#') {
+            if let Some(package) = packages.last_mut()
+                && line.starts_with(char::is_whitespace)
+                && comment.split_whitespace().any(|word| word == "-r" || word == "-c")
+            {
+                package.direct = true;
+            }
+            continue;
+        }
+
+        let requirement = trimmed.split(" #").next().unwrap_or("").trim().trim_end_matches('\\').trim();
+        if requirement.is_empty() || requirement.starts_with('-') || requirement.contains("://") {
+            continue;
+        }
+        let Some(name) = requirement_name(requirement) else {
+            continue;
+        };
+
+        let specifier = requirement[name.len()..].split(';').next().unwrap_or("").trim();
+        let specifier = match specifier.find(']') {
+            Some(end) if specifier.starts_with('[') => specifier[end + 1..].trim(),
+            _ => specifier,
+        };
+        let version = match specifier.strip_prefix("==") {
+            Some(pinned) => pinned.trim().to_string(),
+            None if specifier.is_empty() => "*".to_string(),
+            None => specifier.to_string(),
+        };
+
+        packages.push(LockedDependency {
+            name,
+            version,
+            direct: !compiled,
+        });
+    }
+
+    packages
+}
+
+/// `packages.lock.json` groups packages by target framework; project references are skipped.
+fn parse_nuget_lock(content: &str) -> Result<Vec<LockedDependency>, String> {
+    let lock: serde_json::Value = serde_json::from_str(content).map_err(|e| e.to_string())?;
+    let mut packages = Vec::new();
+
+    let frameworks = lock.get("dependencies").and_then(serde_json::Value::as_object);
+    for dependencies in frameworks.into_iter().flat_map(|frameworks| frameworks.values()) {
+        let Some(dependencies) = dependencies.as_object() else {
+            continue;
+        };
+        for (name, entry) in dependencies {
+            let kind = entry.get("type").and_then(serde_json::Value::as_str).unwrap_or("");
+            let Some(version) = entry.get("resolved").and_then(serde_json::Value::as_str) else {
+                continue;
+            };
+            if kind == "Project" {
+                continue;
+            }
+
+            packages.push(LockedDependency {
+                name: name.clone(),
+                version: version.to_string(),
+                direct: kind == "Direct",
+            });
+        }
+    }
+
+    Ok(packages)
+}
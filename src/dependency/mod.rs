@@ -1,3 +1,23 @@
+pub mod c_resolver;
+pub mod cargo_manifest;
+pub mod clike;
+pub mod coupling;
+pub mod crate_analyzer;
+pub mod csharp_resolver;
+pub mod cycle_analysis;
+pub mod dead_files;
 pub mod dependency_analyzer;
 pub mod dependency_graph;
-pub mod dependency_reporter;
\ No newline at end of file
+pub mod dependency_reporter;
+pub mod go_resolver;
+pub mod graph_diff;
+pub mod graph_export;
+pub mod impact;
+pub mod java_resolver;
+pub mod js_resolver;
+pub mod layer_rules;
+pub mod lockfile;
+pub mod package_inventory;
+pub mod python_resolver;
+pub mod resolver;
+pub mod rust_resolver;
\ No newline at end of file
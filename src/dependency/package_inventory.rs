@@ -0,0 +1,300 @@
+use crate::dependency::lockfile::{Ecosystem, LockedDependency, is_lockfile, parse_lockfile};
+use crate::dependency::python_resolver::normalize_distribution;
+use crate::util::error::AppResult;
+use ignore::WalkBuilder;
+use regex::Regex;
+use serde::Serialize;
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Directories that hold installed packages rather than the project's own lockfiles.
+const VENDORED_DIRECTORIES: [&str; 6] = ["node_modules", "target", ".git", ".venv", "venv", "vendor"];
+
+/// A third-party package at one version, merged across every lockfile that pins it.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Package {
+    pub ecosystem: Ecosystem,
+    pub name: String,
+    pub version: String,
+    /// Declared by a manifest of the project rather than pulled in by another package
+    pub direct: bool,
+    /// License from the locally installed copy, when one is available
+    pub license: Option<String>,
+    pub lockfiles: Vec<String>,
+}
+
+/// A package locked at more than one version.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct DuplicatePackage {
+    pub ecosystem: Ecosystem,
+    pub name: String,
+    pub versions: Vec<String>,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct PackageInventory {
+    pub lockfiles: Vec<String>,
+    /// Sorted by ecosystem, name and version
+    pub packages: Vec<Package>,
+    pub duplicates: Vec<DuplicatePackage>,
+}
+
+impl PackageInventory {
+    pub fn direct_count(&self) -> usize {
+        self.packages.iter().filter(|package| package.direct).count()
+    }
+
+    pub fn transitive_count(&self) -> usize {
+        self.packages.len() - self.direct_count()
+    }
+
+    /// Number of packages per license, with the packages whose license is not known locally
+    /// counted under "Unknown".
+    pub fn license_counts(&self) -> BTreeMap<String, usize> {
+        let mut counts = BTreeMap::new();
+        for package in &self.packages {
+            let license = package.license.clone().unwrap_or_else(|| "Unknown".to_string());
+            *counts.entry(license).or_insert(0) += 1;
+        }
+        counts
+    }
+}
+
+/// Builds the third-party package inventory of a project from its lockfiles, without network
+/// access. Licenses are read from packages already on disk: the Cargo registry cache,
+/// `node_modules`, a `.venv`/`venv` next to the lockfile and the NuGet global packages folder.
+pub struct InventoryScanner {
+    cargo_home: Option<PathBuf>,
+    nuget_packages: Option<PathBuf>,
+}
+
+impl Default for InventoryScanner {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl InventoryScanner {
+    pub fn new() -> Self {
+        let home = std::env::var_os("HOME").or_else(|| std::env::var_os("USERPROFILE")).map(PathBuf::from);
+
+        InventoryScanner {
+            cargo_home: std::env::var_os("CARGO_HOME")
+                .map(PathBuf::from)
+                .or_else(|| home.as_ref().map(|home| home.join(".cargo"))),
+            nuget_packages: std::env::var_os("NUGET_PACKAGES")
+                .map(PathBuf::from)
+                .or_else(|| home.as_ref().map(|home| home.join(".nuget").join("packages"))),
+        }
+    }
+
+    /// Where to look for `registry/src/*/<crate>-<version>` (defaults to `$CARGO_HOME` or `~/.cargo`)
+    #[allow(dead_code)]
+    pub fn cargo_home<P: AsRef<Path>>(mut self, path: P) -> Self {
+        self.cargo_home = Some(path.as_ref().to_path_buf());
+        self
+    }
+
+    /// NuGet global packages folder (defaults to `$NUGET_PACKAGES` or `~/.nuget/packages`)
+    #[allow(dead_code)]
+    pub fn nuget_packages<P: AsRef<Path>>(mut self, path: P) -> Self {
+        self.nuget_packages = Some(path.as_ref().to_path_buf());
+        self
+    }
+
+    pub fn scan<P: AsRef<Path>>(&self, root: P) -> AppResult<PackageInventory> {
+        let root = root.as_ref();
+        let mut lockfile_paths: Vec<PathBuf> = WalkBuilder::new(root)
+            .hidden(false)
+            // Libraries often keep their lockfiles out of version control
+            .git_ignore(false)
+            .filter_entry(|entry| {
+                let name = entry.file_name().to_string_lossy();
+                !(entry.path().is_dir() && VENDORED_DIRECTORIES.contains(&name.as_ref()))
+            })
+            .build()
+            .filter_map(Result::ok)
+            .filter(|entry| entry.path().is_file() && is_lockfile(&entry.file_name().to_string_lossy()))
+            .map(|entry| entry.into_path())
+            .collect();
+        lockfile_paths.sort();
+
+        let mut inventory = PackageInventory::default();
+        let mut packages: BTreeMap<(Ecosystem, String, String), Package> = BTreeMap::new();
+
+        for path in lockfile_paths {
+            let Some(lockfile) = parse_lockfile(&path)? else {
+                continue;
+            };
+            let relative = path.strip_prefix(root).unwrap_or(&path).to_string_lossy().replace('\\', "/");
+            let lockfile_dir = path.parent().unwrap_or(root);
+
+            for locked in lockfile.packages {
+                let key = (lockfile.ecosystem, locked.name.clone(), locked.version.clone());
+                let package = packages.entry(key).or_insert_with(|| Package {
+                    ecosystem: lockfile.ecosystem,
+                    name: locked.name.clone(),
+                    version: locked.version.clone(),
+                    direct: false,
+                    license: None,
+                    lockfiles: Vec::new(),
+                });
+                package.direct |= locked.direct;
+                if package.license.is_none() {
+                    package.license = self.find_license(lockfile.ecosystem, &locked, lockfile_dir);
+                }
+                package.lockfiles.push(relative.clone());
+            }
+            inventory.lockfiles.push(relative);
+        }
+
+        let mut versions: BTreeMap<(Ecosystem, String), BTreeSet<String>> = BTreeMap::new();
+        for package in packages.values() {
+            versions
+                .entry((package.ecosystem, package.name.clone()))
+                .or_default()
+                .insert(package.version.clone());
+        }
+        inventory.duplicates = versions
+            .into_iter()
+            .filter(|(_, versions)| versions.len() > 1)
+            .map(|((ecosystem, name), versions)| {
+                let mut versions: Vec<String> = versions.into_iter().collect();
+                versions.sort_by(|a, b| compare_versions(a, b));
+                DuplicatePackage { ecosystem, name, versions }
+            })
+            .collect();
+        inventory.packages = packages.into_values().collect();
+        inventory.packages.sort_by(|a, b| {
+            (a.ecosystem, &a.name)
+                .cmp(&(b.ecosystem, &b.name))
+                .then_with(|| compare_versions(&a.version, &b.version))
+        });
+
+        Ok(inventory)
+    }
+
+    fn find_license(&self, ecosystem: Ecosystem, package: &LockedDependency, lockfile_dir: &Path) -> Option<String> {
+        match ecosystem {
+            Ecosystem::Cargo => self.cargo_home.as_ref().and_then(|home| cargo_license(home, package)),
+            Ecosystem::Npm => npm_license(lockfile_dir, package),
+            Ecosystem::PyPI => python_license(lockfile_dir, package),
+            Ecosystem::NuGet => self.nuget_packages.as_ref().and_then(|packages| nuget_license(packages, package)),
+        }
+    }
+}
+
+/// Orders versions by their numeric parts, so that `0.9.4` comes before `0.10.1`.
+fn compare_versions(a: &str, b: &str) -> std::cmp::Ordering {
+    let parts = |version: &str| -> Vec<u64> {
+        version
+            .split(|c: char| !c.is_ascii_digit())
+            .filter(|part| !part.is_empty())
+            .map(|part| part.parse().unwrap_or(u64::MAX))
+            .collect()
+    };
+    parts(a).cmp(&parts(b)).then_with(|| a.cmp(b))
+}
+
+/// Reads `license` (or notes a `license-file`) from the crate's manifest in any registry cache.
+fn cargo_license(cargo_home: &Path, package: &LockedDependency) -> Option<String> {
+    let registries = fs::read_dir(cargo_home.join("registry").join("src")).ok()?;
+
+    registries.filter_map(Result::ok).find_map(|registry| {
+        let manifest = registry.path().join(format!("{}-{}", package.name, package.version)).join("Cargo.toml");
+        let value: toml::Value = fs::read_to_string(manifest).ok()?.parse().ok()?;
+        let metadata = value.get("package")?;
+
+        metadata
+            .get("license")
+            .and_then(toml::Value::as_str)
+            .map(String::from)
+            .or_else(|| metadata.get("license-file").map(|_| "Custom".to_string()))
+    })
+}
+
+/// Reads `license` (or the legacy `licenses` list) from `node_modules/<name>/package.json`,
+/// as long as the installed copy is the locked version.
+fn npm_license(lockfile_dir: &Path, package: &LockedDependency) -> Option<String> {
+    let content = fs::read_to_string(lockfile_dir.join("node_modules").join(&package.name).join("package.json")).ok()?;
+    let manifest: serde_json::Value = serde_json::from_str(&content).ok()?;
+    if manifest.get("version").and_then(serde_json::Value::as_str) != Some(package.version.as_str()) {
+        return None;
+    }
+
+    let license_type = |value: &serde_json::Value| -> Option<String> {
+        value
+            .as_str()
+            .or_else(|| value.get("type").and_then(serde_json::Value::as_str))
+            .map(String::from)
+    };
+
+    match manifest.get("license") {
+        Some(license) => license_type(license),
+        None => {
+            let licenses: Vec<String> = manifest.get("licenses")?.as_array()?.iter().filter_map(license_type).collect();
+            (!licenses.is_empty()).then(|| licenses.join(" OR "))
+        }
+    }
+}
+
+/// Reads `License-Expression`, a short `License` field or the license classifiers from the
+/// `METADATA` of the installed distribution in a virtual environment next to the lockfile.
+fn python_license(lockfile_dir: &Path, package: &LockedDependency) -> Option<String> {
+    let wanted = format!("{}-{}.dist-info", normalize_distribution(&package.name), package.version);
+
+    let metadata = ["venv", ".venv"]
+        .iter()
+        .flat_map(|venv| {
+            let venv = lockfile_dir.join(venv);
+            let unix = fs::read_dir(venv.join("lib"))
+                .into_iter()
+                .flatten()
+                .filter_map(Result::ok)
+                .map(|python| python.path().join("site-packages"));
+            unix.chain(std::iter::once(venv.join("Lib").join("site-packages")))
+        })
+        .filter_map(|site_packages| fs::read_dir(site_packages).ok())
+        .flatten()
+        .filter_map(Result::ok)
+        .find(|entry| normalize_distribution(&entry.file_name().to_string_lossy()) == normalize_distribution(&wanted))
+        .and_then(|entry| fs::read_to_string(entry.path().join("METADATA")).ok())?;
+
+    let field = |name: &str| {
+        metadata
+            .lines()
+            .take_while(|line| !line.is_empty())
+            .find_map(|line| line.strip_prefix(name))
+            .map(|value| value.trim().to_string())
+            .filter(|value| !value.is_empty() && value != "UNKNOWN" && !value.contains('\n') && value.len() <= 60)
+    };
+
+    field("License-Expression:").or_else(|| field("License:")).or_else(|| {
+        let classifiers: Vec<&str> = metadata
+            .lines()
+            .filter_map(|line| line.strip_prefix("Classifier: License ::"))
+            .filter_map(|classifier| classifier.rsplit("::").next())
+            .map(str::trim)
+            .collect();
+        (!classifiers.is_empty()).then(|| classifiers.join(" OR "))
+    })
+}
+
+/// Reads the license expression, or failing that the license URL, from the package's `.nuspec`.
+fn nuget_license(packages_dir: &Path, package: &LockedDependency) -> Option<String> {
+    let name = package.name.to_lowercase();
+    let nuspec = packages_dir
+        .join(&name)
+        .join(package.version.to_lowercase())
+        .join(format!("{}.nuspec", name));
+    let content = fs::read_to_string(nuspec).ok()?;
+
+    let expression = Regex::new(r#"<license\s+type="expression"\s*>([^<]+)</license>"#).ok()?;
+    let url = Regex::new(r"<licenseUrl>([^<]+)</licenseUrl>").ok()?;
+
+    expression
+        .captures(&content)
+        .or_else(|| url.captures(&content))
+        .map(|captures| captures[1].trim().to_string())
+}
@@ -0,0 +1,445 @@
+use crate::dependency::clike::count_newlines;
+use crate::dependency::dependency_graph::{EdgeImport, ImportKind};
+use crate::dependency::resolver::{ResolvedDependency, SourceIndex, normalize_path};
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use toml::Value;
+
+const PYTHON_EXTENSIONS: [&str; 2] = [".py", ".pyi"];
+
+/// Top-level modules of the standard library, which are neither project files nor distributions.
+const PYTHON_STDLIB_MODULES: &[&str] = &[
+    "__future__", "_thread", "abc", "argparse", "array", "ast", "asyncio", "atexit", "base64", "binascii",
+    "bisect", "builtins", "bz2", "calendar", "cmath", "cmd", "code", "codecs", "collections", "colorsys",
+    "concurrent", "configparser", "contextlib", "contextvars", "copy", "copyreg", "cProfile", "csv", "ctypes", "curses",
+    "dataclasses", "datetime", "dbm", "decimal", "difflib", "dis", "doctest", "email", "encodings", "enum",
+    "errno", "faulthandler", "fcntl", "filecmp", "fileinput", "fnmatch", "fractions", "ftplib", "functools", "gc",
+    "getopt", "getpass", "gettext", "glob", "graphlib", "grp", "gzip", "hashlib", "heapq", "hmac",
+    "html", "http", "imaplib", "importlib", "inspect", "io", "ipaddress", "itertools", "json", "keyword",
+    "linecache", "locale", "logging", "lzma", "mailbox", "marshal", "math", "mimetypes", "mmap", "multiprocessing",
+    "netrc", "numbers", "operator", "optparse", "os", "pathlib", "pdb", "pickle", "pkgutil", "platform",
+    "plistlib", "posixpath", "pprint", "profile", "pstats", "pty", "pwd", "queue", "random", "re",
+    "readline", "reprlib", "resource", "runpy", "sched", "secrets", "select", "selectors", "shelve", "shlex",
+    "shutil", "signal", "site", "smtplib", "socket", "socketserver", "sqlite3", "ssl", "stat", "statistics",
+    "string", "stringprep", "struct", "subprocess", "symtable", "sys", "sysconfig", "syslog", "tarfile", "tempfile",
+    "termios", "textwrap", "threading", "time", "timeit", "tkinter", "token", "tokenize", "tomllib", "trace",
+    "traceback", "tracemalloc", "tty", "turtle", "types", "typing", "unicodedata", "unittest", "urllib", "uuid",
+    "venv", "warnings", "wave", "weakref", "webbrowser", "winreg", "wsgiref", "xml", "zipfile", "zoneinfo",
+];
+
+/// Import names whose distribution on PyPI is published under a different name.
+const DISTRIBUTION_ALIASES: &[(&str, &str)] = &[
+    ("PIL", "Pillow"),
+    ("yaml", "PyYAML"),
+    ("cv2", "opencv-python"),
+    ("sklearn", "scikit-learn"),
+    ("skimage", "scikit-image"),
+    ("bs4", "beautifulsoup4"),
+    ("dateutil", "python-dateutil"),
+    ("dotenv", "python-dotenv"),
+    ("jwt", "PyJWT"),
+    ("attr", "attrs"),
+    ("serial", "pyserial"),
+    ("OpenSSL", "pyOpenSSL"),
+    ("Crypto", "pycryptodome"),
+    ("fitz", "PyMuPDF"),
+    ("docx", "python-docx"),
+    ("git", "GitPython"),
+    ("jose", "python-jose"),
+    ("MySQLdb", "mysqlclient"),
+    ("google.protobuf", "protobuf"),
+    ("magic", "python-magic"),
+];
+
+/// Returns the modules imported by a Python file as dotted names. `from m import a` yields
+/// `m.a`, which the resolver falls back to `m` for when `a` is not a submodule, and relative
+/// imports keep their leading dots. Imports nested in functions or `try` blocks are included.
+pub fn extract_python_dependencies(content: &str) -> Vec<EdgeImport> {
+    let mut dependencies = Vec::new();
+
+    for (line, statement) in logical_statements(content) {
+        let statement = statement.trim();
+
+        if let Some(rest) = statement.strip_prefix("import ") {
+            for module in rest.split(',') {
+                let module = strip_alias(module);
+                if !module.is_empty() {
+                    dependencies.push(EdgeImport::new(module, line, ImportKind::Import));
+                }
+            }
+        } else if let Some(rest) = statement.strip_prefix("from ")
+            && let Some((module, names)) = rest.split_once(" import ")
+        {
+            let module = module.trim();
+            let names = names.trim().trim_start_matches('(').trim_end_matches(')');
+
+            for name in names.split(',') {
+                let name = strip_alias(name);
+                if name.is_empty() {
+                    continue;
+                }
+
+                let dependency = if name == "*" {
+                    module.to_string()
+                } else if module.chars().all(|c| c == '.') {
+                    format!("{}{}", module, name)
+                } else {
+                    format!("{}.{}", module, name)
+                };
+                dependencies.push(EdgeImport::new(dependency, line, ImportKind::Import));
+            }
+        }
+    }
+
+    dependencies
+}
+
+fn strip_alias(name: &str) -> &str {
+    let name = name.trim();
+    match name.split_once(" as ") {
+        Some((module, _)) => module.trim(),
+        None => name,
+    }
+}
+
+/// Splits source into statements with the line each one starts on, joining bracketed and
+/// backslash-continued lines and dropping comments and string contents so that text inside
+/// docstrings is never matched.
+fn logical_statements(content: &str) -> Vec<(usize, String)> {
+    let chars: Vec<char> = content.chars().collect();
+    let mut statements = Vec::new();
+    let mut current = String::new();
+    let mut depth = 0usize;
+    let mut line = 1;
+    let mut statement_line = 1;
+    let mut counted = 0;
+    let mut i = 0;
+
+    while i < chars.len() {
+        line += count_newlines(&chars[counted..i]);
+        counted = i;
+        let c = chars[i];
+        if !c.is_whitespace() && current.trim().is_empty() {
+            statement_line = line;
+        }
+
+        match c {
+            '#' => {
+                while i < chars.len() && chars[i] != '\n' {
+                    i += 1;
+                }
+                continue;
+            }
+            '"' | '\'' => {
+                let triple = chars.get(i + 1) == Some(&c) && chars.get(i + 2) == Some(&c);
+                i += if triple { 3 } else { 1 };
+
+                while i < chars.len() {
+                    if chars[i] == '\\' {
+                        i += 2;
+                        continue;
+                    }
+                    if triple {
+                        if chars[i] == c && chars.get(i + 1) == Some(&c) && chars.get(i + 2) == Some(&c) {
+                            i += 3;
+                            break;
+                        }
+                    } else if chars[i] == c || chars[i] == '\n' {
+                        i += 1;
+                        break;
+                    }
+                    i += 1;
+                }
+
+                current.push_str("\"\"");
+                continue;
+            }
+            '\\' if chars.get(i + 1) == Some(&'\n') => {
+                current.push(' ');
+                i += 2;
+                continue;
+            }
+            '(' | '[' | '{' => depth += 1,
+            ')' | ']' | '}' => depth = depth.saturating_sub(1),
+            _ => {}
+        }
+
+        let ends_statement = depth == 0 && (c == '\n' || c == ';' || c == ':');
+        if ends_statement {
+            statements.push((statement_line, std::mem::take(&mut current)));
+        } else if c == '\n' || c == '\t' {
+            current.push(' ');
+        } else {
+            current.push(c);
+        }
+        i += 1;
+    }
+
+    statements.push((statement_line, current));
+    statements
+}
+
+/// Python import resolution over the source roots of every project in the tree, following
+/// regular and namespace packages and mapping anything else to a distribution name.
+pub struct PythonModuleResolver {
+    source_roots: Vec<PathBuf>,
+    /// Directories that contain Python files and can therefore be imported as packages
+    package_dirs: HashSet<PathBuf>,
+    /// Normalized names of declared dependencies mapped to their declared spelling
+    declared_distributions: HashMap<String, String>,
+}
+
+impl PythonModuleResolver {
+    pub fn build(index: &SourceIndex, load_content: &dyn Fn(&str) -> Option<String>) -> Self {
+        let index_root = normalize_path(index.root());
+        let mut source_roots = vec![index_root.clone()];
+        let mut package_dirs = HashSet::new();
+        let mut declared_distributions = HashMap::new();
+
+        for file in index.files() {
+            let path = Path::new(file);
+            let dir = normalize_path(path.parent().unwrap_or(Path::new("")));
+            let file_name = path.file_name().and_then(|name| name.to_str()).unwrap_or("");
+
+            if file_name.ends_with(".py") || file_name.ends_with(".pyi") {
+                let mut ancestor = dir.as_path();
+                while ancestor.starts_with(&index_root) && package_dirs.insert(ancestor.to_path_buf()) {
+                    match ancestor.parent() {
+                        Some(parent) => ancestor = parent,
+                        None => break,
+                    }
+                }
+                continue;
+            }
+
+            let Some(content) = load_content(file) else {
+                continue;
+            };
+
+            match file_name {
+                "pyproject.toml" => {
+                    let Ok(value) = content.parse::<Value>() else {
+                        continue;
+                    };
+                    source_roots.push(dir.clone());
+                    source_roots.extend(pyproject_source_roots(&value).iter().map(|root| normalize_path(&dir.join(root))));
+                    for name in pyproject_dependencies(&value) {
+                        declared_distributions.insert(normalize_distribution(&name), name);
+                    }
+                }
+                "setup.cfg" => {
+                    source_roots.push(dir.clone());
+                    source_roots.extend(setup_cfg_source_roots(&content).iter().map(|root| normalize_path(&dir.join(root))));
+                }
+                "setup.py" => source_roots.push(dir.clone()),
+                _ if file_name.starts_with("requirements") && file_name.ends_with(".txt") => {
+                    for name in requirements_dependencies(&content) {
+                        declared_distributions.insert(normalize_distribution(&name), name);
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        source_roots.sort_by_key(|root| (std::cmp::Reverse(root.components().count()), root.clone()));
+        source_roots.dedup();
+
+        PythonModuleResolver {
+            source_roots,
+            package_dirs,
+            declared_distributions,
+        }
+    }
+
+    pub fn resolve(&self, index: &SourceIndex, source_file: &str, dependency: &str) -> Option<ResolvedDependency> {
+        let level = dependency.chars().take_while(|c| *c == '.').count();
+        let segments: Vec<&str> = dependency[level..].split('.').filter(|segment| !segment.is_empty()).collect();
+
+        if level > 0 {
+            let mut base = normalize_path(Path::new(source_file).parent().unwrap_or(Path::new("")));
+            for _ in 1..level {
+                base.pop();
+            }
+            return self.resolve_in_root(index, &base, &segments, true).map(ResolvedDependency::Internal);
+        }
+
+        let top_level = *segments.first()?;
+        let source_dir = normalize_path(Path::new(source_file).parent().unwrap_or(Path::new("")));
+
+        for root in self.source_roots.iter().filter(|root| source_dir.starts_with(root)).chain(self.source_roots.iter()) {
+            if self.package_dirs.contains(&root.join(top_level)) || index.probe(root.join(top_level), &PYTHON_EXTENSIONS).is_some() {
+                return self.resolve_in_root(index, root, &segments, false).map(ResolvedDependency::Internal);
+            }
+        }
+
+        // Scripts can import modules that sit next to them without any package structure
+        if let Some(found) = self.resolve_in_root(index, &source_dir, &segments, false) {
+            return Some(ResolvedDependency::Internal(found));
+        }
+
+        if PYTHON_STDLIB_MODULES.contains(&top_level) {
+            return None;
+        }
+
+        Some(ResolvedDependency::External(self.distribution_name(&segments)))
+    }
+
+    /// Finds the deepest module of `segments` below `root`, dropping trailing names that are
+    /// attributes rather than submodules. Namespace packages have no file and resolve to nothing.
+    /// Only relative imports may land on the `__init__` of `root` itself.
+    fn resolve_in_root(&self, index: &SourceIndex, root: &Path, segments: &[&str], relative: bool) -> Option<String> {
+        let min_len = if relative { 0 } else { 1 };
+
+        for len in (min_len..=segments.len()).rev() {
+            let module_path: PathBuf = segments[..len].iter().collect();
+            let base = root.join(&module_path);
+
+            if len > 0 && let Some(found) = index.probe(&base, &PYTHON_EXTENSIONS) {
+                return Some(found.to_string());
+            }
+            if let Some(found) = index.probe(base.join("__init__"), &PYTHON_EXTENSIONS) {
+                return Some(found.to_string());
+            }
+            if len > 0 && self.package_dirs.contains(&normalize_path(&base)) && len < segments.len() {
+                return None;
+            }
+        }
+
+        None
+    }
+
+    fn distribution_name(&self, segments: &[&str]) -> String {
+        let top_level = segments[0];
+
+        if segments.len() > 1 {
+            let dotted = format!("{}.{}", segments[0], segments[1]);
+            if let Some((_, distribution)) = DISTRIBUTION_ALIASES.iter().find(|(module, _)| *module == dotted) {
+                return distribution.to_string();
+            }
+        }
+
+        if let Some(declared) = self.declared_distributions.get(&normalize_distribution(top_level)) {
+            return declared.clone();
+        }
+
+        DISTRIBUTION_ALIASES
+            .iter()
+            .find(|(module, _)| *module == top_level)
+            .map(|(_, distribution)| distribution.to_string())
+            .unwrap_or_else(|| top_level.to_string())
+    }
+}
+
+/// Package directories declared by setuptools, Poetry, Hatch and PDM, relative to the project.
+fn pyproject_source_roots(value: &Value) -> Vec<String> {
+    let mut roots = Vec::new();
+    let tool = value.get("tool");
+    let lookup = |path: &[&str]| -> Option<&Value> {
+        path.iter().try_fold(tool?, |value, key| value.get(key))
+    };
+
+    if let Some(package_dir) = lookup(&["setuptools", "package-dir"]).and_then(Value::as_table)
+        && let Some(root) = package_dir.get("").and_then(Value::as_str)
+    {
+        roots.push(root.to_string());
+    }
+    if let Some(Value::Array(wheres)) = lookup(&["setuptools", "packages", "find", "where"]) {
+        roots.extend(wheres.iter().filter_map(Value::as_str).map(String::from));
+    }
+    if let Some(Value::Array(packages)) = lookup(&["poetry", "packages"]) {
+        roots.extend(packages.iter().filter_map(|package| package.get("from")?.as_str()).map(String::from));
+    }
+    if let Some(Value::Array(packages)) = lookup(&["hatch", "build", "targets", "wheel", "packages"]) {
+        roots.extend(packages.iter().filter_map(Value::as_str).filter_map(|package| {
+            Path::new(package).parent().map(|parent| parent.to_string_lossy().to_string())
+        }));
+    }
+    if let Some(Value::String(package_dir)) = lookup(&["pdm", "build", "package-dir"]) {
+        roots.push(package_dir.clone());
+    }
+
+    roots.retain(|root| !root.is_empty() && root != ".");
+    roots
+}
+
+fn pyproject_dependencies(value: &Value) -> Vec<String> {
+    let mut names = Vec::new();
+
+    if let Some(Value::Array(dependencies)) = value.get("project").and_then(|project| project.get("dependencies")) {
+        names.extend(dependencies.iter().filter_map(Value::as_str).filter_map(requirement_name));
+    }
+    if let Some(Value::Table(dependencies)) = value
+        .get("tool")
+        .and_then(|tool| tool.get("poetry"))
+        .and_then(|poetry| poetry.get("dependencies"))
+    {
+        names.extend(dependencies.keys().filter(|name| *name != "python").cloned());
+    }
+
+    names
+}
+
+fn requirements_dependencies(content: &str) -> Vec<String> {
+    content
+        .lines()
+        .map(|line| line.split('#').next().unwrap_or("").trim())
+        .filter(|line| !line.is_empty() && !line.starts_with('-'))
+        .filter_map(requirement_name)
+        .collect()
+}
+
+/// Distribution name of a PEP 508 requirement such as `requests[socks]>=2.0; python_version > "3"`.
+pub(crate) fn requirement_name(requirement: &str) -> Option<String> {
+    let name: String = requirement
+        .trim()
+        .chars()
+        .take_while(|c| c.is_alphanumeric() || matches!(c, '-' | '_' | '.'))
+        .collect();
+    (!name.is_empty()).then_some(name)
+}
+
+pub(crate) fn normalize_distribution(name: &str) -> String {
+    name.to_lowercase().replace(['-', '.'], "_")
+}
+
+/// Reads `package_dir = =src` and `[options.packages.find] where = src` from a `setup.cfg`.
+fn setup_cfg_source_roots(content: &str) -> Vec<String> {
+    let mut roots = Vec::new();
+    let mut section = String::new();
+    let mut in_package_dir = false;
+
+    for line in content.lines() {
+        let trimmed = line.trim();
+        if trimmed.starts_with('[') && trimmed.ends_with(']') {
+            section = trimmed[1..trimmed.len() - 1].trim().to_string();
+            in_package_dir = false;
+            continue;
+        }
+
+        let continuation = line.starts_with(' ') || line.starts_with('\t');
+        let (key, value) = match trimmed.split_once('=') {
+            Some((key, value)) if !continuation => (key.trim(), value.trim()),
+            _ if continuation && in_package_dir => ("", trimmed),
+            _ => continue,
+        };
+
+        match (section.as_str(), key) {
+            ("options", "package_dir") => {
+                in_package_dir = true;
+                if let Some(root) = value.strip_prefix('=') {
+                    roots.push(root.trim().to_string());
+                }
+            }
+            ("options", "") => {
+                if let Some(root) = value.strip_prefix('=') {
+                    roots.push(root.trim().to_string());
+                }
+            }
+            ("options.packages.find", "where") => roots.push(value.to_string()),
+            _ => in_package_dir = false,
+        }
+    }
+
+    roots.retain(|root| !root.is_empty() && root != ".");
+    roots
+}
@@ -0,0 +1,171 @@
+use crate::dependency::c_resolver::CIncludeResolver;
+use crate::dependency::csharp_resolver::{CSharpNamespaceResolver, is_project_file};
+use crate::dependency::go_resolver::GoModuleResolver;
+use crate::dependency::java_resolver::JavaPackageResolver;
+use crate::dependency::js_resolver::JsModuleResolver;
+use crate::dependency::python_resolver::PythonModuleResolver;
+use crate::dependency::rust_resolver::RustModuleTree;
+use std::collections::HashMap;
+use std::path::{Component, Path, PathBuf};
+
+/// Where an import statement points once it has been resolved against the project.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum ResolvedDependency {
+    /// A file that is part of the analyzed tree, identified by its graph node id
+    Internal(String),
+    /// A third-party package that is not part of the analyzed tree
+    External(String),
+}
+
+/// Lookup table of every file found during the walk, keyed by lexically normalized path.
+pub struct SourceIndex {
+    root: PathBuf,
+    files: HashMap<PathBuf, String>,
+}
+
+impl SourceIndex {
+    pub fn new<P: AsRef<Path>>(root: P) -> Self {
+        SourceIndex {
+            root: root.as_ref().to_path_buf(),
+            files: HashMap::new(),
+        }
+    }
+
+    pub fn add_file<P: AsRef<Path>>(&mut self, path: P) {
+        let path = path.as_ref();
+        self.files.insert(normalize_path(path), path.to_string_lossy().to_string());
+    }
+
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    pub fn lookup<P: AsRef<Path>>(&self, candidate: P) -> Option<&str> {
+        self.files.get(&normalize_path(candidate.as_ref())).map(|id| id.as_str())
+    }
+
+    pub fn files(&self) -> impl Iterator<Item = &String> {
+        self.files.values()
+    }
+
+    /// Returns the first candidate that exists in the index, trying each extension in order.
+    pub fn probe<P: AsRef<Path>>(&self, base: P, extensions: &[&str]) -> Option<&str> {
+        let base = base.as_ref();
+        let base_str = base.to_string_lossy();
+
+        extensions.iter().find_map(|extension| {
+            if extension.is_empty() {
+                self.lookup(base)
+            } else {
+                self.lookup(PathBuf::from(format!("{}{}", base_str, extension)))
+            }
+        })
+    }
+}
+
+/// Resolves the raw import strings extracted from a file to graph nodes.
+pub struct DependencyResolver {
+    index: SourceIndex,
+    rust_modules: RustModuleTree,
+    js_modules: JsModuleResolver,
+    python_modules: PythonModuleResolver,
+    go_modules: GoModuleResolver,
+    java_packages: JavaPackageResolver,
+    csharp_namespaces: CSharpNamespaceResolver,
+    c_includes: CIncludeResolver,
+}
+
+impl DependencyResolver {
+    pub fn new(
+        index: SourceIndex,
+        load_content: &dyn Fn(&str) -> Option<String>,
+        include_dirs: &[PathBuf],
+    ) -> Self {
+        DependencyResolver {
+            rust_modules: RustModuleTree::build(&index, load_content),
+            js_modules: JsModuleResolver::build(&index, load_content),
+            python_modules: PythonModuleResolver::build(&index, load_content),
+            go_modules: GoModuleResolver::build(&index, load_content),
+            java_packages: JavaPackageResolver::build(&index, load_content),
+            csharp_namespaces: CSharpNamespaceResolver::build(&index, load_content),
+            c_includes: CIncludeResolver::build(&index, include_dirs),
+            index,
+        }
+    }
+
+    /// Resolves one import. Go packages, Java wildcard imports and C# namespaces can span
+    /// several files, so a single import may produce more than one target.
+    pub fn resolve(&self, source_file: &str, language: &str, dependency: &str) -> Vec<ResolvedDependency> {
+        let dependency = dependency.trim();
+        if dependency.is_empty() {
+            return Vec::new();
+        }
+
+        match language {
+            "Rust" => self.rust_modules.resolve(source_file, dependency).into_iter().collect(),
+            "JavaScript" | "TypeScript" => self.js_modules.resolve(&self.index, source_file, dependency).into_iter().collect(),
+            "Python" => self.python_modules.resolve(&self.index, source_file, dependency).into_iter().collect(),
+            "Go" => self.go_modules.resolve(dependency),
+            "Java" => self.java_packages.resolve(dependency),
+            "C#" => self.csharp_namespaces.resolve(source_file, dependency),
+            "DotNetProject" if is_project_file(source_file) => self
+                .csharp_namespaces
+                .resolve_project_reference(&self.index, source_file, dependency)
+                .into_iter()
+                .collect(),
+            "C" | "C++" => self.c_includes.resolve(&self.index, source_file, dependency).into_iter().collect(),
+            _ => Vec::new(),
+        }
+    }
+}
+
+/// Directory that child modules of the given Rust file live in.
+pub fn rust_module_directory(source_file: &Path) -> PathBuf {
+    let parent = source_file.parent().unwrap_or(Path::new("")).to_path_buf();
+    let file_name = source_file.file_name().and_then(|n| n.to_str()).unwrap_or("");
+
+    match file_name {
+        "mod.rs" | "lib.rs" | "main.rs" => parent,
+        _ => {
+            let stem = source_file.file_stem().and_then(|s| s.to_str()).unwrap_or("");
+            parent.join(stem)
+        }
+    }
+}
+
+/// Package name of a bare JavaScript specifier, keeping the scope of `@scope/pkg` names.
+pub fn js_package_name(specifier: &str) -> String {
+    let mut segments = specifier.split('/');
+    let first = segments.next().unwrap_or(specifier);
+
+    if first.starts_with('@') && let Some(second) = segments.next() {
+        return format!("{}/{}", first, second);
+    }
+
+    first.to_string()
+}
+
+/// Lexically removes `.` and `..` components so joined import paths can be compared.
+pub fn normalize_path(path: &Path) -> PathBuf {
+    let mut normalized = PathBuf::new();
+
+    for component in path.components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir => {
+                let can_pop = matches!(
+                    normalized.components().next_back(),
+                    Some(Component::Normal(_))
+                );
+                if can_pop {
+                    normalized.pop();
+                } else if !matches!(normalized.components().next_back(), Some(Component::RootDir)) {
+                    normalized.push("..");
+                }
+            }
+            other => normalized.push(other.as_os_str()),
+        }
+    }
+
+    normalized
+}
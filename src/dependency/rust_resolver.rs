@@ -0,0 +1,700 @@
+use crate::dependency::cargo_manifest::{CargoManifest, WorkspaceDependencies};
+use crate::dependency::clike::count_newlines;
+use crate::dependency::dependency_graph::{EdgeImport, ImportKind};
+use crate::dependency::resolver::{ResolvedDependency, SourceIndex, rust_module_directory};
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+
+const RUST_BUILTIN_CRATES: [&str; 5] = ["std", "core", "alloc", "proc_macro", "test"];
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Ident(String),
+    Str(String),
+    Punct(char),
+    PathSep,
+}
+
+/// A `mod name;` declaration, possibly nested inside inline `mod a { ... }` blocks.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RustModuleDecl {
+    pub inline_path: Vec<String>,
+    pub name: String,
+    pub path_attr: Option<String>,
+}
+
+/// The module-level items of a Rust source file that matter for dependency resolution.
+#[derive(Debug, Default, Clone)]
+pub struct RustSourceItems {
+    pub mod_decls: Vec<RustModuleDecl>,
+    pub inline_modules: Vec<Vec<String>>,
+    /// `mod` declarations, `use` paths and `extern crate` names in source order. `mod`
+    /// declarations become `self::name` paths so they resolve through the module tree like any
+    /// other use.
+    pub imports: Vec<EdgeImport>,
+}
+
+pub fn extract_rust_dependencies(content: &str) -> Vec<EdgeImport> {
+    parse_rust_source(content).imports
+}
+
+pub fn parse_rust_source(content: &str) -> RustSourceItems {
+    let (tokens, lines) = tokenize(content);
+    let mut items = RustSourceItems::default();
+
+    let mut depth = 0usize;
+    let mut inline_stack: Vec<(String, usize)> = Vec::new();
+    let mut pending_path: Option<String> = None;
+    let mut i = 0;
+
+    while i < tokens.len() {
+        match &tokens[i] {
+            Token::Punct('#') => {
+                let (end, path_attr) = parse_attribute(&tokens, i + 1);
+                if path_attr.is_some() {
+                    pending_path = path_attr;
+                }
+                i = end;
+                continue;
+            }
+            Token::Ident(keyword) if keyword == "pub" => {
+                i += 1;
+                if tokens.get(i) == Some(&Token::Punct('(')) {
+                    while i < tokens.len() && tokens[i] != Token::Punct(')') {
+                        i += 1;
+                    }
+                    i += 1;
+                }
+                continue;
+            }
+            Token::Ident(keyword) if keyword == "mod" => {
+                let inline_path: Vec<String> = inline_stack.iter().map(|(name, _)| name.clone()).collect();
+
+                if let Some(Token::Ident(name)) = tokens.get(i + 1) {
+                    match tokens.get(i + 2) {
+                        Some(Token::Punct(';')) => {
+                            let mut segments = vec!["self".to_string()];
+                            segments.extend(inline_path.iter().cloned());
+                            segments.push(name.clone());
+                            items.imports.push(EdgeImport::new(segments.join("::"), lines[i], ImportKind::Module));
+
+                            items.mod_decls.push(RustModuleDecl {
+                                inline_path,
+                                name: name.clone(),
+                                path_attr: pending_path.take(),
+                            });
+                            i += 3;
+                            continue;
+                        }
+                        Some(Token::Punct('{')) => {
+                            let mut module_path = inline_path;
+                            module_path.push(name.clone());
+                            items.inline_modules.push(module_path);
+
+                            depth += 1;
+                            inline_stack.push((name.clone(), depth));
+                            pending_path = None;
+                            i += 3;
+                            continue;
+                        }
+                        _ => {}
+                    }
+                }
+            }
+            Token::Ident(keyword) if keyword == "use" => {
+                let start = i + 1;
+                let mut end = start;
+                while end < tokens.len() && tokens[end] != Token::Punct(';') {
+                    end += 1;
+                }
+
+                let inline_path: Vec<String> = inline_stack.iter().map(|(name, _)| name.clone()).collect();
+                let mut paths = Vec::new();
+                let mut pos = 0;
+                parse_use_tree(&tokens[start..end], &mut pos, Vec::new(), &mut paths);
+
+                for (path, last) in paths {
+                    let path = rebase_inline_path(path, &inline_path);
+                    if !path.is_empty() {
+                        items.imports.push(EdgeImport::new(path.join("::"), lines[start + last], ImportKind::Use));
+                    }
+                }
+
+                pending_path = None;
+                i = end + 1;
+                continue;
+            }
+            Token::Ident(keyword) if keyword == "extern" => {
+                if let (Some(Token::Ident(crate_kw)), Some(Token::Ident(name))) = (tokens.get(i + 1), tokens.get(i + 2))
+                    && crate_kw == "crate"
+                    && name != "self"
+                {
+                    items.imports.push(EdgeImport::new(name.clone(), lines[i + 2], ImportKind::ExternCrate));
+                }
+            }
+            Token::Punct('{') => {
+                depth += 1;
+                pending_path = None;
+            }
+            Token::Punct('}') => {
+                if inline_stack.last().is_some_and(|(_, module_depth)| *module_depth == depth) {
+                    inline_stack.pop();
+                }
+                depth = depth.saturating_sub(1);
+                pending_path = None;
+            }
+            Token::Punct(';') => {
+                pending_path = None;
+            }
+            _ => {}
+        }
+
+        i += 1;
+    }
+
+    items
+}
+
+fn parse_attribute(tokens: &[Token], mut i: usize) -> (usize, Option<String>) {
+    if tokens.get(i) == Some(&Token::Punct('!')) {
+        i += 1;
+    }
+
+    if tokens.get(i) != Some(&Token::Punct('[')) {
+        return (i, None);
+    }
+
+    let path_attr = match (tokens.get(i + 1), tokens.get(i + 2), tokens.get(i + 3)) {
+        (Some(Token::Ident(name)), Some(Token::Punct('=')), Some(Token::Str(value))) if name == "path" => {
+            Some(value.clone())
+        }
+        _ => None,
+    };
+
+    let mut bracket_depth = 0usize;
+    while i < tokens.len() {
+        match tokens[i] {
+            Token::Punct('[') => bracket_depth += 1,
+            Token::Punct(']') => {
+                bracket_depth -= 1;
+                if bracket_depth == 0 {
+                    return (i + 1, path_attr);
+                }
+            }
+            _ => {}
+        }
+        i += 1;
+    }
+
+    (i, path_attr)
+}
+
+/// Collects the paths of a use tree, each with the index of its last token so that paths in a
+/// group spread over several lines keep their own line.
+fn parse_use_tree(tokens: &[Token], pos: &mut usize, prefix: Vec<String>, out: &mut Vec<(Vec<String>, usize)>) {
+    let mut segments = prefix;
+    let mut last = *pos;
+
+    if tokens.get(*pos) == Some(&Token::PathSep) {
+        *pos += 1;
+    }
+
+    loop {
+        match tokens.get(*pos) {
+            Some(Token::Ident(segment)) => {
+                segments.push(segment.clone());
+                last = *pos;
+                *pos += 1;
+                if tokens.get(*pos) == Some(&Token::PathSep) {
+                    *pos += 1;
+                    continue;
+                }
+                break;
+            }
+            Some(Token::Punct('*')) => {
+                out.push((segments, *pos));
+                *pos += 1;
+                return;
+            }
+            Some(Token::Punct('{')) => {
+                *pos += 1;
+                while let Some(token) = tokens.get(*pos) {
+                    match token {
+                        Token::Punct('}') => break,
+                        Token::Punct(',') => *pos += 1,
+                        _ => {
+                            let before = *pos;
+                            parse_use_tree(tokens, pos, segments.clone(), out);
+                            if *pos == before {
+                                *pos += 1;
+                            }
+                        }
+                    }
+                }
+                *pos += 1;
+                return;
+            }
+            _ => break,
+        }
+    }
+
+    if let Some(Token::Ident(keyword)) = tokens.get(*pos)
+        && keyword == "as"
+    {
+        *pos += 2;
+    }
+
+    if segments.last().is_some_and(|segment| segment == "self") {
+        segments.pop();
+    }
+
+    if !segments.is_empty() {
+        out.push((segments, last));
+    }
+}
+
+/// Rewrites `self::`/`super::` paths written inside inline modules so they are relative to the file's module.
+fn rebase_inline_path(path: Vec<String>, inline_path: &[String]) -> Vec<String> {
+    if inline_path.is_empty() || path.is_empty() {
+        return path;
+    }
+
+    match path[0].as_str() {
+        "self" => {
+            let mut rebased = vec!["self".to_string()];
+            rebased.extend(inline_path.iter().cloned());
+            rebased.extend(path[1..].iter().cloned());
+            rebased
+        }
+        "super" => {
+            let supers = path.iter().take_while(|segment| *segment == "super").count();
+            let mut rebased = Vec::new();
+
+            if supers <= inline_path.len() {
+                rebased.push("self".to_string());
+                rebased.extend(inline_path[..inline_path.len() - supers].iter().cloned());
+            } else {
+                rebased.extend(std::iter::repeat_n("super".to_string(), supers - inline_path.len()));
+            }
+
+            rebased.extend(path[supers..].iter().cloned());
+            rebased
+        }
+        _ => path,
+    }
+}
+
+fn tokenize(content: &str) -> (Vec<Token>, Vec<usize>) {
+    let chars: Vec<char> = content.chars().collect();
+    let mut tokens = Vec::new();
+    let mut lines = Vec::new();
+    let mut line = 1;
+    let mut i = 0;
+
+    while i < chars.len() {
+        let token_start = i;
+        let c = chars[i];
+
+        if c.is_whitespace() {
+            i += 1;
+        } else if c == '/' && chars.get(i + 1) == Some(&'/') {
+            while i < chars.len() && chars[i] != '\n' {
+                i += 1;
+            }
+        } else if c == '/' && chars.get(i + 1) == Some(&'*') {
+            let mut nesting = 0usize;
+            while i < chars.len() {
+                if chars[i] == '/' && chars.get(i + 1) == Some(&'*') {
+                    nesting += 1;
+                    i += 2;
+                } else if chars[i] == '*' && chars.get(i + 1) == Some(&'/') {
+                    nesting -= 1;
+                    i += 2;
+                    if nesting == 0 {
+                        break;
+                    }
+                } else {
+                    i += 1;
+                }
+            }
+        } else if let Some((value, end)) = read_raw_string(&chars, i) {
+            tokens.push(Token::Str(value));
+            i = end;
+        } else if c == 'r' && chars.get(i + 1) == Some(&'#') && chars.get(i + 2).is_some_and(|n| is_ident_start(*n)) {
+            let start = i + 2;
+            i = start;
+            while i < chars.len() && is_ident_continue(chars[i]) {
+                i += 1;
+            }
+            tokens.push(Token::Ident(chars[start..i].iter().collect()));
+        } else if c == '"' {
+            let (value, end) = read_string(&chars, i + 1);
+            tokens.push(Token::Str(value));
+            i = end;
+        } else if c == '\'' {
+            i = skip_char_or_lifetime(&chars, i);
+        } else if is_ident_start(c) {
+            let start = i;
+            while i < chars.len() && is_ident_continue(chars[i]) {
+                i += 1;
+            }
+            tokens.push(Token::Ident(chars[start..i].iter().collect()));
+        } else if c.is_ascii_digit() {
+            while i < chars.len() && (chars[i].is_ascii_alphanumeric() || chars[i] == '_') {
+                i += 1;
+            }
+        } else if c == ':' && chars.get(i + 1) == Some(&':') {
+            tokens.push(Token::PathSep);
+            i += 2;
+        } else {
+            tokens.push(Token::Punct(c));
+            i += 1;
+        }
+
+        lines.resize(tokens.len(), line);
+        line += count_newlines(&chars[token_start..i.min(chars.len())]);
+    }
+
+    (tokens, lines)
+}
+
+fn is_ident_start(c: char) -> bool {
+    c.is_alphabetic() || c == '_'
+}
+
+fn is_ident_continue(c: char) -> bool {
+    c.is_alphanumeric() || c == '_'
+}
+
+fn read_string(chars: &[char], mut i: usize) -> (String, usize) {
+    let mut value = String::new();
+
+    while i < chars.len() {
+        match chars[i] {
+            '\\' => {
+                if let Some(next) = chars.get(i + 1) {
+                    value.push(*next);
+                }
+                i += 2;
+            }
+            '"' => return (value, i + 1),
+            other => {
+                value.push(other);
+                i += 1;
+            }
+        }
+    }
+
+    (value, i)
+}
+
+fn read_raw_string(chars: &[char], start: usize) -> Option<(String, usize)> {
+    let mut i = start;
+    if chars.get(i) == Some(&'b') {
+        i += 1;
+    }
+    if chars.get(i) != Some(&'r') {
+        return None;
+    }
+    i += 1;
+
+    let mut hashes = 0;
+    while chars.get(i) == Some(&'#') {
+        hashes += 1;
+        i += 1;
+    }
+    if chars.get(i) != Some(&'"') {
+        return None;
+    }
+    i += 1;
+
+    let body_start = i;
+    while i < chars.len() {
+        if chars[i] == '"' && (1..=hashes).all(|offset| chars.get(i + offset) == Some(&'#')) {
+            let value = chars[body_start..i].iter().collect();
+            return Some((value, i + 1 + hashes));
+        }
+        i += 1;
+    }
+
+    Some((chars[body_start..].iter().collect(), chars.len()))
+}
+
+fn skip_char_or_lifetime(chars: &[char], i: usize) -> usize {
+    if chars.get(i + 1) == Some(&'\\') {
+        let mut j = i + 2;
+        while j < chars.len() && chars[j] != '\'' && chars[j] != '\n' {
+            j += 1;
+        }
+        return j + 1;
+    }
+
+    if chars.get(i + 2) == Some(&'\'') {
+        return i + 3;
+    }
+
+    let mut j = i + 1;
+    while j < chars.len() && is_ident_continue(chars[j]) {
+        j += 1;
+    }
+    j
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+struct ModuleContext {
+    crate_root: String,
+    path: Vec<String>,
+}
+
+/// The module tree of every crate found in the analyzed tree, built by following `mod`
+/// declarations from each `lib.rs`, `main.rs` and `src/bin/*.rs` root.
+#[derive(Default)]
+pub struct RustModuleTree {
+    contexts: HashMap<String, Vec<ModuleContext>>,
+    modules: HashMap<(String, Vec<String>), String>,
+    crate_names: HashMap<String, String>,
+    crate_roots: Vec<String>,
+}
+
+impl RustModuleTree {
+    pub fn build(index: &SourceIndex, load_content: &dyn Fn(&str) -> Option<String>) -> Self {
+        let mut tree = RustModuleTree::default();
+
+        let mut roots: Vec<String> = index
+            .files()
+            .filter(|file| is_crate_root(Path::new(file)))
+            .cloned()
+            .collect();
+        roots.sort_by_key(|root| (!root.ends_with("lib.rs"), root.clone()));
+
+        for root in &roots {
+            tree.walk_crate(root, index, load_content);
+
+            if root.ends_with("lib.rs")
+                && let Some(name) = find_crate_name(Path::new(root), index, load_content)
+            {
+                tree.crate_names.entry(name).or_insert_with(|| root.clone());
+            }
+        }
+
+        tree.crate_roots = roots;
+        tree
+    }
+
+    fn walk_crate(&mut self, root: &str, index: &SourceIndex, load_content: &dyn Fn(&str) -> Option<String>) {
+        let mut visited = HashSet::new();
+        let mut pending = vec![(root.to_string(), Vec::<String>::new())];
+
+        while let Some((file, module_path)) = pending.pop() {
+            if !visited.insert(file.clone()) {
+                continue;
+            }
+
+            let context = ModuleContext { crate_root: root.to_string(), path: module_path.clone() };
+            self.contexts.entry(file.clone()).or_default().push(context);
+            self.modules.entry((root.to_string(), module_path.clone())).or_insert_with(|| file.clone());
+
+            let Some(content) = load_content(&file) else {
+                continue;
+            };
+            let items = parse_rust_source(&content);
+
+            for inline in &items.inline_modules {
+                let mut inline_module_path = module_path.clone();
+                inline_module_path.extend(inline.iter().cloned());
+                self.modules.entry((root.to_string(), inline_module_path)).or_insert_with(|| file.clone());
+            }
+
+            for decl in &items.mod_decls {
+                if let Some(child) = locate_module_file(Path::new(&file), decl, index) {
+                    let mut child_path = module_path.clone();
+                    child_path.extend(decl.inline_path.iter().cloned());
+                    child_path.push(decl.name.clone());
+                    pending.push((child, child_path));
+                }
+            }
+        }
+    }
+
+    pub fn resolve(&self, source_file: &str, dependency: &str) -> Option<ResolvedDependency> {
+        let segments: Vec<&str> = dependency.split("::").filter(|segment| !segment.is_empty()).collect();
+        let first = *segments.first()?;
+
+        if RUST_BUILTIN_CRATES.contains(&first) {
+            return None;
+        }
+
+        let contexts = self.contexts_for(source_file);
+        let mut fallback = None;
+
+        for context in &contexts {
+            match self.resolve_in_context(context, &segments) {
+                Some(ResolvedDependency::Internal(target)) => return Some(ResolvedDependency::Internal(target)),
+                other => {
+                    if fallback.is_none() {
+                        fallback = other;
+                    }
+                }
+            }
+        }
+
+        if contexts.is_empty() {
+            return self.resolve_without_context(&segments);
+        }
+
+        fallback
+    }
+
+    fn contexts_for(&self, source_file: &str) -> Vec<ModuleContext> {
+        if let Some(contexts) = self.contexts.get(source_file) {
+            return contexts.clone();
+        }
+
+        self.infer_context(source_file).into_iter().collect()
+    }
+
+    /// Guesses the module path of a file that no `mod` declaration reaches, using the
+    /// directory layout below the nearest crate root.
+    fn infer_context(&self, source_file: &str) -> Option<ModuleContext> {
+        let source_path = Path::new(source_file);
+
+        let root = self
+            .crate_roots
+            .iter()
+            .filter(|root| {
+                Path::new(root).parent().is_some_and(|root_dir| source_path.starts_with(root_dir))
+            })
+            .max_by_key(|root| Path::new(root).components().count())?;
+
+        let root_dir = Path::new(root).parent()?;
+        let relative = source_path.strip_prefix(root_dir).ok()?;
+
+        let mut path: Vec<String> = relative
+            .iter()
+            .map(|component| component.to_string_lossy().to_string())
+            .collect();
+
+        if let Some(last) = path.pop() {
+            let stem = last.trim_end_matches(".rs").to_string();
+            if stem != "mod" {
+                path.push(stem);
+            }
+        }
+
+        Some(ModuleContext { crate_root: root.clone(), path })
+    }
+
+    fn resolve_in_context(&self, context: &ModuleContext, segments: &[&str]) -> Option<ResolvedDependency> {
+        let first = segments[0];
+        let mut crate_root = context.crate_root.clone();
+
+        let (base, rest): (Vec<String>, &[&str]) = match first {
+            "crate" => (Vec::new(), &segments[1..]),
+            "self" => (context.path.clone(), &segments[1..]),
+            "super" => {
+                let supers = segments.iter().take_while(|segment| **segment == "super").count();
+                let keep = context.path.len().saturating_sub(supers);
+                (context.path[..keep].to_vec(), &segments[supers..])
+            }
+            _ => {
+                let mut child = context.path.clone();
+                child.push(first.to_string());
+
+                if self.modules.contains_key(&(crate_root.clone(), child)) {
+                    (context.path.clone(), segments)
+                } else if self.modules.contains_key(&(crate_root.clone(), vec![first.to_string()])) {
+                    (Vec::new(), segments)
+                } else if let Some(lib_root) = self.crate_names.get(first) {
+                    crate_root = lib_root.clone();
+                    (Vec::new(), &segments[1..])
+                } else {
+                    return Some(ResolvedDependency::External(first.to_string()));
+                }
+            }
+        };
+
+        for take in (0..=rest.len()).rev() {
+            let mut module_path = base.clone();
+            module_path.extend(rest[..take].iter().map(|segment| segment.to_string()));
+
+            if let Some(file) = self.modules.get(&(crate_root.clone(), module_path)) {
+                return Some(ResolvedDependency::Internal(file.clone()));
+            }
+        }
+
+        None
+    }
+
+    fn resolve_without_context(&self, segments: &[&str]) -> Option<ResolvedDependency> {
+        let first = segments[0];
+
+        if matches!(first, "crate" | "self" | "super" | "Self") {
+            return None;
+        }
+
+        if let Some(lib_root) = self.crate_names.get(first) {
+            let context = ModuleContext { crate_root: lib_root.clone(), path: Vec::new() };
+            let mut crate_relative = vec!["crate"];
+            crate_relative.extend_from_slice(&segments[1..]);
+            return self.resolve_in_context(&context, &crate_relative);
+        }
+
+        Some(ResolvedDependency::External(first.to_string()))
+    }
+}
+
+fn is_crate_root(path: &Path) -> bool {
+    let file_name = path.file_name().and_then(|name| name.to_str()).unwrap_or("");
+    if file_name == "lib.rs" || file_name == "main.rs" {
+        return true;
+    }
+
+    let parent = path.parent();
+    let in_bin_dir = parent.and_then(|p| p.file_name()).is_some_and(|name| name == "bin");
+    let in_src_dir = parent
+        .and_then(|p| p.parent())
+        .and_then(|p| p.file_name())
+        .is_some_and(|name| name == "src");
+
+    in_bin_dir && in_src_dir && file_name.ends_with(".rs")
+}
+
+fn locate_module_file(source_file: &Path, decl: &RustModuleDecl, index: &SourceIndex) -> Option<String> {
+    let mut module_dir = rust_module_directory(source_file);
+    for inline in &decl.inline_path {
+        module_dir.push(inline);
+    }
+
+    if let Some(path_attr) = &decl.path_attr {
+        let base_dir: PathBuf = if decl.inline_path.is_empty() {
+            source_file.parent().unwrap_or(Path::new("")).to_path_buf()
+        } else {
+            module_dir
+        };
+        return index.lookup(base_dir.join(path_attr)).map(|file| file.to_string());
+    }
+
+    index
+        .lookup(module_dir.join(format!("{}.rs", decl.name)))
+        .or_else(|| index.lookup(module_dir.join(&decl.name).join("mod.rs")))
+        .map(|file| file.to_string())
+}
+
+/// Reads the library name from the nearest `Cargo.toml` above a crate root.
+fn find_crate_name(root: &Path, index: &SourceIndex, load_content: &dyn Fn(&str) -> Option<String>) -> Option<String> {
+    for dir in root.ancestors().skip(1) {
+        let manifest = dir.join("Cargo.toml");
+        let Some(manifest_id) = index.lookup(&manifest) else {
+            if dir == index.root() {
+                break;
+            }
+            continue;
+        };
+
+        let content = load_content(manifest_id)?;
+        let manifest = CargoManifest::parse(&manifest, &content, &WorkspaceDependencies::default()).ok()??;
+        return Some(manifest.crate_name());
+    }
+
+    None
+}
@@ -156,17 +156,10 @@ impl CodeDescriptor {
                 return;
             }
             
-            let file_name = path.file_name().and_then(|name| name.to_str()).unwrap_or("");
-            let extension = path.extension().and_then(|ext| ext.to_str()).unwrap_or("");
-            
             let language = if let Some(cached_lang) = self.cache.get_language(&path_str) {
                 cached_lang
             } else {
-                let detected_lang = if extension.is_empty() && !file_name.is_empty() {
-                    self.language_detector.detect_by_filename(file_name)
-                } else {
-                    self.language_detector.detect_language(extension)
-                };
+                let detected_lang = self.language_detector.detect(path, Some(&content));
                 self.cache.cache_language(&path_str, detected_lang.clone());
                 detected_lang
             };
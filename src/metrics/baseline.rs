@@ -0,0 +1,174 @@
+use crate::metrics::models::{CodeMetrics, GroupStats};
+use crate::metrics::reporter::METRICS_SCHEMA_VERSION;
+use serde::Deserialize;
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use std::path::Path;
+
+/// Rows in the files that grew the most table.
+const MOST_GROWN_FILES: usize = 10;
+
+/// A file as recorded in a metrics snapshot.
+#[derive(Debug, Clone, Deserialize)]
+pub struct SnapshotFile {
+    pub path: String,
+    pub language: String,
+    pub lines_of_code: usize,
+    pub comment_lines: usize,
+    pub is_test_file: bool,
+}
+
+/// The per-file line counts of a metrics run, read back from its JSON export so that a later
+/// run can be compared with it.
+#[derive(Debug, Clone, Deserialize)]
+pub struct MetricsSnapshot {
+    pub schema_version: u32,
+    /// The analyzed directory; file paths are compared relative to it
+    #[serde(default)]
+    pub root: String,
+    pub files: Vec<SnapshotFile>,
+}
+
+impl MetricsSnapshot {
+    pub fn from_metrics(metrics: &CodeMetrics) -> Self {
+        MetricsSnapshot {
+            schema_version: METRICS_SCHEMA_VERSION,
+            root: metrics.root.clone(),
+            files: metrics
+                .files
+                .iter()
+                .map(|file| SnapshotFile {
+                    path: file.path.clone(),
+                    language: file.language.clone(),
+                    lines_of_code: file.lines_of_code,
+                    comment_lines: file.comment_lines,
+                    is_test_file: file.is_test_file,
+                })
+                .collect(),
+        }
+    }
+
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, String> {
+        let path = path.as_ref();
+        let content = fs::read_to_string(path)
+            .map_err(|e| format!("Failed to read metrics snapshot {}: {}", path.display(), e))?;
+        Self::parse(&content).map_err(|e| format!("{}: {}", path.display(), e))
+    }
+
+    pub fn parse(content: &str) -> Result<Self, String> {
+        let snapshot: MetricsSnapshot =
+            serde_json::from_str(content).map_err(|e| format!("Not a metrics JSON export: {}", e))?;
+
+        if snapshot.schema_version != METRICS_SCHEMA_VERSION {
+            return Err(format!(
+                "Unsupported metrics schema version {}, expected {}",
+                snapshot.schema_version, METRICS_SCHEMA_VERSION
+            ));
+        }
+        Ok(snapshot)
+    }
+
+    /// The path of a file relative to the analyzed directory, so that runs from different
+    /// checkouts line up.
+    fn relative_path(&self, file: &SnapshotFile) -> String {
+        let relative = Path::new(&file.path).strip_prefix(&self.root).unwrap_or(Path::new(&file.path));
+        relative.to_string_lossy().replace('\\', "/")
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct GroupDelta {
+    pub name: String,
+    pub baseline: GroupStats,
+    pub current: GroupStats,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct FileGrowth {
+    pub path: String,
+    pub baseline_lines: usize,
+    pub current_lines: usize,
+}
+
+impl FileGrowth {
+    pub fn growth(&self) -> usize {
+        self.current_lines.saturating_sub(self.baseline_lines)
+    }
+}
+
+/// How a metrics run differs from a baseline snapshot.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MetricsDiff {
+    pub total: GroupDelta,
+    /// Languages whose counts changed, by name
+    pub languages: Vec<GroupDelta>,
+    /// Directories whose counts changed, by path
+    pub directories: Vec<GroupDelta>,
+    /// Files with the largest growth in lines of code, new files included
+    pub grown_files: Vec<FileGrowth>,
+}
+
+impl MetricsDiff {
+    pub fn compare(baseline: &MetricsSnapshot, current: &MetricsSnapshot) -> Self {
+        let mut total: (GroupStats, GroupStats) = Default::default();
+        let mut languages: BTreeMap<String, (GroupStats, GroupStats)> = BTreeMap::new();
+        let mut directories: BTreeMap<String, (GroupStats, GroupStats)> = BTreeMap::new();
+        let mut file_lines: HashMap<String, (usize, usize)> = HashMap::new();
+
+        for (snapshot, is_current) in [(baseline, false), (current, true)] {
+            for file in &snapshot.files {
+                let path = snapshot.relative_path(file);
+                let directory = match path.rsplit_once('/') {
+                    Some((directory, _)) => directory.to_string(),
+                    None => ".".to_string(),
+                };
+
+                add_file(pick(&mut total, is_current), file);
+                add_file(pick(languages.entry(file.language.clone()).or_default(), is_current), file);
+                add_file(pick(directories.entry(directory).or_default(), is_current), file);
+
+                let lines = file_lines.entry(path).or_default();
+                *pick(lines, is_current) = file.lines_of_code;
+            }
+        }
+
+        let mut grown_files: Vec<FileGrowth> = file_lines
+            .into_iter()
+            .map(|(path, (baseline_lines, current_lines))| FileGrowth { path, baseline_lines, current_lines })
+            .filter(|file| file.growth() > 0)
+            .collect();
+        grown_files.sort_by(|a, b| b.growth().cmp(&a.growth()).then_with(|| a.path.cmp(&b.path)));
+        grown_files.truncate(MOST_GROWN_FILES);
+
+        MetricsDiff {
+            total: GroupDelta { name: "Total".to_string(), baseline: total.0, current: total.1 },
+            languages: changed_groups(languages),
+            directories: changed_groups(directories),
+            grown_files,
+        }
+    }
+
+    /// Whether the test to production ratio fell below the baseline's.
+    pub fn test_ratio_dropped(&self) -> bool {
+        match (self.total.baseline.test_ratio(), self.total.current.test_ratio()) {
+            (Some(baseline), Some(current)) => current < baseline,
+            _ => false,
+        }
+    }
+}
+
+fn pick<T>(pair: &mut (T, T), is_current: bool) -> &mut T {
+    if is_current { &mut pair.1 } else { &mut pair.0 }
+}
+
+fn add_file(stats: &mut GroupStats, file: &SnapshotFile) {
+    stats.add_file(file.lines_of_code, file.comment_lines, file.is_test_file);
+}
+
+fn changed_groups(groups: BTreeMap<String, (GroupStats, GroupStats)>) -> Vec<GroupDelta> {
+    groups
+        .into_iter()
+        .filter(|(_, (baseline, current))| baseline != current)
+        .map(|(name, (baseline, current))| GroupDelta { name, baseline, current })
+        .collect()
+}
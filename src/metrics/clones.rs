@@ -0,0 +1,367 @@
+use crate::metrics::complexity::{mask, tokenize};
+use crate::metrics::halstead::keywords;
+use crate::util::parallel::ParallelProcessing;
+use rayon::prelude::*;
+use serde::Serialize;
+use std::collections::hash_map::DefaultHasher;
+use std::collections::{HashMap, HashSet};
+use std::hash::{Hash, Hasher};
+
+/// Shortest duplicated token sequence reported by default.
+pub const DEFAULT_MIN_TOKENS: usize = 50;
+
+/// Fewest lines a duplicated block must span by default.
+pub const DEFAULT_MIN_LINES: usize = 5;
+
+/// Multiplier of the polynomial rolling hash over token hashes.
+const HASH_BASE: u64 = 1_000_003;
+
+/// One copy of a duplicated block.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct CloneInstance {
+    pub path: String,
+    pub start_line: usize,
+    pub end_line: usize,
+}
+
+/// Blocks with the same normalized tokens, found in two or more places.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct CloneClass {
+    pub language: String,
+    pub tokens: usize,
+    /// Lines spanned by the longest copy
+    pub lines: usize,
+    /// Copies ordered by path and line
+    pub instances: Vec<CloneInstance>,
+}
+
+impl CloneClass {
+    /// Tokens that would go away if the copies were merged into one.
+    pub fn duplicated_tokens(&self) -> usize {
+        self.tokens * (self.instances.len() - 1)
+    }
+}
+
+/// A file to look for clones in.
+#[derive(Debug, Clone, Copy)]
+pub struct SourceFile<'a> {
+    pub path: &'a str,
+    pub language: &'a str,
+    pub content: &'a str,
+}
+
+/// What the detector found: the clone classes, largest first, and the duplicated lines of
+/// every file it could analyze.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct CloneDetection {
+    pub classes: Vec<CloneClass>,
+    pub duplicated_lines: HashMap<String, usize>,
+}
+
+/// Whether clones are looked for in a language; the same languages as the Halstead metrics,
+/// whose keyword sets tell keywords from identifiers.
+pub fn supports_language(language: &str) -> bool {
+    keywords(language).is_some()
+}
+
+/// A normalized token: identifiers and literals are abstracted, so that copies with renamed
+/// variables or changed constants still match. Keywords and punctuation are kept as they are.
+#[derive(Debug, Clone, Copy)]
+struct NormalizedToken {
+    hash: u64,
+    line: usize,
+}
+
+fn normalize(content: &str, language: &str) -> Option<Vec<NormalizedToken>> {
+    let keywords = keywords(language)?;
+    let masked = mask(content, language);
+    let tokens = tokenize(&masked.text);
+
+    let hash = |text: &str| {
+        let mut hasher = DefaultHasher::new();
+        text.hash(&mut hasher);
+        hasher.finish()
+    };
+    let mut normalized = Vec::with_capacity(tokens.len());
+    let mut index = 0;
+    while index < tokens.len() {
+        let token = tokens[index];
+
+        let literal = masked.literals.partition_point(|literal| literal.end <= token.offset);
+        if let Some(range) = masked.literals.get(literal).filter(|range| range.contains(&token.offset)) {
+            normalized.push(NormalizedToken { hash: hash("$literal"), line: token.line });
+            while index < tokens.len() && tokens[index].offset < range.end {
+                index += 1;
+            }
+            continue;
+        }
+
+        let text = if token.is_identifier() && !keywords.contains(&token.text) {
+            "$identifier"
+        } else if token.text.starts_with(|c: char| c.is_ascii_digit()) {
+            "$literal"
+        } else {
+            token.text
+        };
+        normalized.push(NormalizedToken { hash: hash(text), line: token.line });
+        index += 1;
+    }
+
+    Some(normalized)
+}
+
+/// Finds duplicated code: windows of `min_tokens` normalized tokens are hashed with a rolling
+/// hash, equal windows in the same language are grouped, and runs of groups whose copies move
+/// forward together are joined into one clone class. Each class is then grown to its full
+/// extent and classes lying inside a longer one are dropped.
+pub struct CloneDetector {
+    min_tokens: usize,
+    min_lines: usize,
+    parallel: bool,
+}
+
+impl Default for CloneDetector {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ParallelProcessing for CloneDetector {
+    fn enable_parallel_processing(mut self, parallel: bool) -> Self {
+        self.parallel = parallel;
+        self
+    }
+
+    fn with_parallel(self, parallel: bool) -> Self {
+        self.enable_parallel_processing(parallel)
+    }
+
+    fn is_parallel(&self) -> bool {
+        self.parallel
+    }
+}
+
+impl CloneDetector {
+    pub fn new() -> Self {
+        CloneDetector { min_tokens: DEFAULT_MIN_TOKENS, min_lines: DEFAULT_MIN_LINES, parallel: true }
+    }
+
+    /// Shortest duplicated token sequence to report
+    pub fn min_tokens(mut self, min_tokens: usize) -> Self {
+        self.min_tokens = min_tokens.max(1);
+        self
+    }
+
+    /// Fewest lines each copy of a reported block must span
+    pub fn min_lines(mut self, min_lines: usize) -> Self {
+        self.min_lines = min_lines;
+        self
+    }
+
+    pub fn detect(&self, files: &[SourceFile]) -> CloneDetection {
+        let normalize_file = |file: &SourceFile| normalize(file.content, file.language);
+        let tokens: Vec<Option<Vec<NormalizedToken>>> = if self.parallel {
+            files.par_iter().map(normalize_file).collect()
+        } else {
+            files.iter().map(normalize_file).collect()
+        };
+
+        let groups = self.matching_windows(files, &tokens);
+        let classes = drop_contained(
+            self.join_windows(groups)
+                .into_iter()
+                .map(|(locations, length)| extend_clone(&tokens, locations, length))
+                .collect(),
+        );
+
+        let mut duplicated: HashMap<usize, HashSet<usize>> = HashMap::new();
+        let mut clone_classes = Vec::new();
+        for (locations, length) in classes {
+            let instances: Vec<CloneInstance> = locations
+                .iter()
+                .map(|&(file, start)| {
+                    let file_tokens = tokens[file].as_deref().unwrap_or_default();
+                    CloneInstance {
+                        path: files[file].path.to_string(),
+                        start_line: file_tokens[start].line,
+                        end_line: file_tokens[start + length - 1].line,
+                    }
+                })
+                .collect();
+            if instances.iter().any(|instance| instance.end_line - instance.start_line + 1 < self.min_lines) {
+                continue;
+            }
+
+            for &(file, start) in &locations {
+                let file_tokens = tokens[file].as_deref().unwrap_or_default();
+                duplicated.entry(file).or_default().extend(file_tokens[start..start + length].iter().map(|token| token.line));
+            }
+            clone_classes.push(CloneClass {
+                language: files[locations[0].0].language.to_string(),
+                tokens: length,
+                lines: instances.iter().map(|instance| instance.end_line - instance.start_line + 1).max().unwrap_or(0),
+                instances,
+            });
+        }
+
+        clone_classes.sort_by(|a, b| {
+            b.duplicated_tokens()
+                .cmp(&a.duplicated_tokens())
+                .then_with(|| (&a.instances[0].path, a.instances[0].start_line).cmp(&(&b.instances[0].path, b.instances[0].start_line)))
+        });
+
+        let duplicated_lines = files
+            .iter()
+            .enumerate()
+            .filter(|(index, _)| tokens[*index].is_some())
+            .map(|(index, file)| (file.path.to_string(), duplicated.get(&index).map_or(0, HashSet::len)))
+            .collect();
+
+        CloneDetection { classes: clone_classes, duplicated_lines }
+    }
+
+    /// Groups of equal windows, as `(file, first token)` locations in order, with at least two
+    /// copies that do not overlap each other.
+    fn matching_windows(&self, files: &[SourceFile], tokens: &[Option<Vec<NormalizedToken>>]) -> Vec<Vec<(usize, usize)>> {
+        let window = self.min_tokens;
+        let mut base_power: u64 = 1;
+        for _ in 1..window {
+            base_power = base_power.wrapping_mul(HASH_BASE);
+        }
+
+        let mut buckets: HashMap<(&str, u64), Vec<(usize, usize)>> = HashMap::new();
+        for (file, file_tokens) in tokens.iter().enumerate() {
+            let Some(file_tokens) = file_tokens.as_deref().filter(|file_tokens| file_tokens.len() >= window) else {
+                continue;
+            };
+
+            let mut hash: u64 = 0;
+            for token in &file_tokens[..window] {
+                hash = hash.wrapping_mul(HASH_BASE).wrapping_add(token.hash);
+            }
+            buckets.entry((files[file].language, hash)).or_default().push((file, 0));
+
+            for start in 1..=file_tokens.len() - window {
+                hash = hash
+                    .wrapping_sub(file_tokens[start - 1].hash.wrapping_mul(base_power))
+                    .wrapping_mul(HASH_BASE)
+                    .wrapping_add(file_tokens[start + window - 1].hash);
+                buckets.entry((files[file].language, hash)).or_default().push((file, start));
+            }
+        }
+
+        let same_tokens = |a: (usize, usize), b: (usize, usize)| {
+            let a_tokens = &tokens[a.0].as_deref().unwrap_or_default()[a.1..a.1 + window];
+            let b_tokens = &tokens[b.0].as_deref().unwrap_or_default()[b.1..b.1 + window];
+            a_tokens.iter().zip(b_tokens).all(|(a, b)| a.hash == b.hash)
+        };
+
+        let mut groups = Vec::new();
+        for locations in buckets.into_values().filter(|locations| locations.len() > 1) {
+            // Hash collisions are rare, but split the bucket by the actual tokens
+            let mut by_tokens: Vec<Vec<(usize, usize)>> = Vec::new();
+            for location in locations {
+                match by_tokens.iter_mut().find(|group| same_tokens(group[0], location)) {
+                    Some(group) => group.push(location),
+                    None => by_tokens.push(vec![location]),
+                }
+            }
+
+            for mut group in by_tokens {
+                group.sort_unstable();
+                let mut kept: Vec<(usize, usize)> = Vec::with_capacity(group.len());
+                for location in group {
+                    if kept.last().is_none_or(|last| last.0 != location.0 || location.1 >= last.1 + window) {
+                        kept.push(location);
+                    }
+                }
+                if kept.len() > 1 {
+                    groups.push(kept);
+                }
+            }
+        }
+        groups
+    }
+
+    /// Joins a group with the one whose copies all start one token earlier, growing the clone
+    /// by a token, and returns each clone's locations and length in tokens.
+    fn join_windows(&self, mut groups: Vec<Vec<(usize, usize)>>) -> Vec<(Vec<(usize, usize)>, usize)> {
+        groups.sort();
+
+        let mut classes: Vec<(Vec<(usize, usize)>, usize)> = Vec::new();
+        // Last window of each clone still growing, by its locations
+        let mut growing: HashMap<Vec<(usize, usize)>, usize> = HashMap::new();
+        for group in groups {
+            let previous: Vec<(usize, usize)> = group
+                .iter()
+                .filter(|location| location.1 > 0)
+                .map(|&(file, start)| (file, start - 1))
+                .collect();
+
+            let class = match growing.remove(&previous) {
+                Some(class) if previous.len() == group.len() && no_self_overlap(&classes[class].0, classes[class].1 + 1) => {
+                    classes[class].1 += 1;
+                    class
+                }
+                _ => {
+                    classes.push((group.clone(), self.min_tokens));
+                    classes.len() - 1
+                }
+            };
+            growing.insert(group, class);
+        }
+        classes
+    }
+}
+
+/// Grows a clone backwards and forwards while all of its copies continue with the same token
+/// and copies in the same file stay apart. Windows shared with a third place split a block into
+/// several runs; growing each run to the whole block makes them equal.
+fn extend_clone(tokens: &[Option<Vec<NormalizedToken>>], mut locations: Vec<(usize, usize)>, mut length: usize) -> (Vec<(usize, usize)>, usize) {
+    let hash_at = |file: usize, index: usize| tokens[file].as_deref().unwrap_or_default().get(index).map(|token| token.hash);
+    let all_equal = |hashes: Option<Vec<u64>>| hashes.is_some_and(|hashes| hashes.windows(2).all(|pair| pair[0] == pair[1]));
+
+    while all_equal(locations.iter().map(|&(file, start)| hash_at(file, start.checked_sub(1)?)).collect()) {
+        let shifted: Vec<(usize, usize)> = locations.iter().map(|&(file, start)| (file, start - 1)).collect();
+        if !no_self_overlap(&shifted, length + 1) {
+            break;
+        }
+        locations = shifted;
+        length += 1;
+    }
+
+    while all_equal(locations.iter().map(|&(file, start)| hash_at(file, start + length)).collect())
+        && no_self_overlap(&locations, length + 1)
+    {
+        length += 1;
+    }
+
+    (locations, length)
+}
+
+/// Keeps the longest clones, dropping any clone whose copies all lie inside copies of a clone
+/// already kept, including repeats of the same clone.
+fn drop_contained(mut classes: Vec<(Vec<(usize, usize)>, usize)>) -> Vec<(Vec<(usize, usize)>, usize)> {
+    classes.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+
+    let mut kept: Vec<(Vec<(usize, usize)>, usize)> = Vec::with_capacity(classes.len());
+    for (locations, length) in classes {
+        let contained = kept.iter().any(|(outer, outer_length)| {
+            locations.iter().all(|&(file, start)| {
+                outer
+                    .iter()
+                    .any(|&(outer_file, outer_start)| outer_file == file && outer_start <= start && start + length <= outer_start + outer_length)
+            })
+        });
+        if !contained {
+            kept.push((locations, length));
+        }
+    }
+    kept
+}
+
+/// Whether copies in the same file stay apart when grown to `length` tokens.
+fn no_self_overlap(locations: &[(usize, usize)], length: usize) -> bool {
+    locations.windows(2).all(|pair| pair[0].0 != pair[1].0 || pair[1].1 >= pair[0].1 + length)
+}
+
@@ -1,5 +1,6 @@
 use crate::analyzer::file_analyzer::FileAnalyzer;
 use crate::cache::AnalysisCache;
+use crate::metrics::clones::{self, CloneDetector, SourceFile};
 use crate::metrics::models::CodeMetrics;
 use crate::util::file_filter::FileFilter;
 use ignore::{DirEntry, WalkBuilder};
@@ -10,6 +11,7 @@ use std::sync::{Arc, Mutex};
 pub struct MetricsCollector {
     file_analyzer: FileAnalyzer,
     cache: Arc<AnalysisCache>,
+    clone_detector: CloneDetector,
     parallel: bool,
 }
 
@@ -27,14 +29,23 @@ impl MetricsCollector {
         MetricsCollector {
             file_analyzer: FileAnalyzer::with_cache(Arc::clone(&cache)),
             cache,
+            clone_detector: CloneDetector::new(),
             parallel: true,
         }
     }
+
+    /// Thresholds of the clone detection: the shortest duplicated token sequence and the fewest
+    /// lines each copy must span.
+    pub fn clone_thresholds(mut self, min_tokens: usize, min_lines: usize) -> Self {
+        self.clone_detector = self.clone_detector.min_tokens(min_tokens).min_lines(min_lines);
+        self
+    }
 }
 
 impl ParallelProcessing for MetricsCollector {
     fn enable_parallel_processing(mut self, parallel: bool) -> Self {
         self.parallel = parallel;
+        self.clone_detector = self.clone_detector.enable_parallel_processing(parallel);
         self
     }
     
@@ -61,7 +72,9 @@ impl MetricsCollector {
         
         self.process_file_entries(&file_entries, &metrics);
         
-        let metrics_result = self.finalize_metrics(&metrics, &dir_count);
+        let mut metrics_result = self.finalize_metrics(&metrics, &dir_count);
+        metrics_result.root = path.to_string_lossy().to_string();
+        self.detect_clones(&mut metrics_result);
         
         self.cache.purge_stale_entries();
         
@@ -127,9 +140,13 @@ impl MetricsCollector {
             let path = entry.path();
             
             if let Some(file_metrics) = self.file_analyzer.analyze_file(path) {
+                let functions = self.file_analyzer.analyze_functions(path);
+                let halstead = self.file_analyzer.analyze_halstead(path);
                 let mut metrics_guard = metrics.lock().unwrap();
                 metrics_guard.total_files += 1;
-                metrics_guard.add_language_metrics(file_metrics, &path.to_string_lossy());
+                metrics_guard
+                    .add_language_metrics(file_metrics, &path.to_string_lossy())
+                    .set_source_metrics(functions, halstead);
             }
         };
         
@@ -144,9 +161,39 @@ impl MetricsCollector {
         }
     }
     
+    /// Looks for duplicated code in the analyzed files, reading their contents from the cache
+    /// filled while counting lines.
+    fn detect_clones(&self, metrics: &mut CodeMetrics) {
+        let contents: Vec<(usize, String)> = metrics
+            .files
+            .iter()
+            .enumerate()
+            .filter(|(_, file)| clones::supports_language(&file.language))
+            .filter_map(|(index, file)| {
+                let content = self.cache.get_file_content(&file.path).or_else(|| std::fs::read_to_string(&file.path).ok())?;
+                Some((index, content))
+            })
+            .collect();
+        let sources: Vec<SourceFile> = contents
+            .iter()
+            .map(|(index, content)| SourceFile {
+                path: &metrics.files[*index].path,
+                language: &metrics.files[*index].language,
+                content,
+            })
+            .collect();
+
+        let detection = self.clone_detector.detect(&sources);
+        for file in &mut metrics.files {
+            file.duplicated_lines = detection.duplicated_lines.get(&file.path).copied();
+        }
+        metrics.clones = detection.classes;
+    }
+    
     fn finalize_metrics(&self, metrics: &Arc<Mutex<CodeMetrics>>, dir_count: &Arc<Mutex<usize>>) -> CodeMetrics {
         let mut metrics_result = metrics.lock().unwrap();
         metrics_result.total_directories = *dir_count.lock().unwrap();
+        metrics_result.files.sort_by(|a, b| a.path.cmp(&b.path));
         
         (*metrics_result).clone()
     }
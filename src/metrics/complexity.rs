@@ -0,0 +1,803 @@
+use crate::metrics::lexer::{self, SpanKind};
+use crate::metrics::models::FunctionMetrics;
+use std::ops::Range;
+
+/// How function boundaries are found: by braces and a per-language header shape, or by
+/// indentation for Python.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Syntax {
+    Rust,
+    Go,
+    JavaScript,
+    CLike,
+    Python,
+}
+
+impl Syntax {
+    fn for_language(language: &str) -> Option<Self> {
+        match language {
+            "Rust" => Some(Syntax::Rust),
+            "Go" => Some(Syntax::Go),
+            "JavaScript" | "TypeScript" | "PHP" => Some(Syntax::JavaScript),
+            "Java" | "C" | "C++" | "C#" => Some(Syntax::CLike),
+            "Python" => Some(Syntax::Python),
+            _ => None,
+        }
+    }
+}
+
+/// Words that are followed by a parenthesis without naming a function.
+const NON_FUNCTION_WORDS: [&str; 30] = [
+    "if", "for", "while", "switch", "catch", "using", "lock", "foreach", "fixed", "synchronized",
+    "return", "sizeof", "typeof", "function", "with", "when", "match", "new", "await", "do",
+    "else", "try", "elif", "checked", "unchecked", "nameof", "decltype", "alignof", "static_assert", "throw",
+];
+
+/// Words that declare a type, whose body is not a function even when the header has parentheses.
+const TYPE_WORDS: [&str; 7] = ["class", "struct", "record", "interface", "enum", "namespace", "union"];
+
+/// Computes per-function metrics for the languages whose function syntax is understood; other
+/// languages have no functions. Comments and string contents are ignored.
+pub fn analyze_functions(content: &str, language: &str) -> Vec<FunctionMetrics> {
+    let Some(syntax) = Syntax::for_language(language) else {
+        return Vec::new();
+    };
+
+    let masked = mask(content, language);
+    let tokens = tokenize(&masked.text);
+
+    let mut functions = if syntax == Syntax::Python {
+        python_functions(&masked.text, &tokens)
+    } else {
+        brace_functions(&tokens, syntax)
+    };
+    functions.sort_by_key(|function| function.start_line);
+    functions
+}
+
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct Token<'a> {
+    pub text: &'a str,
+    /// Byte offset in the source
+    pub offset: usize,
+    pub line: usize,
+    /// Whitespace precedes the token
+    pub spaced: bool,
+}
+
+impl Token<'_> {
+    pub fn is_identifier(&self) -> bool {
+        self.text
+            .chars()
+            .next()
+            .is_some_and(|c| c.is_alphabetic() || c == '_' || c == '$')
+    }
+
+    fn is(&self, text: &str) -> bool {
+        self.text == text
+    }
+}
+
+/// Source text with comments and literal contents blanked out, plus where the literals were.
+pub(crate) struct MaskedSource {
+    pub text: String,
+    /// Byte ranges of string and character literals, quotes included, in source order
+    pub literals: Vec<Range<usize>>,
+}
+
+/// Blanks out comments and the contents of string and character literals, keeping line breaks
+/// and quote characters, so that keywords and braces inside them are not mistaken for code.
+pub(crate) fn mask(content: &str, language: &str) -> MaskedSource {
+    let mut masked = content.as_bytes().to_vec();
+    let mut literals = Vec::new();
+
+    for span in lexer::lex(content, language) {
+        let blanked = if span.kind == SpanKind::Comment {
+            span.range
+        } else {
+            literals.push(span.range);
+            span.content
+        };
+        for byte in &mut masked[blanked] {
+            if *byte != b'\n' {
+                *byte = b' ';
+            }
+        }
+    }
+
+    MaskedSource {
+        text: String::from_utf8(masked).unwrap_or_default(),
+        literals,
+    }
+}
+
+const TWO_CHARACTER_OPERATORS: [&str; 7] = ["&&", "||", "=>", "->", "::", "?.", "??"];
+
+pub(crate) fn tokenize(masked: &str) -> Vec<Token<'_>> {
+    let mut tokens = Vec::new();
+    let mut line = 1;
+    let mut spaced = true;
+    let mut chars = masked.char_indices().peekable();
+
+    while let Some((start, c)) = chars.next() {
+        if c == '\n' {
+            line += 1;
+            spaced = true;
+            continue;
+        }
+        if c.is_whitespace() {
+            spaced = true;
+            continue;
+        }
+
+        let mut end = start + c.len_utf8();
+        if c.is_alphanumeric() || c == '_' || c == '$' {
+            while let Some(&(index, next)) = chars.peek() {
+                if !(next.is_alphanumeric() || next == '_' || next == '$') {
+                    break;
+                }
+                end = index + next.len_utf8();
+                chars.next();
+            }
+        } else if let Some(&(index, next)) = chars.peek()
+            && TWO_CHARACTER_OPERATORS.iter().any(|operator| operator.starts_with(c) && operator.ends_with(next))
+        {
+            end = index + next.len_utf8();
+            chars.next();
+        }
+
+        tokens.push(Token { text: &masked[start..end], offset: start, line, spaced });
+        spaced = false;
+    }
+
+    tokens
+}
+
+/// A function found in the token stream: where its header starts, the token naming it, its
+/// parameter list and its braces.
+struct FunctionSpan {
+    name: String,
+    header_start: usize,
+    anchor: usize,
+    parameters: usize,
+    open: usize,
+    close: usize,
+}
+
+/// What a `{` opens, judged from the tokens between it and the previous `;`, `{` or `}`.
+enum BlockHeader {
+    Function { name: String, anchor: usize, parameters: usize },
+    Lambda { anchor: usize },
+    Other,
+}
+
+fn brace_functions(tokens: &[Token], syntax: Syntax) -> Vec<FunctionMetrics> {
+    let mut spans: Vec<FunctionSpan> = Vec::new();
+    let mut stack: Vec<Option<usize>> = Vec::new();
+    let mut boundary = 0;
+
+    for (index, token) in tokens.iter().enumerate() {
+        match token.text {
+            "{" => {
+                let inside_function = stack.iter().any(Option::is_some);
+                let span = match classify_header(tokens, boundary, index, syntax) {
+                    BlockHeader::Function { name, anchor, parameters } => Some((name, anchor, parameters)),
+                    // Callbacks outside any function are reported on their own
+                    BlockHeader::Lambda { anchor } if !inside_function => Some(("<anonymous>".to_string(), anchor, 0)),
+                    _ => None,
+                };
+                stack.push(span.map(|(name, anchor, parameters)| {
+                    spans.push(FunctionSpan { name, header_start: boundary, anchor, parameters, open: index, close: index });
+                    spans.len() - 1
+                }));
+                boundary = index + 1;
+            }
+            "}" => {
+                if let Some(Some(span)) = stack.pop() {
+                    spans[span].close = index;
+                }
+                boundary = index + 1;
+            }
+            ";" => boundary = index + 1,
+            _ => {}
+        }
+    }
+
+    spans
+        .iter()
+        .filter(|span| span.close > span.open)
+        .map(|span| {
+            let nested: Vec<(usize, usize)> = spans
+                .iter()
+                .filter(|other| other.open > span.open && other.close < span.close && other.close > other.open)
+                .map(|other| (other.header_start, other.close))
+                .collect();
+            let body = (span.open + 1..span.close).filter(|index| !nested.iter().any(|&(from, to)| (from..=to).contains(index)));
+            let score = score_braced_body(tokens, body, syntax, &span.name);
+
+            FunctionMetrics {
+                name: span.name.clone(),
+                start_line: tokens[span.anchor].line,
+                end_line: tokens[span.close].line,
+                length: tokens[span.close].line - tokens[span.anchor].line + 1,
+                parameters: span.parameters,
+                cyclomatic_complexity: score.cyclomatic,
+                cognitive_complexity: score.cognitive,
+                nesting_depth: score.max_nesting,
+            }
+        })
+        .collect()
+}
+
+fn classify_header(tokens: &[Token], start: usize, end: usize, syntax: Syntax) -> BlockHeader {
+    let header = &tokens[start..end];
+    let Some(last) = header.last() else {
+        return BlockHeader::Other;
+    };
+
+    match syntax {
+        Syntax::Rust => {
+            if let Some(position) = (0..header.len()).find(|&i| {
+                header[i].is("fn") && header.get(i + 1).is_some_and(Token::is_identifier)
+            }) {
+                let name_index = position + 1;
+                return match parameter_list(header, name_index + 1) {
+                    Some((open, close)) => BlockHeader::Function {
+                        name: header[name_index].text.to_string(),
+                        anchor: start + name_index,
+                        parameters: count_parameters(&header[open + 1..close], syntax),
+                    },
+                    None => BlockHeader::Other,
+                };
+            }
+            if last.is("|") || last.is("||") {
+                return BlockHeader::Lambda { anchor: end - 1 };
+            }
+        }
+        Syntax::Go => {
+            if let Some(position) = header.iter().position(|token| token.is("func")) {
+                let mut name_index = position + 1;
+                if header.get(name_index).is_some_and(|token| token.is("(")) {
+                    // A method receiver, or the parameters of a function literal
+                    let Some(close) = matching_close(header, name_index) else {
+                        return BlockHeader::Other;
+                    };
+                    name_index = close + 1;
+                }
+                return match header.get(name_index) {
+                    Some(name) if name.is_identifier() && header.get(name_index + 1).is_some_and(|token| token.is("(")) => {
+                        match matching_close(header, name_index + 1) {
+                            Some(close) => BlockHeader::Function {
+                                name: name.text.to_string(),
+                                anchor: start + name_index,
+                                parameters: count_parameters(&header[name_index + 2..close], syntax),
+                            },
+                            None => BlockHeader::Other,
+                        }
+                    }
+                    _ => BlockHeader::Lambda { anchor: start + position },
+                };
+            }
+        }
+        Syntax::JavaScript => {
+            if let Some(position) = depth_zero_positions(header).find(|&i| header[i].is("function")) {
+                let mut name_index = position + 1;
+                if header.get(name_index).is_some_and(|token| token.is("*") || token.is("&")) {
+                    name_index += 1;
+                }
+                return match header.get(name_index) {
+                    Some(name) if name.is_identifier() => match parameter_list(header, name_index + 1) {
+                        Some((open, close)) => BlockHeader::Function {
+                            name: name.text.to_string(),
+                            anchor: start + name_index,
+                            parameters: count_parameters(&header[open + 1..close], syntax),
+                        },
+                        None => BlockHeader::Other,
+                    },
+                    _ => BlockHeader::Lambda { anchor: start + position },
+                };
+            }
+            if last.is("=>") {
+                return arrow_function(header, start, syntax);
+            }
+        }
+        Syntax::CLike => {
+            if last.is("=>") || last.is("->") {
+                return BlockHeader::Lambda { anchor: end - 1 };
+            }
+        }
+        Syntax::Python => return BlockHeader::Other,
+    }
+
+    if matches!(syntax, Syntax::JavaScript | Syntax::CLike) {
+        return clike_function(header, start, syntax);
+    }
+    BlockHeader::Other
+}
+
+/// `name = (a, b) => {`, `name: async x => {` or an anonymous callback.
+fn arrow_function(header: &[Token], start: usize, syntax: Syntax) -> BlockHeader {
+    let anchor = start + header.len() - 1;
+    if paren_depth_at_end(header) > 0 {
+        return BlockHeader::Lambda { anchor };
+    }
+
+    let named = depth_zero_positions(header).find(|&i| {
+        header[i].is_identifier()
+            && header.get(i + 1).is_some_and(|next| next.is("=") || next.is(":"))
+            && !matches!(header[i].text, "const" | "let" | "var" | "export" | "readonly" | "static" | "async")
+    });
+    let Some(name_index) = named else {
+        return BlockHeader::Lambda { anchor };
+    };
+
+    let arrow = header.len() - 1;
+    let parameters = match header[..arrow].iter().rposition(|token| token.is(")")) {
+        Some(close) => match (name_index..close).rev().find(|&i| header[i].is("(") && matching_close(header, i) == Some(close)) {
+            Some(open) => count_parameters(&header[open + 1..close], syntax),
+            None => 0,
+        },
+        // A single parameter without parentheses
+        None => usize::from(arrow > 0 && header[arrow - 1].is_identifier() && arrow - 1 > name_index + 1),
+    };
+
+    BlockHeader::Function {
+        name: header[name_index].text.to_string(),
+        anchor: start + name_index,
+        parameters,
+    }
+}
+
+/// A C-style declaration: the first `name(...)` at the top level of the header, where `name` is
+/// not a keyword, an annotation or a constructor call, and nothing before it is an assignment.
+fn clike_function(header: &[Token], start: usize, syntax: Syntax) -> BlockHeader {
+    for open in depth_zero_positions(header).filter(|&i| header[i].is("(")) {
+        if open == 0 {
+            return BlockHeader::Other;
+        }
+        let name = &header[open - 1];
+        let before = &header[..open - 1];
+        let qualifier = before.last().map(|token| token.text);
+        if !name.is_identifier() || NON_FUNCTION_WORDS.contains(&name.text) || matches!(qualifier, Some("new" | ".")) {
+            return BlockHeader::Other;
+        }
+        if qualifier == Some("@") {
+            // An annotation such as `@SuppressWarnings("x")`
+            continue;
+        }
+        if before.iter().any(|token| token.is("=") || TYPE_WORDS.contains(&token.text)) {
+            return BlockHeader::Other;
+        }
+        let Some(close) = matching_close(header, open) else {
+            return BlockHeader::Other;
+        };
+        if header[close + 1..].iter().any(|token| token.is("=") || token.is("=>")) {
+            return BlockHeader::Other;
+        }
+
+        return BlockHeader::Function {
+            name: qualified_name(header, open - 1),
+            anchor: start + open - 1,
+            parameters: count_parameters(&header[open + 1..close], syntax),
+        };
+    }
+
+    BlockHeader::Other
+}
+
+/// `Type::method` and `~Type` for C++ definitions outside their class.
+fn qualified_name(header: &[Token], name_index: usize) -> String {
+    let mut name = header[name_index].text.to_string();
+    let mut index = name_index;
+    if index >= 1 && header[index - 1].is("~") {
+        name = format!("~{}", name);
+        index -= 1;
+    }
+    while index >= 2 && header[index - 1].is("::") && header[index - 2].is_identifier() {
+        name = format!("{}::{}", header[index - 2].text, name);
+        index -= 2;
+    }
+    name
+}
+
+/// Indexes of the tokens that are not inside parentheses or brackets.
+fn depth_zero_positions<'a>(header: &'a [Token<'a>]) -> impl Iterator<Item = usize> + 'a {
+    let mut depth = 0i32;
+    header.iter().enumerate().filter_map(move |(index, token)| {
+        let at_zero = depth == 0;
+        match token.text {
+            "(" | "[" => depth += 1,
+            ")" | "]" => depth -= 1,
+            _ => {}
+        }
+        at_zero.then_some(index)
+    })
+}
+
+fn paren_depth_at_end(header: &[Token]) -> i32 {
+    header.iter().fold(0, |depth, token| match token.text {
+        "(" | "[" => depth + 1,
+        ")" | "]" => depth - 1,
+        _ => depth,
+    })
+}
+
+fn matching_close(tokens: &[Token], open: usize) -> Option<usize> {
+    let mut depth = 0;
+    for (index, token) in tokens.iter().enumerate().skip(open) {
+        match token.text {
+            "(" | "[" => depth += 1,
+            ")" | "]" => {
+                depth -= 1;
+                if depth == 0 {
+                    return Some(index);
+                }
+            }
+            _ => {}
+        }
+    }
+    None
+}
+
+/// The parameter list following a name, skipping generic parameters such as `<T: Clone>`.
+fn parameter_list(header: &[Token], after_name: usize) -> Option<(usize, usize)> {
+    let mut index = after_name;
+    if header.get(index).is_some_and(|token| token.is("<")) {
+        let mut depth = 0;
+        while index < header.len() {
+            match header[index].text {
+                "<" => depth += 1,
+                ">" => depth -= 1,
+                _ => {}
+            }
+            index += 1;
+            if depth == 0 {
+                break;
+            }
+        }
+    }
+    if !header.get(index).is_some_and(|token| token.is("(")) {
+        return None;
+    }
+    matching_close(header, index).map(|close| (index, close))
+}
+
+/// Counts the comma-separated parameters of a list, leaving out receivers (`self`, `&mut self`
+/// in Rust; `self`/`cls` in Python) and C's `(void)`.
+fn count_parameters(list: &[Token], syntax: Syntax) -> usize {
+    let mut parameters: Vec<Vec<&Token>> = vec![Vec::new()];
+    let mut depth = 0i32;
+
+    for token in list {
+        match token.text {
+            "(" | "[" | "{" | "<" => depth += 1,
+            ")" | "]" | "}" | ">" => depth -= 1,
+            "," if depth == 0 => {
+                parameters.push(Vec::new());
+                continue;
+            }
+            _ => {}
+        }
+        if let Some(current) = parameters.last_mut() {
+            current.push(token);
+        }
+    }
+    parameters.retain(|parameter| !parameter.is_empty());
+
+    if let Some(first) = parameters.first() {
+        let receiver = match syntax {
+            Syntax::Rust => first.iter().any(|token| token.is("self")),
+            Syntax::Python => first.len() == 1 && (first[0].is("self") || first[0].is("cls")),
+            Syntax::CLike => parameters.len() == 1 && first.len() == 1 && first[0].is("void"),
+            _ => false,
+        };
+        if receiver {
+            parameters.remove(0);
+        }
+    }
+
+    parameters.len()
+}
+
+#[derive(Debug, Default)]
+struct Score {
+    cyclomatic: usize,
+    cognitive: usize,
+    max_nesting: usize,
+}
+
+/// Tracks the sequences of `&&`/`||` (or `and`/`or`) that cognitive complexity counts once
+/// per change of operator.
+#[derive(Default)]
+struct LogicalSequence<'a> {
+    last: Option<&'a str>,
+}
+
+impl<'a> LogicalSequence<'a> {
+    fn operator(&mut self, operator: &'a str, score: &mut Score) {
+        score.cyclomatic += 1;
+        if self.last != Some(operator) {
+            score.cognitive += 1;
+        }
+        self.last = Some(operator);
+    }
+
+    fn reset(&mut self) {
+        self.last = None;
+    }
+}
+
+struct Block {
+    nesting: bool,
+    is_do: bool,
+}
+
+/// Cyclomatic complexity counts the decision points plus one. Cognitive complexity follows
+/// the SonarSource rules: every branch or loop adds one plus its nesting level, `else`/`else if`
+/// add one, each run of mixed boolean operators adds one and so does recursion.
+fn score_braced_body(tokens: &[Token], body: impl Iterator<Item = usize>, syntax: Syntax, name: &str) -> Score {
+    let mut score = Score { cyclomatic: 1, ..Score::default() };
+    let mut blocks: Vec<Block> = Vec::new();
+    let mut pending: Option<Block> = None;
+    let mut logical = LogicalSequence::default();
+    let mut closed_do = false;
+    let mut recursive = false;
+    let mut previous: Option<&Token> = None;
+
+    let body: Vec<usize> = body.collect();
+    for (position, &index) in body.iter().enumerate() {
+        let token = &tokens[index];
+        let next = body.get(position + 1).map(|&next| &tokens[next]);
+        let nesting = blocks.iter().filter(|block| block.nesting).count();
+        let after_closing_brace = previous.is_some_and(|previous| previous.is("}"));
+
+        match token.text {
+            "if" => {
+                score.cyclomatic += 1;
+                score.cognitive += if previous.is_some_and(|previous| previous.is("else")) { 1 } else { 1 + nesting };
+                pending = Some(Block { nesting: true, is_do: false });
+            }
+            "else" => {
+                if !next.is_some_and(|next| next.is("if")) {
+                    score.cognitive += 1;
+                }
+                pending = Some(Block { nesting: true, is_do: false });
+            }
+            "while" if after_closing_brace && closed_do => score.cyclomatic += 1,
+            "for" | "while" | "foreach" => {
+                score.cyclomatic += 1;
+                score.cognitive += 1 + nesting;
+                pending = Some(Block { nesting: true, is_do: false });
+            }
+            "loop" if syntax == Syntax::Rust => {
+                score.cognitive += 1 + nesting;
+                pending = Some(Block { nesting: true, is_do: false });
+            }
+            "do" if syntax != Syntax::Rust => {
+                score.cognitive += 1 + nesting;
+                pending = Some(Block { nesting: true, is_do: true });
+            }
+            "switch" | "select" => {
+                score.cognitive += 1 + nesting;
+                pending = Some(Block { nesting: true, is_do: false });
+            }
+            "match" if syntax == Syntax::Rust => {
+                score.cognitive += 1 + nesting;
+                // Each arm is a path; the first is already counted
+                score.cyclomatic = score.cyclomatic.saturating_sub(1);
+                pending = Some(Block { nesting: true, is_do: false });
+            }
+            "=>" if syntax == Syntax::Rust => score.cyclomatic += 1,
+            "case" => score.cyclomatic += 1,
+            "catch" => {
+                score.cyclomatic += 1;
+                score.cognitive += 1 + nesting;
+                pending = Some(Block { nesting: true, is_do: false });
+            }
+            "goto" => score.cognitive += 1,
+            "?" if syntax != Syntax::Rust && token.spaced && next.is_some_and(|next| next.spaced) => {
+                score.cyclomatic += 1;
+                score.cognitive += 1 + nesting;
+            }
+            "&&" => logical.operator("&&", &mut score),
+            // In Rust, `||` after `(`, `,`, `=` or `move` starts a closure
+            "||" if syntax == Syntax::Rust
+                && previous.is_none_or(|previous| matches!(previous.text, "(" | "," | "=" | "move" | "{" | ";" | "return")) => {}
+            "||" => logical.operator("||", &mut score),
+            "{" => {
+                let lambda = previous.is_some_and(|previous| match syntax {
+                    Syntax::Rust => previous.is("|") || previous.is("||"),
+                    Syntax::Go => false,
+                    _ => previous.is("=>") || previous.is("->"),
+                }) || (syntax == Syntax::Go && previous.is_some_and(|previous| previous.is(")")) && pending.is_none());
+                let block = pending.take().unwrap_or(Block { nesting: lambda, is_do: false });
+                blocks.push(block);
+                score.max_nesting = score.max_nesting.max(blocks.iter().filter(|block| block.nesting).count());
+                logical.reset();
+            }
+            "}" => {
+                closed_do = blocks.pop().is_some_and(|block| block.is_do);
+                logical.reset();
+            }
+            ";" | "," => {
+                // A branch without braces ends here
+                if pending.as_ref().is_some_and(|block| !block.is_do) && token.is(";") {
+                    pending = None;
+                }
+                logical.reset();
+            }
+            text if text == name && next.is_some_and(|next| next.is("(")) && !previous.is_some_and(|previous| previous.is(".")) => {
+                recursive = true;
+            }
+            _ => {}
+        }
+
+        if matches!(token.text, "if" | "for" | "while" | "foreach" | "catch" | "switch") && pending.is_some() {
+            score.max_nesting = score.max_nesting.max(nesting + 1);
+        }
+        previous = Some(token);
+    }
+
+    if recursive {
+        score.cognitive += 1;
+    }
+    score
+}
+
+/// Python functions span the `def` line and every following line indented deeper than it.
+fn python_functions(masked: &str, tokens: &[Token]) -> Vec<FunctionMetrics> {
+    let lines: Vec<&str> = masked.lines().collect();
+    let indent = |line: usize| -> Option<usize> {
+        let text = lines.get(line - 1)?;
+        if text.trim().is_empty() {
+            return None;
+        }
+        Some(text.chars().take_while(|c| c.is_whitespace()).map(|c| if c == '\t' { 4 } else { 1 }).sum())
+    };
+
+    struct PythonSpan {
+        name: String,
+        parameters: usize,
+        start_line: usize,
+        end_line: usize,
+        body_start: usize,
+    }
+
+    let mut spans = Vec::new();
+    for (index, token) in tokens.iter().enumerate() {
+        if !token.is("def") || !tokens.get(index + 1).is_some_and(Token::is_identifier) {
+            continue;
+        }
+        let Some(open) = tokens.get(index + 2).filter(|token| token.is("(")).map(|_| index + 2) else {
+            continue;
+        };
+        let Some(close) = matching_close(tokens, open) else {
+            continue;
+        };
+        let Some(def_indent) = indent(token.line) else {
+            continue;
+        };
+        let colon = (close + 1..tokens.len()).find(|&i| tokens[i].is(":")).unwrap_or(close);
+
+        let mut end_line = tokens[colon].line;
+        for line in tokens[colon].line + 1..=lines.len() {
+            match indent(line) {
+                Some(line_indent) if line_indent <= def_indent => break,
+                Some(_) => end_line = line,
+                None => {}
+            }
+        }
+
+        spans.push(PythonSpan {
+            name: tokens[index + 1].text.to_string(),
+            parameters: count_parameters(&tokens[open + 1..close], Syntax::Python),
+            start_line: token.line,
+            end_line,
+            body_start: colon + 1,
+        });
+    }
+
+    spans
+        .iter()
+        .map(|span| {
+            let nested: Vec<(usize, usize)> = spans
+                .iter()
+                .filter(|other| other.start_line > span.start_line && other.end_line <= span.end_line)
+                .map(|other| (other.start_line, other.end_line))
+                .collect();
+            let body = (span.body_start..tokens.len())
+                .take_while(|&index| tokens[index].line <= span.end_line)
+                .filter(|&index| !nested.iter().any(|&(from, to)| (from..=to).contains(&tokens[index].line)));
+            let score = score_python_body(tokens, body, &indent, &span.name);
+
+            FunctionMetrics {
+                name: span.name.clone(),
+                start_line: span.start_line,
+                end_line: span.end_line,
+                length: span.end_line - span.start_line + 1,
+                parameters: span.parameters,
+                cyclomatic_complexity: score.cyclomatic,
+                cognitive_complexity: score.cognitive,
+                nesting_depth: score.max_nesting,
+            }
+        })
+        .collect()
+}
+
+fn score_python_body(
+    tokens: &[Token],
+    body: impl Iterator<Item = usize>,
+    indent: &dyn Fn(usize) -> Option<usize>,
+    name: &str,
+) -> Score {
+    let mut score = Score { cyclomatic: 1, ..Score::default() };
+    let mut blocks: Vec<(usize, bool)> = Vec::new();
+    let mut logical = LogicalSequence::default();
+    let mut recursive = false;
+    let mut line_start: Option<usize> = None;
+
+    let body: Vec<usize> = body.collect();
+    for (position, &index) in body.iter().enumerate() {
+        let token = &tokens[index];
+        let previous = position.checked_sub(1).map(|previous| &tokens[body[previous]]);
+        let next = body.get(position + 1).map(|&next| &tokens[next]);
+
+        let starts_line = previous.is_none_or(|previous| previous.line != token.line);
+        if starts_line {
+            line_start = Some(index);
+            logical.reset();
+            let line_indent = indent(token.line).unwrap_or(0);
+            while blocks.last().is_some_and(|&(block_indent, _)| block_indent >= line_indent) {
+                blocks.pop();
+            }
+        }
+        // `async for` and `async with` start a statement too
+        let statement = starts_line || (line_start == Some(index.saturating_sub(1)) && previous.is_some_and(|previous| previous.is("async")));
+        let nesting = blocks.iter().filter(|&&(_, nesting)| nesting).count();
+        let line_indent = indent(token.line).unwrap_or(0);
+
+        match token.text {
+            "if" | "for" | "while" | "except" | "match" if statement => {
+                if token.text != "match" {
+                    score.cyclomatic += 1;
+                }
+                score.cognitive += 1 + nesting;
+                blocks.push((line_indent, true));
+                score.max_nesting = score.max_nesting.max(nesting + 1);
+            }
+            "elif" | "else" if statement => {
+                if token.is("elif") {
+                    score.cyclomatic += 1;
+                }
+                score.cognitive += 1;
+                blocks.push((line_indent, true));
+            }
+            "try" | "finally" | "with" if statement => blocks.push((line_indent, false)),
+            "case" if statement => {
+                if !next.is_some_and(|next| next.is("_")) {
+                    score.cyclomatic += 1;
+                }
+                blocks.push((line_indent, false));
+            }
+            // Conditional expressions and comprehensions
+            "if" | "for" => {
+                score.cyclomatic += 1;
+                score.cognitive += 1 + nesting;
+            }
+            "and" | "or" => logical.operator(token.text, &mut score),
+            "," => logical.reset(),
+            text if text == name
+                && next.is_some_and(|next| next.is("("))
+                && !previous.is_some_and(|previous| previous.is(".") || previous.is("def")) =>
+            {
+                recursive = true;
+            }
+            _ => {}
+        }
+    }
+
+    if recursive {
+        score.cognitive += 1;
+    }
+    score
+}
@@ -0,0 +1,200 @@
+use crate::metrics::complexity::{Token, mask, tokenize};
+use serde::Serialize;
+use std::collections::HashMap;
+
+/// Halstead's software science measures of a file, from its counts of operators (keywords and
+/// punctuation) and operands (identifiers and literals).
+#[derive(Debug, Default, Clone, PartialEq, Serialize)]
+pub struct HalsteadMetrics {
+    pub distinct_operators: usize,
+    pub distinct_operands: usize,
+    pub total_operators: usize,
+    pub total_operands: usize,
+    /// Distinct operators plus distinct operands
+    pub vocabulary: usize,
+    /// Total operators plus total operands
+    pub length: usize,
+    /// `length * log2(vocabulary)`
+    pub volume: f64,
+    /// `distinct_operators / 2 * total_operands / distinct_operands`
+    pub difficulty: f64,
+    /// `difficulty * volume`
+    pub effort: f64,
+}
+
+impl HalsteadMetrics {
+    pub fn from_counts(
+        distinct_operators: usize,
+        distinct_operands: usize,
+        total_operators: usize,
+        total_operands: usize,
+    ) -> Self {
+        let vocabulary = distinct_operators + distinct_operands;
+        let length = total_operators + total_operands;
+        let volume = if vocabulary > 0 { length as f64 * (vocabulary as f64).log2() } else { 0.0 };
+        let difficulty = if distinct_operands > 0 {
+            distinct_operators as f64 / 2.0 * total_operands as f64 / distinct_operands as f64
+        } else {
+            0.0
+        };
+
+        HalsteadMetrics {
+            distinct_operators,
+            distinct_operands,
+            total_operators,
+            total_operands,
+            vocabulary,
+            length,
+            volume,
+            difficulty,
+            effort: difficulty * volume,
+        }
+    }
+}
+
+/// Maintainability index on the 0–100 scale used by Visual Studio:
+/// `max(0, (171 - 5.2 ln(volume) - 0.23 cyclomatic - 16.2 ln(lines of code)) * 100 / 171)`.
+/// Undefined for files without code.
+pub fn maintainability_index(volume: f64, cyclomatic_complexity: usize, lines_of_code: usize) -> Option<f64> {
+    if volume <= 0.0 || lines_of_code == 0 {
+        return None;
+    }
+
+    let index = 171.0 - 5.2 * volume.ln() - 0.23 * cyclomatic_complexity as f64 - 16.2 * (lines_of_code as f64).ln();
+    Some((index * 100.0 / 171.0).max(0.0))
+}
+
+/// Operators made of several punctuation characters.
+const COMPOUND_OPERATORS: [&str; 38] = [
+    "<<=", ">>=", "===", "!==", "**=", "...", "..=", "//=", "<=>", "?.", "??", "::", "->", "=>",
+    "==", "!=", "<=", ">=", "&&", "||", "++", "--", "+=", "-=", "*=", "/=", "%=", "&=", "|=", "^=",
+    "<<", ">>", "**", "..", "//", ":=", "<-", "@=",
+];
+
+/// Closing brackets are counted with their opening bracket.
+const CLOSING_BRACKETS: [&str; 3] = [")", "]", "}"];
+
+const RUST_KEYWORDS: &[&str] = &[
+    "as", "async", "await", "break", "const", "continue", "crate", "dyn", "else", "enum", "extern", "fn", "for",
+    "if", "impl", "in", "let", "loop", "match", "mod", "move", "mut", "pub", "ref", "return", "static", "struct",
+    "super", "trait", "type", "unsafe", "use", "where", "while", "yield",
+];
+
+const GO_KEYWORDS: &[&str] = &[
+    "break", "case", "chan", "const", "continue", "default", "defer", "else", "fallthrough", "for", "func", "go",
+    "goto", "if", "import", "interface", "map", "package", "range", "return", "select", "struct", "switch", "type",
+    "var",
+];
+
+const JAVASCRIPT_KEYWORDS: &[&str] = &[
+    "abstract", "as", "async", "await", "break", "case", "catch", "class", "const", "continue", "debugger",
+    "declare", "default", "delete", "do", "else", "enum", "export", "extends", "finally", "for", "from", "function",
+    "get", "if", "implements", "import", "in", "instanceof", "interface", "keyof", "let", "namespace", "new", "of",
+    "private", "protected", "public", "readonly", "return", "set", "static", "switch", "throw", "try", "type",
+    "typeof", "var", "void", "while", "with", "yield",
+    // PHP
+    "echo", "elseif", "endif", "endforeach", "endwhile", "foreach", "fn", "global", "include", "include_once",
+    "insteadof", "isset", "list", "print", "require", "require_once", "trait", "unset", "use",
+];
+
+const C_FAMILY_KEYWORDS: &[&str] = &[
+    "abstract", "auto", "base", "bool", "boolean", "break", "byte", "case", "catch", "char", "checked", "class",
+    "const", "constexpr", "continue", "decimal", "default", "delegate", "delete", "do", "double", "else", "enum",
+    "event", "explicit", "extends", "extern", "final", "finally", "fixed", "float", "for", "foreach", "friend",
+    "goto", "if", "implements", "implicit", "import", "in", "inline", "instanceof", "int", "interface", "internal",
+    "is", "lock", "long", "namespace", "new", "operator", "out", "override", "package", "params", "private",
+    "protected", "public", "readonly", "record", "ref", "register", "return", "sealed", "short", "signed",
+    "sizeof", "static", "struct", "switch", "synchronized", "template", "throw", "throws", "try", "typedef",
+    "typename", "typeof", "union", "unsigned", "using", "var", "virtual", "void", "volatile", "while", "yield",
+];
+
+const PYTHON_KEYWORDS: &[&str] = &[
+    "and", "as", "assert", "async", "await", "break", "class", "continue", "def", "del", "elif", "else", "except",
+    "finally", "for", "from", "global", "if", "import", "in", "is", "lambda", "nonlocal", "not", "or", "pass",
+    "raise", "return", "try", "while", "with", "yield",
+];
+
+pub(crate) fn keywords(language: &str) -> Option<&'static [&'static str]> {
+    match language {
+        "Rust" => Some(RUST_KEYWORDS),
+        "Go" => Some(GO_KEYWORDS),
+        "JavaScript" | "TypeScript" | "PHP" => Some(JAVASCRIPT_KEYWORDS),
+        "Java" | "C" | "C++" | "C#" => Some(C_FAMILY_KEYWORDS),
+        "Python" => Some(PYTHON_KEYWORDS),
+        _ => None,
+    }
+}
+
+/// Counts the operators and operands of a file. Keywords and punctuation are operators, with a
+/// pair of brackets counted once; identifiers, numbers and string literals are operands.
+/// Returns `None` for languages without a known keyword set and for files without code.
+pub fn analyze_halstead(content: &str, language: &str) -> Option<HalsteadMetrics> {
+    let keywords = keywords(language)?;
+    let masked = mask(content, language);
+    let tokens = tokenize(&masked.text);
+
+    let mut operators: HashMap<&str, usize> = HashMap::new();
+    let mut operands: HashMap<&str, usize> = HashMap::new();
+
+    let mut index = 0;
+    while index < tokens.len() {
+        let token = tokens[index];
+
+        let literal = masked.literals.partition_point(|literal| literal.end <= token.offset);
+        if let Some(range) = masked.literals.get(literal).filter(|range| range.contains(&token.offset)) {
+            *operands.entry(&content[range.clone()]).or_insert(0) += 1;
+            while index < tokens.len() && tokens[index].offset < range.end {
+                index += 1;
+            }
+            continue;
+        }
+
+        if token.is_identifier() {
+            let counts = if keywords.contains(&token.text) { &mut operators } else { &mut operands };
+            *counts.entry(token.text).or_insert(0) += 1;
+            index += 1;
+        } else if token.text.starts_with(|c: char| c.is_ascii_digit()) {
+            *operands.entry(token.text).or_insert(0) += 1;
+            index += 1;
+        } else {
+            let (operator, consumed) = compound_operator(&masked.text, &tokens[index..]);
+            if !CLOSING_BRACKETS.contains(&operator) {
+                *operators.entry(operator).or_insert(0) += 1;
+            }
+            index += consumed;
+        }
+    }
+
+    if operators.is_empty() && operands.is_empty() {
+        return None;
+    }
+
+    Some(HalsteadMetrics::from_counts(
+        operators.len(),
+        operands.len(),
+        operators.values().sum(),
+        operands.values().sum(),
+    ))
+}
+
+/// Joins adjacent punctuation tokens into the longest known operator, such as `<<=` from
+/// `<`, `<` and `=`. Returns the operator and the number of tokens it spans.
+fn compound_operator<'a>(masked: &'a str, tokens: &[Token<'a>]) -> (&'a str, usize) {
+    let first = tokens[0];
+    let mut end = first.offset + first.text.len();
+    let mut candidates = vec![(first.text, 1)];
+
+    for (count, token) in tokens.iter().enumerate().skip(1).take(2) {
+        if token.offset != end || token.is_identifier() || token.text.starts_with(|c: char| c.is_ascii_digit()) {
+            break;
+        }
+        end = token.offset + token.text.len();
+        candidates.push((&masked[first.offset..end], count + 1));
+    }
+
+    candidates
+        .into_iter()
+        .rev()
+        .find(|(text, _)| text.len() == first.text.len() || COMPOUND_OPERATORS.contains(text))
+        .unwrap_or((first.text, 1))
+}
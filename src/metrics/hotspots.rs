@@ -0,0 +1,282 @@
+use crate::metrics::models::CodeMetrics;
+use crate::output::svg::{self, TreemapItem};
+use crate::util::git::ChangeSet;
+use serde_json::json;
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::path::Path;
+
+/// Commits changing more files than this, such as formatting sweeps or renames, are left out
+/// of the temporal coupling as they say little about which files belong together.
+const MAX_CHANGESET_FILES: usize = 30;
+
+/// How often and how much a file changed.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct FileChurn {
+    pub commits: usize,
+    pub lines_added: usize,
+    pub lines_removed: usize,
+    pub authors: BTreeSet<String>,
+}
+
+/// A changed file with its current size and complexity.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Hotspot {
+    pub path: String,
+    pub language: String,
+    pub lines_of_code: usize,
+    /// Summed cyclomatic complexity of the file's functions; `None` when no functions were
+    /// analyzed, as for languages without function analysis
+    pub complexity: Option<usize>,
+    pub churn: FileChurn,
+    /// Commits times complexity, counting files without analyzed functions as complexity 1
+    pub score: usize,
+}
+
+impl Hotspot {
+    /// The complexity for tables, `-` when no functions were analyzed.
+    pub fn complexity_label(&self) -> String {
+        self.complexity.map_or_else(|| "-".to_string(), |complexity| complexity.to_string())
+    }
+}
+
+/// Two files that were changed in the same commits.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CoupledFiles {
+    pub first: String,
+    pub second: String,
+    pub shared_commits: usize,
+    /// Shared commits over the average commits of the two files, from 0 to 1
+    pub degree: f64,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct HotspotReport {
+    /// Commits in the analyzed time window
+    pub commits: usize,
+    pub min_shared_commits: usize,
+    /// Files changed in the window that still exist, highest score first
+    pub hotspots: Vec<Hotspot>,
+    /// Pairs changed together in at least `min_shared_commits` commits, most coupled first
+    pub couplings: Vec<CoupledFiles>,
+}
+
+impl HotspotReport {
+    /// Joins the churn in `history` with the metrics of the current tree. Paths in the history
+    /// are relative to the analyzed directory; files that no longer exist are left out.
+    pub fn analyze(history: &[ChangeSet], metrics: &CodeMetrics, min_shared_commits: usize) -> Self {
+        let files: HashMap<String, (&str, usize, Option<usize>)> = metrics
+            .files
+            .iter()
+            .map(|file| {
+                let path = Path::new(&file.path).strip_prefix(&metrics.root).unwrap_or(Path::new(&file.path));
+                let complexity = (!file.functions.is_empty())
+                    .then(|| file.functions.iter().map(|function| function.cyclomatic_complexity).sum());
+                (path.to_string_lossy().replace('\\', "/"), (file.language.as_str(), file.lines_of_code, complexity))
+            })
+            .collect();
+
+        let mut churn: BTreeMap<&str, FileChurn> = BTreeMap::new();
+        let mut shared: BTreeMap<(&str, &str), usize> = BTreeMap::new();
+        for change in history {
+            let mut paths: Vec<&str> = change
+                .files
+                .iter()
+                .map(|file| file.path.as_str())
+                .filter(|path| files.contains_key(*path))
+                .collect();
+            paths.sort_unstable();
+            paths.dedup();
+
+            for file in change.files.iter().filter(|file| files.contains_key(&file.path)) {
+                let entry = churn.entry(file.path.as_str()).or_default();
+                entry.lines_added += file.lines_added;
+                entry.lines_removed += file.lines_removed;
+                entry.authors.insert(change.author.clone());
+            }
+            for path in &paths {
+                churn.entry(path).or_default().commits += 1;
+            }
+
+            if paths.len() <= MAX_CHANGESET_FILES {
+                for (index, first) in paths.iter().enumerate() {
+                    for second in &paths[index + 1..] {
+                        *shared.entry((*first, *second)).or_default() += 1;
+                    }
+                }
+            }
+        }
+
+        let mut couplings: Vec<CoupledFiles> = shared
+            .into_iter()
+            .filter(|(_, shared_commits)| *shared_commits >= min_shared_commits.max(1))
+            .map(|((first, second), shared_commits)| {
+                let average = (churn[first].commits + churn[second].commits) as f64 / 2.0;
+                CoupledFiles {
+                    first: first.to_string(),
+                    second: second.to_string(),
+                    shared_commits,
+                    degree: shared_commits as f64 / average,
+                }
+            })
+            .collect();
+        couplings.sort_by(|a, b| {
+            b.degree
+                .total_cmp(&a.degree)
+                .then_with(|| b.shared_commits.cmp(&a.shared_commits))
+                .then_with(|| (&a.first, &a.second).cmp(&(&b.first, &b.second)))
+        });
+
+        let mut hotspots: Vec<Hotspot> = churn
+            .into_iter()
+            .map(|(path, churn)| {
+                let (language, lines_of_code, complexity) = files[path];
+                Hotspot {
+                    path: path.to_string(),
+                    language: language.to_string(),
+                    lines_of_code,
+                    complexity,
+                    score: churn.commits * complexity.unwrap_or(1),
+                    churn,
+                }
+            })
+            .collect();
+        hotspots.sort_by(|a, b| {
+            b.score
+                .cmp(&a.score)
+                .then_with(|| b.churn.commits.cmp(&a.churn.commits))
+                .then_with(|| a.path.cmp(&b.path))
+        });
+
+        HotspotReport { commits: history.len(), min_shared_commits, hotspots, couplings }
+    }
+
+    /// The `top` hotspots and couplings, with the treemap embedded when `chart` names the SVG
+    /// file saved next to the report.
+    pub fn to_markdown(&self, top: usize, chart: Option<&str>) -> String {
+        let mut output = String::from("# Hotspots\n\n");
+        output.push_str(&format!(
+            "{} commits changed {} files that still exist. Files are ranked by commits times the summed cyclomatic complexity of their functions; files without analyzed functions, shown with `-`, count as complexity 1.\n\n",
+            self.commits,
+            self.hotspots.len()
+        ));
+
+        if let Some(chart) = chart {
+            output.push_str(&format!("![Hotspots]({})\n\n", chart));
+        }
+
+        if self.hotspots.is_empty() {
+            output.push_str("No changed files in this time window.\n");
+        } else {
+            output.push_str("| Rank | File | Commits | Added | Removed | Authors | Lines of Code | Complexity | Score |\n");
+            output.push_str("|-----:|------|--------:|------:|--------:|--------:|--------------:|-----------:|------:|\n");
+            for (index, hotspot) in self.hotspots.iter().take(top).enumerate() {
+                output.push_str(&format!(
+                    "| {} | `{}` | {} | {} | {} | {} | {} | {} | {} |\n",
+                    index + 1,
+                    hotspot.path,
+                    hotspot.churn.commits,
+                    hotspot.churn.lines_added,
+                    hotspot.churn.lines_removed,
+                    hotspot.churn.authors.len(),
+                    hotspot.lines_of_code,
+                    hotspot.complexity_label(),
+                    hotspot.score
+                ));
+            }
+        }
+
+        output.push_str("\n## Temporal Coupling\n\n");
+        output.push_str(&format!(
+            "Files changed together in at least {} commits. The degree is the shared commits over the average commits of the two files; commits changing more than {} files are left out.\n\n",
+            self.min_shared_commits, MAX_CHANGESET_FILES
+        ));
+        if self.couplings.is_empty() {
+            output.push_str("No files changed together often enough.\n");
+        } else {
+            output.push_str("| File | Changes With | Shared Commits | Degree |\n");
+            output.push_str("|------|--------------|---------------:|-------:|\n");
+            for coupling in self.couplings.iter().take(top) {
+                output.push_str(&format!(
+                    "| `{}` | `{}` | {} | {:.0}% |\n",
+                    coupling.first,
+                    coupling.second,
+                    coupling.shared_commits,
+                    coupling.degree * 100.0
+                ));
+            }
+        }
+
+        output
+    }
+
+    pub fn to_json(&self, top: usize) -> Result<String, String> {
+        let hotspots: Vec<_> = self
+            .hotspots
+            .iter()
+            .take(top)
+            .map(|hotspot| {
+                json!({
+                    "path": hotspot.path,
+                    "language": hotspot.language,
+                    "commits": hotspot.churn.commits,
+                    "lines_added": hotspot.churn.lines_added,
+                    "lines_removed": hotspot.churn.lines_removed,
+                    "authors": hotspot.churn.authors,
+                    "lines_of_code": hotspot.lines_of_code,
+                    "complexity": hotspot.complexity,
+                    "score": hotspot.score,
+                })
+            })
+            .collect();
+        let couplings: Vec<_> = self
+            .couplings
+            .iter()
+            .take(top)
+            .map(|coupling| {
+                json!({
+                    "first": coupling.first,
+                    "second": coupling.second,
+                    "shared_commits": coupling.shared_commits,
+                    "degree": coupling.degree,
+                })
+            })
+            .collect();
+
+        serde_json::to_string_pretty(&json!({
+            "commits": self.commits,
+            "hotspots": hotspots,
+            "temporal_coupling": couplings,
+        }))
+        .map_err(|e| format!("Failed to serialize hotspots: {}", e))
+    }
+
+    /// Every changed file as a rectangle sized by lines of code and colored by commits.
+    pub fn to_svg(&self) -> String {
+        let most_commits = self.hotspots.iter().map(|hotspot| hotspot.churn.commits).max().unwrap_or(1);
+        let items: Vec<TreemapItem> = self
+            .hotspots
+            .iter()
+            .map(|hotspot| TreemapItem {
+                label: hotspot.path.clone(),
+                value: hotspot.lines_of_code.max(1) as f64,
+                intensity: hotspot.churn.commits as f64 / most_commits as f64,
+                tooltip: format!(
+                    "{}: {} commits, +{} -{}, {} authors, {} lines of code, complexity {}",
+                    hotspot.path,
+                    hotspot.churn.commits,
+                    hotspot.churn.lines_added,
+                    hotspot.churn.lines_removed,
+                    hotspot.churn.authors.len(),
+                    hotspot.lines_of_code,
+                    hotspot.complexity_label()
+                ),
+            })
+            .collect();
+
+        svg::treemap(
+            "Hotspots",
+            &format!("Area: lines of code. Color: commits, up to {} ({} commits analyzed).", most_commits, self.commits),
+            &items,
+        )
+    }
+}
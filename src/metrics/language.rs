@@ -1,4 +1,55 @@
-pub struct LanguageDetector;
+use crate::util::file_filter::glob_match_path;
+use regex::Regex;
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, LazyLock, Mutex};
+
+/// Lines at the start and end of a file searched for Vim and Emacs modelines.
+const MODELINE_LINES: usize = 5;
+
+static VIM_MODELINE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"(?:^|\s)(?:vi|vim|ex)(?:[<=>]?\d+)?:.*?\b(?:ft|filetype|syntax)=([\w+#-]+)").unwrap());
+static EMACS_MODELINE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"-\*-(?:.*?[\s;]mode:\s*([\w+#-]+).*?|\s*([\w+#-]+)\s*)-\*-").unwrap());
+static CPP_HEADER: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"(?m)^\s*(?:template\s*<|namespace\s+\w*\s*\{|class\s+\w+\s*(?::|\{)|(?:public|private|protected):|using\s+namespace\b|#include\s*<(?:iostream|string|vector|map|memory|cstdint|cstdio|cstdlib|algorithm)>)|\bstd::").unwrap()
+});
+static OBJECTIVE_C: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r#"(?m)^\s*(?:@(?:interface|class|protocol|property|end|synthesize|selector|implementation)\b|#import\s+[<"])"#).unwrap()
+});
+static MATLAB: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"(?m)^\s*(?:%|function\b.*=|end\s*$)").unwrap());
+static PERL: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"(?m)^\s*(?:use\s+(?:strict|warnings|v?5)\b|my\s+[$@%]|sub\s+\w+|package\s+[\w:]+;)").unwrap());
+static PROLOG: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"(?m)^[^#%]*:-").unwrap());
+static COQ: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"(?m)^\s*(?:Require\s+(?:Import|Export)|From\s+\w+\s+Require|Theorem|Lemma|Proof\.|Qed\.|Inductive|Fixpoint)\b").unwrap()
+});
+static VERILOG: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"(?m)^\s*(?:module\s+\w+|endmodule\b|`timescale|always\s*@)").unwrap());
+
+/// A `linguist-language` rule of a `.gitattributes` file: files matching the pattern get the
+/// language, or lose an override set by an earlier rule when it is `None`.
+#[derive(Debug, Clone, PartialEq)]
+struct AttributeRule {
+    pattern: String,
+    language: Option<String>,
+}
+
+/// The `linguist-language` rules of a directory's `.gitattributes` file, and whether the
+/// directory is the root of a git repository, where the search for attribute files stops.
+#[derive(Debug, Default)]
+struct DirectoryAttributes {
+    rules: Vec<AttributeRule>,
+    repository_root: bool,
+}
+
+/// Detects the language of a file. [`LanguageDetector::detect`] looks at, in order:
+/// `linguist-language` overrides in `.gitattributes`, Vim and Emacs modelines, well-known file
+/// names, the shebang line of files whose extension says nothing, and the extension, using the
+/// content to tell apart languages that share one (`.h`, `.m`, `.pl`, `.v`, `.ts`).
+pub struct LanguageDetector {
+    /// Attribute files read so far, by directory
+    attributes: Mutex<HashMap<PathBuf, Arc<DirectoryAttributes>>>,
+}
 
 impl Default for LanguageDetector {
     fn default() -> Self {
@@ -8,12 +59,79 @@ impl Default for LanguageDetector {
 
 impl LanguageDetector {
     pub fn new() -> Self {
-        LanguageDetector
+        LanguageDetector { attributes: Mutex::new(HashMap::new()) }
+    }
+
+    /// The language of a file from its path and, when given, its content.
+    pub fn detect(&self, path: &Path, content: Option<&str>) -> String {
+        if let Some(language) = self.gitattributes_language(path) {
+            return language;
+        }
+        if let Some(language) = content.and_then(modeline_language) {
+            return language;
+        }
+
+        let file_name = path.file_name().and_then(|name| name.to_str()).unwrap_or("");
+        let extension = path.extension().and_then(|ext| ext.to_str()).unwrap_or("");
+        let language = if extension.is_empty() && !file_name.is_empty() {
+            self.detect_by_filename(file_name)
+        } else {
+            self.detect_language(extension)
+        };
+
+        let Some(content) = content else {
+            return language;
+        };
+        if language == "Other" {
+            return shebang_language(content).unwrap_or(language);
+        }
+        disambiguate(extension, content).map_or(language, String::from)
+    }
+
+    /// The language a `.gitattributes` file in the file's directory or one of its parents, up to
+    /// the repository root, sets with `linguist-language`. Deeper files and later lines win.
+    fn gitattributes_language(&self, path: &Path) -> Option<String> {
+        let path = fs::canonicalize(path).ok()?;
+
+        let mut directories = Vec::new();
+        for directory in path.ancestors().skip(1) {
+            let attributes = self.directory_attributes(directory);
+            let repository_root = attributes.repository_root;
+            directories.push((directory, attributes));
+            if repository_root {
+                break;
+            }
+        }
+
+        let mut language = None;
+        for (directory, attributes) in directories.iter().rev() {
+            let Ok(relative) = path.strip_prefix(directory) else {
+                continue;
+            };
+            let relative = relative.to_string_lossy().replace('\\', "/");
+            for rule in attributes.rules.iter().filter(|rule| attribute_pattern_matches(&rule.pattern, &relative)) {
+                language = rule.language.clone();
+            }
+        }
+        language
+    }
+
+    fn directory_attributes(&self, directory: &Path) -> Arc<DirectoryAttributes> {
+        if let Some(attributes) = self.attributes.lock().unwrap().get(directory) {
+            return Arc::clone(attributes);
+        }
+
+        let attributes = Arc::new(DirectoryAttributes {
+            rules: fs::read_to_string(directory.join(".gitattributes")).map(|content| parse_gitattributes(&content)).unwrap_or_default(),
+            repository_root: directory.join(".git").exists(),
+        });
+        self.attributes.lock().unwrap().insert(directory.to_path_buf(), Arc::clone(&attributes));
+        attributes
     }
 
     pub fn detect_by_filename(&self, filename: &str) -> String {
         match filename {
-            ".gitignore" => "GitConfig",
+            ".gitignore" | ".gitattributes" => "GitConfig",
             "Dockerfile" => "Docker",
             "Makefile" => "Make",
             ".dockerignore" => "Docker",
@@ -32,16 +150,20 @@ impl LanguageDetector {
     pub fn detect_language(&self, extension: &str) -> String {
         match extension {
             "rs" => "Rust",
-            "js" | "jsx" => "JavaScript",
-            "ts" | "tsx" => "TypeScript",
+            "js" | "jsx" | "mjs" | "cjs" => "JavaScript",
+            "ts" | "tsx" | "mts" | "cts" => "TypeScript",
             "py" => "Python",
             "java" => "Java",
             "c" | "h" => "C",
-            "cpp" | "hpp" => "C++",
+            "cpp" | "hpp" | "cc" | "cxx" | "hh" | "hxx" => "C++",
+            "m" | "mm" => "Objective-C",
+            "pl" | "pm" => "Perl",
+            "v" => "Verilog",
             "go" => "Go",
             "rb" => "Ruby",
             "php" => "PHP",
             "html" => "HTML",
+            "xml" => "XML",
             "css" => "CSS",
             "md" => "Markdown",
             "json" => "JSON",
@@ -65,14 +187,18 @@ impl LanguageDetector {
     pub fn get_comment_syntax(&self, language: &str) -> (String, String, String) {
         match language {
             "Rust" => ("//".to_string(), "/*".to_string(), "*/".to_string()),
-            "JavaScript" | "TypeScript" | "C" | "C++" | "Java" | "Go" | "C#" | "FSharp" => {
+            "JavaScript" | "TypeScript" | "C" | "C++" | "Java" | "Go" | "C#" | "FSharp" | "PHP" | "Objective-C" | "Verilog" => {
                 ("//".to_string(), "/*".to_string(), "*/".to_string())
             }
+            "CSS" => ("".to_string(), "/*".to_string(), "*/".to_string()),
             "VisualBasic" => ("'".to_string(), "/*".to_string(), "*/".to_string()),
             "XAML" | "Razor" | "ASP.NET" => ("".to_string(), "<!--".to_string(), "-->".to_string()),
-            "Python" | "Shell" | "Make" | "Docker" => ("#".to_string(), "".to_string(), "".to_string()),
+            "Python" | "Shell" | "Make" | "Docker" | "Perl" => ("#".to_string(), "".to_string(), "".to_string()),
+            "Prolog" => ("%".to_string(), "/*".to_string(), "*/".to_string()),
+            "MATLAB" => ("%".to_string(), "%{".to_string(), "%}".to_string()),
+            "Coq" => ("".to_string(), "(*".to_string(), "*)".to_string()),
             "Ruby" => ("#".to_string(), "=begin".to_string(), "=end".to_string()),
-            "HTML" | "CSS" => ("".to_string(), "<!--".to_string(), "-->".to_string()),
+            "HTML" | "XML" => ("".to_string(), "<!--".to_string(), "-->".to_string()),
             "Markdown" | "YAML" | "TOML" | "JSON" | "LockFile" | "Sample" | "GitConfig" |
             "License" | "SystemFile" | "DotNetProject" => {
                 ("".to_string(), "".to_string(), "".to_string())
@@ -81,3 +207,120 @@ impl LanguageDetector {
         }
     }
 }
+
+/// Reads the `linguist-language` attributes of a `.gitattributes` file; `-linguist-language` and
+/// `!linguist-language` clear an override.
+fn parse_gitattributes(content: &str) -> Vec<AttributeRule> {
+    let mut rules = Vec::new();
+    for line in content.lines().map(str::trim) {
+        if line.is_empty() || line.starts_with('#') || line.starts_with("[attr]") {
+            continue;
+        }
+        let mut fields = line.split_whitespace();
+        let Some(pattern) = fields.next() else {
+            continue;
+        };
+
+        for attribute in fields {
+            let language = match attribute.split_once('=') {
+                Some(("linguist-language", language)) => Some(language_from_alias(language).unwrap_or_else(|| language.to_string())),
+                None if attribute == "-linguist-language" || attribute == "!linguist-language" => None,
+                _ => continue,
+            };
+            rules.push(AttributeRule { pattern: pattern.to_string(), language });
+        }
+    }
+    rules
+}
+
+/// Matches a path relative to the directory of a `.gitattributes` file: a pattern without a `/`
+/// matches the file name at any depth, any other pattern is anchored to that directory.
+fn attribute_pattern_matches(pattern: &str, path: &str) -> bool {
+    if pattern.ends_with('/') {
+        return false;
+    }
+    if pattern.contains('/') {
+        glob_match_path(pattern.trim_start_matches('/'), path)
+    } else {
+        glob_match_path(&format!("**/{}", pattern), path)
+    }
+}
+
+/// The language named by a Vim `ft=` modeline or an Emacs `-*- mode: ... -*-` line, searched for
+/// in the first and last lines of the file.
+fn modeline_language(content: &str) -> Option<String> {
+    let lines: Vec<&str> = content.lines().collect();
+    let tail = lines.len().saturating_sub(MODELINE_LINES).max(MODELINE_LINES.min(lines.len()));
+    lines[..MODELINE_LINES.min(lines.len())].iter().chain(&lines[tail..]).find_map(|line| {
+        let name = if let Some(captures) = VIM_MODELINE.captures(line) {
+            captures.get(1)
+        } else {
+            EMACS_MODELINE.captures(line).and_then(|captures| captures.get(1).or(captures.get(2)))
+        };
+        language_from_alias(name?.as_str())
+    })
+}
+
+/// The language of the interpreter on a `#!` line, e.g. `#!/bin/sh` or `#!/usr/bin/env -S python3 -u`.
+fn shebang_language(content: &str) -> Option<String> {
+    let command = content.lines().next()?.strip_prefix("#!")?;
+    let mut words = command.split_whitespace();
+    let mut interpreter = words.next()?.rsplit('/').next()?;
+    if interpreter == "env" {
+        interpreter = words.find(|word| !word.starts_with('-') && !word.contains('='))?;
+    }
+
+    let interpreter = interpreter.trim_end_matches(|c: char| c.is_ascii_digit() || c == '.');
+    language_from_alias(interpreter)
+}
+
+/// Tells apart the languages sharing an extension from the content of a file; `None` keeps the
+/// language of the extension.
+fn disambiguate(extension: &str, content: &str) -> Option<&'static str> {
+    match extension {
+        "h" if OBJECTIVE_C.is_match(content) => Some("Objective-C"),
+        "h" if CPP_HEADER.is_match(content) => Some("C++"),
+        "m" if !OBJECTIVE_C.is_match(content) && MATLAB.is_match(content) => Some("MATLAB"),
+        "pl" if !PERL.is_match(content) && PROLOG.is_match(content) => Some("Prolog"),
+        "v" if COQ.is_match(content) && !VERILOG.is_match(content) => Some("Coq"),
+        "ts" if content.trim_start().starts_with("<?xml") || content.contains("<!DOCTYPE TS>") => Some("XML"),
+        _ => None,
+    }
+}
+
+/// The language for a name used by interpreters, modelines and linguist, case-insensitively.
+fn language_from_alias(name: &str) -> Option<String> {
+    let language = match name.to_lowercase().as_str() {
+        "rust" | "rs" | "rust-script" => "Rust",
+        "python" | "py" | "pypy" => "Python",
+        "javascript" | "js" | "node" | "nodejs" => "JavaScript",
+        "typescript" | "ts" | "ts-node" | "deno" => "TypeScript",
+        "sh" | "bash" | "zsh" | "ksh" | "dash" | "ash" | "shell" | "shell-script" | "shellscript" => "Shell",
+        "ruby" | "rb" => "Ruby",
+        "perl" | "pl" => "Perl",
+        "php" => "PHP",
+        "java" => "Java",
+        "go" | "golang" => "Go",
+        "c" => "C",
+        "c++" | "cpp" | "cplusplus" => "C++",
+        "objc" | "objective-c" | "objectivec" => "Objective-C",
+        "c#" | "csharp" | "cs" => "C#",
+        "f#" | "fsharp" => "FSharp",
+        "vb" | "vbnet" | "visual basic .net" => "VisualBasic",
+        "matlab" | "octave" => "MATLAB",
+        "prolog" | "swipl" => "Prolog",
+        "verilog" => "Verilog",
+        "coq" => "Coq",
+        "make" | "makefile" => "Make",
+        "dockerfile" | "docker" => "Docker",
+        "html" => "HTML",
+        "xml" => "XML",
+        "css" => "CSS",
+        "markdown" | "md" => "Markdown",
+        "json" => "JSON",
+        "yaml" | "yml" => "YAML",
+        "toml" => "TOML",
+        _ => return None,
+    };
+    Some(language.to_string())
+}
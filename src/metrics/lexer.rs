@@ -0,0 +1,432 @@
+use crate::metrics::language::LanguageDetector;
+use std::ops::Range;
+
+const BYTE_ORDER_MARK: &[u8] = "\u{feff}".as_bytes();
+
+/// What a physical line of source holds once comments are told apart from code and literals.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LineKind {
+    Code,
+    Comment,
+    Blank,
+    /// Code and a comment on the same line
+    Mixed,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SpanKind {
+    Comment,
+    /// A Python string literal standing alone as a statement, which documents rather than computes
+    Docstring,
+    /// A string or character literal
+    Literal,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Span {
+    pub kind: SpanKind,
+    /// Byte range in the source, delimiters included
+    pub range: Range<usize>,
+    /// Byte range between the delimiters
+    pub content: Range<usize>,
+}
+
+impl Span {
+    pub fn is_comment(&self) -> bool {
+        self.kind != SpanKind::Literal
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Quote {
+    delimiter: u8,
+    multiline: bool,
+    escapes: bool,
+}
+
+const fn quote(delimiter: u8, multiline: bool, escapes: bool) -> Quote {
+    Quote { delimiter, multiline, escapes }
+}
+
+const C_QUOTES: &[Quote] = &[quote(b'"', false, true)];
+const RUST_QUOTES: &[Quote] = &[quote(b'"', true, true)];
+const GO_QUOTES: &[Quote] = &[quote(b'"', false, true), quote(b'`', true, false)];
+const JAVASCRIPT_QUOTES: &[Quote] = &[quote(b'"', false, true), quote(b'\'', false, true), quote(b'`', true, true)];
+const PYTHON_QUOTES: &[Quote] = &[quote(b'"', false, true), quote(b'\'', false, true)];
+const SCRIPT_QUOTES: &[Quote] = &[quote(b'"', true, true), quote(b'\'', true, true)];
+const SHELL_QUOTES: &[Quote] = &[quote(b'"', true, true), quote(b'\'', true, false)];
+const CSS_QUOTES: &[Quote] = &[quote(b'"', false, true), quote(b'\'', false, true)];
+const VISUAL_BASIC_QUOTES: &[Quote] = &[quote(b'"', false, false)];
+
+/// How comments and literals are written in a language. Comment delimiters come from
+/// [`LanguageDetector::get_comment_syntax`]; the rest is specific to the lexer.
+struct Rules {
+    line_comments: Vec<String>,
+    block_comment: Option<(String, String)>,
+    nested_block_comments: bool,
+    /// Block comments open only at the start of a line, as Ruby's `=begin`
+    line_start_block_comments: bool,
+    /// Line comments need whitespace before them, as `#` in shell scripts
+    word_start_line_comments: bool,
+    quotes: &'static [Quote],
+    /// `'a'` is a character literal, while `'a` alone is a lifetime or label
+    char_literals: bool,
+    /// Rust raw strings such as `r#"..."#`
+    raw_strings: bool,
+    /// C# verbatim strings such as `@"..."`
+    verbatim_strings: bool,
+    /// Python triple-quoted strings, which are docstrings when they stand alone
+    docstrings: bool,
+}
+
+impl Rules {
+    fn for_language(language: &str) -> Self {
+        let (line_comment, block_start, block_end) = LanguageDetector::new().get_comment_syntax(language);
+
+        let mut line_comments = Vec::new();
+        if !line_comment.is_empty() {
+            line_comments.push(line_comment);
+        }
+        if language == "PHP" {
+            line_comments.push("#".to_string());
+        }
+
+        let quotes = match language {
+            "Rust" | "FSharp" => RUST_QUOTES,
+            "Go" => GO_QUOTES,
+            "JavaScript" | "TypeScript" => JAVASCRIPT_QUOTES,
+            "Python" => PYTHON_QUOTES,
+            "PHP" | "Ruby" => SCRIPT_QUOTES,
+            "Shell" | "Make" | "Docker" => SHELL_QUOTES,
+            "CSS" => CSS_QUOTES,
+            "VisualBasic" => VISUAL_BASIC_QUOTES,
+            "C" | "C++" | "Java" | "C#" => C_QUOTES,
+            _ => &[],
+        };
+
+        Rules {
+            line_comments,
+            block_comment: (!block_start.is_empty()).then_some((block_start, block_end)),
+            nested_block_comments: language == "Rust",
+            line_start_block_comments: language == "Ruby",
+            word_start_line_comments: matches!(language, "Shell" | "Make" | "Docker"),
+            quotes,
+            char_literals: matches!(language, "Rust" | "Go" | "C" | "C++" | "Java" | "C#"),
+            raw_strings: language == "Rust",
+            verbatim_strings: language == "C#",
+            docstrings: language == "Python",
+        }
+    }
+}
+
+/// Finds the comments, docstrings and literals of a source file, in source order. Comment
+/// markers inside literals and quotes inside comments are not mistaken for either.
+pub fn lex(content: &str, language: &str) -> Vec<Span> {
+    let lexer = Lexer {
+        bytes: content.as_bytes(),
+        rules: Rules::for_language(language),
+        spans: Vec::new(),
+        depth: 0,
+        code_on_line: false,
+        last_code: b'\n',
+    };
+    lexer.run()
+}
+
+/// Classifies each physical line, as split by [`str::lines`]. Literals count as code and
+/// docstrings as comments; lines with only whitespace are blank, even inside a block comment.
+pub fn classify_lines(content: &str, language: &str) -> Vec<LineKind> {
+    let bytes = content.as_bytes();
+    let mut commented = vec![false; bytes.len()];
+    for span in lex(content, language).iter().filter(|span| span.is_comment()) {
+        commented[span.range.clone()].fill(true);
+    }
+
+    let start = if bytes.starts_with(BYTE_ORDER_MARK) { BYTE_ORDER_MARK.len() } else { 0 };
+    let mut offset = 0;
+    content
+        .split_inclusive('\n')
+        .map(|line| {
+            let range = offset.max(start)..offset + line.len();
+            offset += line.len();
+
+            let mut code = false;
+            let mut comment = false;
+            for index in range.filter(|&index| !bytes[index].is_ascii_whitespace()) {
+                if commented[index] {
+                    comment = true;
+                } else {
+                    code = true;
+                }
+            }
+
+            match (code, comment) {
+                (true, true) => LineKind::Mixed,
+                (true, false) => LineKind::Code,
+                (false, true) => LineKind::Comment,
+                (false, false) => LineKind::Blank,
+            }
+        })
+        .collect()
+}
+
+struct Lexer<'a> {
+    bytes: &'a [u8],
+    rules: Rules,
+    spans: Vec<Span>,
+    /// Bracket depth, to tell Python docstrings from strings inside expressions
+    depth: usize,
+    /// Code has been seen on the current line
+    code_on_line: bool,
+    /// The last code byte seen, to spot line continuations
+    last_code: u8,
+}
+
+impl Lexer<'_> {
+    fn run(mut self) -> Vec<Span> {
+        let mut index = if self.bytes.starts_with(BYTE_ORDER_MARK) { BYTE_ORDER_MARK.len() } else { 0 };
+        while index < self.bytes.len() {
+            index = self.step(index);
+        }
+        self.spans
+    }
+
+    /// Consumes the comment, literal or single byte at `index` and returns where to continue.
+    fn step(&mut self, index: usize) -> usize {
+        let byte = self.bytes[index];
+        if byte == b'\n' {
+            self.code_on_line = false;
+            return index + 1;
+        }
+        if byte.is_ascii_whitespace() {
+            return index + 1;
+        }
+
+        if let Some(end) = self.block_comment(index).or_else(|| self.line_comment(index)) {
+            return end;
+        }
+        if let Some(end) = self.literal(index) {
+            return end;
+        }
+
+        self.code_on_line = true;
+        self.last_code = byte;
+        if self.rules.docstrings {
+            match byte {
+                b'(' | b'[' | b'{' => self.depth += 1,
+                b')' | b']' | b'}' => self.depth = self.depth.saturating_sub(1),
+                _ => {}
+            }
+        }
+        index + 1
+    }
+
+    fn push(&mut self, kind: SpanKind, range: Range<usize>, content: Range<usize>) -> usize {
+        let end = range.end;
+        if kind == SpanKind::Literal {
+            self.code_on_line = true;
+            self.last_code = self.bytes[end - 1];
+        }
+        self.spans.push(Span { kind, range, content });
+        end
+    }
+
+    fn block_comment(&mut self, index: usize) -> Option<usize> {
+        let (open, close) = self.rules.block_comment.as_ref()?;
+        let (open, close) = (open.as_bytes(), close.as_bytes());
+        if !self.bytes[index..].starts_with(open) || (self.rules.line_start_block_comments && !self.at_line_start(index)) {
+            return None;
+        }
+
+        let mut depth = 1;
+        let mut position = index + open.len();
+        let mut content_end = self.bytes.len();
+        let mut end = self.bytes.len();
+        while position < self.bytes.len() {
+            let rest = &self.bytes[position..];
+            if self.rules.nested_block_comments && rest.starts_with(open) {
+                depth += 1;
+                position += open.len();
+            } else if rest.starts_with(close) {
+                depth -= 1;
+                position += close.len();
+                if depth == 0 {
+                    content_end = position - close.len();
+                    end = position;
+                    break;
+                }
+            } else {
+                position += 1;
+            }
+        }
+
+        if self.rules.line_start_block_comments {
+            end = self.line_end(end);
+        }
+        Some(self.push(SpanKind::Comment, index..end, index + open.len()..content_end))
+    }
+
+    fn line_comment(&mut self, index: usize) -> Option<usize> {
+        let rest = &self.bytes[index..];
+        let marker = self.rules.line_comments.iter().find(|marker| rest.starts_with(marker.as_bytes()))?.len();
+        if self.rules.word_start_line_comments && index > 0 && !self.bytes[index - 1].is_ascii_whitespace() {
+            return None;
+        }
+
+        let end = self.line_end(index);
+        Some(self.push(SpanKind::Comment, index..end, index + marker..end))
+    }
+
+    fn literal(&mut self, index: usize) -> Option<usize> {
+        let bytes = self.bytes;
+        let rest = &bytes[index..];
+
+        if self.rules.docstrings {
+            let prefix = self.string_prefix(index);
+            let opening = &rest[prefix..];
+            if opening.starts_with(b"\"\"\"") || opening.starts_with(b"'''") {
+                return Some(self.triple_quoted(index, index + prefix));
+            }
+        }
+
+        if self.rules.raw_strings {
+            let prefix = usize::from(rest.starts_with(b"br"));
+            if rest.get(prefix) == Some(&b'r') && self.starts_word(index) {
+                let hashes = rest[prefix + 1..].iter().take_while(|&&byte| byte == b'#').count();
+                if rest.get(prefix + 1 + hashes) == Some(&b'"') {
+                    let start = index + prefix + 2 + hashes;
+                    let mut terminator = vec![b'"'];
+                    terminator.extend(std::iter::repeat_n(b'#', hashes));
+                    let (content_end, end) = match find(bytes, start, &terminator) {
+                        Some(close) => (close, close + terminator.len()),
+                        None => (bytes.len(), bytes.len()),
+                    };
+                    return Some(self.push(SpanKind::Literal, index..end, start..content_end));
+                }
+            }
+        }
+
+        if self.rules.verbatim_strings && (rest.starts_with(b"@\"") || rest.starts_with(b"@$\"")) {
+            let start = index + if rest[1] == b'$' { 3 } else { 2 };
+            let mut position = start;
+            while position < bytes.len() && !(bytes[position] == b'"' && bytes.get(position + 1) != Some(&b'"')) {
+                position += if bytes[position] == b'"' { 2 } else { 1 };
+            }
+            let content_end = position.min(bytes.len());
+            let end = (position + 1).min(bytes.len());
+            return Some(self.push(SpanKind::Literal, index..end, start..content_end));
+        }
+
+        if let Some(quote) = self.rules.quotes.iter().find(|quote| quote.delimiter == rest[0]).copied() {
+            let mut position = index + 1;
+            let mut content_end = bytes.len();
+            let mut end = bytes.len();
+            while position < bytes.len() {
+                let byte = bytes[position];
+                if byte == quote.delimiter {
+                    content_end = position;
+                    end = position + 1;
+                    break;
+                }
+                if byte == b'\n' && !quote.multiline {
+                    content_end = position;
+                    end = position;
+                    break;
+                }
+                position += if quote.escapes && byte == b'\\' { 2 } else { 1 };
+            }
+            return Some(self.push(SpanKind::Literal, index..end, index + 1..content_end.min(bytes.len())));
+        }
+
+        let byte_literal = index > 0 && bytes[index - 1] == b'b' && self.starts_word(index - 1);
+        if self.rules.char_literals && rest[0] == b'\'' && (byte_literal || self.starts_word(index)) {
+            let length = char_literal_length(rest)?;
+            return Some(self.push(SpanKind::Literal, index..index + length, index + 1..index + length - 1));
+        }
+
+        None
+    }
+
+    /// A Python triple-quoted string, which is a docstring when it is a statement of its own.
+    fn triple_quoted(&mut self, index: usize, opening: usize) -> usize {
+        let bytes = self.bytes;
+        let delimiter = &bytes[opening..opening + 3];
+        let start = opening + 3;
+
+        let mut position = start;
+        let (content_end, end) = loop {
+            if position >= bytes.len() {
+                break (bytes.len(), bytes.len());
+            }
+            if bytes[position..].starts_with(delimiter) {
+                break (position, position + 3);
+            }
+            position += if bytes[position] == b'\\' { 2 } else { 1 };
+        };
+
+        let standalone = !self.code_on_line && self.depth == 0 && self.last_code != b'\\';
+        let rest_of_line = &bytes[end..self.line_end(end)];
+        let ends_statement = rest_of_line
+            .iter()
+            .find(|byte| !byte.is_ascii_whitespace())
+            .is_none_or(|&byte| byte == b'#' || byte == b';');
+
+        if standalone && ends_statement {
+            self.spans.push(Span { kind: SpanKind::Docstring, range: index..end, content: start..content_end });
+            end
+        } else {
+            self.push(SpanKind::Literal, index..end, start..content_end)
+        }
+    }
+
+    /// Length of a Python string prefix such as `r`, `f` or `rb` before a quote at `index`.
+    fn string_prefix(&self, index: usize) -> usize {
+        if !self.starts_word(index) {
+            return 0;
+        }
+        let rest = &self.bytes[index..];
+        let letters = rest
+            .iter()
+            .take(2)
+            .take_while(|byte| matches!(byte.to_ascii_lowercase(), b'r' | b'b' | b'u' | b'f'))
+            .count();
+        if matches!(rest.get(letters), Some(b'"' | b'\'')) { letters } else { 0 }
+    }
+
+    fn starts_word(&self, index: usize) -> bool {
+        index == 0 || !is_identifier_byte(self.bytes[index - 1])
+    }
+
+    fn at_line_start(&self, index: usize) -> bool {
+        index == 0 || self.bytes[index - 1] == b'\n'
+    }
+
+    fn line_end(&self, from: usize) -> usize {
+        self.bytes[from..].iter().position(|&byte| byte == b'\n').map_or(self.bytes.len(), |offset| from + offset)
+    }
+}
+
+fn find(bytes: &[u8], from: usize, needle: &[u8]) -> Option<usize> {
+    if from >= bytes.len() {
+        return None;
+    }
+    bytes[from..].windows(needle.len()).position(|window| window == needle).map(|offset| from + offset)
+}
+
+fn is_identifier_byte(byte: u8) -> bool {
+    byte.is_ascii_alphanumeric() || byte == b'_'
+}
+
+/// Length of a character literal such as `'a'`, `'\n'` or `'\u{1F600}'` at the start of `rest`.
+fn char_literal_length(rest: &[u8]) -> Option<usize> {
+    if rest.get(1) == Some(&b'\\') {
+        return rest.iter().skip(3).take(10).position(|&b| b == b'\'').map(|offset| offset + 4);
+    }
+    let width = match rest.get(1)? {
+        byte if *byte < 0x80 => 1,
+        byte if *byte >= 0xF0 => 4,
+        byte if *byte >= 0xE0 => 3,
+        _ => 2,
+    };
+    (rest.get(1 + width) == Some(&b'\'')).then_some(width + 2)
+}
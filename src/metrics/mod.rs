@@ -1,4 +1,12 @@
+pub mod baseline;
+pub mod clones;
 pub mod collector;
+pub mod complexity;
+pub mod halstead;
+pub mod hotspots;
 pub mod language;
+pub mod lexer;
 pub mod models;
+pub mod ownership;
 pub mod reporter;
+pub mod trend;
@@ -1,7 +1,13 @@
-use std::collections::HashMap;
+use crate::metrics::clones::CloneClass;
+use crate::metrics::halstead::{HalsteadMetrics, maintainability_index};
+use serde::Serialize;
+use std::collections::{BTreeMap, HashMap};
+use std::path::Path;
 
 #[derive(Debug, Default, Clone)]
 pub struct CodeMetrics {
+    /// The analyzed directory, as given to the collector
+    pub root: String,
     pub total_files: usize,
     pub total_directories: usize,
     pub lines_of_code: usize,
@@ -22,6 +28,12 @@ pub struct CodeMetrics {
     
     pub prod_by_language: HashMap<String, LanguageMetrics>,
     pub test_by_language: HashMap<String, LanguageMetrics>,
+    
+    /// Every analyzed file; sorted by path once collection finishes
+    pub files: Vec<FileMetrics>,
+
+    /// Duplicated blocks, largest first
+    pub clones: Vec<CloneClass>,
 }
 
 #[derive(Debug, Default, Clone)]
@@ -33,7 +45,7 @@ pub struct LanguageMetrics {
     pub comment_lines: usize,
 }
 
-#[derive(Debug, Default, Clone, PartialEq)]
+#[derive(Debug, Default, Clone, PartialEq, Serialize)]
 pub struct FileMetrics {
     pub path: String,
     pub language: String,
@@ -41,11 +53,210 @@ pub struct FileMetrics {
     pub blank_lines: usize,
     pub comment_lines: usize,
     pub is_test_file: bool,
+    /// Functions found in the file, in source order; empty for languages without function analysis
+    pub functions: Vec<FunctionMetrics>,
+    pub halstead: Option<HalsteadMetrics>,
+    /// 0–100, higher is easier to maintain; see `halstead::maintainability_index`
+    pub maintainability_index: Option<f64>,
+    /// Lines with code copied elsewhere; `None` for languages without clone detection
+    pub duplicated_lines: Option<usize>,
+}
+
+impl FileMetrics {
+    /// Attaches the function and Halstead metrics of the file and derives its maintainability
+    /// index, using the summed cyclomatic complexity of its functions.
+    pub fn set_source_metrics(&mut self, functions: Vec<FunctionMetrics>, halstead: Option<HalsteadMetrics>) {
+        let cyclomatic_complexity = functions
+            .iter()
+            .map(|function| function.cyclomatic_complexity)
+            .sum::<usize>()
+            .max(1);
+        self.maintainability_index = halstead
+            .as_ref()
+            .and_then(|halstead| maintainability_index(halstead.volume, cyclomatic_complexity, self.lines_of_code));
+        self.functions = functions;
+        self.halstead = halstead;
+    }
+}
+
+/// Halstead measures and maintainability of a group of files. Distinct operator and operand
+/// counts do not add up across files, so volume and effort are totals and difficulty is the
+/// mean over the files.
+#[derive(Debug, Default, Clone, PartialEq, Serialize)]
+pub struct HalsteadSummary {
+    pub files: usize,
+    pub volume: f64,
+    pub difficulty: f64,
+    pub effort: f64,
+    /// Mean of the files' indexes, weighted by their lines of code
+    pub maintainability_index: Option<f64>,
+}
+
+impl HalsteadSummary {
+    fn from_files<'a>(files: impl Iterator<Item = &'a FileMetrics>) -> Self {
+        let mut summary = HalsteadSummary::default();
+        let mut weighted_index = 0.0;
+        let mut weight = 0;
+
+        for file in files {
+            let Some(halstead) = &file.halstead else {
+                continue;
+            };
+            summary.files += 1;
+            summary.volume += halstead.volume;
+            summary.difficulty += halstead.difficulty;
+            summary.effort += halstead.effort;
+            if let Some(index) = file.maintainability_index {
+                weighted_index += index * file.lines_of_code as f64;
+                weight += file.lines_of_code;
+            }
+        }
+
+        if summary.files > 0 {
+            summary.difficulty /= summary.files as f64;
+        }
+        if weight > 0 {
+            summary.maintainability_index = Some(weighted_index / weight as f64);
+        }
+        summary
+    }
+}
+
+/// Duplicated lines of a group of files with clone detection.
+#[derive(Debug, Default, Clone, PartialEq, Serialize)]
+pub struct DuplicationSummary {
+    pub files: usize,
+    pub lines_of_code: usize,
+    pub duplicated_lines: usize,
+}
+
+impl DuplicationSummary {
+    fn from_files<'a>(files: impl Iterator<Item = &'a FileMetrics>) -> Self {
+        let mut summary = DuplicationSummary::default();
+        for file in files {
+            if let Some(duplicated_lines) = file.duplicated_lines {
+                summary.files += 1;
+                summary.lines_of_code += file.lines_of_code;
+                summary.duplicated_lines += duplicated_lines;
+            }
+        }
+        summary
+    }
+
+    /// Duplicated lines per hundred lines of code.
+    pub fn percentage(&self) -> f64 {
+        self.duplicated_lines as f64 * 100.0 / self.lines_of_code.max(1) as f64
+    }
+}
+
+/// Line counts of a group of files, such as a language or a directory.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct GroupStats {
+    pub files: usize,
+    pub lines_of_code: usize,
+    pub comment_lines: usize,
+    pub test_lines_of_code: usize,
+}
+
+impl GroupStats {
+    /// The totals of a whole metrics run.
+    pub fn from_metrics(metrics: &CodeMetrics) -> Self {
+        GroupStats {
+            files: metrics.total_files,
+            lines_of_code: metrics.lines_of_code,
+            comment_lines: metrics.comment_lines,
+            test_lines_of_code: metrics.test_lines_of_code,
+        }
+    }
+
+    /// Counts one more file with the given lines.
+    pub fn add_file(&mut self, lines_of_code: usize, comment_lines: usize, is_test_file: bool) {
+        self.files += 1;
+        self.lines_of_code += lines_of_code;
+        self.comment_lines += comment_lines;
+        if is_test_file {
+            self.test_lines_of_code += lines_of_code;
+        }
+    }
+
+    /// Comment lines per line of code or comment; `None` for a group without either.
+    pub fn comment_ratio(&self) -> Option<f64> {
+        let lines = self.lines_of_code + self.comment_lines;
+        (lines > 0).then(|| self.comment_lines as f64 / lines as f64)
+    }
+
+    /// Test lines of code per production line of code; `None` for a group without production code.
+    pub fn test_ratio(&self) -> Option<f64> {
+        let production = self.lines_of_code - self.test_lines_of_code;
+        (production > 0).then(|| self.test_lines_of_code as f64 / production as f64)
+    }
+}
+
+/// Line counts of a directory and everything below it, with its subdirectories down to the
+/// depth the tree was built with. Files deeper than that count towards their deepest shown
+/// ancestor.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct DirectoryMetrics {
+    /// Path relative to the analyzed directory; `.` for the analyzed directory itself
+    pub path: String,
+    pub stats: GroupStats,
+    /// Subdirectories, most lines of code first
+    pub children: Vec<DirectoryMetrics>,
+}
+
+impl DirectoryMetrics {
+    fn new(path: String) -> Self {
+        DirectoryMetrics { path, ..DirectoryMetrics::default() }
+    }
+
+    /// The last component of the path.
+    pub fn name(&self) -> &str {
+        self.path.rsplit('/').next().unwrap_or(&self.path)
+    }
+
+    fn add(&mut self, directories: &[&str], file: &FileMetrics) {
+        self.stats.add_file(file.lines_of_code, file.comment_lines, file.is_test_file);
+
+        let Some((directory, rest)) = directories.split_first() else {
+            return;
+        };
+        let path = if self.path == "." { directory.to_string() } else { format!("{}/{}", self.path, directory) };
+        let child = match self.children.iter().position(|child| child.path == path) {
+            Some(child) => child,
+            None => {
+                self.children.push(DirectoryMetrics::new(path));
+                self.children.len() - 1
+            }
+        };
+        self.children[child].add(rest, file);
+    }
+
+    fn sort(&mut self) {
+        self.children.sort_by(|a, b| b.stats.lines_of_code.cmp(&a.stats.lines_of_code).then_with(|| a.path.cmp(&b.path)));
+        for child in &mut self.children {
+            child.sort();
+        }
+    }
+}
+
+#[derive(Debug, Default, Clone, PartialEq, Serialize)]
+pub struct FunctionMetrics {
+    pub name: String,
+    pub start_line: usize,
+    pub end_line: usize,
+    /// Lines from the signature to the end of the body
+    pub length: usize,
+    pub parameters: usize,
+    pub cyclomatic_complexity: usize,
+    pub cognitive_complexity: usize,
+    /// Deepest nesting of branches, loops and closures inside the function
+    pub nesting_depth: usize,
 }
 
 impl CodeMetrics {
     pub fn new() -> Self {
         CodeMetrics {
+            root: String::new(),
             total_files: 0,
             total_directories: 0,
             lines_of_code: 0,
@@ -65,10 +276,15 @@ impl CodeMetrics {
             by_language: HashMap::new(),
             prod_by_language: HashMap::new(),
             test_by_language: HashMap::new(),
+            
+            files: Vec::new(),
+            clones: Vec::new(),
         }
     }
 
-    pub fn add_language_metrics(&mut self, metrics: LanguageMetrics, file_path: &str) {
+    /// Adds a file's line counts to the totals and returns its entry in `files`, so that
+    /// callers can attach what else they know about the file.
+    pub fn add_language_metrics(&mut self, metrics: LanguageMetrics, file_path: &str) -> &mut FileMetrics {
         self.update_overall_metrics(&metrics);
         
         self.update_language_specific_metrics(&metrics);
@@ -80,8 +296,90 @@ impl CodeMetrics {
         } else {
             self.update_production_metrics(&metrics);
         }
+        
+        self.files.push(FileMetrics {
+            path: file_path.to_string(),
+            language: metrics.language.clone(),
+            lines_of_code: metrics.lines_of_code,
+            blank_lines: metrics.blank_lines,
+            comment_lines: metrics.comment_lines,
+            is_test_file,
+            ..FileMetrics::default()
+        });
+        self.files.last_mut().expect("a file was just added")
+    }
+    
+    /// Every function of every file, paired with the file it belongs to.
+    pub fn functions(&self) -> impl Iterator<Item = (&FileMetrics, &FunctionMetrics)> {
+        self.files
+            .iter()
+            .flat_map(|file| file.functions.iter().map(move |function| (file, function)))
+    }
+
+    /// The `count` functions with the highest cognitive complexity, ties broken by cyclomatic
+    /// complexity and then by location.
+    pub fn most_complex_functions(&self, count: usize) -> Vec<(&FileMetrics, &FunctionMetrics)> {
+        let mut functions: Vec<(&FileMetrics, &FunctionMetrics)> = self.functions().collect();
+        functions.sort_by(|a, b| {
+            (b.1.cognitive_complexity, b.1.cyclomatic_complexity)
+                .cmp(&(a.1.cognitive_complexity, a.1.cyclomatic_complexity))
+                .then_with(|| (&a.0.path, a.1.start_line).cmp(&(&b.0.path, b.1.start_line)))
+        });
+        functions.truncate(count);
+        functions
+    }
+    
+    /// Halstead summary of every file with Halstead metrics.
+    pub fn halstead_summary(&self) -> HalsteadSummary {
+        HalsteadSummary::from_files(self.files.iter())
+    }
+
+    /// Halstead summary per language, for the languages with Halstead metrics.
+    pub fn halstead_by_language(&self) -> BTreeMap<String, HalsteadSummary> {
+        let mut files_by_language: BTreeMap<&str, Vec<&FileMetrics>> = BTreeMap::new();
+        for file in self.files.iter().filter(|file| file.halstead.is_some()) {
+            files_by_language.entry(&file.language).or_default().push(file);
+        }
+
+        files_by_language
+            .into_iter()
+            .map(|(language, files)| (language.to_string(), HalsteadSummary::from_files(files.into_iter())))
+            .collect()
     }
     
+    /// Duplication over every file with clone detection.
+    pub fn duplication_summary(&self) -> DuplicationSummary {
+        DuplicationSummary::from_files(self.files.iter())
+    }
+
+    /// Duplication per language, for the languages with clone detection.
+    pub fn duplication_by_language(&self) -> BTreeMap<String, DuplicationSummary> {
+        let mut files_by_language: BTreeMap<&str, Vec<&FileMetrics>> = BTreeMap::new();
+        for file in self.files.iter().filter(|file| file.duplicated_lines.is_some()) {
+            files_by_language.entry(&file.language).or_default().push(file);
+        }
+
+        files_by_language
+            .into_iter()
+            .map(|(language, files)| (language.to_string(), DuplicationSummary::from_files(files.into_iter())))
+            .collect()
+    }
+
+    /// Files, lines of code, comment ratio and test ratio per directory, down to `depth` levels
+    /// below the analyzed directory.
+    pub fn directory_tree(&self, depth: usize) -> DirectoryMetrics {
+        let mut root = DirectoryMetrics::new(".".to_string());
+        for file in &self.files {
+            let path = Path::new(&file.path);
+            let relative = path.strip_prefix(&self.root).unwrap_or(path).to_string_lossy().replace('\\', "/");
+            let directories: Vec<&str> = relative.split('/').filter(|component| !component.is_empty() && *component != ".").collect();
+            let directories = &directories[..directories.len().saturating_sub(1)];
+            root.add(&directories[..directories.len().min(depth)], file);
+        }
+        root.sort();
+        root
+    }
+
     fn update_overall_metrics(&mut self, metrics: &LanguageMetrics) {
         self.lines_of_code += metrics.lines_of_code;
         self.blank_lines += metrics.blank_lines;
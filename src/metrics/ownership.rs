@@ -0,0 +1,324 @@
+use crate::util::file_filter::glob_match_path;
+use crate::util::git::{self, Author};
+use rayon::prelude::*;
+use serde_json::json;
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Where GitHub and GitLab look for the code owners file, in order.
+const CODEOWNERS_LOCATIONS: [&str; 3] = [".github/CODEOWNERS", "CODEOWNERS", "docs/CODEOWNERS"];
+
+/// The bus factor counts the fewest authors who together wrote more than this share of a module.
+const BUS_FACTOR_SHARE: f64 = 0.5;
+
+/// Authors listed per module in the reports.
+const TOP_AUTHORS: usize = 3;
+
+/// Owners declared in a `CODEOWNERS` file. As on GitHub, the last rule matching a path wins.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct CodeOwners {
+    rules: Vec<(String, Vec<String>)>,
+}
+
+impl CodeOwners {
+    /// Reads the first code owners file found in the repository.
+    pub fn load(repository_root: &Path) -> Option<(PathBuf, Self)> {
+        CODEOWNERS_LOCATIONS.iter().map(|location| repository_root.join(location)).find_map(|path| {
+            let content = fs::read_to_string(&path).ok()?;
+            Some((path, Self::parse(&content)))
+        })
+    }
+
+    pub fn parse(content: &str) -> Self {
+        let rules = content
+            .lines()
+            .map(|line| line.split_once(" #").map_or(line, |(rule, _)| rule).trim())
+            .filter(|line| !line.is_empty() && !line.starts_with('#'))
+            .filter_map(|line| {
+                let mut fields = line.split_whitespace();
+                let pattern = fields.next()?.to_string();
+                Some((pattern, fields.map(String::from).collect()))
+            })
+            .collect();
+        CodeOwners { rules }
+    }
+
+    /// Owners of a path relative to the repository root; empty when no rule matches or the
+    /// matching rule lists no owners.
+    pub fn owners_of(&self, path: &str) -> &[String] {
+        self.rules
+            .iter()
+            .rev()
+            .find(|(pattern, _)| codeowners_pattern_matches(pattern, path))
+            .map_or(&[], |(_, owners)| owners.as_slice())
+    }
+}
+
+/// Matches a path with gitignore-style rules: a pattern without a leading or inner `/` matches at
+/// any depth, and a pattern naming a directory covers everything below it, except that `dir/*`
+/// stops at its direct children.
+fn codeowners_pattern_matches(pattern: &str, path: &str) -> bool {
+    let trimmed = pattern.trim_matches('/');
+    let anchored = pattern.starts_with('/') || trimmed.contains('/');
+    let glob = if anchored { trimmed.to_string() } else { format!("**/{}", trimmed) };
+
+    let matches_itself = !pattern.ends_with('/') && glob_match_path(&glob, path);
+    let matches_directory = !pattern.ends_with("/*") && glob_match_path(&format!("{}/**", glob), path);
+    matches_itself || matches_directory
+}
+
+/// Whether an owner from a `CODEOWNERS` file is the given author. Emails must match exactly;
+/// `@user` handles match the email's user name, a GitHub no-reply address or the author name
+/// without spaces. Teams (`@org/team`) cannot be resolved and never match.
+pub fn owner_is_author(owner: &str, author: &Author) -> bool {
+    let email = author.email.to_lowercase();
+    let owner = owner.to_lowercase();
+
+    match owner.strip_prefix('@') {
+        Some(team) if team.contains('/') => false,
+        Some(handle) => {
+            let local_part = email.split('@').next().unwrap_or_default();
+            let no_reply_user = email
+                .strip_suffix("@users.noreply.github.com")
+                .map(|user| user.rsplit('+').next().unwrap_or(user));
+            local_part == handle
+                || no_reply_user == Some(handle)
+                || author.name.to_lowercase().replace(' ', "") == handle
+        }
+        None => owner == email,
+    }
+}
+
+/// The fewest authors who together wrote more than half of the lines, given lines per author
+/// in descending order.
+pub fn bus_factor(lines_per_author: &[usize]) -> usize {
+    let total: usize = lines_per_author.iter().sum();
+    let mut covered = 0;
+    for (index, lines) in lines_per_author.iter().enumerate() {
+        covered += lines;
+        if covered as f64 > total as f64 * BUS_FACTOR_SHARE {
+            return index + 1;
+        }
+    }
+    lines_per_author.len()
+}
+
+/// Surviving lines per author of one file, relative to the repository root.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FileBlame {
+    pub path: String,
+    pub lines: BTreeMap<Author, usize>,
+}
+
+/// Blames every file once, ordered by path. Paths are canonicalized first, so a file listed
+/// twice or through different relative paths is only counted once; files outside the
+/// repository or not known to git are skipped.
+pub fn blame_files(repository_root: &Path, files: &[PathBuf], parallel: bool) -> Vec<FileBlame> {
+    let relative: BTreeSet<PathBuf> = files
+        .iter()
+        .filter_map(|file| {
+            let absolute = fs::canonicalize(file).ok()?;
+            absolute.strip_prefix(repository_root).ok().map(Path::to_path_buf)
+        })
+        .collect();
+    let relative: Vec<PathBuf> = relative.into_iter().collect();
+
+    let blame = |path: &PathBuf| {
+        let lines = git::blame_authors(repository_root, path).ok()?;
+        Some(FileBlame { path: path.to_string_lossy().replace('\\', "/"), lines })
+    };
+    if parallel {
+        relative.par_iter().filter_map(blame).collect()
+    } else {
+        relative.iter().filter_map(blame).collect()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ModuleOwnership {
+    /// A directory, cut to the configured depth
+    pub module: String,
+    pub files: usize,
+    pub lines: usize,
+    /// Lines per author, most lines first
+    pub authors: Vec<(Author, usize)>,
+    pub bus_factor: usize,
+    /// Owners declared for any file of the module
+    pub declared_owners: Vec<String>,
+    /// Share of the lines written by declared owners; `None` without declared owners or when
+    /// they are all teams
+    pub owner_share: Option<f64>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct OwnershipReport {
+    /// Modules ordered by path
+    pub modules: Vec<ModuleOwnership>,
+    /// The code owners file used, if any
+    pub codeowners: Option<String>,
+    /// Modules whose declared owners wrote less than this share are flagged
+    pub min_owner_share: f64,
+}
+
+impl OwnershipReport {
+    /// Groups the files into modules by their first `depth` directories; files at the top level
+    /// form the `.` module.
+    pub fn analyze(files: &[FileBlame], codeowners: Option<(&str, &CodeOwners)>, depth: usize, min_owner_share: f64) -> Self {
+        #[derive(Default)]
+        struct Totals {
+            files: usize,
+            authors: BTreeMap<Author, usize>,
+            declared_owners: Vec<String>,
+        }
+
+        let mut modules: BTreeMap<String, Totals> = BTreeMap::new();
+        for file in files {
+            let directories: Vec<&str> = file.path.split('/').collect();
+            let directories = &directories[..directories.len() - 1];
+            let module = if directories.is_empty() { ".".to_string() } else { directories[..depth.clamp(1, directories.len())].join("/") };
+
+            let totals = modules.entry(module).or_default();
+            totals.files += 1;
+            for (author, lines) in &file.lines {
+                *totals.authors.entry(author.clone()).or_default() += lines;
+            }
+            if let Some((_, codeowners)) = codeowners {
+                for owner in codeowners.owners_of(&file.path) {
+                    if !totals.declared_owners.contains(owner) {
+                        totals.declared_owners.push(owner.clone());
+                    }
+                }
+            }
+        }
+
+        let modules = modules
+            .into_iter()
+            .map(|(module, totals)| {
+                let mut authors: Vec<(Author, usize)> = totals.authors.into_iter().collect();
+                authors.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+                let lines: usize = authors.iter().map(|(_, lines)| lines).sum();
+                let counts: Vec<usize> = authors.iter().map(|(_, lines)| *lines).collect();
+
+                let resolvable = totals.declared_owners.iter().any(|owner| !(owner.starts_with('@') && owner.contains('/')));
+                let owner_share = (resolvable && lines > 0).then(|| {
+                    let owned: usize = authors
+                        .iter()
+                        .filter(|(author, _)| totals.declared_owners.iter().any(|owner| owner_is_author(owner, author)))
+                        .map(|(_, lines)| lines)
+                        .sum();
+                    owned as f64 / lines as f64
+                });
+
+                ModuleOwnership {
+                    module,
+                    files: totals.files,
+                    lines,
+                    bus_factor: bus_factor(&counts),
+                    authors,
+                    declared_owners: totals.declared_owners,
+                    owner_share,
+                }
+            })
+            .collect();
+
+        OwnershipReport {
+            modules,
+            codeowners: codeowners.map(|(path, _)| path.to_string()),
+            min_owner_share,
+        }
+    }
+
+    /// Modules whose declared owners wrote less than the minimum share of their lines.
+    pub fn flagged(&self) -> Vec<&ModuleOwnership> {
+        self.modules
+            .iter()
+            .filter(|module| module.owner_share.is_some_and(|share| share < self.min_owner_share))
+            .collect()
+    }
+
+    pub fn to_markdown(&self) -> String {
+        let mut output = String::from("# Code Ownership\n\n");
+        output.push_str(&format!(
+            "Surviving lines per author from `git blame`. The bus factor is the fewest authors who wrote more than half of a module. Owners are read from {}.\n\n",
+            self.codeowners.as_deref().map_or("no CODEOWNERS file".to_string(), |path| format!("`{}`", path))
+        ));
+
+        output.push_str("| Module | Files | Lines | Bus Factor | Top Authors | Declared Owners | Owner Share |\n");
+        output.push_str("|--------|------:|------:|-----------:|-------------|-----------------|------------:|\n");
+        for module in &self.modules {
+            output.push_str(&format!(
+                "| `{}` | {} | {} | {} | {} | {} | {} |\n",
+                module.module,
+                module.files,
+                module.lines,
+                module.bus_factor,
+                top_authors(module),
+                if module.declared_owners.is_empty() { "-".to_string() } else { module.declared_owners.join(" ") },
+                module.owner_share.map_or("-".to_string(), |share| format!("{:.0}%", share * 100.0))
+            ));
+        }
+
+        let flagged = self.flagged();
+        output.push_str(&format!(
+            "\n## Owners Who Wrote Little\n\nModules whose declared owners wrote less than {:.0}% of the surviving lines.\n\n",
+            self.min_owner_share * 100.0
+        ));
+        if flagged.is_empty() {
+            output.push_str("None.\n");
+        }
+        for module in flagged {
+            output.push_str(&format!(
+                "- `{}`: {} wrote {:.0}%; most lines are by {}\n",
+                module.module,
+                module.declared_owners.join(" "),
+                module.owner_share.unwrap_or_default() * 100.0,
+                top_authors(module)
+            ));
+        }
+
+        output
+    }
+
+    pub fn to_json(&self) -> Result<String, String> {
+        let modules: Vec<_> = self
+            .modules
+            .iter()
+            .map(|module| {
+                let authors: Vec<_> = module
+                    .authors
+                    .iter()
+                    .map(|(author, lines)| json!({ "name": author.name, "email": author.email, "lines": lines }))
+                    .collect();
+                json!({
+                    "module": module.module,
+                    "files": module.files,
+                    "lines": module.lines,
+                    "bus_factor": module.bus_factor,
+                    "authors": authors,
+                    "declared_owners": module.declared_owners,
+                    "owner_share": module.owner_share,
+                    "flagged": module.owner_share.is_some_and(|share| share < self.min_owner_share),
+                })
+            })
+            .collect();
+
+        serde_json::to_string_pretty(&json!({
+            "codeowners": self.codeowners,
+            "min_owner_share": self.min_owner_share,
+            "modules": modules,
+        }))
+        .map_err(|e| format!("Failed to serialize ownership: {}", e))
+    }
+}
+
+/// The authors with the most lines of a module and their shares, e.g. `Ada (60%), Alan (30%)`.
+pub fn top_authors(module: &ModuleOwnership) -> String {
+    module
+        .authors
+        .iter()
+        .take(TOP_AUTHORS)
+        .map(|(author, lines)| format!("{} ({:.0}%)", author.name, *lines as f64 * 100.0 / module.lines.max(1) as f64))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
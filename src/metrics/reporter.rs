@@ -1,9 +1,37 @@
-use crate::metrics::models::CodeMetrics;
+use crate::metrics::baseline::{GroupDelta, MetricsDiff};
+use crate::metrics::clones::CloneClass;
+use crate::metrics::models::{CodeMetrics, DirectoryMetrics, DuplicationSummary, FileMetrics, FunctionMetrics, HalsteadSummary, LanguageMetrics};
+use crate::output::csv;
 use crate::output::style::*;
+use serde_json::{Value, json};
+use std::collections::HashMap;
 use std::fs;
 use std::path::Path;
 
-pub struct MetricsReporter;
+/// Functions above this cyclomatic complexity are counted as complex in the summary.
+const COMPLEX_FUNCTION_THRESHOLD: usize = 10;
+
+/// Rows in the least maintainable files table.
+const LEAST_MAINTAINABLE_FILES: usize = 10;
+
+/// Rows in the most duplicated files table.
+const MOST_DUPLICATED_FILES: usize = 10;
+
+/// Clone classes listed as the largest.
+const LARGEST_CLONE_CLASSES: usize = 10;
+
+/// Directory levels below the analyzed directory shown in the directory breakdown by default.
+pub const DEFAULT_DIRECTORY_DEPTH: usize = 2;
+
+/// Version of the JSON export's layout, raised whenever a field is renamed, removed or changes
+/// meaning. Adding a field keeps the version.
+pub const METRICS_SCHEMA_VERSION: u32 = 1;
+
+pub struct MetricsReporter {
+    top_functions: usize,
+    directory_depth: usize,
+    baseline: Option<MetricsDiff>,
+}
 
 impl Default for MetricsReporter {
     fn default() -> Self {
@@ -13,7 +41,25 @@ impl Default for MetricsReporter {
 
 impl MetricsReporter {
     pub fn new() -> Self {
-        MetricsReporter
+        MetricsReporter { top_functions: 10, directory_depth: DEFAULT_DIRECTORY_DEPTH, baseline: None }
+    }
+
+    /// Number of rows in the most complex functions table
+    pub fn top_functions(mut self, count: usize) -> Self {
+        self.top_functions = count;
+        self
+    }
+
+    /// Directory levels shown in the directory breakdown
+    pub fn directory_depth(mut self, depth: usize) -> Self {
+        self.directory_depth = depth;
+        self
+    }
+
+    /// Changes since a baseline snapshot, reported after the metrics
+    pub fn baseline(mut self, diff: MetricsDiff) -> Self {
+        self.baseline = Some(diff);
+        self
     }
     
     pub fn export_metrics(&self, metrics: &CodeMetrics, output_path: impl AsRef<Path>) -> Result<(), String> {
@@ -21,6 +67,91 @@ impl MetricsReporter {
         
         fs::write(output_path, content).map_err(|e| format!("Failed to write metrics file: {}", e))
     }
+
+    pub fn export_metrics_json(&self, metrics: &CodeMetrics, output_path: impl AsRef<Path>) -> Result<(), String> {
+        let content = self.format_metrics_json(metrics)?;
+
+        fs::write(output_path, content).map_err(|e| format!("Failed to write metrics file: {}", e))
+    }
+
+    pub fn export_metrics_csv(&self, metrics: &CodeMetrics, output_path: impl AsRef<Path>) -> Result<(), String> {
+        let content = self.format_metrics_csv(metrics);
+
+        fs::write(output_path, content).map_err(|e| format!("Failed to write metrics file: {}", e))
+    }
+
+    /// The JSON report, versioned by `schema_version`. Object keys are sorted and arrays are
+    /// ordered by language or path, so two runs over the same tree produce the same bytes.
+    fn format_metrics_json(&self, metrics: &CodeMetrics) -> Result<String, String> {
+        let halstead_by_language = metrics.halstead_by_language();
+        let duplication_by_language = metrics.duplication_by_language();
+        let mut languages = language_rows(&metrics.by_language);
+        for row in &mut languages {
+            let language = row["language"].as_str().unwrap_or_default().to_string();
+            row["halstead"] = json!(halstead_by_language.get(&language));
+            row["duplication"] = json!(duplication_by_language.get(&language));
+        }
+
+        let mut files: Vec<&FileMetrics> = metrics.files.iter().collect();
+        files.sort_by(|a, b| a.path.cmp(&b.path));
+
+        let report = json!({
+            "schema_version": METRICS_SCHEMA_VERSION,
+            "root": metrics.root,
+            "summary": {
+                "directories": metrics.total_directories,
+                "files": metrics.total_files,
+                "lines_of_code": metrics.lines_of_code,
+                "blank_lines": metrics.blank_lines,
+                "comment_lines": metrics.comment_lines,
+                "halstead": metrics.halstead_summary(),
+                "duplication": metrics.duplication_summary(),
+            },
+            "production": {
+                "files": metrics.prod_files,
+                "lines_of_code": metrics.prod_lines_of_code,
+                "blank_lines": metrics.prod_blank_lines,
+                "comment_lines": metrics.prod_comment_lines,
+                "languages": language_rows(&metrics.prod_by_language),
+            },
+            "test": {
+                "files": metrics.test_files,
+                "lines_of_code": metrics.test_lines_of_code,
+                "blank_lines": metrics.test_blank_lines,
+                "comment_lines": metrics.test_comment_lines,
+                "languages": language_rows(&metrics.test_by_language),
+            },
+            "languages": languages,
+            "directory_tree": directory_json(&metrics.directory_tree(self.directory_depth)),
+            "files": files,
+            "clones": metrics.clones,
+        });
+
+        serde_json::to_string_pretty(&sort_keys(report)).map_err(|e| format!("Failed to serialize metrics: {}", e))
+    }
+
+    /// One row per file, ordered by path:
+    /// `path,language,lines_of_code,blank_lines,comment_lines,is_test_file,duplicated_lines`.
+    fn format_metrics_csv(&self, metrics: &CodeMetrics) -> String {
+        let mut output = csv::row(&["path", "language", "lines_of_code", "blank_lines", "comment_lines", "is_test_file", "duplicated_lines"]);
+
+        let mut files: Vec<&FileMetrics> = metrics.files.iter().collect();
+        files.sort_by(|a, b| a.path.cmp(&b.path));
+
+        for file in files {
+            output.push_str(&csv::row(&[
+                file.path.clone(),
+                file.language.clone(),
+                file.lines_of_code.to_string(),
+                file.blank_lines.to_string(),
+                file.comment_lines.to_string(),
+                file.is_test_file.to_string(),
+                file.duplicated_lines.map_or(String::new(), |lines| lines.to_string()),
+            ]));
+        }
+
+        output
+    }
     
     fn format_metrics_markdown(&self, metrics: &CodeMetrics) -> String {
         let mut output = String::new();
@@ -66,10 +197,120 @@ impl MetricsReporter {
                     language, lang_metrics.files, lang_metrics.lines_of_code));
             }
         }
+
+        if !metrics.files.is_empty() {
+            output.push_str("\n## Breakdown by Directory\n\n");
+            output.push_str("Each directory counts every file below it. The comment ratio is comment lines per line of code or comment; the test ratio is test lines of code per production line of code.\n\n");
+            format_directory_markdown(&metrics.directory_tree(self.directory_depth), 0, &mut output);
+        }
+
+        if metrics.functions().next().is_some() {
+            output.push_str("\n## Function Complexity\n\n");
+            output.push_str("| Metric | Value |\n");
+            output.push_str("|--------|-------|\n");
+            for (label, value) in self.function_summary(metrics) {
+                output.push_str(&format!("| {} | {} |\n", label.trim_end_matches(':'), value));
+            }
+
+            let functions = metrics.most_complex_functions(self.top_functions);
+            output.push_str(&format!("\n### Top {} Most Complex Functions\n\n", functions.len()));
+            output.push_str("| Function | File | Line | Cyclomatic | Cognitive | Nesting | Parameters | Length |\n");
+            output.push_str("|----------|------|------|------------|-----------|---------|------------|--------|\n");
+            for (file, function) in functions {
+                output.push_str(&format!(
+                    "| `{}` | {} | {} | {} | {} | {} | {} | {} |\n",
+                    function.name,
+                    file.path,
+                    function.start_line,
+                    function.cyclomatic_complexity,
+                    function.cognitive_complexity,
+                    function.nesting_depth,
+                    function.parameters,
+                    function.length
+                ));
+            }
+        }
+
+        let halstead_by_language = metrics.halstead_by_language();
+        if !halstead_by_language.is_empty() {
+            output.push_str("\n## Halstead Metrics and Maintainability\n\n");
+            output.push_str("Volume and effort are totals, difficulty is the mean per file and the maintainability index (0-100, higher is better) is weighted by lines of code.\n\n");
+            output.push_str("| Language | Files | Volume | Difficulty | Effort | Maintainability Index |\n");
+            output.push_str("|----------|-------|--------|------------|--------|-----------------------|\n");
+            for (language, summary) in &halstead_by_language {
+                output.push_str(&format_halstead_row(language, summary));
+            }
+            output.push_str(&format_halstead_row("**Total**", &metrics.halstead_summary()));
+
+            let mut files: Vec<&FileMetrics> = metrics.files.iter().filter(|file| file.maintainability_index.is_some()).collect();
+            files.sort_by(|a, b| {
+                a.maintainability_index
+                    .partial_cmp(&b.maintainability_index)
+                    .unwrap_or(std::cmp::Ordering::Equal)
+                    .then_with(|| a.path.cmp(&b.path))
+            });
+            files.truncate(LEAST_MAINTAINABLE_FILES);
+
+            output.push_str(&format!("\n### {} Least Maintainable Files\n\n", files.len()));
+            output.push_str("| File | Language | Lines of Code | Volume | Difficulty | Effort | Maintainability Index |\n");
+            output.push_str("|------|----------|---------------|--------|------------|--------|-----------------------|\n");
+            for file in files {
+                if let Some(halstead) = &file.halstead {
+                    output.push_str(&format!(
+                        "| {} | {} | {} | {:.1} | {:.2} | {:.0} | {:.1} |\n",
+                        file.path,
+                        file.language,
+                        file.lines_of_code,
+                        halstead.volume,
+                        halstead.difficulty,
+                        halstead.effort,
+                        file.maintainability_index.unwrap_or_default()
+                    ));
+                }
+            }
+        }
+
+        let duplication_by_language = metrics.duplication_by_language();
+        if !duplication_by_language.is_empty() {
+            output.push_str(&format_duplication_markdown(metrics, &duplication_by_language));
+        }
+
+        if let Some(diff) = &self.baseline {
+            output.push_str(&format_baseline_markdown(diff));
+        }
         
         output
     }
 
+    fn function_summary(&self, metrics: &CodeMetrics) -> Vec<(&'static str, String)> {
+        let functions: Vec<&FunctionMetrics> = metrics.functions().map(|(_, function)| function).collect();
+        let average = |value: fn(&FunctionMetrics) -> usize| {
+            let total: usize = functions.iter().map(|function| value(function)).sum();
+            format!("{:.2}", total as f64 / functions.len().max(1) as f64)
+        };
+        let maximum = |value: fn(&FunctionMetrics) -> usize| {
+            functions.iter().map(|function| value(function)).max().unwrap_or(0).to_string()
+        };
+
+        vec![
+            ("Functions:", functions.len().to_string()),
+            ("Average Cyclomatic Complexity:", average(|function| function.cyclomatic_complexity)),
+            ("Average Cognitive Complexity:", average(|function| function.cognitive_complexity)),
+            ("Max Cyclomatic Complexity:", maximum(|function| function.cyclomatic_complexity)),
+            ("Max Cognitive Complexity:", maximum(|function| function.cognitive_complexity)),
+            ("Max Nesting Depth:", maximum(|function| function.nesting_depth)),
+            ("Average Length:", average(|function| function.length)),
+            (
+                "Complex Functions (CC > 10):",
+                functions
+                    .iter()
+                    .filter(|function| function.cyclomatic_complexity > COMPLEX_FUNCTION_THRESHOLD)
+                    .count()
+                    .to_string(),
+            ),
+        ]
+    }
+
     pub fn report(&self, metrics: &CodeMetrics) {
         println!();
         print_header("Code Metrics Summary:");
@@ -95,6 +336,284 @@ impl MetricsReporter {
             StyledText::new("=================").foreground(ThemeColors::SEPARATOR)
         );
         self.print_test_metrics(metrics);
+
+        if !metrics.files.is_empty() {
+            println!();
+            print_header("Breakdown by Directory:");
+            println!(
+                "{}",
+                StyledText::new("=======================").foreground(ThemeColors::SEPARATOR)
+            );
+            self.print_directory_tree(&metrics.directory_tree(self.directory_depth));
+        }
+
+        if metrics.functions().next().is_some() {
+            println!();
+            print_header("Function Complexity:");
+            println!(
+                "{}",
+                StyledText::new("====================").foreground(ThemeColors::SEPARATOR)
+            );
+            self.print_function_summary(metrics);
+
+            let functions = metrics.most_complex_functions(self.top_functions);
+            if !functions.is_empty() {
+                println!();
+                println!("{}", highlight(&format!("Top {} most complex functions:", functions.len())));
+                self.print_complex_functions(&functions);
+            }
+        }
+
+        let halstead_by_language = metrics.halstead_by_language();
+        if !halstead_by_language.is_empty() {
+            println!();
+            print_header("Halstead Metrics and Maintainability:");
+            println!(
+                "{}",
+                StyledText::new("=====================================").foreground(ThemeColors::SEPARATOR)
+            );
+            self.print_halstead_table(&halstead_by_language, &metrics.halstead_summary());
+        }
+
+        let duplication_by_language = metrics.duplication_by_language();
+        if !duplication_by_language.is_empty() {
+            println!();
+            print_header("Duplication:");
+            println!(
+                "{}",
+                StyledText::new("============").foreground(ThemeColors::SEPARATOR)
+            );
+            self.print_duplication_table(&duplication_by_language, &metrics.duplication_summary());
+            self.print_largest_clones(&metrics.clones);
+        }
+
+        if let Some(diff) = &self.baseline {
+            println!();
+            print_header("Changes Since Baseline:");
+            println!(
+                "{}",
+                StyledText::new("======================").foreground(ThemeColors::SEPARATOR)
+            );
+            self.print_baseline_diff(diff);
+        }
+    }
+
+    fn print_baseline_diff(&self, diff: &MetricsDiff) {
+        let languages: Vec<&GroupDelta> = diff.languages.iter().chain(std::iter::once(&diff.total)).collect();
+        self.print_baseline_table("Language", &languages);
+
+        if !diff.directories.is_empty() {
+            println!();
+            let directories: Vec<&GroupDelta> = diff.directories.iter().collect();
+            self.print_baseline_table("Directory", &directories);
+        }
+
+        if !diff.grown_files.is_empty() {
+            println!();
+            println!("{}", highlight("Files that grew the most:"));
+            let growth_width = diff.grown_files.iter().map(|file| file.growth().to_string().len() + 1).max().unwrap_or(0);
+            for file in &diff.grown_files {
+                let growth = format!("{:>growth_width$}", format!("+{}", file.growth()));
+                println!(
+                    "  {}  {} ({} → {} lines)",
+                    StyledText::new(&growth).foreground(Color::Yellow),
+                    file.path,
+                    file.baseline_lines,
+                    file.current_lines
+                );
+            }
+        }
+    }
+
+    fn print_baseline_table(&self, name_header: &str, groups: &[&GroupDelta]) {
+        let headers = [name_header, "Files", "Lines of Code", "Comment Ratio", "Test Ratio"];
+        let rows: Vec<[String; 5]> = groups.iter().map(|group| baseline_row(group)).collect();
+
+        let widths: Vec<usize> = (0..headers.len())
+            .map(|column| {
+                rows.iter()
+                    .map(|row| row[column].chars().count())
+                    .chain(std::iter::once(headers[column].len()))
+                    .max()
+                    .unwrap_or(0)
+            })
+            .collect();
+        let pad = |column: usize, text: &str| {
+            let padding = " ".repeat(widths[column] - text.chars().count());
+            if column == 0 { format!("{}{}", text, padding) } else { format!("  {}{}", padding, text) }
+        };
+
+        let header: String = headers.iter().enumerate().map(|(column, text)| pad(column, text)).collect();
+        println!("{}", StyledText::new(&header).foreground(ThemeColors::TABLE_HEADER));
+
+        for row in rows {
+            print!("{}", StyledText::new(&pad(0, &row[0])).foreground(ThemeColors::LANGUAGE).style(Style::Bold));
+            for (column, cell) in row.iter().enumerate().skip(1) {
+                print!("{}", StyledText::new(&pad(column, cell)).foreground(ThemeColors::NUMBER));
+            }
+            println!();
+        }
+    }
+
+    fn print_directory_tree(&self, tree: &DirectoryMetrics) {
+        let mut rows = Vec::new();
+        directory_rows(tree, String::new(), String::new(), &mut rows);
+
+        let name_width = rows.iter().map(|(name, _)| name.chars().count()).max().unwrap_or(0).max("Directory".len()) + 2;
+        let header = format!("{:<name_width$}{:>8}{:>16}{:>16}{:>13}", "Directory", "Files", "Lines of Code", "Comment Ratio", "Test Ratio");
+        println!("{}", StyledText::new(&header).foreground(ThemeColors::TABLE_HEADER));
+
+        for (name, directory) in rows {
+            let padding = " ".repeat(name_width - name.chars().count());
+            let values = format!(
+                "{:>8}{:>16}{:>16}{:>13}",
+                directory.stats.files,
+                directory.stats.lines_of_code,
+                format_ratio(directory.stats.comment_ratio()),
+                format_ratio(directory.stats.test_ratio())
+            );
+            println!(
+                "{}{}{}",
+                StyledText::new(&name).foreground(ThemeColors::LANGUAGE).style(Style::Bold),
+                padding,
+                StyledText::new(&values).foreground(ThemeColors::NUMBER)
+            );
+        }
+    }
+
+    fn print_halstead_table(&self, by_language: &std::collections::BTreeMap<String, HalsteadSummary>, total: &HalsteadSummary) {
+        let language_width = by_language.keys().map(String::len).max().unwrap_or(0).max("Language".len()) + 2;
+        let header = format!(
+            "{:<language_width$}{:>8}{:>14}{:>12}{:>16}{:>8}",
+            "Language", "Files", "Volume", "Difficulty", "Effort", "MI",
+        );
+        println!("{}", StyledText::new(&header).foreground(ThemeColors::TABLE_HEADER));
+
+        let rows = by_language.iter().map(|(language, summary)| (language.as_str(), summary));
+        for (language, summary) in rows.chain(std::iter::once(("Total", total))) {
+            let values = format!(
+                "{:>8}{:>14.1}{:>12.2}{:>16.0}{:>8}",
+                summary.files,
+                summary.volume,
+                summary.difficulty,
+                summary.effort,
+                summary.maintainability_index.map_or("-".to_string(), |index| format!("{:.1}", index))
+            );
+            println!(
+                "{}{}",
+                StyledText::new(&format!("{:<language_width$}", language))
+                    .foreground(ThemeColors::LANGUAGE)
+                    .style(Style::Bold),
+                StyledText::new(&values).foreground(ThemeColors::NUMBER)
+            );
+        }
+    }
+
+    fn print_duplication_table(&self, by_language: &std::collections::BTreeMap<String, DuplicationSummary>, total: &DuplicationSummary) {
+        let language_width = by_language.keys().map(String::len).max().unwrap_or(0).max("Language".len()) + 2;
+        let header = format!(
+            "{:<language_width$}{:>8}{:>16}{:>13}{:>8}",
+            "Language", "Files", "Lines of Code", "Duplicated", "%",
+        );
+        println!("{}", StyledText::new(&header).foreground(ThemeColors::TABLE_HEADER));
+
+        let rows = by_language.iter().map(|(language, summary)| (language.as_str(), summary));
+        for (language, summary) in rows.chain(std::iter::once(("Total", total))) {
+            let values = format!(
+                "{:>8}{:>16}{:>13}{:>7.1}%",
+                summary.files,
+                summary.lines_of_code,
+                summary.duplicated_lines,
+                summary.percentage()
+            );
+            println!(
+                "{}{}",
+                StyledText::new(&format!("{:<language_width$}", language))
+                    .foreground(ThemeColors::LANGUAGE)
+                    .style(Style::Bold),
+                StyledText::new(&values).foreground(ThemeColors::NUMBER)
+            );
+        }
+    }
+
+    fn print_largest_clones(&self, clones: &[CloneClass]) {
+        if clones.is_empty() {
+            return;
+        }
+
+        let clones = &clones[..clones.len().min(LARGEST_CLONE_CLASSES)];
+        println!();
+        println!("{}", highlight(&format!("{} largest clone classes:", clones.len())));
+        for clone in clones {
+            println!(
+                "{}",
+                StyledText::new(&format!(
+                    "{} copies of {} lines ({} tokens, {})",
+                    clone.instances.len(),
+                    clone.lines,
+                    clone.tokens,
+                    clone.language
+                ))
+                .foreground(Color::Yellow)
+            );
+            for instance in &clone.instances {
+                println!("  {}:{}-{}", instance.path, instance.start_line, instance.end_line);
+            }
+        }
+    }
+
+    fn print_function_summary(&self, metrics: &CodeMetrics) {
+        let rows = self.function_summary(metrics);
+        let max_label_len = rows.iter().map(|(label, _)| label.len()).max().unwrap_or(0);
+        let max_value_len = rows.iter().map(|(_, value)| value.len()).max().unwrap_or(0);
+
+        for (label, value) in rows {
+            println!(
+                "{}{}    {}",
+                highlight(label),
+                " ".repeat(max_label_len - label.len()),
+                StyledText::new(&format!("{:>width$}", value, width = max_value_len))
+                    .foreground(ThemeColors::NUMBER)
+                    .style(Style::Bold)
+            );
+        }
+    }
+
+    fn print_complex_functions(&self, functions: &[(&FileMetrics, &FunctionMetrics)]) {
+        let locations: Vec<String> = functions
+            .iter()
+            .map(|(file, function)| format!("{}:{}", file.path, function.start_line))
+            .collect();
+        let name_width = functions.iter().map(|(_, function)| function.name.len()).max().unwrap_or(0).max("Function".len()) + 2;
+        let location_width = locations.iter().map(String::len).max().unwrap_or(0).max("Location".len()) + 2;
+
+        let header = format!(
+            "{:<name_width$}{:<location_width$}{:>6}{:>6}{:>6}{:>8}{:>8}",
+            "Function", "Location", "CC", "COG", "NEST", "PARAMS", "LINES",
+        );
+        println!("{}", StyledText::new(&header).foreground(ThemeColors::TABLE_HEADER));
+
+        for ((_, function), location) in functions.iter().zip(&locations) {
+            let values = format!(
+                "{:>6}{:>6}{:>6}{:>8}{:>8}",
+                function.cyclomatic_complexity,
+                function.cognitive_complexity,
+                function.nesting_depth,
+                function.parameters,
+                function.length
+            );
+            let color = if function.cyclomatic_complexity > COMPLEX_FUNCTION_THRESHOLD {
+                Color::Yellow
+            } else {
+                ThemeColors::NUMBER
+            };
+            println!(
+                "{}{}{}",
+                StyledText::new(&format!("{:<name_width$}", function.name)).foreground(ThemeColors::LANGUAGE),
+                StyledText::new(&format!("{:<location_width$}", location)).foreground(ThemeColors::LABEL),
+                StyledText::new(&values).foreground(color)
+            );
+        }
     }
     
     fn print_overall_metrics(&self, metrics: &CodeMetrics) {
@@ -307,3 +826,226 @@ impl MetricsReporter {
         }
     }
 }
+
+fn format_halstead_row(language: &str, summary: &HalsteadSummary) -> String {
+    format!(
+        "| {} | {} | {:.1} | {:.2} | {:.0} | {} |\n",
+        language,
+        summary.files,
+        summary.volume,
+        summary.difficulty,
+        summary.effort,
+        summary.maintainability_index.map_or("-".to_string(), |index| format!("{:.1}", index))
+    )
+}
+
+/// A directory and its subdirectories as rows of an indented tree, each row's name prefixed
+/// with the tree lines leading to it.
+fn directory_rows<'a>(directory: &'a DirectoryMetrics, prefix: String, child_prefix: String, rows: &mut Vec<(String, &'a DirectoryMetrics)>) {
+    rows.push((format!("{}{}", prefix, directory.name()), directory));
+    for (index, child) in directory.children.iter().enumerate() {
+        let last = index + 1 == directory.children.len();
+        let (branch, continuation) = if last { ("└── ", "    ") } else { ("├── ", "│   ") };
+        directory_rows(child, format!("{}{}", child_prefix, branch), format!("{}{}", child_prefix, continuation), rows);
+    }
+}
+
+/// A directory and its subdirectories as a nested markdown list.
+fn format_directory_markdown(directory: &DirectoryMetrics, level: usize, output: &mut String) {
+    output.push_str(&format!(
+        "{}- `{}`: {} files, {} lines of code, comment ratio {}, test ratio {}\n",
+        "  ".repeat(level),
+        directory.name(),
+        directory.stats.files,
+        directory.stats.lines_of_code,
+        format_ratio(directory.stats.comment_ratio()),
+        format_ratio(directory.stats.test_ratio())
+    ));
+    for child in &directory.children {
+        format_directory_markdown(child, level + 1, output);
+    }
+}
+
+/// A directory and its subdirectories as nested JSON objects.
+fn directory_json(directory: &DirectoryMetrics) -> Value {
+    json!({
+        "path": directory.path,
+        "files": directory.stats.files,
+        "lines_of_code": directory.stats.lines_of_code,
+        "comment_lines": directory.stats.comment_lines,
+        "test_lines_of_code": directory.stats.test_lines_of_code,
+        "comment_ratio": directory.stats.comment_ratio(),
+        "test_ratio": directory.stats.test_ratio(),
+        "children": directory.children.iter().map(directory_json).collect::<Vec<_>>(),
+    })
+}
+
+fn format_ratio(ratio: Option<f64>) -> String {
+    ratio.map_or("-".to_string(), |ratio| format!("{:.1}%", ratio * 100.0))
+}
+
+/// Duplication per language, the most duplicated files and the largest clone classes as
+/// markdown tables.
+fn format_duplication_markdown(metrics: &CodeMetrics, by_language: &std::collections::BTreeMap<String, DuplicationSummary>) -> String {
+    let mut output = String::from("\n## Duplication\n\n");
+    output.push_str("Lines of code that are copied elsewhere, comparing tokens with identifiers and literals abstracted.\n\n");
+    output.push_str("| Language | Files | Lines of Code | Duplicated Lines | Duplication |\n");
+    output.push_str("|----------|-------|---------------|------------------|-------------|\n");
+    let total = metrics.duplication_summary();
+    let rows = by_language.iter().map(|(language, summary)| (language.as_str(), summary));
+    for (language, summary) in rows.chain(std::iter::once(("**Total**", &total))) {
+        output.push_str(&format!(
+            "| {} | {} | {} | {} | {:.1}% |\n",
+            language, summary.files, summary.lines_of_code, summary.duplicated_lines, summary.percentage()
+        ));
+    }
+
+    let mut files: Vec<(&FileMetrics, usize)> = metrics
+        .files
+        .iter()
+        .filter_map(|file| file.duplicated_lines.filter(|lines| *lines > 0).map(|lines| (file, lines)))
+        .collect();
+    let percentage = |(file, lines): &(&FileMetrics, usize)| *lines as f64 * 100.0 / file.lines_of_code.max(1) as f64;
+    files.sort_by(|a, b| percentage(b).total_cmp(&percentage(a)).then_with(|| a.0.path.cmp(&b.0.path)));
+    files.truncate(MOST_DUPLICATED_FILES);
+
+    if !files.is_empty() {
+        output.push_str(&format!("\n### {} Most Duplicated Files\n\n", files.len()));
+        output.push_str("| File | Language | Lines of Code | Duplicated Lines | Duplication |\n");
+        output.push_str("|------|----------|---------------|------------------|-------------|\n");
+        for entry in &files {
+            output.push_str(&format!(
+                "| {} | {} | {} | {} | {:.1}% |\n",
+                entry.0.path,
+                entry.0.language,
+                entry.0.lines_of_code,
+                entry.1,
+                percentage(entry)
+            ));
+        }
+    }
+
+    let clones = &metrics.clones[..metrics.clones.len().min(LARGEST_CLONE_CLASSES)];
+    if !clones.is_empty() {
+        output.push_str(&format!("\n### {} Largest Clone Classes\n\n", clones.len()));
+        output.push_str("| Language | Copies | Lines | Tokens | Locations |\n");
+        output.push_str("|----------|--------|-------|--------|-----------|\n");
+        for clone in clones {
+            let locations: Vec<String> = clone
+                .instances
+                .iter()
+                .map(|instance| format!("{}:{}-{}", instance.path, instance.start_line, instance.end_line))
+                .collect();
+            output.push_str(&format!(
+                "| {} | {} | {} | {} | {} |\n",
+                clone.language,
+                clone.instances.len(),
+                clone.lines,
+                clone.tokens,
+                locations.join("<br>")
+            ));
+        }
+    }
+
+    output
+}
+
+/// Changes since a baseline as markdown tables.
+fn format_baseline_markdown(diff: &MetricsDiff) -> String {
+    let mut output = String::from("\n## Changes Since Baseline\n\n");
+    output.push_str("Cells show the baseline value, the current value and the change. The comment ratio is comment lines per line of code or comment; the test ratio is test lines of code per production line of code.\n");
+
+    let sections = [("Language", &diff.languages, true), ("Directory", &diff.directories, false)];
+    for (name_header, groups, with_total) in sections {
+        if groups.is_empty() && !with_total {
+            continue;
+        }
+        output.push_str(&format!("\n### By {}\n\n", name_header));
+        output.push_str(&format!("| {} | Files | Lines of Code | Comment Ratio | Test Ratio |\n", name_header));
+        output.push_str("|------|-------|---------------|---------------|------------|\n");
+        let total = with_total.then_some(&diff.total);
+        for group in groups.iter().chain(total) {
+            let mut row = baseline_row(group);
+            if with_total && std::ptr::eq(group, &diff.total) {
+                row[0] = format!("**{}**", row[0]);
+            }
+            output.push_str(&format!("| {} |\n", row.join(" | ")));
+        }
+    }
+
+    if !diff.grown_files.is_empty() {
+        output.push_str("\n### Files That Grew the Most\n\n");
+        output.push_str("| File | Baseline | Current | Growth |\n");
+        output.push_str("|------|----------|---------|--------|\n");
+        for file in &diff.grown_files {
+            output.push_str(&format!(
+                "| {} | {} | {} | +{} |\n",
+                file.path, file.baseline_lines, file.current_lines, file.growth()
+            ));
+        }
+    }
+
+    output
+}
+
+/// Name, files, lines of code, comment ratio and test ratio of a group, each as a change.
+fn baseline_row(group: &GroupDelta) -> [String; 5] {
+    let (baseline, current) = (&group.baseline, &group.current);
+    [
+        group.name.clone(),
+        format_count_change(baseline.files, current.files),
+        format_count_change(baseline.lines_of_code, current.lines_of_code),
+        format_ratio_change(baseline.comment_ratio(), current.comment_ratio()),
+        format_ratio_change(baseline.test_ratio(), current.test_ratio()),
+    ]
+}
+
+fn format_count_change(baseline: usize, current: usize) -> String {
+    if baseline == current {
+        current.to_string()
+    } else {
+        format!("{} → {} ({:+})", baseline, current, current as i64 - baseline as i64)
+    }
+}
+
+fn format_ratio_change(baseline: Option<f64>, current: Option<f64>) -> String {
+    let (before, after) = (format_ratio(baseline), format_ratio(current));
+
+    match (baseline, current) {
+        _ if before == after => after,
+        (Some(baseline), Some(current)) => format!("{} → {} ({:+.1} pp)", before, after, (current - baseline) * 100.0),
+        _ => format!("{} → {}", before, after),
+    }
+}
+
+/// Per-language line counts, ordered by language.
+fn language_rows(by_language: &HashMap<String, LanguageMetrics>) -> Vec<Value> {
+    let mut languages: Vec<&LanguageMetrics> = by_language.values().collect();
+    languages.sort_by(|a, b| a.language.cmp(&b.language));
+
+    languages
+        .into_iter()
+        .map(|metrics| {
+            json!({
+                "language": metrics.language,
+                "files": metrics.files,
+                "lines_of_code": metrics.lines_of_code,
+                "blank_lines": metrics.blank_lines,
+                "comment_lines": metrics.comment_lines,
+            })
+        })
+        .collect()
+}
+
+/// Rebuilds every object with its keys in sorted order.
+fn sort_keys(value: Value) -> Value {
+    match value {
+        Value::Object(map) => {
+            let mut entries: Vec<(String, Value)> = map.into_iter().collect();
+            entries.sort_by(|a, b| a.0.cmp(&b.0));
+            Value::Object(entries.into_iter().map(|(key, value)| (key, sort_keys(value))).collect())
+        }
+        Value::Array(items) => Value::Array(items.into_iter().map(sort_keys).collect()),
+        other => other,
+    }
+}
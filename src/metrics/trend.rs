@@ -0,0 +1,254 @@
+use crate::metrics::models::{CodeMetrics, GroupStats};
+use crate::output::csv;
+use crate::output::svg::{self, LineChart, Series};
+use crate::util::git::Commit;
+use serde_json::json;
+use std::collections::BTreeMap;
+
+const SECONDS_PER_WEEK: i64 = 7 * 24 * 60 * 60;
+
+/// Languages drawn as their own line in the chart; the rest are left to the total.
+const CHARTED_LANGUAGES: usize = 6;
+
+/// `count` commits spread evenly over `history`, always including its first and last commit.
+pub fn sample_evenly(history: &[Commit], count: usize) -> Vec<Commit> {
+    if history.len() <= count {
+        return history.to_vec();
+    }
+    match count {
+        0 => Vec::new(),
+        1 => history[history.len() - 1..].to_vec(),
+        _ => {
+            let last = history.len() - 1;
+            (0..count)
+                .map(|index| history[(index * last + (count - 1) / 2) / (count - 1)].clone())
+                .collect()
+        }
+    }
+}
+
+/// The commit current at `since` and at every week after it up to `until` (timestamps in
+/// seconds), skipping weeks without a new commit. `history` is ordered oldest first.
+pub fn sample_weekly(history: &[Commit], since: i64, until: i64) -> Vec<Commit> {
+    let mut boundaries: Vec<i64> = (0..)
+        .map(|week| since + week * SECONDS_PER_WEEK)
+        .take_while(|boundary| *boundary <= until)
+        .collect();
+    if boundaries.last().is_some_and(|last| *last < until) {
+        boundaries.push(until);
+    }
+
+    let mut samples: Vec<Commit> = Vec::new();
+    for boundary in boundaries {
+        let Some(commit) = history.iter().rev().find(|commit| commit.timestamp <= boundary) else {
+            continue;
+        };
+        if samples.last() != Some(commit) {
+            samples.push(commit.clone());
+        }
+    }
+    samples
+}
+
+/// The metrics of the tree at one commit.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TrendPoint {
+    pub commit: String,
+    pub date: String,
+    pub stats: GroupStats,
+    /// Lines of code per language
+    pub languages: BTreeMap<String, usize>,
+}
+
+impl TrendPoint {
+    pub fn from_metrics(commit: &Commit, metrics: &CodeMetrics) -> Self {
+        TrendPoint {
+            commit: commit.short_hash().to_string(),
+            date: commit.date.clone(),
+            stats: GroupStats::from_metrics(metrics),
+            languages: metrics
+                .by_language
+                .iter()
+                .map(|(language, metrics)| (language.clone(), metrics.lines_of_code))
+                .collect(),
+        }
+    }
+}
+
+/// Metrics of a series of commits, oldest first.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct MetricsTrend {
+    pub points: Vec<TrendPoint>,
+}
+
+impl MetricsTrend {
+    pub fn new(points: Vec<TrendPoint>) -> Self {
+        MetricsTrend { points }
+    }
+
+    /// Every language seen in any sample, ordered by name.
+    pub fn languages(&self) -> Vec<String> {
+        let mut languages: Vec<String> = self.points.iter().flat_map(|point| point.languages.keys().cloned()).collect();
+        languages.sort();
+        languages.dedup();
+        languages
+    }
+
+    /// One row per sample, with a lines of code column per language after the totals.
+    pub fn to_csv(&self) -> String {
+        let languages = self.languages();
+        let mut header: Vec<String> = [
+            "commit", "date", "files", "lines_of_code", "comment_lines", "test_lines_of_code", "test_ratio", "comment_density",
+        ]
+        .iter()
+        .map(|column| column.to_string())
+        .collect();
+        header.extend(languages.iter().cloned());
+        let mut output = csv::row(&header);
+
+        for point in &self.points {
+            let mut fields = vec![
+                point.commit.clone(),
+                point.date.clone(),
+                point.stats.files.to_string(),
+                point.stats.lines_of_code.to_string(),
+                point.stats.comment_lines.to_string(),
+                point.stats.test_lines_of_code.to_string(),
+                format_ratio(point.stats.test_ratio()),
+                format_ratio(point.stats.comment_ratio()),
+            ];
+            fields.extend(languages.iter().map(|language| point.languages.get(language).copied().unwrap_or(0).to_string()));
+            output.push_str(&csv::row(&fields));
+        }
+        output
+    }
+
+    pub fn to_json(&self) -> Result<String, String> {
+        let samples: Vec<_> = self
+            .points
+            .iter()
+            .map(|point| {
+                json!({
+                    "commit": point.commit,
+                    "date": point.date,
+                    "files": point.stats.files,
+                    "lines_of_code": point.stats.lines_of_code,
+                    "comment_lines": point.stats.comment_lines,
+                    "test_lines_of_code": point.stats.test_lines_of_code,
+                    "test_ratio": point.stats.test_ratio(),
+                    "comment_density": point.stats.comment_ratio(),
+                    "languages": point.languages,
+                })
+            })
+            .collect();
+
+        serde_json::to_string_pretty(&json!({ "samples": samples }))
+            .map_err(|e| format!("Failed to serialize metrics trend: {}", e))
+    }
+
+    /// A summary table and a language mix table, with the chart embedded when `chart` names
+    /// the SVG file saved next to the report.
+    pub fn to_markdown(&self, chart: Option<&str>) -> String {
+        let mut output = String::from("# Metrics Trend\n\n");
+
+        if let (Some(first), Some(last)) = (self.points.first(), self.points.last()) {
+            output.push_str(&format!(
+                "{} samples from {} to {}. Lines of code went from {} to {}.\n\n",
+                self.points.len(),
+                first.date,
+                last.date,
+                first.stats.lines_of_code,
+                last.stats.lines_of_code
+            ));
+        } else {
+            output.push_str("No commits to sample.\n");
+            return output;
+        }
+
+        if let Some(chart) = chart {
+            output.push_str(&format!("![Metrics trend]({})\n\n", chart));
+        }
+
+        output.push_str("| Date | Commit | Files | Lines of Code | Comment Density | Test Ratio |\n");
+        output.push_str("|------|--------|------:|--------------:|----------------:|-----------:|\n");
+        for point in &self.points {
+            output.push_str(&format!(
+                "| {} | `{}` | {} | {} | {} | {} |\n",
+                point.date,
+                point.commit,
+                point.stats.files,
+                point.stats.lines_of_code,
+                format_percent(point.stats.comment_ratio()),
+                format_percent(point.stats.test_ratio())
+            ));
+        }
+
+        let languages = self.languages();
+        output.push_str("\n## Language Mix\n\nShare of lines of code per language.\n\n");
+        output.push_str(&format!("| Date | {} |\n", languages.join(" | ")));
+        output.push_str(&format!("|------|{}\n", "------:|".repeat(languages.len())));
+        for point in &self.points {
+            let shares: Vec<String> = languages
+                .iter()
+                .map(|language| {
+                    let lines = point.languages.get(language).copied().unwrap_or(0);
+                    let share = (point.stats.lines_of_code > 0).then(|| lines as f64 / point.stats.lines_of_code as f64);
+                    format_percent(share)
+                })
+                .collect();
+            output.push_str(&format!("| {} | {} |\n", point.date, shares.join(" | ")));
+        }
+
+        output
+    }
+
+    /// Lines of code in total and for the largest languages, above the comment density and
+    /// test ratio.
+    pub fn to_svg(&self) -> String {
+        let labels: Vec<String> = self.points.iter().map(|point| point.date.clone()).collect();
+
+        let mut languages = self.languages();
+        let latest = self.points.last().map(|point| &point.languages);
+        let lines_in_latest = |language: &String| latest.and_then(|languages| languages.get(language)).copied().unwrap_or(0);
+        languages.sort_by(|a, b| lines_in_latest(b).cmp(&lines_in_latest(a)).then_with(|| a.cmp(b)));
+        languages.truncate(CHARTED_LANGUAGES);
+
+        let mut lines = vec![Series {
+            name: "Total".to_string(),
+            values: self.points.iter().map(|point| point.stats.lines_of_code as f64).collect(),
+        }];
+        lines.extend(languages.into_iter().map(|language| Series {
+            values: self
+                .points
+                .iter()
+                .map(|point| point.languages.get(&language).copied().unwrap_or(0) as f64)
+                .collect(),
+            name: language,
+        }));
+
+        let percent = |ratio: Option<f64>| (ratio.unwrap_or(0.0) * 1000.0).round() / 10.0;
+        let ratios = vec![
+            Series {
+                name: "Comment density".to_string(),
+                values: self.points.iter().map(|point| percent(point.stats.comment_ratio())).collect(),
+            },
+            Series {
+                name: "Test ratio".to_string(),
+                values: self.points.iter().map(|point| percent(point.stats.test_ratio())).collect(),
+            },
+        ];
+
+        svg::line_charts(&[
+            LineChart { title: "Lines of Code".to_string(), labels: labels.clone(), series: lines, unit: String::new() },
+            LineChart { title: "Comment Density and Test Ratio".to_string(), labels, series: ratios, unit: "%".to_string() },
+        ])
+    }
+}
+
+fn format_ratio(ratio: Option<f64>) -> String {
+    ratio.map_or(String::new(), |ratio| format!("{:.4}", ratio))
+}
+
+pub fn format_percent(ratio: Option<f64>) -> String {
+    ratio.map_or("-".to_string(), |ratio| format!("{:.1}%", ratio * 100.0))
+}
@@ -0,0 +1,14 @@
+/// Quotes a field when it holds a separator, a quote or a line break.
+pub fn escape_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+/// Joins fields into one line of CSV, line break included.
+pub fn row<S: AsRef<str>>(fields: &[S]) -> String {
+    let fields: Vec<String> = fields.iter().map(|field| escape_field(field.as_ref())).collect();
+    format!("{}\n", fields.join(","))
+}
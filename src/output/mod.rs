@@ -1,3 +1,6 @@
+pub mod csv;
 pub mod style;
 pub mod markdown;
 pub mod path;
+pub mod report;
+pub mod svg;
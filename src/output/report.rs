@@ -0,0 +1,46 @@
+use crate::output::path::resolve_output_path;
+use crate::output::style::print_success;
+use crate::util::error::{AppError, AppResult};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Rejects a `--format` value that is not in `valid`.
+pub fn validate_format(format: &str, valid: &[&str]) -> AppResult<()> {
+    if valid.contains(&format) {
+        return Ok(());
+    }
+    Err(AppError::Metrics(format!(
+        "Invalid output format: {}. Valid formats are: {}",
+        format,
+        valid.join(", ")
+    )))
+}
+
+/// Where the report of `command` in `format` is saved; markdown reports get the `md` extension
+/// and every other format is its own extension.
+pub fn report_path(command: &str, output_path: &str, format: &str) -> AppResult<PathBuf> {
+    let extension = if format == "markdown" { "md" } else { format };
+    resolve_output_path(command, output_path, extension)
+}
+
+/// Saves the SVG chart of a markdown report next to it and returns the chart's file name, for
+/// the report to link to.
+pub fn save_chart(report_path: &Path, svg: &str) -> AppResult<Option<String>> {
+    let chart_path = report_path.with_extension("svg");
+    write_report(&chart_path, svg)?;
+    Ok(chart_path.file_name().map(|name| name.to_string_lossy().to_string()))
+}
+
+/// Writes a report and tells the user where `title` was exported to.
+pub fn save_report(path: &Path, content: &str, title: &str) -> AppResult<()> {
+    write_report(path, content)?;
+    print_success(&format!("{} exported to {}", title, path.display()));
+    Ok(())
+}
+
+fn write_report(path: &Path, content: &str) -> AppResult<()> {
+    fs::write(path, content).map_err(|error| AppError::FileSystem {
+        path: path.to_path_buf(),
+        message: format!("Error exporting report: {}", error),
+    })
+}
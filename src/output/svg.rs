@@ -0,0 +1,338 @@
+use std::fmt::Write;
+
+const CHART_WIDTH: f64 = 860.0;
+const CHART_HEIGHT: f64 = 320.0;
+const MARGIN_LEFT: f64 = 70.0;
+const MARGIN_RIGHT: f64 = 170.0;
+const MARGIN_TOP: f64 = 40.0;
+const MARGIN_BOTTOM: f64 = 50.0;
+const GRID_LINES: usize = 5;
+/// Most x axis labels drawn; the rest are skipped so the labels do not overlap
+const MAX_X_LABELS: usize = 10;
+
+const TREEMAP_WIDTH: f64 = 960.0;
+const TREEMAP_HEIGHT: f64 = 600.0;
+const TREEMAP_TOP: f64 = 56.0;
+/// Approximate width of a label character at the default font size
+const CHARACTER_WIDTH: f64 = 7.0;
+
+/// Line colors, reused in order when a chart has more series.
+pub const PALETTE: [&str; 8] = ["#1f77b4", "#ff7f0e", "#2ca02c", "#d62728", "#9467bd", "#8c564b", "#e377c2", "#7f7f7f"];
+
+/// Escapes text for use in SVG elements and attribute values.
+pub fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&apos;")
+}
+
+/// One line of a chart, with a value per x axis label.
+#[derive(Debug, Clone)]
+pub struct Series {
+    pub name: String,
+    pub values: Vec<f64>,
+}
+
+/// A line chart over evenly spaced points, such as samples in time.
+#[derive(Debug, Clone)]
+pub struct LineChart {
+    pub title: String,
+    pub labels: Vec<String>,
+    pub series: Vec<Series>,
+    /// Appended to the y axis values, e.g. `%`
+    pub unit: String,
+}
+
+/// Draws the charts one below the other in a self-contained SVG document, without scripts,
+/// stylesheets or external fonts.
+pub fn line_charts(charts: &[LineChart]) -> String {
+    let height = CHART_HEIGHT * charts.len().max(1) as f64;
+    let mut svg = format!(
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{w}\" height=\"{h}\" viewBox=\"0 0 {w} {h}\" font-family=\"sans-serif\" font-size=\"12\">\n",
+        w = CHART_WIDTH,
+        h = height
+    );
+    svg.push_str(&format!("<rect width=\"{}\" height=\"{}\" fill=\"#ffffff\"/>\n", CHART_WIDTH, height));
+
+    for (index, chart) in charts.iter().enumerate() {
+        let _ = writeln!(svg, "<g transform=\"translate(0,{})\">", CHART_HEIGHT * index as f64);
+        draw_chart(&mut svg, chart);
+        svg.push_str("</g>\n");
+    }
+
+    svg.push_str("</svg>\n");
+    svg
+}
+
+fn draw_chart(svg: &mut String, chart: &LineChart) {
+    let plot_width = CHART_WIDTH - MARGIN_LEFT - MARGIN_RIGHT;
+    let plot_height = CHART_HEIGHT - MARGIN_TOP - MARGIN_BOTTOM;
+    let bottom = MARGIN_TOP + plot_height;
+
+    let maximum = chart
+        .series
+        .iter()
+        .flat_map(|series| series.values.iter().copied())
+        .fold(0.0, f64::max);
+    let ceiling = nice_ceiling(maximum);
+
+    let x = |index: usize| {
+        if chart.labels.len() <= 1 {
+            MARGIN_LEFT + plot_width / 2.0
+        } else {
+            MARGIN_LEFT + plot_width * index as f64 / (chart.labels.len() - 1) as f64
+        }
+    };
+    let y = |value: f64| bottom - plot_height * value / ceiling;
+
+    let _ = writeln!(
+        svg,
+        "<text x=\"{}\" y=\"{}\" font-size=\"15\" font-weight=\"bold\">{}</text>",
+        MARGIN_LEFT,
+        MARGIN_TOP - 16.0,
+        escape(&chart.title)
+    );
+
+    for step in 0..=GRID_LINES {
+        let value = ceiling * step as f64 / GRID_LINES as f64;
+        let line_y = y(value);
+        let _ = writeln!(
+            svg,
+            "<line x1=\"{:.1}\" y1=\"{:.1}\" x2=\"{:.1}\" y2=\"{:.1}\" stroke=\"#dddddd\"/>",
+            MARGIN_LEFT,
+            line_y,
+            MARGIN_LEFT + plot_width,
+            line_y
+        );
+        let _ = writeln!(
+            svg,
+            "<text x=\"{:.1}\" y=\"{:.1}\" text-anchor=\"end\" fill=\"#555555\">{}{}</text>",
+            MARGIN_LEFT - 8.0,
+            line_y + 4.0,
+            format_axis_value(value),
+            escape(&chart.unit)
+        );
+    }
+
+    let label_step = chart.labels.len().div_ceil(MAX_X_LABELS).max(1);
+    for (index, label) in chart.labels.iter().enumerate() {
+        if index % label_step != 0 && index + 1 != chart.labels.len() {
+            continue;
+        }
+        let _ = writeln!(
+            svg,
+            "<text x=\"{:.1}\" y=\"{:.1}\" text-anchor=\"middle\" fill=\"#555555\">{}</text>",
+            x(index),
+            bottom + 20.0,
+            escape(label)
+        );
+    }
+
+    for (number, series) in chart.series.iter().enumerate() {
+        let color = PALETTE[number % PALETTE.len()];
+        let points: Vec<String> = series
+            .values
+            .iter()
+            .enumerate()
+            .map(|(index, value)| format!("{:.1},{:.1}", x(index), y(*value)))
+            .collect();
+        let _ = writeln!(
+            svg,
+            "<polyline points=\"{}\" fill=\"none\" stroke=\"{}\" stroke-width=\"2\"/>",
+            points.join(" "),
+            color
+        );
+        for (index, value) in series.values.iter().enumerate() {
+            let label = chart.labels.get(index).map(String::as_str).unwrap_or_default();
+            let _ = writeln!(
+                svg,
+                "<circle cx=\"{:.1}\" cy=\"{:.1}\" r=\"3\" fill=\"{}\"><title>{}: {}{} ({})</title></circle>",
+                x(index),
+                y(*value),
+                color,
+                escape(&series.name),
+                format_axis_value(*value),
+                escape(&chart.unit),
+                escape(label)
+            );
+        }
+
+        let legend_y = MARGIN_TOP + 18.0 * number as f64;
+        let legend_x = MARGIN_LEFT + plot_width + 16.0;
+        let _ = writeln!(
+            svg,
+            "<rect x=\"{:.1}\" y=\"{:.1}\" width=\"12\" height=\"12\" fill=\"{}\"/>",
+            legend_x,
+            legend_y,
+            color
+        );
+        let _ = writeln!(
+            svg,
+            "<text x=\"{:.1}\" y=\"{:.1}\">{}</text>",
+            legend_x + 18.0,
+            legend_y + 10.0,
+            escape(&series.name)
+        );
+    }
+}
+
+/// The smallest 1, 2 or 5 times a power of ten at or above `value`, so the grid lines fall on
+/// round numbers.
+fn nice_ceiling(value: f64) -> f64 {
+    if value <= 0.0 {
+        return 1.0;
+    }
+    let magnitude = 10f64.powf(value.log10().floor());
+    [1.0, 2.0, 5.0, 10.0]
+        .iter()
+        .map(|step| step * magnitude)
+        .find(|ceiling| *ceiling >= value)
+        .unwrap_or(10.0 * magnitude)
+}
+
+fn format_axis_value(value: f64) -> String {
+    if value.fract() == 0.0 {
+        format!("{}", value as i64)
+    } else {
+        format!("{:.1}", value)
+    }
+}
+
+/// A rectangle of a treemap, with an area proportional to `value` and a color from pale
+/// yellow to dark red by `intensity` (0 to 1).
+#[derive(Debug, Clone)]
+pub struct TreemapItem {
+    pub label: String,
+    pub value: f64,
+    pub intensity: f64,
+    /// Shown when hovering over the rectangle
+    pub tooltip: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Rect {
+    x: f64,
+    y: f64,
+    width: f64,
+    height: f64,
+}
+
+/// Draws a squarified treemap in a self-contained SVG document, with `subtitle` explaining the
+/// sizes and colors below the title.
+pub fn treemap(title: &str, subtitle: &str, items: &[TreemapItem]) -> String {
+    let height = TREEMAP_TOP + TREEMAP_HEIGHT;
+    let mut svg = format!(
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{w}\" height=\"{h}\" viewBox=\"0 0 {w} {h}\" font-family=\"sans-serif\" font-size=\"11\">\n",
+        w = TREEMAP_WIDTH,
+        h = height
+    );
+    svg.push_str(&format!("<rect width=\"{}\" height=\"{}\" fill=\"#ffffff\"/>\n", TREEMAP_WIDTH, height));
+    let _ = writeln!(svg, "<text x=\"0\" y=\"20\" font-size=\"15\" font-weight=\"bold\">{}</text>", escape(title));
+    let _ = writeln!(svg, "<text x=\"0\" y=\"40\" fill=\"#555555\">{}</text>", escape(subtitle));
+
+    let mut items: Vec<&TreemapItem> = items.iter().filter(|item| item.value > 0.0).collect();
+    items.sort_by(|a, b| b.value.total_cmp(&a.value));
+
+    let total: f64 = items.iter().map(|item| item.value).sum();
+    let area = Rect { x: 0.0, y: TREEMAP_TOP, width: TREEMAP_WIDTH, height: TREEMAP_HEIGHT };
+    let scale = area.width * area.height / total.max(1.0);
+    let areas: Vec<f64> = items.iter().map(|item| item.value * scale).collect();
+
+    for (item, rect) in items.iter().zip(squarify(&areas, area)) {
+        let _ = writeln!(
+            svg,
+            "<rect x=\"{:.1}\" y=\"{:.1}\" width=\"{:.1}\" height=\"{:.1}\" fill=\"{}\" stroke=\"#ffffff\"><title>{}</title></rect>",
+            rect.x,
+            rect.y,
+            rect.width,
+            rect.height,
+            heat_color(item.intensity),
+            escape(&item.tooltip)
+        );
+
+        let fits = (rect.width / CHARACTER_WIDTH) as usize;
+        if fits >= 4 && rect.height >= 16.0 {
+            let label: String = if item.label.chars().count() > fits {
+                let kept: String = item.label.chars().rev().take(fits - 1).collect::<Vec<_>>().into_iter().rev().collect();
+                format!("…{}", kept)
+            } else {
+                item.label.clone()
+            };
+            let text_color = if item.intensity > 0.5 { "#ffffff" } else { "#222222" };
+            let _ = writeln!(
+                svg,
+                "<text x=\"{:.1}\" y=\"{:.1}\" fill=\"{}\" pointer-events=\"none\">{}</text>",
+                rect.x + 3.0,
+                rect.y + 13.0,
+                text_color,
+                escape(&label)
+            );
+        }
+    }
+
+    svg.push_str("</svg>\n");
+    svg
+}
+
+/// Lays out areas, sorted largest first and summing to the area of `bounds`, in rows along the
+/// shorter side, adding to a row while that makes its rectangles closer to squares.
+fn squarify(areas: &[f64], bounds: Rect) -> Vec<Rect> {
+    let mut rects = Vec::with_capacity(areas.len());
+    let mut free = bounds;
+    let mut start = 0;
+
+    while start < areas.len() {
+        let side = free.width.min(free.height);
+        let mut end = start + 1;
+        while end < areas.len() && worst_aspect(&areas[start..=end], side) <= worst_aspect(&areas[start..end], side) {
+            end += 1;
+        }
+
+        let row = &areas[start..end];
+        let row_area: f64 = row.iter().sum();
+        if free.width >= free.height {
+            let width = if free.height > 0.0 { row_area / free.height } else { 0.0 };
+            let mut y = free.y;
+            for area in row {
+                let height = if width > 0.0 { area / width } else { 0.0 };
+                rects.push(Rect { x: free.x, y, width, height });
+                y += height;
+            }
+            free.x += width;
+            free.width = (free.width - width).max(0.0);
+        } else {
+            let height = if free.width > 0.0 { row_area / free.width } else { 0.0 };
+            let mut x = free.x;
+            for area in row {
+                let width = if height > 0.0 { area / height } else { 0.0 };
+                rects.push(Rect { x, y: free.y, width, height });
+                x += width;
+            }
+            free.y += height;
+            free.height = (free.height - height).max(0.0);
+        }
+        start = end;
+    }
+    rects
+}
+
+/// The largest ratio of long to short side among the rectangles of a row laid along `side`.
+fn worst_aspect(row: &[f64], side: f64) -> f64 {
+    let sum: f64 = row.iter().sum();
+    let largest = row.iter().copied().fold(0.0, f64::max);
+    let smallest = row.iter().copied().fold(f64::INFINITY, f64::min);
+    if sum <= 0.0 || smallest <= 0.0 {
+        return f64::INFINITY;
+    }
+    let side_squared = side * side;
+    (side_squared * largest / (sum * sum)).max(sum * sum / (side_squared * smallest))
+}
+
+/// Pale yellow at 0 through to dark red at 1.
+fn heat_color(intensity: f64) -> String {
+    let intensity = intensity.clamp(0.0, 1.0);
+    let mix = |from: f64, to: f64| (from + (to - from) * intensity).round() as u8;
+    format!("#{:02x}{:02x}{:02x}", mix(255.0, 189.0), mix(237.0, 0.0), mix(160.0, 38.0))
+}
@@ -1,4 +1,6 @@
+use crate::metrics::complexity;
 use crate::metrics::language::LanguageDetector;
+use crate::metrics::lexer::{self, LineKind};
 use crate::style_analyzer::pattern::{
     IndentationStyle, NamingConvention, StylePattern, StylePatternCollection, StyleRule,
 };
@@ -63,21 +65,15 @@ impl StyleDetector {
         entries.par_iter().for_each(|entry| {
             let file_path = entry.path();
             
-            let extension = file_path.extension().and_then(|ext| ext.to_str()).unwrap_or("");
-            let file_name = file_path.file_name().and_then(|name| name.to_str()).unwrap_or("");
-            
-            let language = if extension.is_empty() && !file_name.is_empty() {
-                self.language_detector.detect_by_filename(file_name)
-            } else {
-                self.language_detector.detect_language(extension)
-            };
+            let content = fs::read_to_string(file_path).ok();
+            let language = self.language_detector.detect(file_path, content.as_deref());
             
             {
                 let mut lang_files = language_files.lock().unwrap();
                 *lang_files.entry(language.clone()).or_insert(0) += 1;
             }
             
-            if let Ok(content) = fs::read_to_string(file_path) {
+            if let Some(content) = content {
                 self.analyze_file_style(&content, &language, file_path.to_string_lossy().as_ref(), patterns.clone());
             }
         });
@@ -191,47 +187,11 @@ impl StyleDetector {
             return;
         }
         
-        let mut comment_lines = 0;
-        let mut code_lines = 0;
+        let kinds = lexer::classify_lines(content, language);
         
-        let (single_line_comment, multi_line_start, multi_line_end) = match language.to_lowercase().as_str() {
-            "rust" => ("//", "/*", "*/"),
-            "javascript" | "typescript" | "java" | "c" | "cpp" | "c++" | "csharp" | "c#" => ("//", "/*", "*/"),
-            "python" => ("#", "\"\"\"", "\"\"\""),
-            "ruby" => ("#", "=begin", "=end"),
-            "html" | "xml" => ("", "<!--", "-->"),
-            "css" | "scss" | "sass" => ("//", "/*", "*/"),
-            "shell" | "bash" => ("#", "", ""),
-            _ => ("//", "/*", "*/"),
-        };
-        
-        let mut in_multi_line_comment = false;
+        let comment_lines = kinds.iter().filter(|kind| matches!(kind, LineKind::Comment | LineKind::Mixed)).count();
+        let total_lines = kinds.iter().filter(|kind| **kind != LineKind::Blank).count();
         
-        for line in &lines {
-            let trimmed = line.trim();
-            
-            if trimmed.is_empty() {
-                continue;
-            }
-            
-            if in_multi_line_comment {
-                comment_lines += 1;
-                if !multi_line_end.is_empty() && trimmed.contains(multi_line_end) {
-                    in_multi_line_comment = false;
-                }
-            } else if !single_line_comment.is_empty() && trimmed.starts_with(single_line_comment) {
-                comment_lines += 1;
-            } else if !multi_line_start.is_empty() && trimmed.contains(multi_line_start) && 
-                     (!trimmed.contains(multi_line_end) || 
-                      trimmed.find(multi_line_start).unwrap() < trimmed.find(multi_line_end).unwrap()) {
-                comment_lines += 1;
-                in_multi_line_comment = true;
-            } else {
-                code_lines += 1;
-            }
-        }
-        
-        let total_lines = comment_lines + code_lines;
         if total_lines > 0 {
             let density = (comment_lines as f64 * 100.0 / total_lines as f64) as usize;
             
@@ -241,28 +201,13 @@ impl StyleDetector {
             let mut pattern = StylePattern::new(style_rule, language);
             pattern.add_occurrence(None);
             
-            let mut comment_examples = Vec::new();
-            in_multi_line_comment = false;
-            
-            for line in &lines {
-                if comment_examples.len() >= 3 {
-                    break;
-                }
-                
-                let trimmed = line.trim();
-                
-                if in_multi_line_comment {
-                    comment_examples.push(line.to_string());
-                    if !multi_line_end.is_empty() && trimmed.contains(multi_line_end) {
-                        in_multi_line_comment = false;
-                    }
-                } else if !single_line_comment.is_empty() && trimmed.starts_with(single_line_comment) {
-                    comment_examples.push(line.to_string());
-                } else if !multi_line_start.is_empty() && trimmed.contains(multi_line_start) {
-                    comment_examples.push(line.to_string());
-                    in_multi_line_comment = true;
-                }
-            }
+            let comment_examples = lines
+                .iter()
+                .zip(&kinds)
+                .filter(|(_, kind)| **kind == LineKind::Comment)
+                .take(3)
+                .map(|(line, _)| line.to_string())
+                .collect();
             
             pattern.examples = comment_examples;
             pattern.update_consistency(total_lines);
@@ -271,64 +216,10 @@ impl StyleDetector {
     }
     
     fn detect_function_size(&self, content: &str, language: &str, patterns: Arc<Mutex<StylePatternCollection>>) {
-        let lines: Vec<&str> = content.lines().collect();
-        
-        if lines.is_empty() {
-            return;
-        }
-        
-        let (fn_keyword, fn_end) = match language.to_lowercase().as_str() {
-            "rust" => (Some("fn "), Some("}")),
-            "javascript" | "typescript" => (Some("function "), Some("}")),
-            "python" => (Some("def "), None),
-            "java" | "c++" | "c#" => (None, Some("}")),
-            _ => (Some("fn "), Some("}")),
-        };
-        
-        let mut function_sizes = Vec::new();
-        let mut in_function = false;
-        let mut current_function_start = 0;
-        let mut bracket_count = 0;
-        
-        for (i, line) in lines.iter().enumerate() {
-            let trimmed = line.trim();
-            
-            if !in_function {
-                if (fn_keyword.is_some() && trimmed.contains(fn_keyword.unwrap())) ||
-                   (fn_keyword.is_none() && (trimmed.contains(" class ") || trimmed.contains(" void ") || 
-                                          trimmed.contains(" int ") || trimmed.contains(" String "))) {
-                    in_function = true;
-                    current_function_start = i;
-                    bracket_count = 0;
-                    
-                    if trimmed.contains("{") {
-                        bracket_count += 1;
-                    }
-                }
-            } else {
-                if let Some(end_marker) = fn_end {
-                    if trimmed.contains("{") {
-                        bracket_count += 1;
-                    }
-                    
-                    if trimmed.contains(end_marker) {
-                        bracket_count -= 1;
-                        
-                        if bracket_count <= 0 {
-                            let function_size = i - current_function_start + 1;
-                            function_sizes.push(function_size);
-                            in_function = false;
-                        }
-                    }
-                } else {
-                    if i > current_function_start && !trimmed.is_empty() && !trimmed.starts_with(" ") && !trimmed.starts_with("\t") {
-                        let function_size = i - current_function_start;
-                        function_sizes.push(function_size);
-                        in_function = false;
-                    }
-                }
-            }
-        }
+        let mut function_sizes: Vec<usize> = complexity::analyze_functions(content, language)
+            .iter()
+            .map(|function| function.length)
+            .collect();
         
         if !function_sizes.is_empty() {
             let total_size: usize = function_sizes.iter().sum();
@@ -28,6 +28,9 @@ pub enum AppError {
     #[error("Analysis error: {0}")]
     Analysis(String),
     
+    #[error("Git error: {0}")]
+    Git(String),
+    
     #[error("Style analysis error: {0}")]
     StyleAnalysis(String),
     
@@ -1,6 +1,5 @@
 use std::path::{Path, PathBuf};
 use std::fs;
-use std::sync::{Arc, Mutex};
 use rayon::prelude::*;
 
 /// Common file filtering utilities for determining which files to include in analysis
@@ -129,6 +128,11 @@ fn get_source_files_sequential(root_path: &Path) -> std::io::Result<Vec<PathBuf>
     Ok(source_files)
 }
 
+fn is_skipped_dir(path: &Path) -> bool {
+    let dir_name = path.file_name().unwrap_or_default().to_string_lossy();
+    dir_name == "node_modules" || dir_name == "target" || dir_name == ".git"
+}
+
 fn visit_dirs(dir: &Path, source_files: &mut Vec<PathBuf>) -> std::io::Result<()> {
     if dir.is_dir() {
         for entry in fs::read_dir(dir)? {
@@ -136,8 +140,7 @@ fn visit_dirs(dir: &Path, source_files: &mut Vec<PathBuf>) -> std::io::Result<()
             let path = entry.path();
             
             if path.is_dir() {
-                let dir_name = path.file_name().unwrap_or_default().to_string_lossy();
-                if dir_name == "node_modules" || dir_name == "target" || dir_name == ".git" {
+                if is_skipped_dir(&path) {
                     continue;
                 }
                 visit_dirs(&path, source_files)?;
@@ -149,40 +152,82 @@ fn visit_dirs(dir: &Path, source_files: &mut Vec<PathBuf>) -> std::io::Result<()
     Ok(())
 }
 
+/// Lists the files directly in the root, then walks each subdirectory on its own thread, so
+/// that every file is found exactly once.
 fn get_source_files_parallel(root_path: &Path) -> std::io::Result<Vec<PathBuf>> {
-    let source_files = Arc::new(Mutex::new(Vec::new()));
+    let mut source_files = Vec::new();
+    let mut subdirectories = Vec::new();
     
-    let mut dirs_to_process = Vec::new();
-    dirs_to_process.push(root_path.to_path_buf());
+    for entry in fs::read_dir(root_path)? {
+        let path = entry?.path();
+        if path.is_dir() {
+            if !is_skipped_dir(&path) {
+                subdirectories.push(path);
+            }
+        } else if FileFilter::is_source_file(&path) {
+            source_files.push(path);
+        }
+    }
     
-    for entry in walkdir::WalkDir::new(root_path)
-        .min_depth(1)
-        .max_depth(1)
-        .into_iter()
-        .filter_entry(|e| {
-            let is_dir = e.path().is_dir();
-            let path_str = e.path().to_string_lossy();
-            
-            is_dir && !path_str.contains("node_modules") && 
-            !path_str.contains("/target") && 
-            !path_str.contains("/.git")
+    let nested: Vec<Vec<PathBuf>> = subdirectories
+        .into_par_iter()
+        .map(|dir| {
+            let mut local_files = Vec::new();
+            let _ = visit_dirs(&dir, &mut local_files);
+            local_files
         })
-        .filter_map(Result::ok)
-        .filter(|e| e.path().is_dir())
-    {
-        dirs_to_process.push(entry.path().to_path_buf());
+        .collect();
+    source_files.extend(nested.into_iter().flatten());
+    
+    Ok(source_files)
+}
+
+/// Matches a single path component against a pattern using `*` and `?` wildcards
+pub fn wildcard_match(pattern: &str, name: &str) -> bool {
+    fn matches_from(pattern: &[char], name: &[char]) -> bool {
+        match pattern.first() {
+            None => name.is_empty(),
+            Some('*') => (0..=name.len()).any(|skip| matches_from(&pattern[1..], &name[skip..])),
+            Some('?') => !name.is_empty() && matches_from(&pattern[1..], &name[1..]),
+            Some(c) => name.first() == Some(c) && matches_from(&pattern[1..], &name[1..]),
+        }
     }
     
-    dirs_to_process.into_par_iter().for_each(|dir| {
-        let mut local_files = Vec::new();
-        if let Err(_) = visit_dirs(&dir, &mut local_files) {
-            return;
+    let pattern: Vec<char> = pattern.chars().collect();
+    let name: Vec<char> = name.chars().collect();
+    matches_from(&pattern, &name)
+}
+
+/// Matches a `/`-separated relative path against a glob where `**` spans any number of directories
+pub fn glob_match_path(pattern: &str, path: &str) -> bool {
+    fn matches_from(pattern: &[&str], path: &[&str]) -> bool {
+        match pattern.first() {
+            None => path.is_empty(),
+            Some(&"**") => (0..=path.len()).any(|skip| matches_from(&pattern[1..], &path[skip..])),
+            Some(component) => !path.is_empty()
+                && wildcard_match(component, path[0])
+                && matches_from(&pattern[1..], &path[1..]),
         }
-        
-        let mut files = source_files.lock().unwrap();
-        files.extend(local_files);
-    });
+    }
     
-    let result = source_files.lock().unwrap().clone();
-    Ok(result)
+    let split = |value: &str| -> Vec<String> {
+        value.split('/').filter(|c| !c.is_empty() && *c != ".").map(String::from).collect()
+    };
+    let pattern = split(pattern);
+    let path = split(path);
+    let pattern: Vec<&str> = pattern.iter().map(String::as_str).collect();
+    let path: Vec<&str> = path.iter().map(String::as_str).collect();
+    matches_from(&pattern, &path)
+}
+
+/// Matches a relative path against a glob (`src/domain/**`) or, when the pattern has no wildcards,
+/// against a path prefix such as `src/commands`
+pub fn matches_path_pattern(pattern: &str, path: &str) -> bool {
+    let pattern = pattern.trim_start_matches("./").trim_end_matches('/');
+    
+    if pattern.contains(['*', '?']) {
+        glob_match_path(pattern, path)
+    } else {
+        path == pattern || path.starts_with(&format!("{}/", pattern))
+    }
 }
@@ -0,0 +1,254 @@
+use crate::util::error::{AppError, AppResult};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use tempfile::TempDir;
+
+/// Runs `git -C <dir> <args>` and returns its standard output.
+pub fn run_git<P: AsRef<Path>>(dir: P, args: &[&str]) -> AppResult<String> {
+    let output = Command::new("git")
+        .arg("-C")
+        .arg(dir.as_ref())
+        .args(args)
+        .output()
+        .map_err(|e| AppError::Git(format!("Failed to run git: {}", e)))?;
+
+    if !output.status.success() {
+        return Err(AppError::Git(format!(
+            "git {} failed: {}",
+            args.join(" "),
+            String::from_utf8_lossy(&output.stderr).trim()
+        )));
+    }
+
+    Ok(String::from_utf8_lossy(&output.stdout).to_string())
+}
+
+/// Refuses a revision that git would read as an option, such as `--output=<file>`.
+fn check_revision(revision: &str) -> AppResult<()> {
+    if revision.starts_with('-') {
+        return Err(AppError::Git(format!("Invalid revision: {}", revision)));
+    }
+    Ok(())
+}
+
+/// Top-level directory of the repository containing `dir`.
+pub fn repository_root<P: AsRef<Path>>(dir: P) -> AppResult<PathBuf> {
+    let output = run_git(dir, &["rev-parse", "--show-toplevel"])?;
+    Ok(PathBuf::from(output.trim()))
+}
+
+/// Files touched by a revision range (`main...HEAD`, `HEAD~3..HEAD`) or, for a single revision,
+/// between it and the working tree. Paths are absolute; deleted files are included.
+pub fn changed_files<P: AsRef<Path>>(dir: P, range: &str) -> AppResult<Vec<PathBuf>> {
+    check_revision(range)?;
+    let dir = dir.as_ref();
+    let root = repository_root(dir)?;
+    let output = run_git(dir, &["diff", "--name-only", range, "--"])?;
+
+    Ok(output
+        .lines()
+        .filter(|line| !line.is_empty())
+        .map(|line| root.join(line))
+        .collect())
+}
+
+/// Extracts the tree at `revision` into a temporary directory using `git archive`, leaving the
+/// working copy and index alone. When `dir` is a subdirectory of the repository, only that
+/// subdirectory is extracted, so the result mirrors `dir` itself.
+pub fn export_revision<P: AsRef<Path>>(dir: P, revision: &str) -> AppResult<TempDir> {
+    check_revision(revision)?;
+    let dir = dir.as_ref();
+    let prefix = run_git(dir, &["rev-parse", "--show-prefix"])?;
+    let prefix = prefix.trim().trim_end_matches('/');
+    let tree = if prefix.is_empty() {
+        revision.to_string()
+    } else {
+        format!("{}:{}", revision, prefix)
+    };
+
+    // Fail early with a clear message when the revision does not exist
+    run_git(dir, &["rev-parse", "--verify", "--quiet", &format!("{}^{{tree}}", revision)])
+        .map_err(|_| AppError::Git(format!("Unknown revision: {}", revision)))?;
+
+    let target = tempfile::Builder::new()
+        .prefix("aicodeanalyzer-")
+        .tempdir()
+        .map_err(|e| AppError::Git(format!("Failed to create temporary directory: {}", e)))?;
+
+    // `git archive` resolves `<revision>:<prefix>` from the top of the repository
+    let mut archive = Command::new("git")
+        .arg("-C")
+        .arg(repository_root(dir)?)
+        .args(["archive", "--format=tar", &tree])
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|e| AppError::Git(format!("Failed to run git: {}", e)))?;
+    let archive_stdout = archive
+        .stdout
+        .take()
+        .ok_or_else(|| AppError::Git("Failed to read git archive output".to_string()))?;
+
+    let extract = Command::new("tar")
+        .arg("-xf")
+        .arg("-")
+        .arg("-C")
+        .arg(target.path())
+        .stdin(archive_stdout)
+        .output()
+        .map_err(|e| AppError::Git(format!("Failed to run tar: {}", e)))?;
+    let archived = archive
+        .wait_with_output()
+        .map_err(|e| AppError::Git(format!("Failed to run git: {}", e)))?;
+
+    if !archived.status.success() {
+        return Err(AppError::Git(format!(
+            "git archive {} failed: {}",
+            tree,
+            String::from_utf8_lossy(&archived.stderr).trim()
+        )));
+    }
+    if !extract.status.success() {
+        return Err(AppError::Git(format!(
+            "Failed to extract {}: {}",
+            revision,
+            String::from_utf8_lossy(&extract.stderr).trim()
+        )));
+    }
+
+    Ok(target)
+}
+
+/// A commit on the first-parent history of a revision.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Commit {
+    pub hash: String,
+    /// Committer time in seconds since the Unix epoch
+    pub timestamp: i64,
+    /// Committer date as `YYYY-MM-DD`
+    pub date: String,
+}
+
+impl Commit {
+    pub fn short_hash(&self) -> &str {
+        &self.hash[..self.hash.len().min(8)]
+    }
+}
+
+/// Commits that touched `dir` on the first-parent history of `revision`, oldest first.
+pub fn first_parent_history<P: AsRef<Path>>(dir: P, revision: &str) -> AppResult<Vec<Commit>> {
+    check_revision(revision)?;
+    let output = run_git(dir, &["log", "--first-parent", "--format=%H %ct %cs", revision, "--", "."])?;
+
+    let mut commits: Vec<Commit> = output
+        .lines()
+        .filter_map(|line| {
+            let mut fields = line.split(' ');
+            Some(Commit {
+                hash: fields.next()?.to_string(),
+                timestamp: fields.next()?.parse().ok()?,
+                date: fields.next()?.to_string(),
+            })
+        })
+        .collect();
+    commits.reverse();
+    Ok(commits)
+}
+
+/// Lines added and removed in one file by a commit.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FileChange {
+    /// Relative to the directory the history was read from
+    pub path: String,
+    /// Zero for binary files
+    pub lines_added: usize,
+    pub lines_removed: usize,
+}
+
+/// A non-merge commit with the files it changed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ChangeSet {
+    pub commit: String,
+    pub author: String,
+    pub timestamp: i64,
+    pub files: Vec<FileChange>,
+}
+
+/// Non-merge commits that changed files under `dir` in a time window, newest first. `since` and
+/// `until` take anything `git log` does, such as `2024-01-01` or `6 months ago`.
+pub fn change_history<P: AsRef<Path>>(dir: P, since: &str, until: Option<&str>) -> AppResult<Vec<ChangeSet>> {
+    let since = format!("--since={}", since);
+    let until = until.map(|until| format!("--until={}", until));
+    let mut args = vec!["log", "--no-merges", "--no-renames", "--relative", "--numstat", "--format=commit%x09%H%x09%ct%x09%aN", &since];
+    if let Some(until) = &until {
+        args.push(until);
+    }
+    args.extend(["--", "."]);
+
+    Ok(parse_numstat(&run_git(dir, &args)?))
+}
+
+/// Parses `git log --numstat` output whose commit lines are formatted as
+/// `commit<TAB>hash<TAB>timestamp<TAB>author`.
+pub fn parse_numstat(log: &str) -> Vec<ChangeSet> {
+    let mut changes: Vec<ChangeSet> = Vec::new();
+
+    for line in log.lines() {
+        let fields: Vec<&str> = line.splitn(4, '\t').collect();
+        match fields.as_slice() {
+            ["commit", hash, timestamp, author] => changes.push(ChangeSet {
+                commit: hash.to_string(),
+                author: author.to_string(),
+                timestamp: timestamp.parse().unwrap_or_default(),
+                files: Vec::new(),
+            }),
+            [added, removed, path] if !path.is_empty() => {
+                if let Some(change) = changes.last_mut() {
+                    change.files.push(FileChange {
+                        path: path.to_string(),
+                        lines_added: added.parse().unwrap_or(0),
+                        lines_removed: removed.parse().unwrap_or(0),
+                    });
+                }
+            }
+            _ => {}
+        }
+    }
+    changes
+}
+
+/// The author of a line as recorded by git.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Author {
+    pub name: String,
+    /// Without the angle brackets
+    pub email: String,
+}
+
+/// Lines of `file` per author of their last change, from `git blame`. Whitespace-only changes
+/// are ignored, and uncommitted lines are left out.
+pub fn blame_authors<P: AsRef<Path>>(dir: P, file: &Path) -> AppResult<BTreeMap<Author, usize>> {
+    let file = file.to_string_lossy();
+    Ok(parse_blame_authors(&run_git(dir, &["blame", "-w", "--line-porcelain", "--", &file])?))
+}
+
+/// Counts the `author`/`author-mail` pairs of `git blame --line-porcelain` output, which repeats
+/// them for every line.
+pub fn parse_blame_authors(porcelain: &str) -> BTreeMap<Author, usize> {
+    let mut authors = BTreeMap::new();
+    let mut name = "";
+
+    for line in porcelain.lines() {
+        if let Some(author) = line.strip_prefix("author ") {
+            name = author;
+        } else if let Some(email) = line.strip_prefix("author-mail ") {
+            let email = email.trim_start_matches('<').trim_end_matches('>');
+            if email != "not.committed.yet" {
+                let author = Author { name: name.to_string(), email: email.to_string() };
+                *authors.entry(author).or_default() += 1;
+            }
+        }
+    }
+    authors
+}
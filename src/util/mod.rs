@@ -1,3 +1,5 @@
 pub mod parallel;
 pub mod error;
-pub mod file_filter;
\ No newline at end of file
+pub mod file_filter;
+pub mod git;
+pub mod paths;
\ No newline at end of file
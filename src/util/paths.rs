@@ -0,0 +1,7 @@
+use crate::dependency::resolver::normalize_path;
+use std::path::Path;
+
+/// Node path without `./` and `..` segments; package names pass through unchanged.
+pub fn display_path(node: &str) -> String {
+    normalize_path(Path::new(node)).to_string_lossy().to_string()
+}
@@ -0,0 +1,13 @@
+/// <summary>Documentation</summary>
+public class Paths
+{
+    private string folder = @"C:\temp\"; // escaped by doubling, not backslash
+    private string verbatim = @"multi // line
+        ""verbatim"" string";
+    private char slash = '/'; /* block */
+
+    /*
+     * Multi-line block
+     */
+    public string Folder => folder;
+}
@@ -0,0 +1,9 @@
+package main
+
+// main prints a raw string.
+func main() {
+	raw := `C:\path\ // not a comment
+/* still raw */`
+	r := '"' // a rune
+	println(raw, r) /* done */
+}
@@ -0,0 +1,21 @@
+#!/usr/bin/env python3
+"""Module docstring."""
+
+import re
+
+
+def parse(text):
+    """
+    Function docstring spanning lines,
+    with a # that is not a comment.
+    """
+    pattern = re.compile(r"#\d+")  # trailing comment
+    query = """
+    SELECT * FROM table  # part of the string
+    """
+    items = [
+        """an element, not a docstring""",
+    ]
+    return pattern, query, items  # done
+
+    # indented comment
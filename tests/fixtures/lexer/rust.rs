@@ -0,0 +1,16 @@
+//! Module documentation
+use std::fmt;
+
+/* A block comment /* with a nested block */
+   that continues after the nested one */
+fn parse(input: &str) -> usize { // trailing comment
+    let url = "http://example.com/*not a comment*/";
+    let raw = r#"a "quoted" // raw string"#;
+    let multiline = "first line
+        // still inside the string
+        last line";
+    let quote = '"'; let slash = '/';
+    let lifetime: &'static str = "x"; /* inline */ let after = 1;
+
+    input.len() /* before */ + url.len()
+}
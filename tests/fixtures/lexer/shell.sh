@@ -0,0 +1,7 @@
+#!/bin/sh
+# Count arguments
+count=${#}  # trailing comment
+echo "a # inside quotes"
+echo 'single # quoted' # comment
+url=http://example.com/#anchor
+
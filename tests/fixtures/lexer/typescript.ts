@@ -0,0 +1,11 @@
+/**
+ * A documented function.
+ */
+export function render(name: string): string {
+  const url = 'https://example.com'; // trailing
+  const glob = "src/**/*.ts";
+  const template = `line one
+    /* not a comment */ ${name}
+  `;
+  /* a block */ return template + url + glob;
+}
@@ -1,3 +1,4 @@
+use super::support::write_file;
 use ai_code_analyzer::commands::architecture_diagram;
 use tempfile::TempDir;
 use std::path::Path;
@@ -38,6 +39,8 @@ async fn test_architecture_diagram_command() {
         false, // include_tests
         false, // group_by_module
         None, // focus
+        false, // hide_external
+        false, // crate_level
     ).await;
     
     // Verify the command succeeded
@@ -54,6 +57,8 @@ async fn test_architecture_diagram_command() {
         false, // include_tests
         true, // group_by_module
         None, // focus
+        false, // hide_external
+        false, // crate_level
     ).await;
     
     // Verify the command succeeded
@@ -70,6 +75,8 @@ async fn test_architecture_diagram_command() {
         false, // include_tests
         false, // group_by_module
         None, // focus
+        false, // hide_external
+        false, // crate_level
     ).await;
     
     // Verify the command failed due to invalid format
@@ -90,6 +97,8 @@ async fn test_architecture_diagram_command() {
         false, // include_tests
         false, // group_by_module
         None, // focus
+        false, // hide_external
+        false, // crate_level
     ).await;
     
     // Verify the command succeeded
@@ -116,6 +125,8 @@ async fn test_architecture_diagram_command() {
         false, // include_tests
         false, // group_by_module
         None, // focus
+        false, // hide_external
+        false, // crate_level
     ).await;
     
     // Verify the command succeeded
@@ -128,4 +139,126 @@ async fn test_architecture_diagram_command() {
     let svg_content = fs::read_to_string(&svg_output_path).unwrap();
     assert!(svg_content.contains("<svg"));
     assert!(svg_content.contains("</svg>"));
-}
\ No newline at end of file
+}
+
+#[tokio::test]
+async fn test_architecture_diagram_uses_import_edges() {
+    let temp_dir = TempDir::new().unwrap();
+    let temp_path = temp_dir.path().to_str().unwrap().to_string();
+    
+    let src_dir = temp_dir.path().join("src");
+    fs::create_dir_all(src_dir.join("utils")).unwrap();
+    
+    // main.rs imports utils and an external crate, but not its sibling config.rs
+    fs::write(src_dir.join("main.rs"), "mod utils;\nuse serde::Serialize;\nfn main() {}\n").unwrap();
+    fs::write(src_dir.join("config.rs"), "pub struct Config;\n").unwrap();
+    fs::write(src_dir.join("utils").join("mod.rs"), "pub fn helper() {}\n").unwrap();
+    
+    let output_path = temp_dir.path().join("arch.dot").to_str().unwrap().to_string();
+    
+    let result = architecture_diagram::execute(
+        temp_path.clone(),
+        false, // no_output
+        Some(output_path.clone()), // output_path
+        true, // no_parallel
+        "dot".to_string(), // format
+        "medium".to_string(), // detail
+        false, // include_tests
+        false, // group_by_module
+        None, // focus
+        false, // hide_external
+        false, // crate_level
+    ).await;
+    
+    assert_eq!(result, 0);
+    
+    let dot_content = fs::read_to_string(&output_path).unwrap();
+    let edges: Vec<&str> = dot_content.lines().filter(|line| line.contains("->")).collect();
+    
+    // Every arrow corresponds to a real `mod`/`use`
+    assert_eq!(edges.len(), 2);
+    assert!(edges.iter().any(|edge| edge.contains("main_rs") && edge.contains("utils_mod_rs")));
+    assert!(edges.iter().any(|edge| edge.contains("main_rs") && edge.contains("\"serde\"")));
+    assert!(!edges.iter().any(|edge| edge.contains("config_rs")));
+    
+    // External packages are rendered as distinct nodes
+    assert!(dot_content.contains("\"serde\" [label=\"serde\", shape=ellipse"));
+    
+    let result_hidden = architecture_diagram::execute(
+        temp_path.clone(),
+        false, // no_output
+        Some(output_path.clone()), // output_path
+        true, // no_parallel
+        "dot".to_string(), // format
+        "medium".to_string(), // detail
+        false, // include_tests
+        false, // group_by_module
+        None, // focus
+        true, // hide_external
+        false, // crate_level
+    ).await;
+    
+    assert_eq!(result_hidden, 0);
+    
+    let dot_content = fs::read_to_string(&output_path).unwrap();
+    assert!(!dot_content.contains("serde"));
+    assert_eq!(dot_content.lines().filter(|line| line.contains("->")).count(), 1);
+}
+
+async fn render_diagram(root: &Path, format: &str, group_by_module: bool) -> String {
+    let output_path = root.join(format!("arch.{}", format)).to_str().unwrap().to_string();
+    let result = architecture_diagram::execute(
+        root.join("src").to_str().unwrap().to_string(),
+        false, // no_output
+        Some(output_path.clone()), // output_path
+        false, // no_parallel
+        format.to_string(), // format
+        "medium".to_string(), // detail
+        false, // include_tests
+        group_by_module, // group_by_module
+        None, // focus
+        false, // hide_external
+        false, // crate_level
+    ).await;
+    assert_eq!(result, 0);
+    fs::read_to_string(output_path).unwrap()
+}
+
+#[tokio::test]
+async fn test_architecture_diagram_keeps_similar_paths_apart() {
+    let temp_dir = TempDir::new().unwrap();
+    let root = temp_dir.path();
+    
+    // Both file names reduce to the same identifier once punctuation is replaced
+    write_file(root, "src/main.js", "import { a } from './x-y';\n");
+    write_file(root, "src/x-y.js", "export const a = 1;\n");
+    write_file(root, "src/x_y.js", "import { a } from './x-y';\nexport const b = 2;\n");
+    
+    let dot = render_diagram(root, "dot", false).await;
+    let nodes: Vec<&str> = dot.lines().filter(|line| line.contains("[label=")).collect();
+    assert_eq!(nodes.len(), 3);
+    assert!(nodes.iter().any(|node| node.contains("_src_x_y_js\" [label=\"x-y.js\"]")));
+    assert!(nodes.iter().any(|node| node.contains("_src_x_y_js_2\" [label=\"x_y.js\"]")));
+    let edges: Vec<&str> = dot.lines().filter(|line| line.contains("->")).collect();
+    assert_eq!(edges.len(), 2);
+    assert!(edges.iter().any(|edge| edge.contains("_src_main_js\" -> ") && edge.ends_with("_src_x_y_js\";")));
+    assert!(edges.iter().any(|edge| edge.contains("_src_x_y_js_2\" -> ") && edge.ends_with("_src_x_y_js\";")));
+    
+    for format in ["plantuml", "mermaid"] {
+        let diagram = render_diagram(root, format, false).await;
+        let targets: Vec<&str> = diagram.lines().filter_map(|line| line.split_once("--> ")).map(|(_, to)| to).collect();
+        assert_eq!(targets.len(), 2, "{}", diagram);
+        assert!(targets.iter().all(|target| target.ends_with("_src_x_y_js")), "{}", diagram);
+    }
+    let c4 = render_diagram(root, "c4", false).await;
+    assert_eq!(c4.matches("_src_x_y_js, \"depends on\")").count(), 2);
+    assert!(c4.contains("Component(") && c4.contains("_src_x_y_js_2, \"x_y.js\", \"File\")"));
+    
+    // Nodes and edges come out in the same order on every run
+    for format in ["dot", "plantuml", "mermaid", "c4"] {
+        for group_by_module in [false, true] {
+            let first = render_diagram(root, format, group_by_module).await;
+            assert_eq!(render_diagram(root, format, group_by_module).await, first);
+        }
+    }
+}
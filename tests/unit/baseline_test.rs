@@ -0,0 +1,69 @@
+use ai_code_analyzer::metrics::baseline::{MetricsDiff, MetricsSnapshot};
+use ai_code_analyzer::metrics::models::{CodeMetrics, LanguageMetrics};
+use ai_code_analyzer::metrics::reporter::MetricsReporter;
+use std::fs;
+use tempfile::tempdir;
+
+fn metrics(root: &str, files: &[(&str, &str, usize, usize)]) -> CodeMetrics {
+    let mut metrics = CodeMetrics::new();
+    metrics.root = root.to_string();
+    for (path, language, lines_of_code, comment_lines) in files {
+        let mut file = LanguageMetrics::new(language.to_string());
+        file.files = 1;
+        file.lines_of_code = *lines_of_code;
+        file.comment_lines = *comment_lines;
+        metrics.add_language_metrics(file, &format!("{}/{}", root, path));
+    }
+    metrics
+}
+
+#[test]
+fn test_diff_against_saved_snapshot() {
+    let dir = tempdir().unwrap();
+    let baseline_path = dir.path().join("baseline.json");
+    let baseline = metrics(
+        "/old/checkout",
+        &[("src/lib.rs", "Rust", 100, 20), ("src/util.rs", "Rust", 50, 0), ("tests/lib_test.rs", "Rust", 60, 0)],
+    );
+    MetricsReporter::new().export_metrics_json(&baseline, &baseline_path).unwrap();
+
+    let current = metrics(
+        "/new/checkout",
+        &[
+            ("src/lib.rs", "Rust", 180, 20),
+            ("src/util.rs", "Rust", 50, 0),
+            ("tests/lib_test.rs", "Rust", 60, 0),
+            ("web/app.js", "JavaScript", 30, 3),
+        ],
+    );
+
+    let snapshot = MetricsSnapshot::load(&baseline_path).unwrap();
+    let diff = MetricsDiff::compare(&snapshot, &MetricsSnapshot::from_metrics(&current));
+
+    assert_eq!((diff.total.baseline.files, diff.total.current.files), (3, 4));
+    assert_eq!((diff.total.baseline.lines_of_code, diff.total.current.lines_of_code), (210, 320));
+    assert_eq!(diff.total.baseline.test_ratio(), Some(0.4));
+    assert!(diff.test_ratio_dropped());
+
+    // Unchanged groups are left out, paths are relative to each run's root
+    let languages: Vec<&str> = diff.languages.iter().map(|group| group.name.as_str()).collect();
+    assert_eq!(languages, vec!["JavaScript", "Rust"]);
+    let directories: Vec<&str> = diff.directories.iter().map(|group| group.name.as_str()).collect();
+    assert_eq!(directories, vec!["src", "web"]);
+
+    let grown: Vec<(&str, usize)> = diff.grown_files.iter().map(|file| (file.path.as_str(), file.growth())).collect();
+    assert_eq!(grown, vec![("src/lib.rs", 80), ("web/app.js", 30)]);
+
+    let report = dir.path().join("metrics.md");
+    MetricsReporter::new().baseline(diff).export_metrics(&current, &report).unwrap();
+    let markdown = fs::read_to_string(&report).unwrap();
+    assert!(markdown.contains("## Changes Since Baseline"));
+    assert!(markdown.contains("| **Total** | 3 → 4 (+1) | 210 → 320 (+110) | 8.7% → 6.7% (-2.0 pp) | 40.0% → 23.1% (-16.9 pp) |"));
+}
+
+#[test]
+fn test_snapshot_schema_version_is_checked() {
+    let error = MetricsSnapshot::parse(r#"{"schema_version": 99, "files": []}"#).unwrap_err();
+    assert!(error.contains("Unsupported metrics schema version 99"));
+    assert!(MetricsSnapshot::parse(r#"{"files": []}"#).is_err());
+}
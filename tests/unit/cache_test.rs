@@ -1,4 +1,5 @@
 use ai_code_analyzer::cache::AnalysisCache;
+use ai_code_analyzer::dependency::dependency_graph::{EdgeImport, ImportKind};
 use ai_code_analyzer::metrics::models::FileMetrics;
 use std::fs::File;
 use std::io::Write;
@@ -67,6 +68,7 @@ fn test_metrics_cache() {
         blank_lines: 0,
         comment_lines: 0,
         is_test_file: false,
+        ..FileMetrics::default()
     };
     
     cache.cache_metrics(&path_str, metrics.clone());
@@ -93,7 +95,7 @@ fn test_dependencies_cache() {
     
     assert_eq!(cache.get_dependencies(&path_str), None);
     
-    let deps = vec!["std::io".to_string(), "std::path".to_string()];
+    let deps = vec![EdgeImport::new("std::io", 1, ImportKind::Use), EdgeImport::new("std::path", 2, ImportKind::Use)];
     cache.cache_dependencies(&path_str, deps.clone());
     
     let cached_deps = cache.get_dependencies(&path_str).unwrap();
@@ -0,0 +1,117 @@
+use ai_code_analyzer::metrics::clones::{CloneDetector, CloneInstance, SourceFile};
+use ai_code_analyzer::metrics::collector::MetricsCollector;
+use std::fs;
+
+const ORIGINAL: &str = r#"fn total_price(items: &[Item], discount: f64) -> f64 {
+    let mut sum = 0.0;
+    for item in items {
+        if item.quantity > 0 {
+            sum += item.price * item.quantity as f64;
+        }
+    }
+    sum * (1.0 - discount)
+}
+"#;
+
+/// The same function with other names, other constants and a comment
+const RENAMED: &str = r#"// Copied from the billing service
+fn order_value(lines: &[Line], rebate: f64) -> f64 {
+    let mut acc = 0.0;
+    for line in lines {
+        if line.count > 3 {
+            acc += line.cost * line.count as f64; // "per unit"
+        }
+    }
+    acc * (2.5 - rebate)
+}
+"#;
+
+const UNRELATED: &str = r#"fn main() {
+    let args: Vec<String> = std::env::args().collect();
+    match args.len() {
+        1 => println!("no arguments"),
+        _ => println!("{} arguments", args.len() - 1),
+    }
+}
+"#;
+
+fn source<'a>(path: &'a str, content: &'a str) -> SourceFile<'a> {
+    SourceFile { path, language: "Rust", content }
+}
+
+#[test]
+fn test_detects_renamed_copies() {
+    let files = [source("a.rs", ORIGINAL), source("b.rs", RENAMED), source("c.rs", UNRELATED)];
+    let detection = CloneDetector::new().min_tokens(40).min_lines(5).detect(&files);
+
+    assert_eq!(detection.classes.len(), 1);
+    let clone = &detection.classes[0];
+    assert_eq!(clone.instances.len(), 2);
+    assert_eq!(clone.instances[0], CloneInstance { path: "a.rs".to_string(), start_line: 1, end_line: 9 });
+    assert_eq!(clone.instances[1], CloneInstance { path: "b.rs".to_string(), start_line: 2, end_line: 10 });
+    assert_eq!(clone.lines, 9);
+
+    assert_eq!(detection.duplicated_lines["a.rs"], 9);
+    assert_eq!(detection.duplicated_lines["b.rs"], 9);
+    assert_eq!(detection.duplicated_lines["c.rs"], 0);
+
+    // Above the threshold nothing is reported; other languages are not compared
+    assert!(CloneDetector::new().min_tokens(500).detect(&files).classes.is_empty());
+    let markdown = SourceFile { path: "notes.md", language: "Markdown", content: ORIGINAL };
+    let detection = CloneDetector::new().min_tokens(40).detect(&[source("a.rs", ORIGINAL), markdown]);
+    assert!(detection.classes.is_empty());
+    assert!(!detection.duplicated_lines.contains_key("notes.md"));
+}
+
+#[test]
+fn test_metrics_report_duplication() {
+    let dir = tempfile::tempdir().unwrap();
+    fs::write(dir.path().join("a.rs"), ORIGINAL).unwrap();
+    fs::write(dir.path().join("b.rs"), format!("{}\n{}", UNRELATED, RENAMED)).unwrap();
+    fs::write(dir.path().join("README.md"), "# Notes\n").unwrap();
+
+    let metrics = MetricsCollector::new().clone_thresholds(40, 5).collect_metrics(dir.path()).unwrap();
+
+    assert_eq!(metrics.clones.len(), 1);
+    assert_eq!(metrics.clones[0].instances[1].start_line, 10);
+    let duplicated: Vec<(String, Option<usize>)> = metrics
+        .files
+        .iter()
+        .map(|file| (file.path.rsplit('/').next().unwrap().to_string(), file.duplicated_lines))
+        .collect();
+    assert_eq!(
+        duplicated,
+        vec![("README.md".to_string(), None), ("a.rs".to_string(), Some(9)), ("b.rs".to_string(), Some(9))]
+    );
+
+    let summary = metrics.duplication_summary();
+    assert_eq!((summary.files, summary.duplicated_lines), (2, 18));
+    assert_eq!(metrics.duplication_by_language().keys().collect::<Vec<_>>(), vec!["Rust"]);
+}
+
+/// A block whose statements repeat, so that its windows also match each other
+const REGISTRATIONS: &str = r#"fn register(registry: &mut Registry) {
+    registry.add("alpha", 1);
+    registry.add("beta", 2);
+    registry.add("gamma", 3);
+    registry.add("delta", 4);
+    registry.add("epsilon", 5);
+    registry.add("zeta", 6);
+    registry.add("eta", 7);
+    registry.add("theta", 8);
+}
+"#;
+
+#[test]
+fn test_repeated_block_is_one_class() {
+    let copy = format!("{}\n{}", UNRELATED, REGISTRATIONS);
+    let files = [source("a.rs", REGISTRATIONS), source("b.rs", &copy)];
+    let detection = CloneDetector::new().min_tokens(30).min_lines(3).detect(&files);
+
+    assert_eq!(detection.classes.len(), 1, "{:#?}", detection.classes);
+    let clone = &detection.classes[0];
+    assert_eq!(clone.instances, vec![
+        CloneInstance { path: "a.rs".to_string(), start_line: 1, end_line: 10 },
+        CloneInstance { path: "b.rs".to_string(), start_line: 9, end_line: 18 },
+    ]);
+}
@@ -0,0 +1,201 @@
+use ai_code_analyzer::metrics::collector::MetricsCollector;
+use ai_code_analyzer::metrics::complexity::analyze_functions;
+use ai_code_analyzer::metrics::models::FunctionMetrics;
+use std::fs;
+use tempfile::tempdir;
+
+fn find<'a>(functions: &'a [FunctionMetrics], name: &str) -> &'a FunctionMetrics {
+    functions
+        .iter()
+        .find(|function| function.name == name)
+        .unwrap_or_else(|| panic!("function {} not found in {:?}", name, functions))
+}
+
+/// (parameters, cyclomatic, cognitive, nesting depth)
+fn scores(function: &FunctionMetrics) -> (usize, usize, usize, usize) {
+    (
+        function.parameters,
+        function.cyclomatic_complexity,
+        function.cognitive_complexity,
+        function.nesting_depth,
+    )
+}
+
+const RUST_SOURCE: &str = r#"// if this were code { it would count }
+fn classify(value: i32, flags: &[bool]) -> &'static str {
+    let label = "if while for { }";
+    if value > 10 && flags.len() > 2 {
+        for flag in flags {
+            if *flag || value > 20 {
+                return "high";
+            }
+        }
+    } else if value > 5 {
+        return "medium";
+    } else {
+        return label;
+    }
+    match value {
+        0 => "zero",
+        _ => "other",
+    }
+}
+
+impl Counter {
+    pub fn add(&mut self, amount: usize) -> usize {
+        let double = |x: usize| { x * 2 };
+        self.total += double(amount);
+        self.total
+    }
+}
+
+fn factorial(n: u64) -> u64 {
+    if n == 0 { 1 } else { n * factorial(n - 1) }
+}
+"#;
+
+#[test]
+fn test_rust_function_metrics() {
+    let functions = analyze_functions(RUST_SOURCE, "Rust");
+    assert_eq!(functions.len(), 3);
+
+    let classify = find(&functions, "classify");
+    assert_eq!((classify.start_line, classify.end_line, classify.length), (2, 19, 18));
+    assert_eq!(scores(classify), (2, 8, 11, 3));
+
+    // The receiver is not a parameter and the closure adds a level of nesting
+    assert_eq!(scores(find(&functions, "add")), (1, 1, 0, 1));
+
+    // Recursion adds to cognitive complexity
+    assert_eq!(scores(find(&functions, "factorial")), (1, 2, 3, 1));
+
+    assert!(analyze_functions(RUST_SOURCE, "Markdown").is_empty());
+}
+
+#[test]
+fn test_python_function_metrics() {
+    let source = r##"class Parser:
+    def parse(self, text, strict=False):
+        """if for while"""
+        result = []
+        for line in text.splitlines():
+            if line and not line.startswith("#"):
+                result.append(line)
+            elif strict:
+                raise ValueError("empty")
+
+        def helper(value):
+            return value if value else None
+        return [helper(x) for x in result if x]
+"##;
+    let functions = analyze_functions(source, "Python");
+    assert_eq!(functions.len(), 2);
+
+    let parse = find(&functions, "parse");
+    assert_eq!((parse.start_line, parse.end_line), (2, 13));
+    assert_eq!(scores(parse), (2, 7, 7, 2));
+
+    let helper = find(&functions, "helper");
+    assert_eq!((helper.start_line, helper.length), (11, 2));
+    assert_eq!(scores(helper), (1, 2, 1, 0));
+}
+
+#[test]
+fn test_brace_language_function_metrics() {
+    let javascript = r#"export function render(items, options) {
+  const visible = items.filter((item) => {
+    return item.active && !item.hidden;
+  });
+  switch (options.mode) {
+    case "list":
+      return visible.length > 0 ? visible : null;
+    case "grid":
+      return visible;
+    default:
+      return [];
+  }
+}
+
+const format = (value) => {
+  return value ?? "";
+};
+
+class View {
+  constructor(root) {
+    this.root = root;
+  }
+
+  update(state) {
+    if (state.dirty) {
+      this.render();
+    }
+  }
+}
+
+describe("view", () => {
+  it("renders", () => {
+    expect(render([], {})).toEqual([]);
+  });
+});
+"#;
+    let functions = analyze_functions(javascript, "JavaScript");
+    let names: Vec<&str> = functions.iter().map(|function| function.name.as_str()).collect();
+    assert_eq!(names, vec!["render", "format", "constructor", "update", "<anonymous>"]);
+    assert_eq!(scores(find(&functions, "render")), (2, 5, 4, 1));
+    assert_eq!(scores(find(&functions, "format")), (1, 1, 0, 0));
+    assert_eq!(scores(find(&functions, "update")), (1, 2, 1, 1));
+
+    let java = r#"public class Orders {
+    @Override
+    public String toString() {
+        return "Orders";
+    }
+
+    @SuppressWarnings("unchecked")
+    public int total(List<Order> orders, boolean includeTax) throws IOException {
+        int sum = 0;
+        for (Order order : orders) {
+            try {
+                sum += order.isValid() ? order.amount() : 0;
+            } catch (IllegalStateException e) {
+                continue;
+            }
+        }
+        do {
+            sum--;
+        } while (sum > 1000);
+        return sum;
+    }
+}
+"#;
+    let functions = analyze_functions(java, "Java");
+    assert_eq!(functions.len(), 2);
+    assert_eq!(scores(find(&functions, "toString")), (0, 1, 0, 0));
+    assert_eq!(scores(find(&functions, "total")), (2, 5, 6, 2));
+
+    let go = "func (s *Server) Handle(w http.ResponseWriter, r *http.Request) {\n\tif r.Method != \"GET\" {\n\t\treturn\n\t}\n\tgo func() {\n\t\ts.log(r)\n\t}()\n}\n";
+    let functions = analyze_functions(go, "Go");
+    assert_eq!(functions.len(), 1);
+    assert_eq!(scores(find(&functions, "Handle")), (2, 2, 1, 1));
+}
+
+#[test]
+fn test_collected_function_metrics() {
+    let dir = tempdir().unwrap();
+    fs::write(dir.path().join("lib.rs"), RUST_SOURCE).unwrap();
+    fs::write(dir.path().join("notes.md"), "# if for while\n").unwrap();
+
+    let metrics = MetricsCollector::new().collect_metrics(dir.path()).unwrap();
+
+    let rust_file = metrics.files.iter().find(|file| file.path.ends_with("lib.rs")).unwrap();
+    assert_eq!(rust_file.functions.len(), 3);
+    let markdown_file = metrics.files.iter().find(|file| file.path.ends_with("notes.md")).unwrap();
+    assert!(markdown_file.functions.is_empty());
+
+    let top: Vec<&str> = metrics
+        .most_complex_functions(2)
+        .iter()
+        .map(|(_, function)| function.name.as_str())
+        .collect();
+    assert_eq!(top, vec!["classify", "factorial"]);
+}
@@ -0,0 +1,107 @@
+use ai_code_analyzer::dependency::coupling::{CouplingSort, TypeCounts, analyze_coupling, count_type_declarations};
+use ai_code_analyzer::dependency::dependency_graph::DependencyGraph;
+use std::collections::HashMap;
+
+fn counts(abstract_types: usize, concrete_types: usize) -> Option<TypeCounts> {
+    Some(TypeCounts { abstract_types, concrete_types })
+}
+
+#[test]
+fn test_count_type_declarations() {
+    let rust = r#"
+pub trait Store { fn get(&self) -> u32; }
+pub(crate) struct Memory<'a> { data: &'a [u8] }
+enum Mode { A, B }
+unsafe impl Send for Memory<'_> {}
+// struct Commented;
+"#;
+    assert_eq!(count_type_declarations("Rust", rust), counts(1, 2));
+
+    let java = r#"
+public interface Repository {}
+public abstract class BaseRepository implements Repository {
+    abstract void save();
+}
+public final class OrderRepository extends BaseRepository { Class<?> type = String.class; }
+enum Status { ACTIVE }
+"#;
+    assert_eq!(count_type_declarations("Java", java), counts(2, 2));
+
+    let go = "type Store interface {\n}\n\ntype (\n    Memory struct{}\n    ID string\n)\n";
+    assert_eq!(count_type_declarations("Go", go), counts(1, 1));
+
+    let python = "from abc import ABC\n\nclass Base(ABC):\n    pass\n\nclass Reader(Protocol):\n    pass\n\nclass Impl(Base):\n    pass\n";
+    assert_eq!(count_type_declarations("Python", python), counts(2, 1));
+
+    let cpp = r#"
+template <class T> class Shape {
+public:
+    virtual double area() const = 0;
+    int sides = 0;
+};
+struct Point { int x = 0; };
+class Forward;
+enum class Color { Red };
+"#;
+    assert_eq!(count_type_declarations("C++", cpp), counts(1, 1));
+
+    assert_eq!(count_type_declarations("JavaScript", "class A {}"), None);
+}
+
+#[test]
+fn test_coupling_per_file_and_directory() {
+    let mut graph = DependencyGraph::new();
+    for node in ["core/store.rs", "core/model.rs", "app/main.rs", "app/cli.rs"] {
+        graph.add_node(node);
+    }
+    graph.add_external_node("serde");
+    graph.add_edge("app/main.rs", "core/store.rs");
+    graph.add_edge("app/main.rs", "app/cli.rs");
+    graph.add_edge("app/cli.rs", "core/model.rs");
+    graph.add_edge("core/store.rs", "core/model.rs");
+    graph.add_edge("core/model.rs", "serde");
+
+    let contents: HashMap<&str, &str> = HashMap::from([
+        ("core/store.rs", "pub trait Store {}\n"),
+        ("core/model.rs", "pub struct Order;\n"),
+        ("app/main.rs", "fn main() {}\n"),
+        ("app/cli.rs", "struct Args;\n"),
+    ]);
+    let load = |path: &str| contents.get(path).map(|content| content.to_string());
+
+    let mut report = analyze_coupling(&graph, &load);
+
+    let model = report.files.iter().find(|metrics| metrics.name == "core/model.rs").unwrap();
+    assert_eq!((model.afferent, model.efferent), (2, 1));
+    assert!((model.instability() - 1.0 / 3.0).abs() < 1e-9);
+    assert_eq!(model.abstractness(), Some(0.0));
+
+    let store = report.files.iter().find(|metrics| metrics.name == "core/store.rs").unwrap();
+    assert_eq!(store.abstractness(), Some(1.0));
+    assert!((store.distance().unwrap() - 0.5).abs() < 1e-9);
+
+    let main = report.files.iter().find(|metrics| metrics.name == "app/main.rs").unwrap();
+    assert_eq!(main.abstractness(), None);
+    assert_eq!(main.distance(), None);
+
+    // Edges inside a directory do not count towards its coupling
+    let core = report.directories.iter().find(|metrics| metrics.name == "core").unwrap();
+    assert_eq!((core.files, core.afferent, core.efferent), (2, 2, 1));
+    assert_eq!(core.abstractness(), Some(0.5));
+
+    let app = report.directories.iter().find(|metrics| metrics.name == "app").unwrap();
+    assert_eq!((app.afferent, app.efferent), (0, 2));
+
+    // Default order is by instability, most unstable first
+    assert_eq!(report.directories[0].name, "app");
+
+    report.sort_by("ca".parse::<CouplingSort>().unwrap());
+    assert_eq!(report.files[0].name, "core/model.rs");
+
+    // Ties fall back to the name and files without abstractness come last
+    report.sort_by(CouplingSort::Distance);
+    let order: Vec<&str> = report.files.iter().map(|metrics| metrics.name.as_str()).collect();
+    assert_eq!(order, vec!["core/model.rs", "app/cli.rs", "core/store.rs", "app/main.rs"]);
+
+    assert!("fragility".parse::<CouplingSort>().is_err());
+}
@@ -0,0 +1,190 @@
+use super::support::write_file;
+use ai_code_analyzer::dependency::cargo_manifest::{CargoDependencyKind, CargoWorkspace};
+use ai_code_analyzer::dependency::crate_analyzer::CrateDependencyAnalyzer;
+use ai_code_analyzer::dependency::graph_export::GraphExporter;
+use std::path::Path;
+use tempfile::tempdir;
+
+fn create_workspace(root: &Path) {
+    write_file(root, "Cargo.toml", r#"
+[workspace]
+members = ["crates/*", "tools/cli"]
+exclude = ["crates/experimental"]
+
+[workspace.dependencies]
+serde = "1.0"
+core-types = { path = "crates/core" }
+"#);
+
+    write_file(root, "crates/core/Cargo.toml", r#"
+[package]
+name = "core-types"
+version = "0.1.0"
+
+[dependencies]
+serde = { workspace = true }
+
+[dev-dependencies]
+app-server = { path = "../server" }
+"#);
+
+    write_file(root, "crates/server/Cargo.toml", r#"
+[package]
+name = "app-server"
+version = "0.1.0"
+
+[features]
+default = ["metrics"]
+metrics = ["dep:prometheus"]
+tracing = ["dep:tracing"]
+
+[dependencies]
+core-types = { workspace = true }
+prometheus = { version = "0.13", optional = true }
+tracing = { version = "0.1", optional = true }
+
+[build-dependencies]
+cc = "1.0"
+"#);
+
+    write_file(root, "crates/experimental/Cargo.toml", r#"
+[package]
+name = "experimental"
+version = "0.0.1"
+"#);
+
+    write_file(root, "tools/cli/Cargo.toml", r#"
+[package]
+name = "cli"
+version = "0.1.0"
+
+[dependencies]
+server = { package = "app-server", path = "../../crates/server" }
+helpers = { path = "../../vendor/helpers" }
+
+[target.'cfg(unix)'.dependencies]
+libc = "0.2"
+"#);
+
+    write_file(root, "vendor/helpers/Cargo.toml", r#"
+[package]
+name = "helpers"
+version = "0.1.0"
+"#);
+
+    write_file(root, "Cargo.lock", r#"
+version = 3
+
+[[package]]
+name = "app-server"
+version = "0.1.0"
+dependencies = ["cc", "core-types", "prometheus", "tracing"]
+
+[[package]]
+name = "cli"
+version = "0.1.0"
+dependencies = ["app-server", "helpers", "libc 0.2.150"]
+
+[[package]]
+name = "libc"
+version = "0.2.150"
+source = "registry+https://github.com/rust-lang/crates.io-index"
+
+[[package]]
+name = "libc"
+version = "0.1.12"
+source = "registry+https://github.com/rust-lang/crates.io-index"
+
+[[package]]
+name = "serde"
+version = "1.0.197"
+source = "registry+https://github.com/rust-lang/crates.io-index"
+"#);
+}
+
+#[test]
+fn test_workspace_members_and_path_dependencies() {
+    let dir = tempdir().unwrap();
+    create_workspace(dir.path());
+
+    let workspace = CargoWorkspace::load(dir.path()).unwrap();
+    let names: Vec<&str> = workspace.members.iter().map(|member| member.name.as_str()).collect();
+
+    assert_eq!(names, vec!["app-server", "cli", "core-types", "helpers"]);
+    assert!(workspace.lock.is_some());
+
+    let server = workspace.member("app-server").unwrap();
+    let cc = server.dependencies.iter().find(|d| d.name == "cc").unwrap();
+    assert_eq!(cc.kind, CargoDependencyKind::Build);
+
+    let cli = workspace.member("cli").unwrap();
+    let renamed = cli.dependencies.iter().find(|d| d.alias == "server").unwrap();
+    assert_eq!(renamed.name, "app-server");
+}
+
+#[test]
+fn test_crate_graph_edges() {
+    let dir = tempdir().unwrap();
+    create_workspace(dir.path());
+
+    let graph = CrateDependencyAnalyzer::new().analyze_crates(dir.path()).unwrap();
+
+    assert_eq!(graph.get_internal_nodes().len(), 4);
+    assert!(graph.get_dependencies("cli").contains(&"app-server".to_string()));
+    assert!(graph.get_dependencies("cli").contains(&"helpers".to_string()));
+    assert!(graph.get_dependencies("cli").contains(&"libc@0.2.150".to_string()));
+    assert!(graph.get_dependencies("core-types").contains(&"serde@1.0.197".to_string()));
+    assert!(graph.is_external("serde@1.0.197"));
+
+    // Optional dependencies only count when a default feature enables them
+    let server_dependencies = graph.get_dependencies("app-server");
+    assert!(server_dependencies.contains(&"prometheus".to_string()));
+    assert!(!server_dependencies.contains(&"tracing".to_string()));
+    assert!(server_dependencies.contains(&"cc".to_string()));
+
+    // Dev-dependencies are excluded by default, so the dev-only back edge does not form a cycle
+    assert!(graph.find_circular_dependencies().is_empty());
+}
+
+#[test]
+fn test_crate_graph_dot_quotes_ids() {
+    let dir = tempdir().unwrap();
+    create_workspace(dir.path());
+
+    let graph = CrateDependencyAnalyzer::new().analyze_crates(dir.path()).unwrap();
+    let dot = GraphExporter::new(&graph).to_dot();
+
+    assert!(dot.contains("  \"serde@1.0.197\" [label=\"serde@1.0.197\", shape=ellipse, fillcolor=lightgrey, external=true, "));
+    assert!(dot.contains("  \"core-types\" -> \"serde@1.0.197\""));
+
+    let statements = dot.lines().filter(|line| line.contains(" [label=") || line.contains(" -> "));
+    for statement in statements {
+        let ids = statement.trim().split(" [").next().unwrap().trim_end_matches(';');
+        for id in ids.split(" -> ") {
+            assert!(id.len() > 1 && id.starts_with('"') && id.ends_with('"'), "unquoted id in {}", statement);
+        }
+    }
+}
+
+#[test]
+fn test_crate_graph_options() {
+    let dir = tempdir().unwrap();
+    create_workspace(dir.path());
+
+    let graph = CrateDependencyAnalyzer::new()
+        .include_dev_dependencies(true)
+        .include_external(false)
+        .all_features(true)
+        .analyze_crates(dir.path())
+        .unwrap();
+
+    assert!(graph.get_external_nodes().is_empty());
+    assert!(graph.get_dependencies("core-types").contains(&"app-server".to_string()));
+    assert!(!graph.find_circular_dependencies().is_empty());
+}
+
+#[test]
+fn test_missing_manifest_is_an_error() {
+    let dir = tempdir().unwrap();
+    assert!(CrateDependencyAnalyzer::new().analyze_crates(dir.path()).is_err());
+}
@@ -0,0 +1,83 @@
+use super::support::{analyze, write_file};
+use ai_code_analyzer::dependency::dead_files::{DeadFileAnalysis, DeadFileReport};
+use ai_code_analyzer::metrics::collector::MetricsCollector;
+use ai_code_analyzer::util::parallel::ParallelProcessing;
+use std::path::Path;
+use tempfile::tempdir;
+
+fn write_project(root: &Path) {
+    write_file(root, "package.json", r#"{ "name": "shop", "main": "src/server.js", "bin": { "shop": "bin/cli.js" } }"#);
+    write_file(root, "src/server.js", "import { routes } from './routes';\n");
+    write_file(root, "src/routes.js", "export const routes = [];\n");
+    write_file(root, "bin/cli.js", "const util = require('../src/util');\n");
+    write_file(root, "src/util.js", "module.exports = {};\n");
+    write_file(root, "src/fixtures.js", "export const fixtures = [];\n");
+    write_file(root, "src/__tests__/routes.test.js", "import { routes } from '../routes';\nimport { fixtures } from '../fixtures';\n");
+    write_file(root, "vite.config.js", "export default {};\n");
+    write_file(root, "src/legacy.js", "import { helper } from './legacy_helper';\n\n// old checkout flow\nexport function checkout() {\n  return helper();\n}\n");
+    write_file(root, "src/legacy_helper.js", "export function helper() {}\n");
+
+    write_file(root, "cmd/app/main.go", "package main\n\nfunc main() {}\n");
+    write_file(root, "cmd/app/flags.go", "package main\n\nvar verbose bool\n");
+    write_file(root, "scripts/migrate.py", "def run():\n    pass\n\nif __name__ == \"__main__\":\n    run()\n");
+    write_file(root, "scripts/unused.py", "def helper():\n    pass\n");
+}
+
+fn dead_paths(report: &DeadFileReport, root: &Path) -> Vec<(String, bool)> {
+    let mut dead: Vec<(String, bool)> = report
+        .dead_files
+        .iter()
+        .map(|file| (Path::new(&file.path).strip_prefix(root).unwrap().to_string_lossy().to_string(), file.orphan))
+        .collect();
+    dead.sort();
+    dead
+}
+
+#[test]
+fn test_detected_entry_points() {
+    let dir = tempdir().unwrap();
+    let root = dir.path();
+    write_project(root);
+
+    let graph = analyze(root);
+    let metrics = MetricsCollector::new()
+        .enable_parallel_processing(false)
+        .collect_metrics(root)
+        .unwrap();
+    let report = DeadFileAnalysis::new().run(&graph, root, &metrics.files);
+
+    assert_eq!(dead_paths(&report, root), vec![
+        ("scripts/unused.py".to_string(), true),
+        ("src/legacy.js".to_string(), true),
+        ("src/legacy_helper.js".to_string(), false),
+    ]);
+
+    // Largest first, with sizes from the metrics collector
+    assert!(report.dead_files[0].path.ends_with("src/legacy.js"));
+    assert_eq!(report.dead_files[0].metrics.as_ref().unwrap().lines_of_code, 4);
+    assert_eq!(report.dead_lines_of_code(), 7);
+    assert_eq!(report.reachable_files, 10);
+}
+
+#[test]
+fn test_overridden_entry_points() {
+    let dir = tempdir().unwrap();
+    let root = dir.path();
+    write_project(root);
+
+    let graph = analyze(root);
+    let report = DeadFileAnalysis::new()
+        .entry_points(vec!["src/legacy.js".to_string(), "cmd/**".to_string()])
+        .run(&graph, root, &[]);
+
+    let dead: Vec<String> = dead_paths(&report, root).into_iter().map(|(path, _)| path).collect();
+    assert_eq!(dead, vec![
+        "bin/cli.js",
+        "scripts/migrate.py",
+        "scripts/unused.py",
+        "src/server.js",
+        "src/util.js",
+        "vite.config.js",
+    ]);
+    assert!(report.dead_files.iter().all(|file| file.metrics.is_none()));
+}
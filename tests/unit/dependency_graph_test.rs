@@ -1,4 +1,19 @@
+use ai_code_analyzer::dependency::cycle_analysis::find_dependency_cycles;
 use ai_code_analyzer::dependency::dependency_graph::DependencyGraph;
+use ai_code_analyzer::dependency::dependency_reporter::DependencyReporter;
+use ai_code_analyzer::dependency::graph_export::GraphExporter;
+
+fn graph_with_edges(edges: &[(&str, &str)]) -> DependencyGraph {
+    let mut graph = DependencyGraph::new();
+    for (from, to) in edges {
+        graph.add_node(from);
+        graph.add_node(to);
+    }
+    for (from, to) in edges {
+        graph.add_edge(from, to);
+    }
+    graph
+}
 
 #[test]
 fn test_dependency_graph_creation() {
@@ -86,13 +101,133 @@ fn test_dot_format() {
     graph.add_node("file2.rs");
     graph.add_edge("file1.rs", "file2.rs");
     
-    let dot = graph.to_dot_format();
+    let dot = GraphExporter::new(&graph).to_dot();
     
     assert!(dot.starts_with("digraph DependencyGraph {"));
     assert!(dot.ends_with("}\n"));
     
-    assert!(dot.contains("file1_rs"));
-    assert!(dot.contains("file2_rs"));
+    assert!(dot.contains("  \"file1.rs\" [label=\"file1.rs\", language=\"Rust\", test=false];\n"));
+    assert!(dot.contains("  \"file2.rs\" [label=\"file2.rs\", language=\"Rust\", test=false];\n"));
+    
+    assert!(dot.contains("\"file1.rs\" -> \"file2.rs\";"));
+}
+
+#[test]
+fn test_dot_format_quotes_ids() {
+    let mut graph = DependencyGraph::new();
+    
+    graph.add_node("src/a.b.rs");
+    graph.add_node("src/a_b.rs");
+    graph.add_node("src/say \"hi\".js");
+    graph.add_external_node("@scope/pkg");
+    graph.add_edge("src/a.b.rs", "@scope/pkg");
+    graph.add_edge("src/a_b.rs", "src/a.b.rs");
+    
+    let dot = GraphExporter::new(&graph).to_dot();
     
-    assert!(dot.contains("file1_rs -> file2_rs"));
+    assert!(dot.contains("  \"@scope/pkg\" [label=\"@scope/pkg\", shape=ellipse, fillcolor=lightgrey, external=true, test=false];\n"));
+    assert!(dot.contains("  \"src/a.b.rs\" [label=\"a.b.rs\", "));
+    assert!(dot.contains("  \"src/a_b.rs\" [label=\"a_b.rs\", "));
+    assert!(dot.contains("  \"src/say \\\"hi\\\".js\" [label=\"say \\\"hi\\\".js\", "));
+    assert!(dot.contains("  \"src/a.b.rs\" -> \"@scope/pkg\";\n"));
+    assert!(dot.contains("  \"src/a_b.rs\" -> \"src/a.b.rs\";\n"));
+    
+    // Paths that looked alike once punctuation was replaced stay separate nodes
+    assert_eq!(dot.lines().filter(|line| line.contains(" [label=")).count(), 4);
+}
+
+#[test]
+fn test_external_nodes() {
+    let mut graph = DependencyGraph::new();
+    
+    graph.add_node("src/main.rs");
+    graph.add_external_node("serde");
+    graph.add_edge("src/main.rs", "serde");
+    
+    assert_eq!(graph.get_nodes().len(), 2);
+    assert!(graph.is_external("serde"));
+    assert!(!graph.is_external("src/main.rs"));
+    assert_eq!(graph.get_internal_nodes(), vec!["src/main.rs".to_string()]);
+    assert!(graph.get_dependents("serde").contains(&"src/main.rs".to_string()));
+    
+    let dot = GraphExporter::new(&graph).to_dot();
+    assert!(dot.contains("\"serde\" [label=\"serde\", shape=ellipse, fillcolor=lightgrey, external=true, test=false];"));
+}
+
+#[test]
+fn test_overlapping_cycles_are_reported_as_one_tangle() {
+    // A <-> B and B <-> C share B, and D only points into the tangle
+    let graph = graph_with_edges(&[
+        ("A", "B"),
+        ("B", "A"),
+        ("B", "C"),
+        ("C", "B"),
+        ("C", "A"),
+        ("D", "A"),
+    ]);
+
+    let cycles = find_dependency_cycles(&graph);
+    assert_eq!(cycles.len(), 1);
+
+    let tangle = &cycles[0];
+    assert_eq!(tangle.members, vec!["A", "B", "C"]);
+    assert_eq!(tangle.internal_edges, 5);
+    assert!(!tangle.suggested_removals.is_empty());
+    assert!(tangle.suggested_removals.len() < tangle.internal_edges);
+
+    // Dropping the suggested edges leaves no cycle behind
+    let remaining: Vec<(&str, &str)> = [("A", "B"), ("B", "A"), ("B", "C"), ("C", "B"), ("C", "A"), ("D", "A")]
+        .into_iter()
+        .filter(|(from, to)| {
+            !tangle.suggested_removals.contains(&(from.to_string(), to.to_string()))
+        })
+        .collect();
+    assert!(graph_with_edges(&remaining).find_circular_dependencies().is_empty());
+}
+
+#[test]
+fn test_cycle_report_details() {
+    let graph = graph_with_edges(&[
+        ("a.rs", "b.rs"),
+        ("b.rs", "c.rs"),
+        ("c.rs", "a.rs"),
+        ("d.rs", "d.rs"),
+        ("e.rs", "a.rs"),
+    ]);
+
+    let cycles = find_dependency_cycles(&graph);
+    assert_eq!(cycles.len(), 2);
+
+    assert_eq!(cycles[0].members, vec!["a.rs", "b.rs", "c.rs"]);
+    assert_eq!(cycles[0].internal_edges, 3);
+    assert_eq!(cycles[0].suggested_removals.len(), 1);
+
+    // A file importing itself is a tangle whose only fix is that edge
+    assert_eq!(cycles[1].members, vec!["d.rs"]);
+    assert_eq!(cycles[1].suggested_removals, vec![("d.rs".to_string(), "d.rs".to_string())]);
+
+    let markdown = DependencyReporter::new().format_markdown(&graph);
+    assert!(markdown.contains("## Circular Dependencies"));
+    assert!(markdown.contains("### By File"));
+    assert!(markdown.contains("### Tangle 1: 3 files, 3 internal edges"));
+    assert!(markdown.contains("- `b.rs`"));
+    assert!(markdown.contains("| `d.rs` | `d.rs` |"));
+    assert!(!markdown.contains("- `e.rs`"));
+}
+
+#[test]
+fn test_long_cycle_does_not_overflow() {
+    let names: Vec<String> = (0..50_000).map(|i| format!("file_{}", i)).collect();
+    let mut graph = DependencyGraph::new();
+    for name in &names {
+        graph.add_node(name);
+    }
+    for pair in names.windows(2) {
+        graph.add_edge(&pair[0], &pair[1]);
+    }
+    graph.add_edge(&names[names.len() - 1], &names[0]);
+
+    let cycles = graph.find_circular_dependencies();
+    assert_eq!(cycles.len(), 1);
+    assert_eq!(cycles[0].len(), names.len());
 }
@@ -75,9 +75,10 @@ def main():
 
     assert_eq!(metrics.language, "Python");
     assert_eq!(metrics.files, 1);
-    assert_eq!(metrics.lines_of_code, 7);
+    // The docstring stands alone as a statement, so its lines are comments
+    assert_eq!(metrics.lines_of_code, 3);
     assert_eq!(metrics.blank_lines, 2);
-    assert_eq!(metrics.comment_lines, 2);
+    assert_eq!(metrics.comment_lines, 6);
 }
 
 #[test]
@@ -0,0 +1,126 @@
+use super::support::{analyze, commit_all, init_repository, write_file};
+use ai_code_analyzer::commands::dependency_diff::format_markdown;
+use ai_code_analyzer::dependency::dependency_graph::DependencyGraph;
+use ai_code_analyzer::dependency::graph_diff::{diff_graphs, relative_to};
+use ai_code_analyzer::util::git;
+use std::fs;
+use std::path::Path;
+use tempfile::tempdir;
+
+fn relative_graph(root: &Path) -> DependencyGraph {
+    relative_to(&analyze(root), root)
+}
+
+fn write_base(root: &Path) {
+    write_file(root, "src/app.js", "import { a } from './core/a';\nimport { b } from './util/b';\n");
+    write_file(root, "src/core/a.js", "import { b } from '../util/b';\nexport const a = 1;\n");
+    write_file(root, "src/util/b.js", "import { a } from '../core/a';\nexport const b = 2;\n");
+}
+
+fn edge(from: &str, to: &str) -> (String, String) {
+    (from.to_string(), to.to_string())
+}
+
+#[test]
+fn test_diff_directories() {
+    let base = tempdir().unwrap();
+    let head = tempdir().unwrap();
+    write_base(base.path());
+    write_base(head.path());
+
+    // The head breaks the a <-> b tangle, introduces a new one and adds a file
+    write_file(head.path(), "src/util/b.js", "import { c } from './c';\nexport const b = 2;\n");
+    write_file(head.path(), "src/util/c.js", "import { b } from './b';\nexport const c = 3;\n");
+
+    let diff = diff_graphs(&relative_graph(base.path()), &relative_graph(head.path()), &|_| None, &|_| None);
+
+    assert_eq!(diff.added_files, vec!["src/util/c.js"]);
+    assert!(diff.removed_files.is_empty());
+    assert_eq!(diff.added_edges, vec![edge("src/util/b.js", "src/util/c.js"), edge("src/util/c.js", "src/util/b.js")]);
+    assert_eq!(diff.removed_edges, vec![edge("src/util/b.js", "src/core/a.js")]);
+
+    assert_eq!(diff.broken_cycles.len(), 1);
+    assert_eq!(diff.broken_cycles[0].members, vec!["src/core/a.js", "src/util/b.js"]);
+    assert_eq!(diff.new_cycles.len(), 1);
+    assert_eq!(diff.new_cycles[0].members, vec!["src/util/b.js", "src/util/c.js"]);
+    assert_eq!((diff.before.tangles, diff.after.tangles), (1, 1));
+    assert_eq!((diff.before.files, diff.after.files), (3, 4));
+
+    // src/util no longer depends on src/core, so it became fully stable
+    let util = diff.directory_changes.iter().find(|change| change.name == "src/util").unwrap();
+    assert_eq!(util.before.as_ref().unwrap().efferent, 1);
+    assert_eq!(util.after.as_ref().unwrap().efferent, 0);
+    assert_eq!(diff.directory_changes[0].name, "src/util");
+    assert!(diff.file_changes.iter().any(|change| change.name == "src/util/c.js" && change.before.is_none()));
+    assert!(diff.file_changes.iter().all(|change| change.name != "src/app.js"));
+}
+
+#[test]
+fn test_unchanged_tree() {
+    let dir = tempdir().unwrap();
+    write_base(dir.path());
+
+    let graph = relative_graph(dir.path());
+    let diff = diff_graphs(&graph, &graph, &|_| None, &|_| None);
+
+    assert!(diff.is_empty());
+    assert!(diff.new_cycles.is_empty() && diff.broken_cycles.is_empty());
+    assert_eq!(diff.before, diff.after);
+}
+
+#[test]
+fn test_markdown_shows_only_visible_changes() {
+    let base = tempdir().unwrap();
+    let head = tempdir().unwrap();
+    write_base(base.path());
+    write_base(head.path());
+
+    // A file nobody imports leaves the coupling of src/core as it was
+    write_file(head.path(), "src/core/notes.js", "export const notes = [];\n");
+    write_file(head.path(), "src/extra/report.js", "export const report = {};\n");
+
+    let diff = diff_graphs(&relative_graph(base.path()), &relative_graph(head.path()), &|_| None, &|_| None);
+    assert!(diff.directory_changes.iter().all(|change| change.name != "src/core"));
+
+    let markdown = format_markdown(&diff, "`base`", "`head`");
+    assert!(markdown.contains("| `src/extra` | 0 (added) | 0 (added) | 0.00 (added) |"), "{}", markdown);
+    assert!(!markdown.contains("| `src/core` |"));
+    assert!(!markdown.contains("- → -"));
+}
+
+#[test]
+fn test_export_revision() {
+    let dir = tempdir().unwrap();
+    let root = dir.path();
+    if !init_repository(root) {
+        eprintln!("Skipping: git is not available");
+        return;
+    }
+
+    write_base(root);
+    write_file(root, "README.md", "# Shop\n");
+    commit_all(root, "Initial");
+
+    // Uncommitted changes stay in the working tree and out of the export
+    write_file(root, "src/util/b.js", "export const b = 2;\n");
+
+    let checkout = git::export_revision(root, "HEAD").unwrap();
+    assert!(checkout.path().join("README.md").exists());
+    assert_eq!(
+        fs::read_to_string(checkout.path().join("src/util/b.js")).unwrap(),
+        "import { a } from '../core/a';\nexport const b = 2;\n"
+    );
+    assert_eq!(fs::read_to_string(root.join("src/util/b.js")).unwrap(), "export const b = 2;\n");
+
+    // A subdirectory exports just that subdirectory
+    let subdirectory = git::export_revision(root.join("src"), "HEAD").unwrap();
+    assert!(subdirectory.path().join("app.js").exists());
+    assert!(!subdirectory.path().join("README.md").exists());
+
+    let diff = diff_graphs(&relative_graph(checkout.path()), &relative_graph(root), &|_| None, &|_| None);
+    assert_eq!(diff.removed_edges, vec![edge("src/util/b.js", "src/core/a.js")]);
+    assert_eq!(diff.broken_cycles.len(), 1);
+
+    assert!(git::export_revision(root, "no-such-branch").is_err());
+    assert!(git::export_revision(root, "--remote=origin").is_err());
+}
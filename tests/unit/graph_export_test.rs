@@ -0,0 +1,147 @@
+use super::support::write_file;
+use ai_code_analyzer::dependency::c_resolver::extract_c_includes;
+use ai_code_analyzer::dependency::csharp_resolver::{extract_csharp_dependencies, extract_msbuild_dependencies};
+use ai_code_analyzer::dependency::dependency_analyzer::DependencyAnalyzer;
+use ai_code_analyzer::dependency::dependency_graph::{DependencyGraph, EdgeImport, ImportKind};
+use ai_code_analyzer::dependency::graph_export::{GraphExporter, GraphFormat};
+use ai_code_analyzer::dependency::go_resolver::extract_go_dependencies;
+use ai_code_analyzer::dependency::js_resolver::extract_js_dependencies;
+use ai_code_analyzer::dependency::python_resolver::extract_python_dependencies;
+use ai_code_analyzer::dependency::rust_resolver::extract_rust_dependencies;
+use ai_code_analyzer::metrics::models::FileMetrics;
+use ai_code_analyzer::util::parallel::ParallelProcessing;
+use tempfile::tempdir;
+
+fn sample_graph() -> DependencyGraph {
+    let mut graph = DependencyGraph::new();
+    graph.add_node("src/main.rs");
+    graph.add_node("src/a&b.rs");
+    graph.add_node("tests/cli_test.rs");
+    graph.add_external_node("serde");
+    graph.add_edge_with_import("src/main.rs", "src/a&b.rs", EdgeImport {
+        specifier: "crate::a_b".to_string(),
+        line: Some(3),
+        kind: Some(ImportKind::Module),
+    });
+    graph.add_edge_with_import("src/main.rs", "serde", EdgeImport {
+        specifier: "serde::Serialize, \"derive\"".to_string(),
+        line: Some(1),
+        kind: Some(ImportKind::Use),
+    });
+    graph.add_edge("tests/cli_test.rs", "src/main.rs");
+    graph
+}
+
+#[test]
+fn test_extractors_record_import_kind() {
+    let rust = "use std::fmt;\npub(crate) mod model;\nuse crate::{\n    store::Cache,\n};\nextern crate serde;\n";
+    assert_eq!(extract_rust_dependencies(rust), vec![
+        EdgeImport::new("std::fmt", 1, ImportKind::Use),
+        EdgeImport::new("self::model", 2, ImportKind::Module),
+        EdgeImport::new("crate::store::Cache", 4, ImportKind::Use),
+        EdgeImport::new("serde", 6, ImportKind::ExternCrate),
+    ]);
+
+    let js = "import {\n  a,\n} from './a';\nexport * from './b';\nconst c = require('./c');\nconst d = await import('./d');\n";
+    assert_eq!(extract_js_dependencies(js), vec![
+        EdgeImport::new("./a", 3, ImportKind::Import),
+        EdgeImport::new("./b", 4, ImportKind::ReExport),
+        EdgeImport::new("./c", 5, ImportKind::Require),
+        EdgeImport::new("./d", 6, ImportKind::DynamicImport),
+    ]);
+
+    let project = "<Project>\n  <ItemGroup>\n    <ProjectReference Include=\"..\\Core\\Core.csproj\" />\n    <PackageReference Include=\"Serilog\" />\n  </ItemGroup>\n</Project>\n";
+    assert_eq!(extract_msbuild_dependencies(project), vec![
+        EdgeImport::new("../Core/Core.csproj", 3, ImportKind::ProjectReference),
+        EdgeImport::new("Serilog", 4, ImportKind::PackageReference),
+    ]);
+
+    assert_eq!(extract_c_includes("#include \"config.h\"\n"), vec![EdgeImport::new("config.h", 1, ImportKind::Include)]);
+    assert_eq!(extract_csharp_dependencies("using System.Text;\n"), vec![EdgeImport::new("System.Text", 1, ImportKind::Using)]);
+    assert_eq!(extract_go_dependencies("import (\n    \"fmt\"\n)\n"), vec![EdgeImport::new("fmt", 2, ImportKind::Import)]);
+    assert_eq!(extract_python_dependencies("from .models import Order\n"), vec![EdgeImport::new(".models.Order", 1, ImportKind::Import)]);
+}
+
+#[test]
+fn test_serde_round_trip() {
+    let graph = sample_graph();
+
+    let json = serde_json::to_string(&graph).unwrap();
+    let restored: DependencyGraph = serde_json::from_str(&json).unwrap();
+
+    assert_eq!(serde_json::to_string(&restored).unwrap(), json);
+    assert!(restored.is_external("serde"));
+    assert!(restored.has_edge("tests/cli_test.rs", "src/main.rs"));
+    assert_eq!(restored.get_edge_import("src/main.rs", "src/a&b.rs").unwrap().line, Some(3));
+}
+
+#[test]
+fn test_export_formats() {
+    let graph = sample_graph();
+    let files = vec![FileMetrics {
+        path: "./src/main.rs".to_string(),
+        language: "Rust".to_string(),
+        lines_of_code: 42,
+        ..Default::default()
+    }];
+    let exporter = GraphExporter::new(&graph).file_metrics(&files);
+
+    let json: serde_json::Value = serde_json::from_str(&exporter.render(GraphFormat::Json).unwrap()).unwrap();
+    let nodes = json["nodes"].as_array().unwrap();
+    assert_eq!(nodes.len(), 4);
+    let main = nodes.iter().find(|node| node["id"] == "src/main.rs").unwrap();
+    assert_eq!(main["language"], "Rust");
+    assert_eq!(main["lines_of_code"], 42);
+    assert_eq!(main["test"], false);
+    let test = nodes.iter().find(|node| node["id"] == "tests/cli_test.rs").unwrap();
+    assert_eq!(test["test"], true);
+    assert_eq!(json["edges"][1]["import"]["kind"], "module");
+    assert_eq!(json["edges"][1]["import"]["line"], 3);
+
+    let graphml = exporter.render(GraphFormat::GraphMl).unwrap();
+    assert!(graphml.contains("<node id=\"src/a&amp;b.rs\">"));
+    assert!(graphml.contains("<data key=\"loc\">42</data>"));
+    assert!(graphml.contains("<edge id=\"e1\" source=\"src/main.rs\" target=\"src/a&amp;b.rs\">"));
+
+    let gexf = exporter.render(GraphFormat::Gexf).unwrap();
+    assert!(gexf.contains("<attvalue for=\"kind\" value=\"use\"/>"));
+    assert!(gexf.contains("<edge id=\"2\" source=\"tests/cli_test.rs\" target=\"src/main.rs\"/>"));
+
+    let dot = exporter.render(GraphFormat::Dot).unwrap();
+    assert!(dot.contains("  \"src/main.rs\" [label=\"main.rs\", language=\"Rust\", lines_of_code=42, test=false];\n"));
+    assert!(dot.contains("  \"serde\" [label=\"serde\", shape=ellipse, fillcolor=lightgrey, external=true, test=false];\n"));
+    assert!(dot.contains("  \"tests/cli_test.rs\" [label=\"cli_test.rs\", language=\"Rust\", test=true];\n"));
+    assert!(dot.contains("  \"src/main.rs\" -> \"serde\" [kind=\"use\", specifier=\"serde::Serialize, \\\"derive\\\"\", line=1];\n"));
+    assert!(dot.contains("  \"tests/cli_test.rs\" -> \"src/main.rs\";\n"));
+
+    assert_eq!(exporter.render(GraphFormat::Csv).unwrap(), concat!(
+        "source,target,kind,specifier,line\n",
+        "src/main.rs,serde,use,\"serde::Serialize, \"\"derive\"\"\",1\n",
+        "src/main.rs,src/a&b.rs,module,crate::a_b,3\n",
+        "tests/cli_test.rs,src/main.rs,,,\n",
+    ));
+
+    assert!("yaml".parse::<GraphFormat>().is_err());
+    assert_eq!("GraphML".parse::<GraphFormat>(), Ok(GraphFormat::GraphMl));
+}
+
+#[test]
+fn test_analyzer_records_import_kind() {
+    let dir = tempdir().unwrap();
+    let root = dir.path();
+    write_file(root, "src/app.js", "import { a } from './a';\nconst b = require('./b');\n");
+    write_file(root, "src/a.js", "export const a = 1;\n");
+    write_file(root, "src/b.js", "module.exports = {};\n");
+
+    let graph = DependencyAnalyzer::new()
+        .enable_parallel_processing(false)
+        .analyze_dependencies(root)
+        .unwrap();
+
+    let app = root.join("src/app.js").to_string_lossy().to_string();
+    let a = root.join("src/a.js").to_string_lossy().to_string();
+    let b = root.join("src/b.js").to_string_lossy().to_string();
+    assert_eq!(graph.get_edge_import(&app, &a).unwrap().kind, Some(ImportKind::Import));
+    assert_eq!(graph.get_edge_import(&app, &b).unwrap().kind, Some(ImportKind::Require));
+    assert_eq!(graph.get_edge_import(&app, &b).unwrap().line, Some(2));
+}
@@ -0,0 +1,80 @@
+use ai_code_analyzer::metrics::collector::MetricsCollector;
+use ai_code_analyzer::metrics::halstead::{HalsteadMetrics, analyze_halstead, maintainability_index};
+use ai_code_analyzer::metrics::reporter::MetricsReporter;
+use std::fs;
+use tempfile::tempdir;
+
+fn counts(metrics: &HalsteadMetrics) -> (usize, usize, usize, usize) {
+    (
+        metrics.distinct_operators,
+        metrics.distinct_operands,
+        metrics.total_operators,
+        metrics.total_operands,
+    )
+}
+
+#[test]
+fn test_halstead_measures() {
+    let metrics = HalsteadMetrics::from_counts(3, 2, 5, 4);
+    assert_eq!((metrics.vocabulary, metrics.length), (5, 9));
+    assert!((metrics.volume - 9.0 * 5f64.log2()).abs() < 1e-9);
+    assert!((metrics.difficulty - 3.0).abs() < 1e-9);
+    assert!((metrics.effort - 3.0 * metrics.volume).abs() < 1e-9);
+
+    let index = maintainability_index(1000.0, 10, 100).unwrap();
+    assert!((index - 34.02).abs() < 0.01, "{}", index);
+    assert_eq!(maintainability_index(1e12, 500, 100_000), Some(0.0));
+    assert_eq!(maintainability_index(0.0, 1, 10), None);
+}
+
+#[test]
+fn test_operator_and_operand_counts() {
+    // Operators: fn ( : , -> { + (closing brackets count with their opening bracket)
+    // Operands: add a i32 b
+    let rust = analyze_halstead("fn add(a: i32, b: i32) -> i32 { a + b }\n", "Rust").unwrap();
+    assert_eq!(counts(&rust), (7, 4, 8, 8));
+
+    // The string is one operand and the comment is ignored
+    let python = analyze_halstead("print(\"a # b\")  # print(x)\n", "Python").unwrap();
+    assert_eq!(counts(&python), (1, 2, 1, 2));
+
+    // Adjacent punctuation forms one operator
+    let javascript = analyze_halstead("x <<= y === z;\n", "JavaScript").unwrap();
+    assert_eq!(counts(&javascript), (3, 3, 3, 3));
+
+    assert!(analyze_halstead("# Title\n", "Markdown").is_none());
+    assert!(analyze_halstead("// only a comment\n", "Rust").is_none());
+}
+
+#[test]
+fn test_collected_halstead_metrics() {
+    let dir = tempdir().unwrap();
+    fs::write(dir.path().join("lib.rs"), "fn add(a: i32, b: i32) -> i32 {\n    a + b\n}\n").unwrap();
+    fs::write(dir.path().join("main.py"), "def main():\n    if ready:\n        run()\n").unwrap();
+    fs::write(dir.path().join("README.md"), "# Readme\n").unwrap();
+
+    let metrics = MetricsCollector::new().collect_metrics(dir.path()).unwrap();
+
+    let rust_file = metrics.files.iter().find(|file| file.path.ends_with("lib.rs")).unwrap();
+    let expected = maintainability_index(rust_file.halstead.as_ref().unwrap().volume, 1, 3);
+    assert_eq!(rust_file.maintainability_index, expected);
+    let readme = metrics.files.iter().find(|file| file.path.ends_with("README.md")).unwrap();
+    assert!(readme.halstead.is_none() && readme.maintainability_index.is_none());
+
+    let by_language = metrics.halstead_by_language();
+    assert_eq!(by_language.keys().collect::<Vec<_>>(), vec!["Python", "Rust"]);
+    assert_eq!(metrics.halstead_summary().files, 2);
+    let total_volume: f64 = by_language.values().map(|summary| summary.volume).sum();
+    assert!((metrics.halstead_summary().volume - total_volume).abs() < 1e-9);
+
+    let output = dir.path().join("metrics.json");
+    MetricsReporter::new().export_metrics_json(&metrics, &output).unwrap();
+    let json: serde_json::Value = serde_json::from_str(&fs::read_to_string(&output).unwrap()).unwrap();
+    assert_eq!(json["summary"]["halstead"]["files"], 2);
+    assert_eq!(json["languages"][0]["language"], "Markdown");
+    assert!(json["languages"][0]["halstead"].is_null());
+    assert_eq!(json["languages"][2]["halstead"]["files"], 1);
+    let rust_json = json["files"].as_array().unwrap().iter().find(|file| file["language"] == "Rust").unwrap();
+    assert_eq!(rust_json["halstead"]["distinct_operators"], 7);
+    assert_eq!(rust_json["functions"][0]["name"], "add");
+}
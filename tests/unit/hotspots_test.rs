@@ -0,0 +1,124 @@
+use ai_code_analyzer::metrics::hotspots::HotspotReport;
+use ai_code_analyzer::metrics::models::{CodeMetrics, FileMetrics, FunctionMetrics};
+use ai_code_analyzer::util::git::{ChangeSet, FileChange, parse_numstat};
+
+fn file(path: &str, lines_of_code: usize, complexities: &[usize]) -> FileMetrics {
+    FileMetrics {
+        path: format!("/repo/{}", path),
+        language: "Rust".to_string(),
+        lines_of_code,
+        functions: complexities
+            .iter()
+            .map(|complexity| FunctionMetrics { cyclomatic_complexity: *complexity, ..Default::default() })
+            .collect(),
+        ..Default::default()
+    }
+}
+
+fn change(author: &str, paths: &[&str]) -> ChangeSet {
+    ChangeSet {
+        commit: format!("{}-{}", author, paths.join("+")),
+        author: author.to_string(),
+        timestamp: 0,
+        files: paths
+            .iter()
+            .map(|path| FileChange { path: path.to_string(), lines_added: 10, lines_removed: 2 })
+            .collect(),
+    }
+}
+
+#[test]
+fn test_parse_numstat() {
+    let log = "commit\tabc\t1700000000\tAda Lovelace\n\n3\t1\tsrc/lib.rs\n-\t-\tlogo.png\ncommit\tdef\t1690000000\tAlan\n\n1\t0\tREADME.md\n";
+    let changes = parse_numstat(log);
+
+    assert_eq!(changes.len(), 2);
+    assert_eq!(changes[0].author, "Ada Lovelace");
+    assert_eq!(changes[0].timestamp, 1_700_000_000);
+    assert_eq!(
+        changes[0].files,
+        vec![
+            FileChange { path: "src/lib.rs".to_string(), lines_added: 3, lines_removed: 1 },
+            FileChange { path: "logo.png".to_string(), lines_added: 0, lines_removed: 0 },
+        ]
+    );
+    assert_eq!(changes[1].files.len(), 1);
+}
+
+#[test]
+fn test_hotspots_and_coupling() {
+    let metrics = CodeMetrics {
+        root: "/repo".to_string(),
+        files: vec![
+            file("src/parser.rs", 400, &[12, 8]),
+            file("src/lexer.rs", 200, &[5]),
+            file("src/util.rs", 50, &[1]),
+            file("README.md", 30, &[]),
+        ],
+        ..Default::default()
+    };
+    let history = vec![
+        change("ada", &["src/parser.rs", "src/lexer.rs"]),
+        change("alan", &["src/parser.rs", "src/lexer.rs"]),
+        change("ada", &["src/parser.rs", "src/lexer.rs", "README.md"]),
+        change("ada", &["src/parser.rs", "src/util.rs"]),
+        change("grace", &["src/util.rs", "src/deleted.rs"]),
+    ];
+
+    let report = HotspotReport::analyze(&history, &metrics, 2);
+    assert_eq!(report.commits, 5);
+
+    // Deleted files are left out; the rest ranked by commits times complexity
+    let ranking: Vec<(&str, usize, usize)> =
+        report.hotspots.iter().map(|hotspot| (hotspot.path.as_str(), hotspot.churn.commits, hotspot.score)).collect();
+    assert_eq!(
+        ranking,
+        vec![("src/parser.rs", 4, 80), ("src/lexer.rs", 3, 15), ("src/util.rs", 2, 2), ("README.md", 1, 1)]
+    );
+    let parser = &report.hotspots[0];
+    assert_eq!((parser.churn.lines_added, parser.churn.lines_removed, parser.churn.authors.len()), (40, 8, 2));
+
+    // Pairs sharing at least two commits, by degree
+    assert_eq!(report.couplings.len(), 1);
+    let coupling = &report.couplings[0];
+    assert_eq!((coupling.first.as_str(), coupling.second.as_str(), coupling.shared_commits), ("src/lexer.rs", "src/parser.rs", 3));
+    assert!((coupling.degree - 3.0 / 3.5).abs() < 1e-9);
+
+    let markdown = report.to_markdown(2, Some("hotspots.svg"));
+    assert!(markdown.contains("![Hotspots](hotspots.svg)"));
+    assert!(markdown.contains("| 1 | `src/parser.rs` | 4 | 40 | 8 | 2 | 400 | 20 | 80 |"));
+    assert!(!markdown.contains("`src/util.rs`"));
+    assert!(markdown.contains("| `src/lexer.rs` | `src/parser.rs` | 3 | 86% |"));
+
+    let svg = report.to_svg();
+    assert_eq!(svg.matches("<title>").count(), 4);
+    assert!(svg.contains("<title>src/parser.rs: 4 commits, +40 -8, 2 authors, 400 lines of code, complexity 20</title>"));
+    assert!(svg.trim_end().ends_with("</svg>"));
+}
+
+#[test]
+fn test_files_without_analyzed_functions() {
+    let mut config = file("deploy/config.yaml", 120, &[]);
+    config.language = "YAML".to_string();
+    let metrics = CodeMetrics {
+        root: "/repo".to_string(),
+        files: vec![config, file("src/main.rs", 20, &[1])],
+        ..Default::default()
+    };
+    let history = vec![
+        change("ada", &["deploy/config.yaml"]),
+        change("ada", &["deploy/config.yaml"]),
+        change("alan", &["deploy/config.yaml", "src/main.rs"]),
+    ];
+
+    // Frequently changed files still rank when their language has no function analysis
+    let report = HotspotReport::analyze(&history, &metrics, 2);
+    let ranking: Vec<(&str, Option<usize>, usize)> =
+        report.hotspots.iter().map(|hotspot| (hotspot.path.as_str(), hotspot.complexity, hotspot.score)).collect();
+    assert_eq!(ranking, vec![("deploy/config.yaml", None, 3), ("src/main.rs", Some(1), 1)]);
+
+    assert!(report.to_markdown(10, None).contains("| 1 | `deploy/config.yaml` | 3 | 30 | 6 | 2 | 120 | - | 3 |"));
+    let json: serde_json::Value = serde_json::from_str(&report.to_json(10).unwrap()).unwrap();
+    assert!(json["hotspots"][0]["complexity"].is_null());
+    assert_eq!(json["hotspots"][1]["complexity"], 1);
+}
@@ -0,0 +1,117 @@
+use super::support::{analyze, commit_all, init_repository, write_file};
+use ai_code_analyzer::dependency::impact::ImpactQuery;
+use ai_code_analyzer::util::git::changed_files;
+use std::fs;
+use std::path::Path;
+use tempfile::tempdir;
+
+fn write_project(root: &Path) {
+    write_file(root, "src/config.js", "export const config = {};\n");
+    write_file(root, "src/store.js", "import { config } from './config';\n");
+    write_file(root, "src/api.js", "import { store } from './store';\nimport axios from 'axios';\n");
+    write_file(root, "src/app.js", "import { api } from './api';\n");
+    write_file(root, "src/__tests__/store.test.js", "import { store } from '../store';\n");
+    write_file(root, "src/__tests__/app.test.js", "import { app } from '../app';\n");
+    write_file(root, "src/unrelated.js", "export const unrelated = 1;\n");
+}
+
+fn relative(root: &Path, path: &str) -> String {
+    Path::new(path).strip_prefix(root).unwrap().to_string_lossy().to_string()
+}
+
+#[test]
+fn test_transitive_dependents_with_depth() {
+    let dir = tempdir().unwrap();
+    let root = dir.path();
+    write_project(root);
+    let graph = analyze(root);
+
+    let report = ImpactQuery::new().run(&graph, &[root.join("src/config.js")]);
+
+    let dependents: Vec<(String, usize, bool)> = report
+        .dependents
+        .iter()
+        .map(|file| (relative(root, &file.path), file.depth, file.test))
+        .collect();
+    assert_eq!(dependents, vec![
+        ("src/store.js".to_string(), 1, false),
+        ("src/__tests__/store.test.js".to_string(), 2, true),
+        ("src/api.js".to_string(), 2, false),
+        ("src/app.js".to_string(), 3, false),
+        ("src/__tests__/app.test.js".to_string(), 4, true),
+    ]);
+
+    let tests: Vec<String> = report.affected_tests.iter().map(|path| relative(root, path)).collect();
+    assert_eq!(tests, vec!["src/__tests__/app.test.js", "src/__tests__/store.test.js"]);
+    assert!(report.dependencies.is_empty());
+    assert_eq!(report.affected_files().len(), 6);
+}
+
+#[test]
+fn test_depth_limit_and_dependencies() {
+    let dir = tempdir().unwrap();
+    let root = dir.path();
+    write_project(root);
+    let graph = analyze(root);
+
+    let report = ImpactQuery::new()
+        .max_depth(Some(1))
+        .include_dependencies(true)
+        .run(&graph, &[root.join("src/api.js"), root.join("docs/guide.md")]);
+
+    let dependents: Vec<String> = report.dependents.iter().map(|file| relative(root, &file.path)).collect();
+    assert_eq!(dependents, vec!["src/app.js"]);
+    assert!(report.affected_tests.is_empty());
+
+    let dependencies: Vec<&str> = report.dependencies.iter().map(|file| file.path.as_str()).collect();
+    assert_eq!(dependencies.len(), 2);
+    assert!(dependencies.contains(&"axios"));
+    assert!(report.dependencies.iter().all(|file| file.depth == 1));
+
+    assert_eq!(report.not_in_graph.len(), 1);
+    assert!(report.not_in_graph[0].ends_with("docs/guide.md"));
+}
+
+#[test]
+fn test_changed_test_file_is_affected() {
+    let dir = tempdir().unwrap();
+    let root = dir.path();
+    write_project(root);
+    let graph = analyze(root);
+
+    let report = ImpactQuery::new().run(&graph, &[root.join("src/__tests__/store.test.js")]);
+
+    assert!(report.dependents.is_empty());
+    let tests: Vec<String> = report.affected_tests.iter().map(|path| relative(root, path)).collect();
+    assert_eq!(tests, vec!["src/__tests__/store.test.js"]);
+}
+
+#[test]
+fn test_changed_files_from_git_range() {
+    let dir = tempdir().unwrap();
+    let root = dir.path();
+    if !init_repository(root) {
+        return;
+    }
+    write_project(root);
+    commit_all(root, "initial");
+
+    write_file(root, "src/store.js", "import { config } from './config';\nexport const store = {};\n");
+    fs::remove_file(root.join("src/unrelated.js")).unwrap();
+
+    let changed = changed_files(root, "HEAD").unwrap();
+    let root = fs::canonicalize(root).unwrap();
+    let mut changed: Vec<String> = changed
+        .iter()
+        .map(|path| path.strip_prefix(&root).unwrap().to_string_lossy().to_string())
+        .collect();
+    changed.sort();
+    assert_eq!(changed, vec!["src/store.js", "src/unrelated.js"]);
+
+    assert!(changed_files(&root, "no-such-revision").is_err());
+
+    // A range that looks like an option is refused rather than passed on to git
+    let injected = root.join("injected.diff");
+    assert!(changed_files(&root, &format!("--output={}", injected.display())).is_err());
+    assert!(!injected.exists());
+}
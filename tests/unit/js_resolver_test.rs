@@ -0,0 +1,90 @@
+use super::support::{specifiers, write_file};
+use ai_code_analyzer::dependency::dependency_analyzer::DependencyAnalyzer;
+use ai_code_analyzer::dependency::js_resolver::extract_js_dependencies;
+use ai_code_analyzer::util::parallel::ParallelProcessing;
+use tempfile::tempdir;
+
+#[test]
+fn test_extract_import_forms() {
+    let content = r#"
+import './polyfills';
+import React, { useState } from "react";
+import {
+    Button,
+    type ButtonProps,
+} from '@acme/ui/button';
+import * as path from 'node:path';
+export { helper } from './helper';
+export * from "./types";
+export type { Config } from './config';
+const lazy = () => import('./lazy-page');
+const legacy = require('lodash');
+// import commented from './commented';
+const text = "import fake from './fake'";
+const pattern = /from '.\/regex'/;
+const meta = import.meta.url;
+"#;
+
+    assert_eq!(specifiers(extract_js_dependencies(content)), vec![
+        "./polyfills",
+        "react",
+        "@acme/ui/button",
+        "node:path",
+        "./helper",
+        "./types",
+        "./config",
+        "./lazy-page",
+        "lodash",
+    ]);
+}
+
+#[test]
+fn test_resolve_typescript_monorepo() {
+    let dir = tempdir().unwrap();
+    let root = dir.path();
+
+    write_file(root, "package.json", r#"{ "name": "monorepo", "private": true, "workspaces": ["packages/*"] }"#);
+    write_file(root, "packages/app/tsconfig.json", r#"{
+        // comments and trailing commas are allowed here
+        "extends": "../../tsconfig.base.json",
+        "compilerOptions": { "baseUrl": "src", },
+    }"#);
+    write_file(root, "tsconfig.base.json", r#"{ "compilerOptions": { "paths": { "@shared/*": ["packages/shared/src/*"] } } }"#);
+    write_file(root, "packages/app/src/main.ts", r#"
+import { render } from './view.js';
+import { Button } from '@acme/ui';
+import { format } from '@acme/ui/format';
+import { log } from '@shared/log';
+import { settings } from 'config/settings';
+import 'reflect-metadata';
+"#);
+    write_file(root, "packages/app/src/view.ts", "export const render = () => {};");
+    write_file(root, "packages/app/src/config/settings/index.ts", "export const settings = {};");
+    write_file(root, "packages/ui/package.json", r#"{
+        "name": "@acme/ui",
+        "exports": {
+            ".": { "types": "./dist/index.d.ts", "import": "./dist/index.js" },
+            "./*": "./dist/*.js"
+        }
+    }"#);
+    write_file(root, "packages/ui/src/index.tsx", "export const Button = () => null;");
+    write_file(root, "packages/ui/src/format.ts", "export const format = () => '';");
+    write_file(root, "packages/shared/src/log.ts", "export const log = () => {};");
+
+    let graph = DependencyAnalyzer::new()
+        .enable_parallel_processing(false)
+        .analyze_dependencies(root)
+        .unwrap();
+
+    let main = root.join("packages/app/src/main.ts").to_string_lossy().to_string();
+    let dependencies = graph.get_dependencies(&main);
+    let expect = |relative: &str| root.join(relative).to_string_lossy().to_string();
+
+    assert!(dependencies.contains(&expect("packages/app/src/view.ts")));
+    assert!(dependencies.contains(&expect("packages/ui/src/index.tsx")));
+    assert!(dependencies.contains(&expect("packages/ui/src/format.ts")));
+    assert!(dependencies.contains(&expect("packages/shared/src/log.ts")));
+    assert!(dependencies.contains(&expect("packages/app/src/config/settings/index.ts")));
+    assert!(dependencies.contains(&"reflect-metadata".to_string()));
+    assert!(graph.is_external("reflect-metadata"));
+}
@@ -1,4 +1,6 @@
 use ai_code_analyzer::metrics::language::LanguageDetector;
+use std::fs;
+use std::path::Path;
 
 #[test]
 fn test_detect_by_filename() {
@@ -78,3 +80,70 @@ fn test_get_comment_syntax() {
     assert_eq!(block_start, "");
     assert_eq!(block_end, "");
 }
+
+#[test]
+fn test_detect_from_content() {
+    let detector = LanguageDetector::new();
+    let detect = |path: &str, content: &str| detector.detect(Path::new(path), Some(content));
+
+    assert_eq!(detect("bin/deploy", "#!/usr/bin/env bash\nset -e\n"), "Shell");
+    assert_eq!(detect("bin/serve", "#!/usr/bin/env -S node --no-warnings\n"), "JavaScript");
+    assert_eq!(detect("scripts/report", "#!/usr/bin/python3.11 -u\nimport sys\n"), "Python");
+    assert_eq!(detect("tools/run.txt", "#!/usr/bin/perl -w\n"), "Perl");
+    assert_eq!(detect("bin/unknown", "no shebang here\n"), "Other");
+    assert_eq!(detect("Makefile", "#!/bin/sh\nall:\n"), "Make");
+    assert_eq!(detect("src/main.rs", "#!/usr/bin/env python\n"), "Rust");
+
+    assert_eq!(detect("config/build", "x = 1\n# vim: set ft=python :\n"), "Python");
+    assert_eq!(detect("lib/widget.h", "// -*- mode: c++; indent-tabs-mode: nil -*-\nint x;\n"), "C++");
+    assert_eq!(detect("lib/widget.inc", "/* -*- C -*- */\n"), "C");
+
+    assert_eq!(detect("include/point.h", "struct point { int x, y; };\n"), "C");
+    assert_eq!(detect("include/widget.h", "namespace ui {\nclass Widget {\npublic:\n};\n}\n"), "C++");
+    assert_eq!(detect("include/view.h", "#import <UIKit/UIKit.h>\n@interface View : UIView\n@end\n"), "Objective-C");
+    assert_eq!(detect("src/view.m", "#import \"View.h\"\n@implementation View\n@end\n"), "Objective-C");
+    assert_eq!(detect("src/solve.m", "% Solves the system\nfunction x = solve(A, b)\n  x = A \\ b;\nend\n"), "MATLAB");
+    assert_eq!(detect("src/tool.pl", "use strict;\nmy $name = shift;\n"), "Perl");
+    assert_eq!(detect("src/family.pl", "parent(tom, bob).\nancestor(X, Y) :- parent(X, Y).\n"), "Prolog");
+    assert_eq!(detect("rtl/counter.v", "module counter(input clk);\nendmodule\n"), "Verilog");
+    assert_eq!(detect("proofs/nat.v", "Require Import Arith.\nTheorem plus_0 : forall n, n + 0 = n.\nProof.\nQed.\n"), "Coq");
+    assert_eq!(detect("i18n/app_de.ts", "<?xml version=\"1.0\"?>\n<!DOCTYPE TS>\n<TS version=\"2.1\">\n</TS>\n"), "XML");
+    assert_eq!(detect("src/app.ts", "export const answer = 42;\n"), "TypeScript");
+
+    assert_eq!(detector.detect(Path::new("include/point.h"), None), "C");
+}
+
+#[test]
+fn test_detect_with_gitattributes() {
+    let dir = tempfile::tempdir().unwrap();
+    fs::create_dir_all(dir.path().join(".git")).unwrap();
+    fs::create_dir_all(dir.path().join("vendor/legacy")).unwrap();
+    fs::write(
+        dir.path().join(".gitattributes"),
+        "# Headers are C++ here\n*.h linguist-language=C++ linguist-vendored\n/tools/* linguist-language=Python\n",
+    )
+    .unwrap();
+    fs::write(dir.path().join("vendor/legacy/.gitattributes"), "*.h -linguist-language\n*.inc linguist-language=cpp\n").unwrap();
+
+    let files = [
+        ("include/point.h", "struct point { int x, y; };\n"),
+        ("tools/bump", "print('bumped')\n"),
+        ("vendor/legacy/old.h", "struct old;\n"),
+        ("vendor/legacy/table.inc", "int table[] = {1, 2};\n"),
+    ];
+    for (path, content) in files {
+        let path = dir.path().join(path);
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(path, content).unwrap();
+    }
+
+    let detector = LanguageDetector::new();
+    let detect = |path: &str| {
+        let path = dir.path().join(path);
+        detector.detect(&path, Some(&fs::read_to_string(&path).unwrap()))
+    };
+    assert_eq!(detect("include/point.h"), "C++");
+    assert_eq!(detect("tools/bump"), "Python");
+    assert_eq!(detect("vendor/legacy/old.h"), "C");
+    assert_eq!(detect("vendor/legacy/table.inc"), "C++");
+}
@@ -0,0 +1,208 @@
+use super::support::{specifiers, write_file};
+use ai_code_analyzer::dependency::c_resolver::extract_c_includes;
+use ai_code_analyzer::dependency::csharp_resolver::{extract_msbuild_dependencies, parse_csharp_source};
+use ai_code_analyzer::dependency::dependency_analyzer::DependencyAnalyzer;
+use ai_code_analyzer::dependency::go_resolver::extract_go_dependencies;
+use ai_code_analyzer::dependency::java_resolver::parse_java_source;
+use ai_code_analyzer::util::parallel::ParallelProcessing;
+use std::path::{Path, PathBuf};
+use tempfile::tempdir;
+
+fn file(root: &Path, relative: &str) -> String {
+    root.join(relative).to_string_lossy().to_string()
+}
+
+#[test]
+fn test_extract_go_imports() {
+    let content = r#"
+package main
+
+import "fmt"
+import log "github.com/sirupsen/logrus"
+
+import (
+    "os"
+    _ "embed"
+    . "example.com/shop/internal/util" // dot import
+    /* "commented/out" */
+)
+"#;
+
+    assert_eq!(specifiers(extract_go_dependencies(content)), vec![
+        "fmt",
+        "github.com/sirupsen/logrus",
+        "os",
+        "embed",
+        "example.com/shop/internal/util",
+    ]);
+}
+
+#[test]
+fn test_resolve_go_module_packages() {
+    let dir = tempdir().unwrap();
+    let root = dir.path();
+
+    write_file(root, "go.mod", "module example.com/shop\n\nrequire (\n    github.com/gorilla/mux v1.8.0\n)\n");
+    write_file(root, "cmd/server/main.go", r#"package main
+
+import (
+    "fmt"
+    "example.com/shop/internal/store"
+    "github.com/gorilla/mux/middleware"
+)
+"#);
+    write_file(root, "internal/store/store.go", "package store\n");
+    write_file(root, "internal/store/cache.go", "package store\n");
+    write_file(root, "internal/store/store_test.go", "package store\n");
+
+    let graph = DependencyAnalyzer::new()
+        .enable_parallel_processing(false)
+        .include_test_files(true)
+        .analyze_dependencies(root)
+        .unwrap();
+    let dependencies = graph.get_dependencies(&file(root, "cmd/server/main.go"));
+
+    assert!(dependencies.contains(&file(root, "internal/store/store.go")));
+    assert!(dependencies.contains(&file(root, "internal/store/cache.go")));
+    assert!(!dependencies.contains(&file(root, "internal/store/store_test.go")));
+    assert!(dependencies.contains(&"github.com/gorilla/mux".to_string()));
+    assert_eq!(dependencies.len(), 3);
+}
+
+#[test]
+fn test_resolve_java_packages() {
+    let dir = tempdir().unwrap();
+    let root = dir.path();
+
+    let app = r#"
+package com.acme.app;
+
+import java.util.List;
+import com.acme.model.Order;
+import com.acme.util.*;
+import static com.acme.model.Status.ACTIVE;
+import org.junit.jupiter.api.Test;
+
+/** import com.acme.fake.Ignored; */
+public class App {}
+"#;
+    let items = parse_java_source(app);
+    assert_eq!(items.package.as_deref(), Some("com.acme.app"));
+    assert_eq!(items.imports.len(), 5);
+
+    write_file(root, "src/main/java/com/acme/app/App.java", app);
+    write_file(root, "src/main/java/com/acme/model/Order.java", "package com.acme.model;\npublic class Order {}\n");
+    write_file(root, "src/main/java/com/acme/model/Status.java", "package com.acme.model;\npublic enum Status { ACTIVE }\n");
+    write_file(root, "src/main/java/com/acme/util/Strings.java", "package com.acme.util;\npublic class Strings {}\n");
+
+    let graph = DependencyAnalyzer::new()
+        .enable_parallel_processing(false)
+        .analyze_dependencies(root)
+        .unwrap();
+    let dependencies = graph.get_dependencies(&file(root, "src/main/java/com/acme/app/App.java"));
+
+    assert!(dependencies.contains(&file(root, "src/main/java/com/acme/model/Order.java")));
+    assert!(dependencies.contains(&file(root, "src/main/java/com/acme/model/Status.java")));
+    assert!(dependencies.contains(&file(root, "src/main/java/com/acme/util/Strings.java")));
+    assert!(dependencies.contains(&"org.junit.jupiter".to_string()));
+    assert_eq!(dependencies.len(), 4);
+}
+
+#[test]
+fn test_resolve_csharp_namespaces_and_projects() {
+    let dir = tempdir().unwrap();
+    let root = dir.path();
+
+    let program = r#"
+using System;
+using Acme.Core.Models;
+using static Acme.Core.Helpers.Guard;
+using Json = Newtonsoft.Json.JsonConvert;
+
+namespace Acme.Web
+{
+    public class Program
+    {
+        void Run() { using (var scope = Create()) { } }
+    }
+}
+"#;
+    let items = parse_csharp_source(program);
+    assert_eq!(specifiers(items.usings), vec!["System", "Acme.Core.Models", "Acme.Core.Helpers.Guard", "Newtonsoft.Json.JsonConvert"]);
+    assert_eq!(items.types, vec!["Acme.Web.Program"]);
+
+    let web_project = r#"<Project Sdk="Microsoft.NET.Sdk.Web">
+  <ItemGroup>
+    <ProjectReference Include="..\Core\Core.csproj" />
+    <PackageReference Include="Newtonsoft.Json" Version="13.0.3" />
+  </ItemGroup>
+</Project>"#;
+    assert_eq!(specifiers(extract_msbuild_dependencies(web_project)), vec!["../Core/Core.csproj", "Newtonsoft.Json"]);
+
+    write_file(root, "Web/Web.csproj", web_project);
+    write_file(root, "Web/Program.cs", program);
+    write_file(root, "Core/Core.csproj", "<Project Sdk=\"Microsoft.NET.Sdk\"></Project>");
+    write_file(root, "Core/Models/Order.cs", "namespace Acme.Core.Models;\npublic record Order(int Id);\n");
+    write_file(root, "Core/Models/Customer.cs", "namespace Acme.Core.Models { public class Customer {} }\n");
+    write_file(root, "Core/Helpers/Guard.cs", "namespace Acme.Core.Helpers { public static class Guard {} }\n");
+    // Same namespace in a project Web does not reference
+    write_file(root, "Legacy/Legacy.csproj", "<Project Sdk=\"Microsoft.NET.Sdk\"></Project>");
+    write_file(root, "Legacy/OldOrder.cs", "namespace Acme.Core.Models { class OldOrder {} }\n");
+
+    let graph = DependencyAnalyzer::new()
+        .enable_parallel_processing(false)
+        .analyze_dependencies(root)
+        .unwrap();
+    let dependencies = graph.get_dependencies(&file(root, "Web/Program.cs"));
+
+    assert!(dependencies.contains(&file(root, "Core/Models/Order.cs")));
+    assert!(dependencies.contains(&file(root, "Core/Models/Customer.cs")));
+    assert!(dependencies.contains(&file(root, "Core/Helpers/Guard.cs")));
+    assert!(!dependencies.contains(&file(root, "Legacy/OldOrder.cs")));
+    assert!(dependencies.contains(&"Newtonsoft.Json".to_string()));
+    assert!(!dependencies.contains(&"System".to_string()));
+
+    let project_dependencies = graph.get_dependencies(&file(root, "Web/Web.csproj"));
+    assert!(project_dependencies.contains(&file(root, "Core/Core.csproj")));
+    assert!(project_dependencies.contains(&"Newtonsoft.Json".to_string()));
+}
+
+#[test]
+fn test_resolve_c_includes() {
+    let content = r#"
+#include <stdio.h>
+#  include "config.h"
+/* #include "commented.h"
+   #include "still_commented.h" */
+#include <mylib/api.h>
+#include <boost/optional.hpp>
+"#;
+    assert_eq!(specifiers(extract_c_includes(content)), vec!["<stdio.h>", "config.h", "<mylib/api.h>", "<boost/optional.hpp>"]);
+
+    let dir = tempdir().unwrap();
+    let root = dir.path();
+
+    write_file(root, "src/main.c", content);
+    write_file(root, "src/config.h", "#define DEBUG 1\n");
+    write_file(root, "third_party/mylib/api.h", "void api(void);\n");
+
+    let analyze = |include_dirs: Vec<PathBuf>| {
+        DependencyAnalyzer::new()
+            .enable_parallel_processing(false)
+            .include_directories(include_dirs)
+            .analyze_dependencies(root)
+            .unwrap()
+    };
+
+    let graph = analyze(Vec::new());
+    let dependencies = graph.get_dependencies(&file(root, "src/main.c"));
+    assert!(dependencies.contains(&file(root, "src/config.h")));
+    assert!(dependencies.contains(&"mylib".to_string()));
+    assert!(dependencies.contains(&"boost".to_string()));
+    assert!(!dependencies.iter().any(|dependency| dependency.contains("stdio")));
+
+    let graph = analyze(vec![PathBuf::from("third_party")]);
+    let dependencies = graph.get_dependencies(&file(root, "src/main.c"));
+    assert!(dependencies.contains(&file(root, "third_party/mylib/api.h")));
+    assert!(!dependencies.contains(&"mylib".to_string()));
+}
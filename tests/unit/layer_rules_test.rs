@@ -0,0 +1,114 @@
+use super::support::write_file;
+use ai_code_analyzer::dependency::c_resolver::extract_c_includes;
+use ai_code_analyzer::dependency::dependency_analyzer::DependencyAnalyzer;
+use ai_code_analyzer::dependency::dependency_graph::EdgeImport;
+use ai_code_analyzer::dependency::dependency_reporter::DependencyReporter;
+use ai_code_analyzer::dependency::js_resolver::extract_js_dependencies;
+use ai_code_analyzer::dependency::layer_rules::LayerRules;
+use ai_code_analyzer::dependency::python_resolver::extract_python_dependencies;
+use ai_code_analyzer::dependency::rust_resolver::extract_rust_dependencies;
+use ai_code_analyzer::util::parallel::ParallelProcessing;
+use std::path::Path;
+use tempfile::tempdir;
+
+#[test]
+fn test_extractors_record_import_lines() {
+    let lines = |imports: Vec<EdgeImport>| -> Vec<(String, Option<usize>)> {
+        imports.into_iter().map(|import| (import.specifier, import.line)).collect()
+    };
+
+    let rust = "use std::fmt;\nuse crate::{\n    model::Order,\n    store::{self, Cache},\n};\n\nfn order() {}\n";
+    assert_eq!(lines(extract_rust_dependencies(rust)), vec![
+        ("std::fmt".to_string(), Some(1)),
+        ("crate::model::Order".to_string(), Some(3)),
+        ("crate::store".to_string(), Some(4)),
+        ("crate::store::Cache".to_string(), Some(4)),
+    ]);
+
+    let js = "// TODO: move from './utils' later\nimport { a } from './utils';\nconst b = require(\"../lib/b\");\n";
+    assert_eq!(lines(extract_js_dependencies(js)), vec![
+        ("./utils".to_string(), Some(2)),
+        ("../lib/b".to_string(), Some(3)),
+    ]);
+
+    let c = "#include <stdio.h>\n/* #include \"old.h\" */\n#include \"config.h\"\n";
+    assert_eq!(lines(extract_c_includes(c)), vec![
+        ("<stdio.h>".to_string(), Some(1)),
+        ("config.h".to_string(), Some(3)),
+    ]);
+
+    let python = "\"\"\"Docs: from os import path\"\"\"\n# import sys\nfrom .models import (\n    Order,\n    Customer,\n)\nimport os\n";
+    assert_eq!(lines(extract_python_dependencies(python)), vec![
+        (".models.Order".to_string(), Some(3)),
+        (".models.Customer".to_string(), Some(3)),
+        ("os".to_string(), Some(7)),
+    ]);
+}
+
+#[test]
+fn test_layering_violations() {
+    let dir = tempdir().unwrap();
+    let root = dir.path();
+
+    write_file(root, "Cargo.toml", "[package]\nname = \"shop\"\nversion = \"0.1.0\"\n\n[dependencies]\nsqlx = \"0.7\"\n");
+    write_file(root, "src/lib.rs", "mod analyzer;\nmod commands;\nmod domain;\nmod infrastructure;\n");
+    write_file(root, "src/domain/mod.rs", "mod order;\n");
+    write_file(root, "src/domain/order.rs", "use std::fmt;\n\nuse crate::infrastructure::db::Pool;\nuse sqlx::Row;\n");
+    write_file(root, "src/infrastructure/mod.rs", "pub mod db;\n");
+    write_file(root, "src/infrastructure/db.rs", "use crate::domain;\n");
+    write_file(root, "src/analyzer.rs", "use crate::domain;\nuse crate::commands::run;\n");
+    write_file(root, "src/commands.rs", "use crate::analyzer;\npub fn run() {}\n");
+
+    let rules = LayerRules::parse(r#"
+[layers]
+domain = ["src/domain/**"]
+infrastructure = ["src/infrastructure/**"]
+
+[[rules]]
+from = "domain"
+forbid = ["infrastructure", "sqlx"]
+reason = "keep the domain persistence-agnostic"
+
+[[rules]]
+from = "src/analyzer.rs"
+allow = ["domain", "src/util"]
+"#).unwrap();
+
+    let graph = DependencyAnalyzer::new()
+        .enable_parallel_processing(false)
+        .analyze_dependencies(root)
+        .unwrap();
+    let violations = rules.check(&graph, root);
+
+    let summary: Vec<(String, String, Option<usize>)> = violations
+        .iter()
+        .map(|violation| {
+            let source = Path::new(&violation.source).strip_prefix(root).unwrap().to_string_lossy().to_string();
+            let target = Path::new(&violation.target)
+                .strip_prefix(root)
+                .map(|path| path.to_string_lossy().to_string())
+                .unwrap_or_else(|_| violation.target.clone());
+            (source, target, violation.line)
+        })
+        .collect();
+
+    assert_eq!(summary, vec![
+        ("src/analyzer.rs".to_string(), "src/commands.rs".to_string(), Some(2)),
+        ("src/domain/order.rs".to_string(), "src/infrastructure/db.rs".to_string(), Some(3)),
+        ("src/domain/order.rs".to_string(), "sqlx".to_string(), Some(4)),
+    ]);
+    assert_eq!(violations[1].rule, "domain must not depend on infrastructure, sqlx");
+    assert_eq!(violations[1].reason.as_deref(), Some("keep the domain persistence-agnostic"));
+    assert_eq!(violations[0].import.as_deref(), Some("crate::commands::run"));
+
+    let markdown = DependencyReporter::new().layer_violations(violations).format_markdown(&graph);
+    assert!(markdown.contains("## Layering Violations"));
+    assert!(markdown.contains("src/analyzer.rs:2`"));
+}
+
+#[test]
+fn test_invalid_rules() {
+    assert!(LayerRules::parse("[[rules]]\nfrom = \"domain\"\n").is_err());
+    assert!(LayerRules::parse("[[rules]]\nfrom = \"domain\"\nforbids = [\"x\"]\n").is_err());
+    assert!(LayerRules::parse("").unwrap().check(&Default::default(), ".").is_empty());
+}
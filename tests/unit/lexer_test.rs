@@ -0,0 +1,71 @@
+use ai_code_analyzer::metrics::language::LanguageDetector;
+use ai_code_analyzer::metrics::lexer::{LineKind, SpanKind, classify_lines, lex};
+use std::fs;
+use std::path::Path;
+
+const FIXTURES: &str = "tests/fixtures/lexer";
+
+fn label(kind: LineKind) -> &'static str {
+    match kind {
+        LineKind::Code => "code",
+        LineKind::Comment => "comment",
+        LineKind::Blank => "blank",
+        LineKind::Mixed => "mixed",
+    }
+}
+
+/// Each source line prefixed with its kind, as stored in the `.golden` files.
+fn annotate(content: &str, language: &str) -> String {
+    content
+        .lines()
+        .zip(classify_lines(content, language))
+        .map(|(line, kind)| format!("{:<7} | {}\n", label(kind), line).replace(" | \n", " |\n"))
+        .collect()
+}
+
+/// Compares every fixture with its `.golden` file. Run with `UPDATE_GOLDEN=1` to rewrite them
+/// after an intended change, then review the diff.
+#[test]
+fn test_golden_line_classification() {
+    let detector = LanguageDetector::new();
+    let mut fixtures: Vec<_> = fs::read_dir(FIXTURES)
+        .unwrap()
+        .map(|entry| entry.unwrap().path())
+        .filter(|path| path.extension().is_some_and(|extension| extension != "golden"))
+        .collect();
+    fixtures.sort();
+    assert!(fixtures.len() >= 6);
+
+    for fixture in fixtures {
+        let extension = fixture.extension().unwrap().to_str().unwrap();
+        let language = detector.detect_language(extension);
+        let actual = annotate(&fs::read_to_string(&fixture).unwrap(), &language);
+
+        let golden = Path::new(&format!("{}.golden", fixture.display())).to_path_buf();
+        if std::env::var_os("UPDATE_GOLDEN").is_some() {
+            fs::write(&golden, &actual).unwrap();
+        }
+        let expected = fs::read_to_string(&golden).unwrap();
+        assert_eq!(actual, expected, "line kinds of {} differ from {}", fixture.display(), golden.display());
+    }
+}
+
+#[test]
+fn test_spans() {
+    let source = "let a = \"/* x */\"; // note\n/* outer /* inner */ still */ let b = 'c';\n";
+    let spans = lex(source, "Rust");
+    let kinds: Vec<(SpanKind, &str)> = spans.iter().map(|span| (span.kind, &source[span.range.clone()])).collect();
+    assert_eq!(
+        kinds,
+        vec![
+            (SpanKind::Literal, "\"/* x */\""),
+            (SpanKind::Comment, "// note"),
+            (SpanKind::Comment, "/* outer /* inner */ still */"),
+            (SpanKind::Literal, "'c'"),
+        ]
+    );
+    assert_eq!(&source[spans[0].content.clone()], "/* x */");
+
+    // Languages without comment syntax are all code
+    assert_eq!(classify_lines("# Title\n\ntext // more\n", "Markdown"), vec![LineKind::Code, LineKind::Blank, LineKind::Code]);
+}
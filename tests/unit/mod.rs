@@ -1,13 +1,34 @@
 mod ai_config_test;
 mod analyzer_test;
 mod architecture_diagram_test;
+mod baseline_test;
 mod cache_test;
+mod clones_test;
+mod complexity_test;
+mod coupling_test;
+mod crate_analyzer_test;
+mod dead_files_test;
 mod delete_comments_test;
 mod dependency_graph_test;
 mod description_test;
 mod file_analyzer_test;
+mod graph_diff_test;
+mod graph_export_test;
+mod halstead_test;
+mod hotspots_test;
+mod impact_test;
+mod js_resolver_test;
+mod layer_rules_test;
+mod language_resolvers_test;
 mod language_detector_test;
+mod lexer_test;
 mod metrics_collector_test;
 mod models_test;
+mod ownership_test;
+mod package_inventory_test;
+mod python_resolver_test;
 mod reporter_test;
+mod rust_resolver_test;
 mod style_test;
+mod support;
+mod trend_test;
@@ -70,3 +70,39 @@ fn test_add_language_metrics() {
     assert_eq!(metrics.comment_lines, 420);
     assert_eq!(metrics.by_language.len(), 2);
 }
+
+#[test]
+fn test_directory_tree() {
+    let mut metrics = CodeMetrics::new();
+    metrics.root = "./project".to_string();
+    let files = [
+        ("./project/build.rs", 10, 0),
+        ("./project/src/main.rs", 100, 20),
+        ("./project/src/metrics/models.rs", 200, 30),
+        ("./project/src/metrics/deep/reporter.rs", 50, 0),
+        ("./project/tests/unit/models_test.rs", 60, 0),
+    ];
+    for (path, lines_of_code, comment_lines) in files {
+        let mut lang_metrics = LanguageMetrics::new("Rust".to_string());
+        lang_metrics.files = 1;
+        lang_metrics.lines_of_code = lines_of_code;
+        lang_metrics.comment_lines = comment_lines;
+        metrics.add_language_metrics(lang_metrics, path);
+    }
+
+    let tree = metrics.directory_tree(2);
+    assert_eq!((tree.path.as_str(), tree.stats.files, tree.stats.lines_of_code), (".", 5, 420));
+    assert_eq!(tree.stats.test_ratio(), Some(60.0 / 360.0));
+
+    let children: Vec<(&str, usize, usize)> =
+        tree.children.iter().map(|child| (child.path.as_str(), child.stats.files, child.stats.lines_of_code)).collect();
+    assert_eq!(children, vec![("src", 3, 350), ("tests", 1, 60)]);
+
+    let metrics_dir = &tree.children[0].children[0];
+    assert_eq!((metrics_dir.path.as_str(), metrics_dir.name(), metrics_dir.stats.lines_of_code), ("src/metrics", "metrics", 250));
+    assert!(metrics_dir.children.is_empty());
+    assert_eq!(metrics_dir.stats.comment_ratio(), Some(30.0 / 280.0));
+    assert_eq!(tree.children[1].stats.test_ratio(), None);
+
+    assert!(metrics.directory_tree(0).children.is_empty());
+}
@@ -0,0 +1,129 @@
+use super::support::{commit_all, init_repository, write_file};
+use ai_code_analyzer::metrics::ownership::{CodeOwners, FileBlame, OwnershipReport, blame_files, bus_factor, owner_is_author};
+use ai_code_analyzer::util::file_filter::get_all_source_files;
+use ai_code_analyzer::util::git::{Author, parse_blame_authors};
+use std::collections::BTreeMap;
+use std::fs;
+use tempfile::tempdir;
+
+fn author(name: &str, email: &str) -> Author {
+    Author { name: name.to_string(), email: email.to_string() }
+}
+
+fn blame(path: &str, lines: &[(&Author, usize)]) -> FileBlame {
+    FileBlame {
+        path: path.to_string(),
+        lines: lines.iter().map(|(author, lines)| ((*author).clone(), *lines)).collect::<BTreeMap<_, _>>(),
+    }
+}
+
+#[test]
+fn test_parse_blame_authors() {
+    let porcelain = "\
+abc 1 1 2
+author Ada Lovelace
+author-mail <ada@example.com>
+author-time 1700000000
+filename src/lib.rs
+\tfn main() {
+abc 2 2
+author Ada Lovelace
+author-mail <ada@example.com>
+\t}
+0000000 3 3 1
+author Not Committed Yet
+author-mail <not.committed.yet>
+\t// new
+";
+    let authors = parse_blame_authors(porcelain);
+    assert_eq!(authors.len(), 1);
+    assert_eq!(authors[&author("Ada Lovelace", "ada@example.com")], 2);
+}
+
+#[test]
+fn test_codeowners_and_bus_factor() {
+    let codeowners = CodeOwners::parse(
+        "# Default owners\n*       @core\n/docs/  docs@example.com\nsrc/metrics/* @ada # metrics\n*.sh    @alan\n",
+    );
+    assert_eq!(codeowners.owners_of("README.md"), ["@core"]);
+    assert_eq!(codeowners.owners_of("docs/guide/intro.md"), ["docs@example.com"]);
+    assert_eq!(codeowners.owners_of("src/metrics/reporter.rs"), ["@ada"]);
+    assert_eq!(codeowners.owners_of("src/metrics/sub/deep.rs"), ["@core"]);
+    assert_eq!(codeowners.owners_of("scripts/build/run.sh"), ["@alan"]);
+
+    let ada = author("Ada Lovelace", "12345+ada@users.noreply.github.com");
+    assert!(owner_is_author("@ada", &ada));
+    assert!(owner_is_author("@AdaLovelace", &ada));
+    assert!(!owner_is_author("@org/ada", &ada));
+    assert!(owner_is_author("alan@example.com", &author("Alan", "Alan@Example.com")));
+
+    assert_eq!(bus_factor(&[60, 30, 10]), 1);
+    assert_eq!(bus_factor(&[50, 30, 20]), 2);
+    assert_eq!(bus_factor(&[25, 25, 25, 25]), 3);
+    assert_eq!(bus_factor(&[]), 0);
+}
+
+#[test]
+fn test_ownership_report() {
+    let ada = author("Ada", "ada@example.com");
+    let alan = author("Alan", "alan@example.com");
+    let grace = author("Grace", "grace@example.com");
+    let files = vec![
+        blame("src/metrics/reporter.rs", &[(&ada, 90), (&alan, 10)]),
+        blame("src/metrics/trend/chart.rs", &[(&ada, 50)]),
+        blame("src/commands/run.rs", &[(&alan, 40), (&grace, 40), (&ada, 20)]),
+        blame("README.md", &[(&grace, 5)]),
+    ];
+    let codeowners = CodeOwners::parse("/src/metrics/ @ada\n/src/commands/ @ada\n");
+
+    let report = OwnershipReport::analyze(&files, Some((".github/CODEOWNERS", &codeowners)), 2, 0.25);
+    let modules: Vec<(&str, usize, usize, usize)> =
+        report.modules.iter().map(|module| (module.module.as_str(), module.files, module.lines, module.bus_factor)).collect();
+    assert_eq!(modules, vec![(".", 1, 5, 1), ("src/commands", 1, 100, 2), ("src/metrics", 2, 150, 1)]);
+
+    let commands = &report.modules[1];
+    assert_eq!(commands.owner_share, Some(0.2));
+    assert_eq!(report.modules[0].owner_share, None);
+    assert_eq!(report.flagged().len(), 1);
+    assert_eq!(report.flagged()[0].module, "src/commands");
+
+    let markdown = report.to_markdown();
+    assert!(markdown.contains("| `src/metrics` | 2 | 150 | 1 | Ada (93%), Alan (7%) | @ada | 93% |"));
+    assert!(markdown.contains("- `src/commands`: @ada wrote 20%; most lines are by Alan (40%), Grace (40%), Ada (20%)"));
+
+    let json: serde_json::Value = serde_json::from_str(&report.to_json().unwrap()).unwrap();
+    assert_eq!(json["modules"][1]["flagged"], true);
+    assert_eq!(json["codeowners"], ".github/CODEOWNERS");
+}
+
+#[test]
+fn test_parallel_scan_blames_each_file_once() {
+    let dir = tempdir().unwrap();
+    if !init_repository(dir.path()) {
+        return;
+    }
+    write_file(dir.path(), "main.rs", "fn main() {}\n");
+    write_file(dir.path(), "src/ai/client.rs", "pub struct Client;\npub fn connect() {}\n");
+    write_file(dir.path(), "src/ai/config.rs", "pub struct Config;\n");
+    write_file(dir.path(), "src/metrics/mod.rs", "pub mod ownership;\n");
+    commit_all(dir.path(), "Initial");
+    let root = fs::canonicalize(dir.path()).unwrap();
+
+    let module_counts = |parallel: bool| {
+        let files = get_all_source_files(root.to_str().unwrap(), parallel).unwrap();
+        let report = OwnershipReport::analyze(&blame_files(&root, &files, parallel), None, 2, 0.2);
+        report.modules.iter().map(|module| (module.module.clone(), module.files, module.lines)).collect::<Vec<_>>()
+    };
+    let expected = vec![(".".to_string(), 1, 1), ("src/ai".to_string(), 2, 3), ("src/metrics".to_string(), 1, 1)];
+    assert_eq!(module_counts(false), expected);
+    assert_eq!(module_counts(true), expected);
+
+    let mut parallel_files = get_all_source_files(root.to_str().unwrap(), true).unwrap();
+    let mut files = get_all_source_files(root.to_str().unwrap(), false).unwrap();
+    parallel_files.sort();
+    files.sort();
+    assert_eq!(parallel_files, files);
+
+    // A file listed twice is still blamed once
+    assert_eq!(blame_files(&root, &[files.clone(), files].concat(), false).len(), 4);
+}
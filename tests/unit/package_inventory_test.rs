@@ -0,0 +1,231 @@
+use super::support::write_file;
+use ai_code_analyzer::dependency::lockfile::{Ecosystem, parse_lockfile};
+use ai_code_analyzer::dependency::package_inventory::{InventoryScanner, Package, PackageInventory};
+use std::path::Path;
+use tempfile::tempdir;
+
+fn find<'a>(inventory: &'a PackageInventory, ecosystem: Ecosystem, name: &str, version: &str) -> &'a Package {
+    inventory
+        .packages
+        .iter()
+        .find(|package| package.ecosystem == ecosystem && package.name == name && package.version == version)
+        .unwrap_or_else(|| panic!("{} {} not in inventory", name, version))
+}
+
+fn locked(root: &Path, relative: &str) -> Vec<(String, String, bool)> {
+    let mut packages: Vec<(String, String, bool)> = parse_lockfile(&root.join(relative))
+        .unwrap()
+        .unwrap()
+        .packages
+        .into_iter()
+        .map(|package| (package.name, package.version, package.direct))
+        .collect();
+    packages.sort();
+    packages
+}
+
+fn entry(name: &str, version: &str, direct: bool) -> (String, String, bool) {
+    (name.to_string(), version.to_string(), direct)
+}
+
+#[test]
+fn test_parse_npm_lockfiles() {
+    let dir = tempdir().unwrap();
+    let root = dir.path();
+
+    write_file(root, "web/package-lock.json", r#"{
+        "lockfileVersion": 3,
+        "packages": {
+            "": { "name": "web", "dependencies": { "react": "^18.0.0" }, "devDependencies": { "@types/react": "^18.0.0" } },
+            "node_modules/react": { "version": "18.2.0", "dependencies": { "loose-envify": "^1.1.0" } },
+            "node_modules/@types/react": { "version": "18.2.1", "dev": true },
+            "node_modules/loose-envify": { "version": "1.4.0" },
+            "node_modules/react/node_modules/loose-envify": { "version": "1.3.0" },
+            "node_modules/shared": { "resolved": "packages/shared", "link": true }
+        }
+    }"#);
+    assert_eq!(locked(root, "web/package-lock.json"), vec![
+        entry("@types/react", "18.2.1", true),
+        entry("loose-envify", "1.3.0", false),
+        entry("loose-envify", "1.4.0", false),
+        entry("react", "18.2.0", true),
+    ]);
+
+    write_file(root, "app/package.json", r#"{ "dependencies": { "@babel/core": "^7.0.0" } }"#);
+    write_file(root, "app/yarn.lock", concat!(
+        "# yarn lockfile v1\n\n",
+        "\"@babel/core@^7.0.0\", \"@babel/core@^7.1.0\":\n",
+        "  version \"7.24.0\"\n",
+        "  dependencies:\n",
+        "    debug \"^4.1.0\"\n\n",
+        "debug@^4.1.0:\n",
+        "  version \"4.3.4\"\n",
+    ));
+    assert_eq!(locked(root, "app/yarn.lock"), vec![
+        entry("@babel/core", "7.24.0", true),
+        entry("debug", "4.3.4", false),
+    ]);
+
+    write_file(root, "site/pnpm-lock.yaml", concat!(
+        "lockfileVersion: '9.0'\n\n",
+        "importers:\n\n",
+        "  .:\n",
+        "    dependencies:\n",
+        "      vue:\n",
+        "        specifier: ^3.4.0\n",
+        "        version: 3.4.21\n\n",
+        "packages:\n\n",
+        "  '@vue/shared@3.4.21':\n",
+        "    resolution: {integrity: sha512-abc}\n\n",
+        "  vue@3.4.21:\n",
+        "    resolution: {integrity: sha512-def}\n\n",
+        "snapshots:\n\n",
+        "  vue@3.4.21(typescript@5.4.2):\n",
+        "    dependencies:\n",
+        "      '@vue/shared': 3.4.21\n",
+    ));
+    assert_eq!(locked(root, "site/pnpm-lock.yaml"), vec![
+        entry("@vue/shared", "3.4.21", false),
+        entry("vue", "3.4.21", true),
+    ]);
+}
+
+#[test]
+fn test_parse_python_and_nuget_lockfiles() {
+    let dir = tempdir().unwrap();
+    let root = dir.path();
+
+    write_file(root, "api/pyproject.toml", concat!(
+        "[tool.poetry.dependencies]\n",
+        "python = \"^3.11\"\n",
+        "Flask = \"^3.0\"\n\n",
+        "[tool.poetry.group.dev.dependencies]\n",
+        "pytest = \"^8.0\"\n",
+    ));
+    write_file(root, "api/poetry.lock", concat!(
+        "[[package]]\nname = \"flask\"\nversion = \"3.0.2\"\n\n",
+        "[[package]]\nname = \"pytest\"\nversion = \"8.1.1\"\n\n",
+        "[[package]]\nname = \"werkzeug\"\nversion = \"3.0.1\"\n",
+    ));
+    assert_eq!(locked(root, "api/poetry.lock"), vec![
+        entry("flask", "3.0.2", true),
+        entry("pytest", "8.1.1", true),
+        entry("werkzeug", "3.0.1", false),
+    ]);
+
+    write_file(root, "worker/requirements.txt", concat!(
+        "celery==5.3.6\n",
+        "    # via -r requirements.in\n",
+        "kombu==5.3.5\n",
+        "    # via celery\n",
+        "redis[hiredis]==5.0.1 ; python_version >= \"3.8\"\n",
+        "    # via\n",
+        "    #   -r requirements.in\n",
+        "    #   celery\n",
+    ));
+    assert_eq!(locked(root, "worker/requirements.txt"), vec![
+        entry("celery", "5.3.6", true),
+        entry("kombu", "5.3.5", false),
+        entry("redis", "5.0.1", true),
+    ]);
+
+    write_file(root, "scripts/requirements-dev.txt", "-r requirements.txt\nblack>=24.0  # formatter\nruff\n");
+    assert_eq!(locked(root, "scripts/requirements-dev.txt"), vec![
+        entry("black", ">=24.0", true),
+        entry("ruff", "*", true),
+    ]);
+
+    write_file(root, "Service/packages.lock.json", r#"{
+        "version": 1,
+        "dependencies": {
+            "net8.0": {
+                "Serilog": { "type": "Direct", "requested": "[3.1.1, )", "resolved": "3.1.1" },
+                "System.Memory": { "type": "Transitive", "resolved": "4.5.5" },
+                "Service.Core": { "type": "Project" }
+            },
+            "net6.0": {
+                "Serilog": { "type": "Direct", "requested": "[3.1.1, )", "resolved": "3.1.1" }
+            }
+        }
+    }"#);
+    assert_eq!(locked(root, "Service/packages.lock.json"), vec![
+        entry("Serilog", "3.1.1", true),
+        entry("System.Memory", "4.5.5", false),
+    ]);
+
+    assert!(parse_lockfile(&root.join("api/pyproject.toml")).unwrap().is_none());
+}
+
+#[test]
+fn test_inventory_with_local_licenses() {
+    let dir = tempdir().unwrap();
+    let root = dir.path().join("project");
+    let cargo_home = dir.path().join("cargo");
+    let nuget = dir.path().join("nuget");
+
+    write_file(&root, "Cargo.lock", concat!(
+        "version = 3\n\n",
+        "[[package]]\nname = \"app\"\nversion = \"0.1.0\"\ndependencies = [\"rand 0.8.5\", \"serde\"]\n\n",
+        "[[package]]\nname = \"rand\"\nversion = \"0.8.5\"\nsource = \"registry+https://github.com/rust-lang/crates.io-index\"\ndependencies = [\"rand_core 0.6.4\"]\n\n",
+        "[[package]]\nname = \"rand\"\nversion = \"0.10.0\"\nsource = \"registry+https://github.com/rust-lang/crates.io-index\"\n\n",
+        "[[package]]\nname = \"rand_core\"\nversion = \"0.6.4\"\nsource = \"registry+https://github.com/rust-lang/crates.io-index\"\n\n",
+        "[[package]]\nname = \"serde\"\nversion = \"1.0.197\"\nsource = \"registry+https://github.com/rust-lang/crates.io-index\"\n",
+    ));
+    write_file(&cargo_home, "registry/src/index.crates.io-6f17d22bba15001f/serde-1.0.197/Cargo.toml",
+        "[package]\nname = \"serde\"\nversion = \"1.0.197\"\nlicense = \"MIT OR Apache-2.0\"\n");
+    write_file(&cargo_home, "registry/src/index.crates.io-6f17d22bba15001f/rand_core-0.6.4/Cargo.toml",
+        "[package]\nname = \"rand_core\"\nversion = \"0.6.4\"\nlicense-file = \"LICENSE\"\n");
+
+    write_file(&root, "web/package.json", r#"{ "dependencies": { "lodash": "^4.17.0" } }"#);
+    write_file(&root, "web/yarn.lock", "lodash@^4.17.0:\n  version \"4.17.21\"\n");
+    write_file(&root, "web/node_modules/lodash/package.json", r#"{ "name": "lodash", "version": "4.17.21", "license": "MIT" }"#);
+    // Lockfiles of installed packages are not part of the project
+    write_file(&root, "web/node_modules/lodash/package-lock.json", r#"{ "lockfileVersion": 3, "packages": {} }"#);
+
+    write_file(&root, "tools/requirements.txt", "requests==2.31.0\n");
+    write_file(&root, "tools/.venv/lib/python3.12/site-packages/requests-2.31.0.dist-info/METADATA",
+        "Metadata-Version: 2.1\nName: requests\nVersion: 2.31.0\nLicense: Apache 2.0\n\nLong description\n");
+
+    write_file(&root, "Service/packages.lock.json",
+        r#"{ "version": 1, "dependencies": { "net8.0": { "Serilog": { "type": "Direct", "resolved": "3.1.1" } } } }"#);
+    write_file(&nuget, "serilog/3.1.1/serilog.nuspec",
+        "<package><metadata><id>Serilog</id><license type=\"expression\">Apache-2.0</license></metadata></package>");
+
+    let inventory = InventoryScanner::new()
+        .cargo_home(&cargo_home)
+        .nuget_packages(&nuget)
+        .scan(&root)
+        .unwrap();
+
+    assert_eq!(inventory.lockfiles, vec![
+        "Cargo.lock",
+        "Service/packages.lock.json",
+        "tools/requirements.txt",
+        "web/yarn.lock",
+    ]);
+    assert_eq!(inventory.packages.len(), 7);
+    assert_eq!(inventory.direct_count(), 5);
+    assert_eq!(inventory.transitive_count(), 2);
+
+    let serde = find(&inventory, Ecosystem::Cargo, "serde", "1.0.197");
+    assert!(serde.direct);
+    assert_eq!(serde.license.as_deref(), Some("MIT OR Apache-2.0"));
+    assert!(!find(&inventory, Ecosystem::Cargo, "rand", "0.10.0").direct);
+    assert!(find(&inventory, Ecosystem::Cargo, "rand", "0.8.5").direct);
+    assert_eq!(find(&inventory, Ecosystem::Cargo, "rand_core", "0.6.4").license.as_deref(), Some("Custom"));
+    assert_eq!(find(&inventory, Ecosystem::Npm, "lodash", "4.17.21").license.as_deref(), Some("MIT"));
+    assert_eq!(find(&inventory, Ecosystem::PyPI, "requests", "2.31.0").license.as_deref(), Some("Apache 2.0"));
+    assert_eq!(find(&inventory, Ecosystem::NuGet, "Serilog", "3.1.1").license.as_deref(), Some("Apache-2.0"));
+
+    assert_eq!(inventory.duplicates.len(), 1);
+    assert_eq!(inventory.duplicates[0].name, "rand");
+    assert_eq!(inventory.duplicates[0].versions, vec!["0.8.5", "0.10.0"]);
+
+    let licenses = inventory.license_counts();
+    assert_eq!(licenses.get("Unknown"), Some(&2));
+    assert_eq!(licenses.get("MIT"), Some(&1));
+
+    let json: serde_json::Value = serde_json::to_value(&inventory).unwrap();
+    assert_eq!(json["packages"][0]["ecosystem"], "cargo");
+    assert_eq!(json["duplicates"][0]["versions"][1], "0.10.0");
+}
@@ -0,0 +1,85 @@
+use super::support::{specifiers, write_file};
+use ai_code_analyzer::dependency::dependency_analyzer::DependencyAnalyzer;
+use ai_code_analyzer::dependency::python_resolver::extract_python_dependencies;
+use ai_code_analyzer::util::parallel::ParallelProcessing;
+use tempfile::tempdir;
+
+#[test]
+fn test_extract_python_import_forms() {
+    let content = r#"
+"""Module docstring mentioning import fake."""
+import os, sys as system
+from . import sibling
+from ..core.models import (
+    User,
+    Group as Team,
+)
+from .utils import *
+import numpy as np  # import commented
+
+def load():
+    from yaml import safe_load
+    return safe_load
+
+try: import ujson as json
+except ImportError: pass
+"#;
+
+    assert_eq!(specifiers(extract_python_dependencies(content)), vec![
+        "os",
+        "sys",
+        ".sibling",
+        "..core.models.User",
+        "..core.models.Group",
+        ".utils",
+        "numpy",
+        "yaml.safe_load",
+        "ujson",
+    ]);
+}
+
+#[test]
+fn test_resolve_src_layout_packages() {
+    let dir = tempdir().unwrap();
+    let root = dir.path();
+
+    write_file(root, "pyproject.toml", r#"
+[project]
+name = "shop"
+dependencies = ["requests>=2.0", "PyYAML"]
+
+[tool.setuptools.packages.find]
+where = ["src"]
+"#);
+    write_file(root, "src/shop/__init__.py", "");
+    write_file(root, "src/shop/core/__init__.py", "from .models import User\n");
+    write_file(root, "src/shop/core/models.py", "class User: pass\n");
+    write_file(root, "src/shop/plugins/loader.py", "def load(): pass\n");
+    write_file(root, "src/shop/api/views.py", r#"
+import json
+import requests
+import yaml
+from shop.core import models
+from ..core.models import User
+from .. import core
+from shop.plugins.loader import load
+"#);
+
+    let graph = DependencyAnalyzer::new()
+        .enable_parallel_processing(false)
+        .analyze_dependencies(root)
+        .unwrap();
+
+    let file = |relative: &str| root.join(relative).to_string_lossy().to_string();
+    let dependencies = graph.get_dependencies(&file("src/shop/api/views.py"));
+
+    assert!(dependencies.contains(&file("src/shop/core/models.py")));
+    assert!(dependencies.contains(&file("src/shop/core/__init__.py")));
+    assert!(dependencies.contains(&file("src/shop/plugins/loader.py")));
+    assert!(dependencies.contains(&"requests".to_string()));
+    assert!(dependencies.contains(&"PyYAML".to_string()));
+    assert!(!dependencies.contains(&"json".to_string()));
+
+    let init_dependencies = graph.get_dependencies(&file("src/shop/core/__init__.py"));
+    assert!(init_dependencies.contains(&file("src/shop/core/models.py")));
+}
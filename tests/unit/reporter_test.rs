@@ -34,3 +34,44 @@ fn test_reporter_report() {
 
     reporter.report(&metrics);
 }
+
+#[test]
+fn test_export_metrics_json_and_csv() {
+    let dir = tempfile::tempdir().unwrap();
+    let mut metrics = CodeMetrics::new();
+    let mut test_metrics = LanguageMetrics::new("Rust".to_string());
+    test_metrics.lines_of_code = 12;
+    metrics.add_language_metrics(test_metrics, "tests/parser_test.rs");
+    let mut source_metrics = LanguageMetrics::new("Rust".to_string());
+    source_metrics.lines_of_code = 40;
+    source_metrics.comment_lines = 5;
+    metrics.add_language_metrics(source_metrics, "src/parser, v2.rs");
+
+    let reporter = MetricsReporter::new();
+    let first = dir.path().join("first.json");
+    let second = dir.path().join("second.json");
+    reporter.export_metrics_json(&metrics, &first).unwrap();
+    reporter.export_metrics_json(&metrics, &second).unwrap();
+    let content = std::fs::read_to_string(&first).unwrap();
+    assert_eq!(content, std::fs::read_to_string(&second).unwrap());
+
+    let json: serde_json::Value = serde_json::from_str(&content).unwrap();
+    assert_eq!(json["schema_version"], ai_code_analyzer::metrics::reporter::METRICS_SCHEMA_VERSION);
+    let keys: Vec<&String> = json.as_object().unwrap().keys().collect();
+    assert_eq!(keys, vec!["clones", "directory_tree", "files", "languages", "production", "root", "schema_version", "summary", "test"]);
+    assert_eq!(json["directory_tree"]["lines_of_code"], 52);
+    assert_eq!(json["directory_tree"]["children"][0]["path"], "src");
+    assert_eq!(json["directory_tree"]["children"][1]["test_ratio"], serde_json::Value::Null);
+    assert_eq!(json["files"][0]["path"], "src/parser, v2.rs");
+    assert_eq!(json["production"]["languages"][0]["lines_of_code"], 40);
+    assert_eq!(json["test"]["languages"][0]["lines_of_code"], 12);
+
+    let csv = dir.path().join("metrics.csv");
+    reporter.export_metrics_csv(&metrics, &csv).unwrap();
+    assert_eq!(
+        std::fs::read_to_string(&csv).unwrap(),
+        "path,language,lines_of_code,blank_lines,comment_lines,is_test_file,duplicated_lines\n\
+         \"src/parser, v2.rs\",Rust,40,0,5,false,\n\
+         tests/parser_test.rs,Rust,12,0,0,true,\n"
+    );
+}
@@ -0,0 +1,148 @@
+use super::support::{analyze, specifiers, write_file};
+use ai_code_analyzer::dependency::dependency_analyzer::DependencyAnalyzer;
+use ai_code_analyzer::dependency::dependency_graph::{EdgeImport, ImportKind};
+use ai_code_analyzer::dependency::rust_resolver::parse_rust_source;
+use ai_code_analyzer::util::parallel::ParallelProcessing;
+use std::path::Path;
+use tempfile::tempdir;
+
+#[test]
+fn test_parse_grouped_and_multiline_use() {
+    let content = r#"
+use std::collections::HashMap;
+use crate::{
+    dependency::{dependency_graph::DependencyGraph, self},
+    util::error::AppError as Error,
+};
+pub(crate) use super::sibling::*;
+// use crate::commented::Out;
+const TEXT: &str = "use crate::inside::string;";
+"#;
+    
+    let items = parse_rust_source(content);
+    
+    assert_eq!(specifiers(items.imports), vec![
+        "std::collections::HashMap",
+        "crate::dependency::dependency_graph::DependencyGraph",
+        "crate::dependency",
+        "crate::util::error::AppError",
+        "super::sibling",
+    ]);
+}
+
+#[test]
+fn test_parse_mod_declarations_and_inline_modules() {
+    let content = r#"
+pub mod visible;
+#[cfg(feature = "extra")]
+#[path = "platform/unix.rs"]
+mod platform;
+extern crate serde;
+
+mod outer {
+    pub mod inner;
+    use super::visible::Thing;
+    use self::inner::Other;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+}
+"#;
+    
+    let items = parse_rust_source(content);
+    
+    assert_eq!(items.mod_decls.len(), 3);
+    assert_eq!(items.mod_decls[0].name, "visible");
+    assert_eq!(items.mod_decls[1].name, "platform");
+    assert_eq!(items.mod_decls[1].path_attr.as_deref(), Some("platform/unix.rs"));
+    assert_eq!(items.mod_decls[2].name, "inner");
+    assert_eq!(items.mod_decls[2].inline_path, vec!["outer".to_string()]);
+    
+    assert_eq!(items.inline_modules, vec![vec!["outer".to_string()], vec!["tests".to_string()]]);
+    
+    // Paths inside inline modules are rebased onto the file's own module
+    assert_eq!(specifiers(items.imports), vec![
+        "self::visible",
+        "self::platform",
+        "serde",
+        "self::outer::inner",
+        "self::visible::Thing",
+        "self::outer::inner::Other",
+        "self",
+    ]);
+}
+
+fn sorted_dependencies(graph: &ai_code_analyzer::dependency::dependency_graph::DependencyGraph, root: &Path, file: &str) -> Vec<String> {
+    let node = root.join(file).to_string_lossy().to_string();
+    let root_prefix = format!("{}/", root.to_string_lossy());
+    
+    let mut dependencies: Vec<String> = graph
+        .get_dependencies(&node)
+        .into_iter()
+        .map(|dependency| dependency.trim_start_matches(&root_prefix).to_string())
+        .collect();
+    dependencies.sort();
+    dependencies
+}
+
+#[test]
+fn test_rust_module_resolution() {
+    let temp_dir = tempdir().unwrap();
+    let root = temp_dir.path();
+    
+    write_file(root, "Cargo.toml", "[package]\nname = \"demo-crate\"\nversion = \"0.1.0\"\n");
+    write_file(root, "src/lib.rs", "pub mod a;\n#[path = \"custom/b_impl.rs\"]\nmod b;\nmod c {\n    pub mod d;\n}\n");
+    write_file(root, "src/a.rs", "mod nested;\nuse crate::{b::Thing, c::d::helper};\npub struct Widget;\n");
+    write_file(root, "src/a/nested.rs", "use super::super::b::*;\n");
+    write_file(root, "src/custom/b_impl.rs", "pub struct Thing;\nmod inner {\n    use super::super::a::Widget;\n}\n");
+    write_file(root, "src/c/d.rs", "use serde::{\n    Serialize,\n    Deserialize,\n};\npub fn helper() {}\n");
+    write_file(root, "src/main.rs", "use demo_crate::a::Widget;\nfn main() {}\n");
+    
+    let analyzer = DependencyAnalyzer::new().enable_parallel_processing(false);
+    let graph = analyzer.analyze_dependencies(root).unwrap();
+    
+    assert_eq!(sorted_dependencies(&graph, root, "src/lib.rs"), vec!["src/a.rs", "src/c/d.rs", "src/custom/b_impl.rs"]);
+    assert_eq!(sorted_dependencies(&graph, root, "src/a.rs"), vec!["src/a/nested.rs", "src/c/d.rs", "src/custom/b_impl.rs"]);
+    assert_eq!(sorted_dependencies(&graph, root, "src/a/nested.rs"), vec!["src/custom/b_impl.rs"]);
+    assert_eq!(sorted_dependencies(&graph, root, "src/custom/b_impl.rs"), vec!["src/a.rs"]);
+    assert_eq!(sorted_dependencies(&graph, root, "src/c/d.rs"), vec!["serde"]);
+    assert_eq!(sorted_dependencies(&graph, root, "src/main.rs"), vec!["src/a.rs"]);
+    
+    assert!(graph.is_external("serde"));
+    assert!(!graph.get_nodes().contains("std"));
+    assert!(!graph.get_nodes().contains("crate"));
+}
+
+#[test]
+fn test_rust_lib_name_from_manifest() {
+    let temp_dir = tempdir().unwrap();
+    let root = temp_dir.path();
+    
+    write_file(root, "Cargo.toml", "[package]\nname = \"demo-crate\" # published name\n\n[lib]\nname = 'engine'\npath = \"src/lib.rs\"\n");
+    write_file(root, "src/lib.rs", "pub mod a;\n");
+    write_file(root, "src/a.rs", "pub struct Widget;\n");
+    write_file(root, "src/main.rs", "use engine::a::Widget;\nfn main() {}\n");
+    
+    let analyzer = DependencyAnalyzer::new().enable_parallel_processing(false);
+    let graph = analyzer.analyze_dependencies(root).unwrap();
+    
+    assert_eq!(sorted_dependencies(&graph, root, "src/main.rs"), vec!["src/a.rs"]);
+    assert!(!graph.get_nodes().contains("engine"));
+}
+
+#[test]
+fn test_mod_declaration_keeps_its_own_line() {
+    let temp_dir = tempdir().unwrap();
+    let root = temp_dir.path();
+    
+    write_file(root, "src/util.rs", "pub fn greet(_name: &str) {}\n");
+    write_file(root, "src/main.rs", "//! Entry point; see util for the helpers.\n\nuse std::env;\nmod util;\n\nfn main() {\n    let name = env::args().next().unwrap_or_default();\n    let label = \"use util\";\n    use util::greet;\n    greet(&name);\n    greet(label);\n}\n");
+    
+    let graph = analyze(root);
+    let main = root.join("src/main.rs").to_string_lossy().to_string();
+    let util = root.join("src/util.rs").to_string_lossy().to_string();
+    
+    assert_eq!(graph.get_edge_import(&main, &util), Some(&EdgeImport::new("self::util", 4, ImportKind::Module)));
+}
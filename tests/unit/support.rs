@@ -0,0 +1,38 @@
+use ai_code_analyzer::dependency::dependency_analyzer::DependencyAnalyzer;
+use ai_code_analyzer::dependency::dependency_graph::{DependencyGraph, EdgeImport};
+use ai_code_analyzer::util::git::run_git;
+use ai_code_analyzer::util::parallel::ParallelProcessing;
+use std::fs;
+use std::path::Path;
+
+/// Writes `content` to `relative` under `root`, creating parent directories as needed.
+pub fn write_file(root: &Path, relative: &str, content: &str) {
+    let path = root.join(relative);
+    fs::create_dir_all(path.parent().unwrap()).unwrap();
+    fs::write(path, content).unwrap();
+}
+
+/// The dependency graph of `root`, built sequentially and including test files.
+pub fn analyze(root: &Path) -> DependencyGraph {
+    DependencyAnalyzer::new()
+        .enable_parallel_processing(false)
+        .include_test_files(true)
+        .analyze_dependencies(root)
+        .unwrap()
+}
+
+/// The specifiers of imports returned by a language extractor, in order.
+pub fn specifiers(imports: Vec<EdgeImport>) -> Vec<String> {
+    imports.into_iter().map(|import| import.specifier).collect()
+}
+
+/// Creates a git repository at `root`; `false` when git is not available.
+pub fn init_repository(root: &Path) -> bool {
+    run_git(root, &["init", "--quiet"]).is_ok()
+}
+
+/// Commits everything in the repository at `root` as a test author.
+pub fn commit_all(root: &Path, message: &str) {
+    run_git(root, &["add", "-A"]).unwrap();
+    run_git(root, &["-c", "user.name=Test", "-c", "user.email=test@example.com", "commit", "--quiet", "-m", message]).unwrap();
+}
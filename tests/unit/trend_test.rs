@@ -0,0 +1,101 @@
+use super::support::{commit_all, init_repository, write_file};
+use ai_code_analyzer::metrics::models::GroupStats;
+use ai_code_analyzer::metrics::trend::{MetricsTrend, TrendPoint, sample_evenly, sample_weekly};
+use ai_code_analyzer::util::git::{self, Commit};
+use std::collections::BTreeMap;
+use tempfile::tempdir;
+
+const DAY: i64 = 24 * 60 * 60;
+
+fn history(days: &[i64]) -> Vec<Commit> {
+    days.iter()
+        .enumerate()
+        .map(|(index, day)| Commit {
+            hash: format!("{:040}", index),
+            timestamp: day * DAY,
+            date: format!("day-{}", day),
+        })
+        .collect()
+}
+
+fn point(date: &str, lines_of_code: usize, test_lines_of_code: usize, languages: &[(&str, usize)]) -> TrendPoint {
+    TrendPoint {
+        commit: date.replace('-', ""),
+        date: date.to_string(),
+        stats: GroupStats { files: 3, lines_of_code, comment_lines: lines_of_code / 4, test_lines_of_code },
+        languages: languages.iter().map(|(language, lines)| (language.to_string(), *lines)).collect::<BTreeMap<_, _>>(),
+    }
+}
+
+#[test]
+fn test_sampling() {
+    let commits = history(&[0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10]);
+    let dates = |samples: Vec<Commit>| samples.into_iter().map(|commit| commit.date).collect::<Vec<_>>();
+
+    // Evenly spread, first and last included
+    assert_eq!(dates(sample_evenly(&commits, 3)), vec!["day-0", "day-5", "day-10"]);
+    assert_eq!(dates(sample_evenly(&commits, 1)), vec!["day-10"]);
+    assert_eq!(sample_evenly(&commits, 50).len(), 11);
+
+    // Weekly: the latest commit at each boundary, skipping weeks without a new commit
+    let sparse = history(&[1, 3, 30]);
+    assert_eq!(dates(sample_weekly(&sparse, 2 * DAY, 31 * DAY)), vec!["day-1", "day-3", "day-30"]);
+    assert_eq!(dates(sample_weekly(&sparse, 0, 2 * DAY)), vec!["day-1"]);
+}
+
+#[test]
+fn test_trend_exports() {
+    let trend = MetricsTrend::new(vec![
+        point("2024-01-01", 100, 20, &[("Rust", 100)]),
+        point("2024-01-08", 200, 50, &[("Rust", 150), ("Python", 50)]),
+    ]);
+
+    let csv = trend.to_csv();
+    let lines: Vec<&str> = csv.lines().collect();
+    assert_eq!(
+        lines[0],
+        "commit,date,files,lines_of_code,comment_lines,test_lines_of_code,test_ratio,comment_density,Python,Rust"
+    );
+    assert_eq!(lines[1], "20240101,2024-01-01,3,100,25,20,0.2500,0.2000,0,100");
+    assert_eq!(lines[2], "20240108,2024-01-08,3,200,50,50,0.3333,0.2000,50,150");
+
+    let markdown = trend.to_markdown(Some("trend.svg"));
+    assert!(markdown.contains("2 samples from 2024-01-01 to 2024-01-08. Lines of code went from 100 to 200."));
+    assert!(markdown.contains("![Metrics trend](trend.svg)"));
+    assert!(markdown.contains("| 2024-01-08 | `20240108` | 3 | 200 | 20.0% | 33.3% |"));
+    assert!(markdown.contains("| Date | Python | Rust |"));
+    assert!(markdown.contains("| 2024-01-08 | 25.0% | 75.0% |"));
+
+    let json: serde_json::Value = serde_json::from_str(&trend.to_json().unwrap()).unwrap();
+    assert_eq!(json["samples"][1]["languages"]["Python"], 50);
+    assert_eq!(json["samples"][0]["test_ratio"], 0.25);
+
+    let svg = trend.to_svg();
+    assert!(svg.starts_with("<svg xmlns=\"http://www.w3.org/2000/svg\""));
+    assert!(svg.trim_end().ends_with("</svg>"));
+    assert_eq!(svg.matches("<polyline").count(), 5);
+    assert!(svg.contains("<text x=\"724.0\" y=\"50.0\">Total</text>"));
+    assert!(!svg.contains("<script"));
+}
+
+#[test]
+fn test_first_parent_history() {
+    let dir = tempdir().unwrap();
+    let root = dir.path();
+    if !init_repository(root) {
+        return;
+    }
+    write_file(root, "src/main.rs", "fn main() {}\n");
+    commit_all(root, "Initial");
+    write_file(root, "src/lib.rs", "pub fn run() {}\n");
+    commit_all(root, "Add library");
+
+    let commits = git::first_parent_history(root, "HEAD").unwrap();
+    assert_eq!(commits.len(), 2);
+    assert!(commits[0].timestamp <= commits[1].timestamp);
+
+    // A revision that looks like an option is refused rather than passed on to git log
+    let injected = root.join("injected.log");
+    assert!(git::first_parent_history(root, &format!("--output={}", injected.display())).is_err());
+    assert!(!injected.exists());
+}